@@ -18,6 +18,9 @@ use clap_complete::engine::{ArgValueCompleter, CompletionCandidate, ValueComplet
     treemd -l README.md           # List all headings\n  \
     treemd --tree README.md       # Show heading tree\n  \
     treemd -s Installation doc.md # Extract section\n  \
+    treemd tasks --open TODO.md   # List open tasks\n  \
+    treemd graph notes/ -o dot    # Vault link graph\n  \
+    treemd section get doc.md API # Print one section\n  \
     treemd --setup-completions    # Set up shell completions"
 )]
 pub struct Cli {
@@ -50,6 +53,14 @@ pub struct Cli {
     #[arg(long = "tree")]
     pub tree: bool,
 
+    /// Print the document with Mermaid diagrams drawn as text (non-interactive)
+    ///
+    /// Flowcharts and sequence diagrams in ```mermaid blocks are replaced by
+    /// box-drawing diagrams; other diagram types are printed unchanged.
+    /// Combine with -s to render a single section.
+    #[arg(long = "render")]
+    pub render: bool,
+
     /// Filter headings by text pattern (case-insensitive)
     ///
     /// Only shows headings containing the specified text.
@@ -77,14 +88,18 @@ pub struct Cli {
     #[arg(short = 'o', long = "output", default_value = "plain")]
     pub output: OutputFormat,
 
-    /// Extract specific section by heading name
+    /// Extract specific sections by heading
     ///
     /// Extracts content from a heading until the next heading of same or higher level.
-    /// Useful for pulling specific sections from large documents.
+    /// Useful for pulling specific sections from large documents. Repeat to extract
+    /// several sections, printed in the order given.
     ///
-    /// Example: -s "Usage" extracts the Usage section
+    /// A section is picked by heading text (case-insensitive), a path of headings,
+    /// an index among repeated headings, an anchor or a line:
+    ///   -s Usage          -s API/Errors     -s "Errors[2]"
+    ///   -s "#errors-1"    -s @120           (section containing line 120)
     #[arg(short = 's', long = "section", value_name = "HEADING")]
-    pub section: Option<String>,
+    pub section: Vec<String>,
 
     /// Count headings by level (shows statistics)
     ///
@@ -157,10 +172,28 @@ pub struct Cli {
     ///   jsonl  - Line-delimited JSON
     ///   md     - Raw markdown
     ///   tree   - Tree structure
+    ///   csv    - Comma-separated values (tables as header + rows)
+    ///   tsv    - Tab-separated values
     ///
     /// Example: -q '.h2' --query-output json
     #[arg(long = "query-output", value_name = "FORMAT")]
     pub query_output: Option<String>,
+
+    /// Open the document read-only (TUI mode)
+    ///
+    /// Disables checkbox toggles, table cell edits, creating files from
+    /// links and launching $EDITOR. Can also be set in the config file
+    /// with `read_only = true` under `[security]`.
+    #[arg(long = "read-only")]
+    pub read_only: bool,
+
+    /// Safe mode for untrusted documents (TUI mode, implies --read-only)
+    ///
+    /// Additionally confines relative and wiki link following to the
+    /// directory of the opened file and never opens external URLs.
+    /// Can also be set in the config file with `safe = true` under `[security]`.
+    #[arg(long = "safe")]
+    pub safe: bool,
 }
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -187,6 +220,243 @@ pub enum Command {
         /// before this line.
         line: usize,
     },
+
+    /// List, check and uncheck task list items
+    ///
+    /// Lists `- [ ]` / `- [x]` items with their line numbers. Items inside
+    /// code blocks are skipped and nested items are indented. With --toggle,
+    /// --check or --uncheck the file is rewritten in place (atomically).
+    ///
+    /// Due dates (📅 2025-01-31) and priorities (🔺 ⏫ 🔼 🔽 ⏬) in the
+    /// Obsidian Tasks format are shown in JSON output and can be filtered on.
+    ///
+    /// Examples:
+    ///   treemd tasks TODO.md --open              # Unchecked tasks
+    ///   treemd tasks TODO.md -s "This week" -o json
+    ///   treemd tasks TODO.md --open --due-by 2025-02-01 --priority high
+    ///   treemd tasks TODO.md --toggle 12         # Flip the task on line 12
+    ///   treemd tasks TODO.md --check "release notes"
+    Tasks(TasksArgs),
+
+    /// Print the link graph of a vault
+    ///
+    /// Reads every note under the folder and resolves its wikilinks, embeds
+    /// and relative markdown links the way the TUI follows them. Given a
+    /// file, the graph covers its vault (the folder holding `.obsidian/` or
+    /// the configured `[vault] root`), or else the file's folder.
+    ///
+    /// Besides the links, the output lists orphans (notes with no links in
+    /// or out), dead ends (notes linked to that link nowhere) and links to
+    /// notes that don't exist.
+    ///
+    /// Examples:
+    ///   treemd graph notes/                     # Links and loose ends
+    ///   treemd graph notes/ -o dot | dot -Tsvg > graph.svg
+    ///   treemd graph notes/index.md -o json
+    Graph(GraphArgs),
+
+    /// Rename a note or heading and fix the links to it
+    ///
+    /// Rewrites the wikilinks, embeds and relative links that point at the
+    /// note (or at the heading's anchor) in every note of the workspace: the
+    /// vault holding the file, or else its folder. A note moved to another
+    /// folder also gets its own relative links updated.
+    ///
+    /// Every changed line is listed; with --dry-run nothing is written.
+    /// Files are written atomically, then the note is renamed.
+    ///
+    /// Examples:
+    ///   treemd rename notes/draft.md notes/design.md
+    ///   treemd rename notes/draft.md archive/draft.md --dry-run
+    ///   treemd rename README.md --heading "Getting Started" "Quick Start"
+    Rename(RenameArgs),
+
+    /// Read or edit one section of a document
+    ///
+    /// A section is a heading with everything under it, subsections
+    /// included. It is addressed like with `treemd -s`: by heading text
+    /// (case-insensitive), a path of headings (`Install/Linux`), an index
+    /// among repeats (`Errors[2]`), an anchor (`#install-1`) or a line (`@42`,
+    /// the section containing line 42).
+    ///
+    /// Edits print the whole updated document, or write it back atomically
+    /// with -i. Content for set, append and prepend is read from stdin.
+    ///
+    /// Examples:
+    ///   treemd section get README.md Install/Linux
+    ///   echo "New text" | treemd section set -i README.md "#usage"
+    ///   treemd section append -i CHANGELOG.md Unreleased < entry.md
+    ///   treemd section move -i README.md FAQ --after Usage
+    ///   cat notes.md | treemd section append --shift-levels 1 doc.md API
+    #[command(subcommand)]
+    Section(SectionCommand),
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SectionCommand {
+    /// Print a section
+    Get(SectionTarget),
+    /// Replace everything under a section's heading with stdin
+    Set(SectionInsertArgs),
+    /// Add stdin at the end of a section, after its subsections
+    Append(SectionInsertArgs),
+    /// Add stdin right under a section's heading
+    Prepend(SectionInsertArgs),
+    /// Remove a section and its subsections
+    Delete(SectionEditArgs),
+    /// Move a section before or after another one
+    Move(SectionMoveArgs),
+}
+
+#[derive(Debug, clap::Args)]
+pub struct SectionTarget {
+    /// Markdown file
+    #[arg(add = markdown_file_completer())]
+    pub file: PathBuf,
+
+    /// Heading text, heading path (`API/Errors`), `Errors[2]`, `#anchor` or `@line`
+    pub section: String,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct SectionEditArgs {
+    #[command(flatten)]
+    pub target: SectionTarget,
+
+    /// Write the result back to the file instead of printing it
+    #[arg(short = 'i', long = "in-place")]
+    pub in_place: bool,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct SectionInsertArgs {
+    #[command(flatten)]
+    pub edit: SectionEditArgs,
+
+    /// Shift the headings of the new content by N levels (negative raises them)
+    #[arg(
+        long = "shift-levels",
+        value_name = "N",
+        allow_negative_numbers = true,
+        default_value_t = 0
+    )]
+    pub shift_levels: isize,
+}
+
+#[derive(Debug, clap::Args)]
+#[command(group = clap::ArgGroup::new("position").args(["before", "after"]).required(true))]
+pub struct SectionMoveArgs {
+    #[command(flatten)]
+    pub edit: SectionEditArgs,
+
+    /// Put the section right before this one
+    #[arg(long = "before", value_name = "SECTION")]
+    pub before: Option<String>,
+
+    /// Put the section after this one and its subsections
+    #[arg(long = "after", value_name = "SECTION")]
+    pub after: Option<String>,
+
+    /// Shift the headings of the moved section by N levels (negative raises them)
+    #[arg(
+        long = "shift-levels",
+        value_name = "N",
+        allow_negative_numbers = true,
+        default_value_t = 0
+    )]
+    pub shift_levels: isize,
+}
+
+#[derive(Debug, clap::Args)]
+#[command(group = clap::ArgGroup::new("edit").args(["toggle", "check", "uncheck"]))]
+pub struct TasksArgs {
+    /// Markdown file, or '-' for stdin (listing only)
+    #[arg(add = markdown_file_completer())]
+    pub file: PathBuf,
+
+    /// Only unchecked tasks
+    #[arg(long = "open", conflicts_with = "done")]
+    pub open: bool,
+
+    /// Only checked tasks
+    #[arg(long = "done")]
+    pub done: bool,
+
+    /// Only tasks in this section, including subsections (heading, path,
+    /// #anchor or @line, as for `treemd -s`)
+    #[arg(short = 's', long = "section", value_name = "HEADING")]
+    pub section: Option<String>,
+
+    /// Only tasks due on or before this date (YYYY-MM-DD)
+    #[arg(long = "due-by", value_name = "DATE")]
+    pub due_by: Option<String>,
+
+    /// Only tasks with at least this priority (lowest, low, medium, high, highest)
+    #[arg(long = "priority", value_name = "LEVEL")]
+    pub priority: Option<treemd::parser::tasks::Priority>,
+
+    /// Output format for the listing
+    ///
+    ///   plain - `LINE: - [ ] text`, one task per line (default)
+    ///   json  - JSON array with text, checked, line, depth, heading_path, due, priority
+    ///   tree  - Tasks grouped under their headings
+    #[arg(short = 'o', long = "output", default_value = "plain")]
+    pub output: OutputFormat,
+
+    /// Check or uncheck the task on this line
+    #[arg(long = "toggle", value_name = "LINE")]
+    pub toggle: Option<usize>,
+
+    /// Check the open task with this text (exact, or a unique substring)
+    #[arg(long = "check", value_name = "TEXT")]
+    pub check: Option<String>,
+
+    /// Uncheck the done task with this text (exact, or a unique substring)
+    #[arg(long = "uncheck", value_name = "TEXT")]
+    pub uncheck: Option<String>,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct GraphArgs {
+    /// Vault folder, or a note inside it
+    pub path: PathBuf,
+
+    /// Output format
+    ///
+    ///   plain - `note → target, ...` per note, then the loose ends (default)
+    ///   dot   - Graphviz DOT source
+    ///   json  - Notes with incoming and outgoing links, orphans, dead ends
+    #[arg(short = 'o', long = "output", default_value = "plain")]
+    pub output: GraphFormat,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct RenameArgs {
+    /// Note to rename, or the note holding the heading
+    #[arg(add = markdown_file_completer())]
+    pub file: PathBuf,
+
+    /// New path of the note (it keeps its extension if none is given), or
+    /// the new heading text with --heading
+    pub new_name: String,
+
+    /// Rename this heading (case-insensitive) instead of the note
+    #[arg(long = "heading", value_name = "HEADING")]
+    pub heading: Option<String>,
+
+    /// List the changes without writing anything
+    #[arg(short = 'n', long = "dry-run")]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum GraphFormat {
+    /// Links per note and the loose ends
+    Plain,
+    /// Graphviz DOT
+    Dot,
+    /// JSON output
+    Json,
 }
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -0,0 +1,60 @@
+//! The `graph` subcommand: the link graph of a vault as text, DOT or JSON.
+
+use super::{GraphArgs, GraphFormat};
+use treemd::Config;
+use treemd::graph::LinkGraph;
+use treemd::vault::Vault;
+
+/// Run `treemd graph`
+pub fn run(args: &GraphArgs) -> Result<(), String> {
+    let path = args
+        .path
+        .canonicalize()
+        .map_err(|e| format!("Failed to open {}: {}", args.path.display(), e))?;
+    let vault = if path.is_dir() {
+        Vault::open(path)
+    } else {
+        Vault::workspace(&path, Config::load().vault.root.as_deref())
+            .ok_or_else(|| format!("No folder for {}", args.path.display()))?
+    };
+
+    let graph = LinkGraph::build(&vault);
+    match args.output {
+        GraphFormat::Plain => print_plain(&graph),
+        GraphFormat::Dot => print!("{}", graph.to_dot()),
+        GraphFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&graph.to_json())
+                .map_err(|e| format!("Failed to serialize graph: {}", e))?
+        ),
+    }
+    Ok(())
+}
+
+fn print_plain(graph: &LinkGraph) {
+    let name = |idx: usize| graph.notes()[idx].display().to_string();
+    let names = |notes: Vec<usize>| notes.into_iter().map(name).collect::<Vec<_>>().join(", ");
+
+    for idx in 0..graph.notes().len() {
+        let outgoing = graph.outgoing(idx);
+        if !outgoing.is_empty() {
+            println!("{} → {}", name(idx), names(outgoing));
+        }
+    }
+
+    // `Label (n): a, b`, without the list part when there is nothing to list
+    let summary = |label: &str, notes: Vec<usize>| match notes.len() {
+        0 => format!("{} (0)", label),
+        count => format!("{} ({}): {}", label, count, names(notes)),
+    };
+    println!();
+    println!("{}", summary("Orphans", graph.orphans()));
+    println!("{}", summary("Dead ends", graph.dead_ends()));
+    match graph.unresolved().len() {
+        0 => println!("Unresolved links (0)"),
+        count => println!("Unresolved links ({}):", count),
+    }
+    for (from, target) in graph.unresolved() {
+        println!("  {} → {}", name(*from), target);
+    }
+}
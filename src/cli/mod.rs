@@ -1,4 +1,11 @@
 mod commands;
+pub mod graph;
+pub mod rename;
+pub mod section;
 pub mod setup;
+pub mod tasks;
 
-pub use commands::{Cli, ColorModeArg, OutputFormat};
+pub use commands::{
+    Cli, ColorModeArg, Command, GraphArgs, GraphFormat, OutputFormat, RenameArgs, SectionCommand,
+    SectionEditArgs, SectionInsertArgs, SectionTarget, TasksArgs,
+};
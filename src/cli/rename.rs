@@ -0,0 +1,69 @@
+//! The `rename` subcommand: rename a note or heading and rewrite the links to it.
+
+use super::RenameArgs;
+use std::path::Path;
+use treemd::Config;
+use treemd::rename::{self, RenamePlan};
+use treemd::vault::Vault;
+
+/// Run `treemd rename`
+pub fn run(args: &RenameArgs) -> Result<(), String> {
+    let workspace = Vault::workspace(&args.file, Config::load().vault.root.as_deref())
+        .ok_or_else(|| format!("Failed to open {}", args.file.display()))?;
+
+    let plan = match &args.heading {
+        Some(heading) => {
+            let content = std::fs::read_to_string(&args.file)
+                .map_err(|e| format!("Failed to read {}: {}", args.file.display(), e))?;
+            let offset = rename::find_heading(&content, heading)?;
+            let plan = rename::rename_heading(&workspace, &args.file, offset, &args.new_name)?;
+            println!("Heading '{}' → '{}'", heading, args.new_name.trim());
+            plan
+        }
+        None => {
+            let plan = rename::rename_file(&workspace, &args.file, Path::new(&args.new_name))?;
+            if let Some((_, to)) = &plan.move_file
+                && !to.starts_with(workspace.root())
+            {
+                return Err(format!(
+                    "{} is outside the workspace ({})",
+                    args.new_name,
+                    workspace.root().display()
+                ));
+            }
+            if let Some((from, to)) = &plan.move_file {
+                println!("{} → {}", plan.display_path(from), plan.display_path(to));
+            }
+            plan
+        }
+    };
+
+    print_changes(&plan);
+    let summary = format!(
+        "{} line{} in {} note{}",
+        plan.change_count(),
+        if plan.change_count() == 1 { "" } else { "s" },
+        plan.rewrites.len(),
+        if plan.rewrites.len() == 1 { "" } else { "s" }
+    );
+    if args.dry_run {
+        println!("Dry run: {} would change", summary);
+        return Ok(());
+    }
+    plan.apply()?;
+    println!("Updated {}", summary);
+    Ok(())
+}
+
+/// Each changed line as `path:line` with the old and new text
+fn print_changes(plan: &RenamePlan) {
+    for rewrite in &plan.rewrites {
+        for change in &rewrite.changes {
+            println!();
+            println!("{}:{}", plan.display_path(&rewrite.path), change.line);
+            println!("  - {}", change.before);
+            println!("  + {}", change.after);
+        }
+    }
+    println!();
+}
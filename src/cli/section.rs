@@ -0,0 +1,124 @@
+//! The `section` subcommand: print or edit one section of a document.
+
+use super::{SectionCommand, SectionEditArgs, SectionInsertArgs, SectionTarget};
+use std::io::{self, Read};
+use treemd::parser::{self, Document};
+use treemd::section;
+
+/// Run `treemd section`
+pub fn run(command: &SectionCommand) -> Result<(), String> {
+    match command {
+        SectionCommand::Get(target) => {
+            let (doc, idx) = load(target)?;
+            println!("{}", section::text(&doc, idx));
+            Ok(())
+        }
+        SectionCommand::Set(args) => insert(args, section::set_body),
+        SectionCommand::Append(args) => insert(args, section::append),
+        SectionCommand::Prepend(args) => insert(args, section::prepend),
+        SectionCommand::Delete(args) => {
+            let (doc, idx) = load(&args.target)?;
+            finish(args, section::delete_section(&doc, idx))
+        }
+        SectionCommand::Move(args) => {
+            let (doc, idx) = load(&args.edit.target)?;
+            let (target, after) = match (&args.before, &args.after) {
+                (Some(before), _) => (before, false),
+                (None, Some(after)) => (after, true),
+                (None, None) => return Err("--before or --after is required".to_string()),
+            };
+            let target = section::find(&doc, target)?;
+            let moved = section::move_to(&doc, idx, target, after, args.shift_levels)?;
+            finish(&args.edit, moved)
+        }
+    }
+}
+
+/// Read the document and find the section
+fn load(target: &SectionTarget) -> Result<(Document, usize), String> {
+    let content = std::fs::read_to_string(&target.file)
+        .map_err(|e| format!("Failed to read {}: {}", target.file.display(), e))?;
+    let doc = parser::parse_markdown(&content);
+    let idx = section::find(&doc, &target.section)?;
+    Ok((doc, idx))
+}
+
+/// Put stdin into the section with `edit`
+fn insert(
+    args: &SectionInsertArgs,
+    edit: fn(&Document, usize, &str) -> String,
+) -> Result<(), String> {
+    let (doc, idx) = load(&args.edit.target)?;
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|e| format!("Failed to read stdin: {}", e))?;
+    if args.shift_levels != 0 {
+        input = section::shift_levels(&input, args.shift_levels)?;
+    }
+    finish(&args.edit, edit(&doc, idx, &input))
+}
+
+/// Print the updated document, or write it back with -i
+fn finish(args: &SectionEditArgs, content: String) -> Result<(), String> {
+    if args.in_place {
+        treemd::edit::write_file_atomic(&args.target.file, &content)
+    } else {
+        print!("{}", content);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Command};
+    use clap::Parser;
+
+    /// Run `treemd section move` on `content` with `-i` and read the file back
+    fn move_section(content: &str, args: &[&str]) -> Result<String, String> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("doc.md");
+        std::fs::write(&path, content).unwrap();
+
+        let path = path.to_str().unwrap();
+        let argv = ["treemd", "section", "move", "-i", path]
+            .into_iter()
+            .chain(args.iter().copied());
+        let Some(Command::Section(command)) = Cli::parse_from(argv).command else {
+            unreachable!()
+        };
+        run(&command)?;
+        Ok(std::fs::read_to_string(path).unwrap())
+    }
+
+    #[test]
+    fn test_move_with_shift_levels() {
+        let doc = "# T\n\n## A\n\nx\n\n### A1\n\ny\n\n## B\n\nb\n\n## C\n\nc\n";
+        assert_eq!(
+            move_section(doc, &["C", "--after", "A", "--shift-levels", "1"]).unwrap(),
+            "# T\n\n## A\n\nx\n\n### A1\n\ny\n\n### C\n\nc\n\n## B\n\nb\n"
+        );
+        assert_eq!(
+            move_section(doc, &["B", "--after", "A", "--shift-levels", "1"]).unwrap(),
+            "# T\n\n## A\n\nx\n\n### A1\n\ny\n\n### B\n\nb\n\n## C\n\nc\n"
+        );
+        assert_eq!(
+            move_section(doc, &["A1", "--after", "B", "--shift-levels", "-1"]).unwrap(),
+            "# T\n\n## A\n\nx\n\n## B\n\nb\n\n## A1\n\ny\n\n## C\n\nc\n"
+        );
+    }
+
+    #[test]
+    fn test_move_next_to_itself_or_parent() {
+        let doc = "# T\n\n## A\n\nx\n\n### A1\n\ny\n\nmore a\n\n## B\n\nb\n";
+        for args in [
+            ["A", "--after", "A"],
+            ["A", "--before", "A1"],
+            ["A1", "--after", "A"],
+            ["A1", "--before", "T"],
+        ] {
+            assert!(move_section(doc, &args).is_err(), "{:?}", args);
+        }
+    }
+}
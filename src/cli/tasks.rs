@@ -0,0 +1,209 @@
+//! The `tasks` subcommand: list task list items and check/uncheck them in place.
+
+use super::{OutputFormat, TasksArgs};
+use std::io::{self, Read};
+use std::path::Path;
+use treemd::parser::tasks::{Priority, Task, extract_tasks, find_task_by_text, set_task_checked};
+use treemd::parser::{self, Document};
+use treemd::section;
+
+/// Run `treemd tasks`
+pub fn run(args: &TasksArgs) -> Result<(), String> {
+    let editing = args.toggle.is_some() || args.check.is_some() || args.uncheck.is_some();
+    let from_stdin = args.file == Path::new("-");
+    if editing && from_stdin {
+        return Err("--toggle, --check and --uncheck need a file, not stdin".to_string());
+    }
+    if let Some(date) = &args.due_by
+        && !is_iso_date(date)
+    {
+        return Err(format!("Invalid date '{}' (expected YYYY-MM-DD)", date));
+    }
+
+    let content = if from_stdin {
+        let mut content = String::new();
+        io::stdin()
+            .read_to_string(&mut content)
+            .map_err(|e| format!("Failed to read stdin: {}", e))?;
+        content
+    } else {
+        std::fs::read_to_string(&args.file)
+            .map_err(|e| format!("Failed to read {}: {}", args.file.display(), e))?
+    };
+
+    let doc = parser::parse_markdown(&content);
+    let tasks = tasks_in_scope(&doc, args.section.as_deref())?;
+
+    if editing {
+        return edit(args, &content, &tasks);
+    }
+
+    let tasks: Vec<Task> = tasks.into_iter().filter(|t| matches(args, t)).collect();
+    print_tasks(&tasks, &args.output);
+    Ok(())
+}
+
+/// All tasks, or those in one section (including its subsections)
+fn tasks_in_scope(doc: &Document, section: Option<&str>) -> Result<Vec<Task>, String> {
+    let tasks = extract_tasks(doc);
+    let Some(section) = section else {
+        return Ok(tasks);
+    };
+
+    let range = section::span(doc, section::find(doc, section)?);
+    Ok(tasks
+        .into_iter()
+        .filter(|t| range.contains(&t.marker_offset))
+        .collect())
+}
+
+/// Whether a task passes the listing filters
+fn matches(args: &TasksArgs, task: &Task) -> bool {
+    if (args.open && task.checked) || (args.done && !task.checked) {
+        return false;
+    }
+    if let Some(date) = &args.due_by
+        && task.due.as_deref().is_none_or(|due| due > date.as_str())
+    {
+        return false;
+    }
+    if let Some(min) = args.priority {
+        // Tasks without a priority rank between low and medium
+        let passes = match task.priority {
+            Some(priority) => priority >= min,
+            None => min <= Priority::Low,
+        };
+        if !passes {
+            return false;
+        }
+    }
+    true
+}
+
+fn print_tasks(tasks: &[Task], format: &OutputFormat) {
+    match format {
+        OutputFormat::Plain => {
+            for task in tasks {
+                println!("{}: {}", task.line, task_line(task));
+            }
+        }
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(tasks).unwrap();
+            println!("{}", json);
+        }
+        OutputFormat::Tree => {
+            let mut current_path: Option<&[String]> = None;
+            for task in tasks {
+                if current_path != Some(task.heading_path.as_slice()) {
+                    if current_path.is_some() {
+                        println!();
+                    }
+                    if task.heading_path.is_empty() {
+                        println!("(no heading)");
+                    } else {
+                        println!("{}", task.heading_path.join(" › "));
+                    }
+                    current_path = Some(task.heading_path.as_slice());
+                }
+                println!("  {}: {}", task.line, task_line(task));
+            }
+        }
+    }
+}
+
+/// A task as a markdown list item, indented by its nesting depth
+fn task_line(task: &Task) -> String {
+    let mark = if task.checked { 'x' } else { ' ' };
+    format!("{}- [{}] {}", "  ".repeat(task.depth), mark, task.text)
+}
+
+/// Apply --toggle / --check / --uncheck and write the file
+fn edit(args: &TasksArgs, content: &str, tasks: &[Task]) -> Result<(), String> {
+    let task = if let Some(line) = args.toggle {
+        tasks
+            .iter()
+            .find(|t| t.line == line)
+            .ok_or_else(|| format!("No task on line {}", line))?
+    } else {
+        let (text, check) = match (&args.check, &args.uncheck) {
+            (Some(text), _) => (text.as_str(), true),
+            (None, Some(text)) => (text.as_str(), false),
+            (None, None) => unreachable!("edit() is only called with an edit flag"),
+        };
+        match find_for_edit(tasks, text, check)? {
+            Some(task) => task,
+            None => {
+                let state = if check { "checked" } else { "unchecked" };
+                println!("Already {}: {}", state, text);
+                return Ok(());
+            }
+        }
+    };
+
+    let checked = !task.checked;
+    let new_content = set_task_checked(content, task, checked);
+    treemd::edit::write_file_atomic(&args.file, &new_content)?;
+
+    let action = if checked { "Checked" } else { "Unchecked" };
+    println!("{} line {}: {}", action, task.line, task.text);
+    Ok(())
+}
+
+/// Find the task to check (or uncheck) by exact text, then by a unique
+/// case-insensitive substring. `Ok(None)` means it is already in that state.
+fn find_for_edit<'a>(
+    tasks: &'a [Task],
+    text: &str,
+    check: bool,
+) -> Result<Option<&'a Task>, String> {
+    if let Some(task) = find_task_by_text(tasks, text, Some(!check), None) {
+        return Ok(Some(task));
+    }
+
+    let needle = text.to_lowercase();
+    let matching: Vec<&Task> = tasks
+        .iter()
+        .filter(|t| t.text.to_lowercase().contains(&needle))
+        .collect();
+    let candidates: Vec<&Task> = matching
+        .iter()
+        .copied()
+        .filter(|t| t.checked != check)
+        .collect();
+
+    match candidates.as_slice() {
+        [task] => Ok(Some(task)),
+        [] if find_task_by_text(tasks, text, Some(check), None).is_some()
+            || !matching.is_empty() =>
+        {
+            Ok(None)
+        }
+        [] => Err(format!(
+            "No {} task matching '{}'",
+            if check { "open" } else { "done" },
+            text
+        )),
+        _ => Err(format!(
+            "'{}' matches {} tasks (lines {}); use --toggle LINE",
+            text,
+            candidates.len(),
+            candidates
+                .iter()
+                .map(|t| t.line.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Whether a string is a `YYYY-MM-DD` date
+fn is_iso_date(date: &str) -> bool {
+    let bytes = date.as_bytes();
+    bytes.len() == 10
+        && bytes[4] == b'-'
+        && bytes[7] == b'-'
+        && bytes
+            .iter()
+            .enumerate()
+            .all(|(i, b)| i == 4 || i == 7 || b.is_ascii_digit())
+}
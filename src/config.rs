@@ -2,6 +2,7 @@ use crate::keybindings::{Keybindings, KeybindingsConfig};
 use crate::tui::theme::ThemeName;
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -18,6 +19,15 @@ pub struct Config {
 
     #[serde(default)]
     pub keybindings: KeybindingsConfig,
+
+    #[serde(default)]
+    pub security: SecurityConfig,
+
+    #[serde(default)]
+    pub exec: ExecConfig,
+
+    #[serde(default)]
+    pub vault: VaultConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,10 +44,76 @@ pub struct TerminalConfig {
     #[serde(default = "default_color_mode")]
     pub color_mode: String,
 
+    /// Image previews: "auto", "kitty", "sixel", "iterm2", "halfblock" or "off"
+    #[serde(default = "default_images")]
+    pub images: String,
+
     #[serde(default)]
     pub warned_terminal_app: bool,
 }
 
+/// Restrictions for viewing untrusted documents
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SecurityConfig {
+    /// Disable all file modification and launching `$EDITOR`
+    #[serde(default)]
+    pub read_only: bool,
+
+    /// Read-only, plus confine link following to the starting directory and
+    /// never open external URLs
+    #[serde(default)]
+    pub safe: bool,
+}
+
+/// Interpreters for running code blocks from interactive mode
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecConfig {
+    /// Command per code block language, e.g. `python = "python3 -u"`.
+    /// The script path is appended. Entries extend and override the defaults.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub interpreters: HashMap<String, String>,
+}
+
+impl ExecConfig {
+    /// Interpreter command (program and arguments) for a code block language
+    pub fn interpreter(&self, language: &str) -> Option<Vec<String>> {
+        let language = language.to_lowercase();
+        let command = self
+            .interpreters
+            .iter()
+            .find(|(lang, _)| lang.to_lowercase() == language)
+            .map(|(_, command)| command.as_str())
+            .or_else(|| default_interpreter(&language))?;
+        let parts: Vec<String> = command.split_whitespace().map(String::from).collect();
+        (!parts.is_empty()).then_some(parts)
+    }
+}
+
+/// Where wikilinks are resolved across notes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VaultConfig {
+    /// Vault folder for documents inside it. Other documents use the nearest
+    /// folder holding `.obsidian/`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+}
+
+fn default_interpreter(language: &str) -> Option<&'static str> {
+    match language {
+        "sh" => Some("sh"),
+        "bash" | "shell" => Some("bash"),
+        "zsh" => Some("zsh"),
+        "fish" => Some("fish"),
+        "python" | "py" | "python3" => Some("python3"),
+        "ruby" | "rb" => Some("ruby"),
+        "node" | "js" | "javascript" => Some("node"),
+        "perl" => Some("perl"),
+        "lua" => Some("lua"),
+        "php" => Some("php"),
+        _ => None,
+    }
+}
+
 /// Custom theme color overrides
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CustomThemeConfig {
@@ -108,6 +184,17 @@ pub struct CustomThemeConfig {
     pub search_current_bg: Option<ColorValue>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub search_current_fg: Option<ColorValue>,
+    // Callouts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callout_note: Option<ColorValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callout_tip: Option<ColorValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callout_important: Option<ColorValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callout_warning: Option<ColorValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callout_caution: Option<ColorValue>,
 }
 
 /// Color value that can be specified in multiple formats
@@ -164,6 +251,7 @@ impl Default for TerminalConfig {
     fn default() -> Self {
         Self {
             color_mode: default_color_mode(),
+            images: default_images(),
             warned_terminal_app: false,
         }
     }
@@ -181,12 +269,21 @@ fn default_color_mode() -> String {
     "auto".to_string()
 }
 
+fn default_images() -> String {
+    "auto".to_string()
+}
+
 impl Config {
     /// Get the config file path (platform-specific)
     pub fn config_path() -> Option<PathBuf> {
         dirs::config_dir().map(|p| p.join("treemd").join("config.toml"))
     }
 
+    /// Get the command palette history file path
+    pub fn history_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|p| p.join("treemd").join("history"))
+    }
+
     /// Load config from file, or return default if file doesn't exist
     pub fn load() -> Self {
         Self::config_path()
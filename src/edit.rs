@@ -0,0 +1,34 @@
+//! Writing edited markdown back to disk.
+//!
+//! Shared by the TUI's in-place edits and the CLI commands that modify files,
+//! so both write the same way.
+
+use std::path::Path;
+
+/// Atomic write: write to temp file, then rename (prevents data corruption)
+pub fn write_file_atomic(path: &Path, content: &str) -> Result<(), String> {
+    use std::io::Write;
+    let parent_dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        Some(_) => Path::new("."),
+        None => return Err("Cannot determine parent directory".to_string()),
+    };
+
+    let mut temp_file = tempfile::NamedTempFile::new_in(parent_dir)
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+
+    temp_file
+        .write_all(content.as_bytes())
+        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+
+    temp_file
+        .flush()
+        .map_err(|e| format!("Failed to flush temp file: {}", e))?;
+
+    // Atomic rename (same filesystem guarantees atomicity)
+    temp_file
+        .persist(path)
+        .map_err(|e| format!("Failed to save file: {}", e))?;
+
+    Ok(())
+}
@@ -0,0 +1,311 @@
+//! Link graph of the notes in a vault.
+//!
+//! Edges are the wikilinks, embeds and relative markdown links between notes,
+//! resolved the way the TUI follows them. Links to notes that don't exist
+//! are kept as unresolved, next to the two other kinds of loose ends: orphans
+//! (no links in or out) and dead ends (linked to, but linking nowhere).
+
+use crate::parser::embeds::Embed;
+use crate::parser::{LinkTarget, extract_links};
+use crate::vault::{Vault, is_note_extension, normalize_path};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Notes and the links between them
+#[derive(Debug, Clone)]
+pub struct LinkGraph {
+    root: PathBuf,
+    /// Notes relative to the root
+    notes: Vec<PathBuf>,
+    /// Links as (source, target) note indices; no duplicates or self links
+    links: Vec<(usize, usize)>,
+    /// Links to missing notes: source index and the target as written
+    unresolved: Vec<(usize, String)>,
+}
+
+/// Where a link in a note points
+enum NoteLink {
+    /// Wikilink or embed file part
+    Wiki(String),
+    /// Relative markdown link path
+    File(PathBuf),
+}
+
+impl LinkGraph {
+    /// Read every note in the vault and resolve its links
+    pub fn build(vault: &Vault) -> Self {
+        let root = vault.root().to_path_buf();
+        let notes: Vec<PathBuf> = vault.notes().iter().map(|note| note.path.clone()).collect();
+        let index: HashMap<&Path, usize> = notes
+            .iter()
+            .enumerate()
+            .map(|(idx, path)| (path.as_path(), idx))
+            .collect();
+
+        let mut links = Vec::new();
+        let mut unresolved = Vec::new();
+        let mut seen = HashSet::new();
+        for (source, note) in notes.iter().enumerate() {
+            let path = root.join(note);
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            for link in note_links(&content) {
+                let (target, written) = match link {
+                    NoteLink::Wiki(file) => {
+                        let target = vault
+                            .resolve(&file, &path)
+                            .first()
+                            .and_then(|found| found.strip_prefix(&root).ok())
+                            .and_then(|found| index.get(found).copied());
+                        (target, format!("[[{}]]", file))
+                    }
+                    NoteLink::File(file) => {
+                        let target = path
+                            .parent()
+                            .and_then(|dir| normalize_path(&dir.join(&file)))
+                            .and_then(|found| {
+                                let found = found.strip_prefix(&root).ok()?;
+                                index.get(found).copied()
+                            });
+                        (target, file.display().to_string())
+                    }
+                };
+                match target {
+                    Some(target) if target != source => {
+                        if seen.insert((source, target)) {
+                            links.push((source, target));
+                        }
+                    }
+                    Some(_) => {}
+                    None => {
+                        let link = (source, written);
+                        if !unresolved.contains(&link) {
+                            unresolved.push(link);
+                        }
+                    }
+                }
+            }
+        }
+
+        Self {
+            root,
+            notes,
+            links,
+            unresolved,
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Notes relative to the root
+    pub fn notes(&self) -> &[PathBuf] {
+        &self.notes
+    }
+
+    pub fn links(&self) -> &[(usize, usize)] {
+        &self.links
+    }
+
+    pub fn unresolved(&self) -> &[(usize, String)] {
+        &self.unresolved
+    }
+
+    /// Index of a note, by absolute path or path relative to the root
+    pub fn index_of(&self, path: &Path) -> Option<usize> {
+        let canonical = path.canonicalize().ok();
+        let relative = canonical
+            .as_deref()
+            .and_then(|path| path.strip_prefix(&self.root).ok())
+            .unwrap_or(path);
+        self.notes.iter().position(|note| note == relative)
+    }
+
+    /// Notes `note` links to
+    pub fn outgoing(&self, note: usize) -> Vec<usize> {
+        self.links
+            .iter()
+            .filter(|(from, _)| *from == note)
+            .map(|&(_, to)| to)
+            .collect()
+    }
+
+    /// Notes linking to `note`
+    pub fn incoming(&self, note: usize) -> Vec<usize> {
+        self.links
+            .iter()
+            .filter(|(_, to)| *to == note)
+            .map(|&(from, _)| from)
+            .collect()
+    }
+
+    /// Unresolved link targets in `note`
+    pub fn unresolved_from(&self, note: usize) -> Vec<&str> {
+        self.unresolved
+            .iter()
+            .filter(|(from, _)| *from == note)
+            .map(|(_, target)| target.as_str())
+            .collect()
+    }
+
+    /// Notes with no links to or from other notes
+    pub fn orphans(&self) -> Vec<usize> {
+        (0..self.notes.len())
+            .filter(|&note| {
+                !self
+                    .links
+                    .iter()
+                    .any(|&(from, to)| from == note || to == note)
+            })
+            .collect()
+    }
+
+    /// Notes that are linked to but don't link to any other note
+    pub fn dead_ends(&self) -> Vec<usize> {
+        (0..self.notes.len())
+            .filter(|&note| {
+                self.links.iter().any(|&(_, to)| to == note)
+                    && !self.links.iter().any(|&(from, _)| from == note)
+            })
+            .collect()
+    }
+
+    /// Graphviz DOT source. Unresolved targets are dashed nodes.
+    pub fn to_dot(&self) -> String {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let name = |idx: usize| quote(&self.notes[idx].display().to_string());
+
+        let mut dot = String::from("digraph notes {\n    node [shape=box];\n");
+        for idx in 0..self.notes.len() {
+            dot.push_str(&format!("    {};\n", name(idx)));
+        }
+        for &(from, to) in &self.links {
+            dot.push_str(&format!("    {} -> {};\n", name(from), name(to)));
+        }
+        for (from, target) in &self.unresolved {
+            dot.push_str(&format!(
+                "    {} -> {} [style=dashed];\n    {} [style=dashed];\n",
+                name(*from),
+                quote(target),
+                quote(target)
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Notes with their links, plus the loose ends
+    pub fn to_json(&self) -> serde_json::Value {
+        let name = |idx: usize| self.notes[idx].display().to_string();
+        let names = |notes: Vec<usize>| notes.into_iter().map(name).collect::<Vec<_>>();
+        serde_json::json!({
+            "root": self.root.display().to_string(),
+            "notes": (0..self.notes.len())
+                .map(|idx| serde_json::json!({
+                    "path": name(idx),
+                    "outgoing": names(self.outgoing(idx)),
+                    "incoming": names(self.incoming(idx)),
+                }))
+                .collect::<Vec<_>>(),
+            "links": self
+                .links
+                .iter()
+                .map(|&(from, to)| serde_json::json!({ "from": name(from), "to": name(to) }))
+                .collect::<Vec<_>>(),
+            "unresolved": self
+                .unresolved
+                .iter()
+                .map(|(from, target)| serde_json::json!({ "from": name(*from), "target": target }))
+                .collect::<Vec<_>>(),
+            "orphans": names(self.orphans()),
+            "dead_ends": names(self.dead_ends()),
+        })
+    }
+}
+
+/// Links in a note that can point at another note
+fn note_links(content: &str) -> Vec<NoteLink> {
+    let mut links: Vec<NoteLink> = extract_links(content)
+        .into_iter()
+        .filter_map(|link| match link.target {
+            LinkTarget::WikiLink { target, .. } => {
+                let file = target.split_once('#').map_or(target.as_str(), |(f, _)| f);
+                (!file.trim().is_empty()).then(|| NoteLink::Wiki(file.trim().to_string()))
+            }
+            LinkTarget::RelativeFile { path, .. } => {
+                let is_note = path.extension().is_none_or(is_note_extension);
+                is_note.then(|| match path.extension() {
+                    Some(_) => NoteLink::File(path),
+                    None => NoteLink::File(path.with_extension("md")),
+                })
+            }
+            _ => None,
+        })
+        .collect();
+
+    links.extend(
+        turbovault_parser::parse_embeds(content)
+            .into_iter()
+            .filter_map(|embed| {
+                let embed = Embed::parse(&embed.target);
+                (!embed.file.is_empty() && !embed.is_image())
+                    .then(|| NoteLink::Wiki(embed.file.to_string()))
+            }),
+    );
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_build_graph() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("projects")).unwrap();
+        fs::write(
+            root.join("index.md"),
+            "# Index\n\n[[Design]] and [plan](projects/plan.md), [[design#Goals]] again.\n\n![[missing]] ![[chart.png]]\n\n```\n[[not a link]]\n```\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("projects/Design.md"),
+            "See [back](../index.md), [[#Goals]].",
+        )
+        .unwrap();
+        fs::write(root.join("projects/plan.md"), "# Plan\n").unwrap();
+        fs::write(
+            root.join("lonely.md"),
+            "No links, [site](https://example.com).",
+        )
+        .unwrap();
+
+        let graph = LinkGraph::build(&Vault::open(root.clone()));
+        let names = |notes: Vec<usize>| {
+            notes
+                .into_iter()
+                .map(|idx| graph.notes()[idx].display().to_string())
+                .collect::<Vec<_>>()
+        };
+        let index = graph.index_of(&root.join("index.md")).unwrap();
+        assert_eq!(
+            names(graph.outgoing(index)),
+            vec!["projects/Design.md", "projects/plan.md"]
+        );
+        assert_eq!(names(graph.incoming(index)), vec!["projects/Design.md"]);
+        assert_eq!(graph.unresolved_from(index), vec!["[[missing]]"]);
+        assert_eq!(names(graph.orphans()), vec!["lonely.md"]);
+        assert_eq!(names(graph.dead_ends()), vec!["projects/plan.md"]);
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("\"index.md\" -> \"projects/plan.md\";"));
+        assert!(dot.contains("\"[[missing]]\" [style=dashed];"));
+        let json = graph.to_json();
+        assert_eq!(json["dead_ends"][0], "projects/plan.md");
+        assert_eq!(json["notes"].as_array().unwrap().len(), 4);
+    }
+}
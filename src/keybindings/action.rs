@@ -93,6 +93,46 @@ pub enum Action {
     InteractiveLeft,
     /// Navigate right in table
     InteractiveRight,
+    /// Run the selected code block (or stop it while running)
+    RunCodeBlock,
+    /// Collapse/expand the selected code block's output
+    ToggleCodeOutput,
+    /// Switch the selected Mermaid block between diagram and source
+    ToggleDiagramSource,
+    /// Edit the selected code block in $EDITOR
+    EditCodeBlock,
+    /// Sort table by the selected column (ascending, descending, off)
+    TableSort,
+    /// Filter table rows by typed text
+    TableFilter,
+    /// Scroll table columns left
+    TableScrollLeft,
+    /// Scroll table columns right
+    TableScrollRight,
+    /// Toggle wrapping of long table cells
+    TableToggleWrap,
+    /// Insert an empty table row below the selection
+    TableInsertRowBelow,
+    /// Insert an empty table row above the selection
+    TableInsertRowAbove,
+    /// Delete the selected table row
+    TableDeleteRow,
+    /// Move the selected table row down
+    TableMoveRowDown,
+    /// Move the selected table row up
+    TableMoveRowUp,
+    /// Insert a table column right of the selection
+    TableInsertColumnRight,
+    /// Insert a table column left of the selection
+    TableInsertColumnLeft,
+    /// Delete the selected table column
+    TableDeleteColumn,
+    /// Move the selected table column left
+    TableMoveColumnLeft,
+    /// Move the selected table column right
+    TableMoveColumnRight,
+    /// Cycle the selected column's alignment (left, center, right, default)
+    TableCycleAlignment,
 
     // === View ===
     /// Toggle raw markdown source view
@@ -103,12 +143,26 @@ pub enum Action {
     ToggleThemePicker,
     /// Apply selected theme (in theme picker)
     ApplyTheme,
+    /// Toggle the task list popup
+    ToggleTaskList,
+    /// Toggle inline previews of local images
+    ToggleImages,
+    /// Toggle the tag browser popup
+    ToggleTagBrowser,
+    /// Toggle the link graph popup
+    ToggleGraphView,
 
     // === Clipboard ===
     /// Copy current section content
     CopyContent,
     /// Copy anchor/heading text
     CopyAnchor,
+    /// Copy the selected table as CSV
+    CopyTableCsv,
+    /// Copy the selected table as TSV
+    CopyTableTsv,
+    /// Replace the selected table with CSV/TSV from the clipboard
+    ImportTable,
 
     // === File Operations ===
     /// Navigate back in file history
@@ -117,12 +171,38 @@ pub enum Action {
     GoForward,
     /// Open current file in external editor
     OpenInEditor,
+    /// Rename the selected heading, fixing links to it
+    RenameHeading,
+
+    // === Outline Editing ===
+    /// Swap the selected section with the one above it
+    SectionMoveUp,
+    /// Swap the selected section with the one below it
+    SectionMoveDown,
+    /// Raise the selected section and its subsections one level
+    SectionPromote,
+    /// Lower the selected section and its subsections one level
+    SectionDemote,
+    /// Delete the selected section (asks first)
+    SectionDelete,
+    /// Move the selected section's body to a new file
+    SectionExtract,
+    /// Undo the last checkbox toggle or cell edit
+    UndoEdit,
+    /// Redo the last undone edit
+    RedoEdit,
 
     // === Dialog Actions ===
     /// Confirm action in dialog
     ConfirmAction,
     /// Cancel action in dialog
     CancelAction,
+    /// Reload the file, discarding the conflicting edit
+    ConflictReload,
+    /// Write the edit over external changes
+    ConflictOverwrite,
+    /// Merge the edited line into the externally changed file
+    ConflictMerge,
 
     // === Application ===
     /// Quit the application
@@ -168,6 +248,50 @@ pub enum Action {
     /// Move to previous theme in picker
     ThemePickerPrevious,
 
+    // === Task List ===
+    /// Move to next task in the task list
+    TaskListNext,
+    /// Move to previous task in the task list
+    TaskListPrevious,
+    /// Check or uncheck the selected task and save
+    TaskListToggle,
+    /// Jump to the heading of the selected task
+    TaskListJump,
+    /// Show or hide completed tasks in the task list
+    TaskListToggleDone,
+
+    // === Note Picker ===
+    /// Move to next note in the note picker
+    NotePickerNext,
+    /// Move to previous note in the note picker
+    NotePickerPrevious,
+
+    // === Tag Browser ===
+    /// Move to next tag (or location) in the tag browser
+    TagBrowserNext,
+    /// Move to previous tag (or location) in the tag browser
+    TagBrowserPrevious,
+    /// List the selected tag's locations, or jump to the selected location
+    TagBrowserOpen,
+    /// Go back from a tag's locations to the tag list
+    TagBrowserBack,
+    /// Switch the tag browser between the document and the workspace
+    TagBrowserToggleScope,
+
+    // === Link Graph ===
+    /// Move to next note in the link graph
+    GraphNext,
+    /// Move to previous note in the link graph
+    GraphPrevious,
+    /// Open the selected note in the link graph
+    GraphOpen,
+    /// Redraw the link graph around the selected note
+    GraphRecenter,
+    /// Follow links one step further in the link graph
+    GraphDeeper,
+    /// Follow links one step less in the link graph
+    GraphShallower,
+
     // === Search Input ===
     /// Delete last character in search
     SearchBackspace,
@@ -179,6 +303,12 @@ pub enum Action {
     CommandPaletteNext,
     /// Navigate to previous command
     CommandPalettePrev,
+    /// Complete command name or argument
+    CommandPaletteComplete,
+    /// Recall previous command from history
+    CommandHistoryPrev,
+    /// Recall next command from history
+    CommandHistoryNext,
 
     // === Doc Search Navigation ===
     /// Next search match
@@ -237,25 +367,64 @@ impl Action {
             Action::InteractivePreviousLink => "Previous link in element",
             Action::InteractiveLeft => "Navigate left (table)",
             Action::InteractiveRight => "Navigate right (table)",
+            Action::RunCodeBlock => "Run code block (or stop it)",
+            Action::ToggleCodeOutput => "Collapse/expand code output",
+            Action::ToggleDiagramSource => "Toggle diagram/source view",
+            Action::EditCodeBlock => "Edit code block in $EDITOR",
+            Action::TableSort => "Sort table by column",
+            Action::TableFilter => "Filter table rows",
+            Action::TableScrollLeft => "Scroll table columns left",
+            Action::TableScrollRight => "Scroll table columns right",
+            Action::TableToggleWrap => "Toggle table cell wrapping",
+            Action::TableInsertRowBelow => "Add table row below",
+            Action::TableInsertRowAbove => "Add table row above",
+            Action::TableDeleteRow => "Delete table row",
+            Action::TableMoveRowDown => "Move table row down",
+            Action::TableMoveRowUp => "Move table row up",
+            Action::TableInsertColumnRight => "Add table column right",
+            Action::TableInsertColumnLeft => "Add table column left",
+            Action::TableDeleteColumn => "Delete table column",
+            Action::TableMoveColumnLeft => "Move table column left",
+            Action::TableMoveColumnRight => "Move table column right",
+            Action::TableCycleAlignment => "Cycle table column alignment",
 
             // View
             Action::ToggleRawSource => "Toggle raw source view",
             Action::ToggleHelp => "Toggle help",
             Action::ToggleThemePicker => "Open theme picker",
             Action::ApplyTheme => "Apply selected theme",
+            Action::ToggleTaskList => "Open task list",
+            Action::ToggleImages => "Toggle image previews",
+            Action::ToggleTagBrowser => "Open tag browser",
+            Action::ToggleGraphView => "Open link graph",
 
             // Clipboard
             Action::CopyContent => "Copy content",
             Action::CopyAnchor => "Copy heading/anchor",
+            Action::CopyTableCsv => "Copy table as CSV",
+            Action::CopyTableTsv => "Copy table as TSV",
+            Action::ImportTable => "Import table from clipboard (CSV/TSV)",
 
             // File operations
             Action::GoBack => "Go back",
             Action::GoForward => "Go forward",
             Action::OpenInEditor => "Open in editor",
+            Action::RenameHeading => "Rename heading and fix links",
+            Action::SectionMoveUp => "Move section up",
+            Action::SectionMoveDown => "Move section down",
+            Action::SectionPromote => "Promote section",
+            Action::SectionDemote => "Demote section",
+            Action::SectionDelete => "Delete section",
+            Action::SectionExtract => "Extract section to a new file",
+            Action::UndoEdit => "Undo last edit",
+            Action::RedoEdit => "Redo last edit",
 
             // Dialog
             Action::ConfirmAction => "Confirm",
             Action::CancelAction => "Cancel",
+            Action::ConflictReload => "Reload file (discard edit)",
+            Action::ConflictOverwrite => "Overwrite external changes",
+            Action::ConflictMerge => "Merge edit into external changes",
 
             // Application
             Action::Quit => "Quit",
@@ -294,6 +463,32 @@ impl Action {
             Action::ThemePickerNext => "Next theme",
             Action::ThemePickerPrevious => "Previous theme",
 
+            // Task list
+            Action::TaskListNext => "Next task",
+            Action::TaskListPrevious => "Previous task",
+            Action::TaskListToggle => "Check/uncheck task",
+            Action::TaskListJump => "Jump to task's section",
+            Action::TaskListToggleDone => "Show/hide completed tasks",
+
+            // Note picker
+            Action::NotePickerNext => "Next note",
+            Action::NotePickerPrevious => "Previous note",
+
+            // Tag browser
+            Action::TagBrowserNext => "Next tag",
+            Action::TagBrowserPrevious => "Previous tag",
+            Action::TagBrowserOpen => "Show tag's locations / jump to location",
+            Action::TagBrowserBack => "Back to tag list",
+            Action::TagBrowserToggleScope => "Switch between document and workspace tags",
+
+            // Link graph
+            Action::GraphNext => "Next note",
+            Action::GraphPrevious => "Previous note",
+            Action::GraphOpen => "Open selected note",
+            Action::GraphRecenter => "Center graph on selected note",
+            Action::GraphDeeper => "Show more levels of links",
+            Action::GraphShallower => "Show fewer levels of links",
+
             // Search
             Action::SearchBackspace => "Delete character",
 
@@ -301,6 +496,9 @@ impl Action {
             Action::OpenCommandPalette => "Open command palette",
             Action::CommandPaletteNext => "Next command",
             Action::CommandPalettePrev => "Previous command",
+            Action::CommandPaletteComplete => "Complete command",
+            Action::CommandHistoryPrev => "Previous command in history",
+            Action::CommandHistoryNext => "Next command in history",
 
             // Doc search
             Action::NextMatch => "Next search match",
@@ -348,18 +546,62 @@ impl Action {
             | Action::InteractiveNextLink
             | Action::InteractivePreviousLink
             | Action::InteractiveLeft
-            | Action::InteractiveRight => "Interactive",
+            | Action::InteractiveRight
+            | Action::RunCodeBlock
+            | Action::ToggleCodeOutput
+            | Action::ToggleDiagramSource
+            | Action::EditCodeBlock
+            | Action::TableSort
+            | Action::TableFilter
+            | Action::TableScrollLeft
+            | Action::TableScrollRight
+            | Action::TableToggleWrap
+            | Action::TableInsertRowBelow
+            | Action::TableInsertRowAbove
+            | Action::TableDeleteRow
+            | Action::TableMoveRowDown
+            | Action::TableMoveRowUp
+            | Action::TableInsertColumnRight
+            | Action::TableInsertColumnLeft
+            | Action::TableDeleteColumn
+            | Action::TableMoveColumnLeft
+            | Action::TableMoveColumnRight
+            | Action::TableCycleAlignment => "Interactive",
 
             Action::ToggleRawSource
             | Action::ToggleHelp
             | Action::ToggleThemePicker
-            | Action::ApplyTheme => "View",
-
-            Action::CopyContent | Action::CopyAnchor => "Clipboard",
-
-            Action::GoBack | Action::GoForward | Action::OpenInEditor => "Files",
-
-            Action::ConfirmAction | Action::CancelAction => "Dialog",
+            | Action::ApplyTheme
+            | Action::ToggleTaskList
+            | Action::ToggleImages
+            | Action::ToggleTagBrowser
+            | Action::ToggleGraphView => "View",
+
+            Action::CopyContent
+            | Action::CopyAnchor
+            | Action::CopyTableCsv
+            | Action::CopyTableTsv
+            | Action::ImportTable => "Clipboard",
+
+            Action::GoBack
+            | Action::GoForward
+            | Action::OpenInEditor
+            | Action::RenameHeading
+            | Action::UndoEdit
+            | Action::RedoEdit => "Files",
+
+            Action::SectionMoveUp
+            | Action::SectionMoveDown
+            | Action::SectionPromote
+            | Action::SectionDemote
+            | Action::SectionDelete
+            | Action::SectionExtract => "Outline Editing",
+
+            Action::ConfirmAction
+            | Action::CancelAction
+            | Action::ConflictReload
+            | Action::ConflictOverwrite
+            | Action::ConflictMerge => "Dialog",
 
             Action::Quit => "Application",
 
@@ -389,11 +631,35 @@ impl Action {
 
             Action::ThemePickerNext | Action::ThemePickerPrevious => "Theme Picker",
 
+            Action::TaskListNext
+            | Action::TaskListPrevious
+            | Action::TaskListToggle
+            | Action::TaskListJump
+            | Action::TaskListToggleDone => "Task List",
+
+            Action::NotePickerNext | Action::NotePickerPrevious => "Note Picker",
+
+            Action::TagBrowserNext
+            | Action::TagBrowserPrevious
+            | Action::TagBrowserOpen
+            | Action::TagBrowserBack
+            | Action::TagBrowserToggleScope => "Tag Browser",
+
+            Action::GraphNext
+            | Action::GraphPrevious
+            | Action::GraphOpen
+            | Action::GraphRecenter
+            | Action::GraphDeeper
+            | Action::GraphShallower => "Link Graph",
+
             Action::SearchBackspace | Action::NextMatch | Action::PrevMatch => "Search",
 
             Action::OpenCommandPalette
             | Action::CommandPaletteNext
-            | Action::CommandPalettePrev => "Command Palette",
+            | Action::CommandPalettePrev
+            | Action::CommandPaletteComplete
+            | Action::CommandHistoryPrev
+            | Action::CommandHistoryNext => "Command Palette",
         }
     }
 }
@@ -18,6 +18,18 @@ pub fn default_keybindings() -> Keybindings {
     // Theme picker mode
     add_theme_picker_mode(&mut kb);
 
+    // Task list mode
+    add_task_list_mode(&mut kb);
+
+    // Note picker mode
+    add_note_picker_mode(&mut kb);
+
+    // Tag browser mode
+    add_tag_browser_mode(&mut kb);
+
+    // Link graph mode
+    add_graph_view_mode(&mut kb);
+
     // Interactive mode
     add_interactive_mode(&mut kb);
 
@@ -42,9 +54,15 @@ pub fn default_keybindings() -> Keybindings {
     // Confirm dialog mode
     add_confirm_dialog_mode(&mut kb);
 
+    // Conflict dialog mode
+    add_conflict_dialog_mode(&mut kb);
+
     // Cell edit mode
     add_cell_edit_mode(&mut kb);
 
+    // Table filter input mode
+    add_table_filter_mode(&mut kb);
+
     kb
 }
 
@@ -96,6 +114,10 @@ fn add_normal_mode(kb: &mut Keybindings) {
     // View
     bind(kb, Normal, "r", ToggleRawSource);
     bind(kb, Normal, "t", ToggleThemePicker);
+    bind(kb, Normal, "T", ToggleTaskList);
+    bind(kb, Normal, "I", ToggleImages);
+    bind(kb, Normal, "#", ToggleTagBrowser);
+    bind(kb, Normal, "L", ToggleGraphView);
     bind(kb, Normal, "?", ToggleHelp);
 
     // Clipboard
@@ -107,6 +129,15 @@ fn add_normal_mode(kb: &mut Keybindings) {
     bind(kb, Normal, "Backspace", GoBack);
     bind(kb, Normal, "F", GoForward);
     bind(kb, Normal, "e", OpenInEditor);
+    bind(kb, Normal, "R", RenameHeading);
+    bind(kb, Normal, "K", SectionMoveUp);
+    bind(kb, Normal, "J", SectionMoveDown);
+    bind(kb, Normal, "<", SectionPromote);
+    bind(kb, Normal, ">", SectionDemote);
+    bind(kb, Normal, "D", SectionDelete);
+    bind(kb, Normal, "X", SectionExtract);
+    bind(kb, Normal, "U", UndoEdit);
+    bind(kb, Normal, "Ctrl+r", RedoEdit);
 
     // Application
     bind(kb, Normal, "q", Quit);
@@ -176,6 +207,101 @@ fn add_theme_picker_mode(kb: &mut Keybindings) {
     bind(kb, ThemePicker, "q", Quit);
 }
 
+fn add_task_list_mode(kb: &mut Keybindings) {
+    use Action::*;
+    use KeybindingMode::TaskList;
+
+    // Navigation
+    bind(kb, TaskList, "j", TaskListNext);
+    bind(kb, TaskList, "Down", TaskListNext);
+    bind(kb, TaskList, "k", TaskListPrevious);
+    bind(kb, TaskList, "Up", TaskListPrevious);
+
+    // Actions
+    bind(kb, TaskList, "Space", TaskListToggle);
+    bind(kb, TaskList, "x", TaskListToggle);
+    bind(kb, TaskList, "Enter", TaskListJump);
+    bind(kb, TaskList, "a", TaskListToggleDone);
+    bind(kb, TaskList, "u", UndoEdit);
+    bind(kb, TaskList, "Ctrl+r", RedoEdit);
+
+    // Close
+    bind(kb, TaskList, "Escape", ToggleTaskList);
+    bind(kb, TaskList, "T", ToggleTaskList);
+
+    // Quit
+    bind(kb, TaskList, "q", Quit);
+}
+
+fn add_note_picker_mode(kb: &mut Keybindings) {
+    use Action::*;
+    use KeybindingMode::NotePicker;
+
+    // Navigation
+    bind(kb, NotePicker, "j", NotePickerNext);
+    bind(kb, NotePicker, "Down", NotePickerNext);
+    bind(kb, NotePicker, "k", NotePickerPrevious);
+    bind(kb, NotePicker, "Up", NotePickerPrevious);
+
+    // Open the selected note
+    bind(kb, NotePicker, "Enter", ConfirmAction);
+
+    // Close
+    bind(kb, NotePicker, "Escape", CancelAction);
+    bind(kb, NotePicker, "q", CancelAction);
+}
+
+fn add_tag_browser_mode(kb: &mut Keybindings) {
+    use Action::*;
+    use KeybindingMode::TagBrowser;
+
+    // Navigation
+    bind(kb, TagBrowser, "j", TagBrowserNext);
+    bind(kb, TagBrowser, "Down", TagBrowserNext);
+    bind(kb, TagBrowser, "k", TagBrowserPrevious);
+    bind(kb, TagBrowser, "Up", TagBrowserPrevious);
+
+    // Actions
+    bind(kb, TagBrowser, "Enter", TagBrowserOpen);
+    bind(kb, TagBrowser, "l", TagBrowserOpen);
+    bind(kb, TagBrowser, "Backspace", TagBrowserBack);
+    bind(kb, TagBrowser, "h", TagBrowserBack);
+    bind(kb, TagBrowser, "Tab", TagBrowserToggleScope);
+
+    // Close
+    bind(kb, TagBrowser, "Escape", ToggleTagBrowser);
+    bind(kb, TagBrowser, "#", ToggleTagBrowser);
+
+    // Quit
+    bind(kb, TagBrowser, "q", Quit);
+}
+
+fn add_graph_view_mode(kb: &mut Keybindings) {
+    use Action::*;
+    use KeybindingMode::GraphView;
+
+    // Navigation
+    bind(kb, GraphView, "j", GraphNext);
+    bind(kb, GraphView, "Down", GraphNext);
+    bind(kb, GraphView, "k", GraphPrevious);
+    bind(kb, GraphView, "Up", GraphPrevious);
+
+    // Actions
+    bind(kb, GraphView, "Enter", GraphOpen);
+    bind(kb, GraphView, "l", GraphRecenter);
+    bind(kb, GraphView, "Right", GraphRecenter);
+    bind(kb, GraphView, "Plus", GraphDeeper);
+    bind(kb, GraphView, "=", GraphDeeper);
+    bind(kb, GraphView, "-", GraphShallower);
+
+    // Close
+    bind(kb, GraphView, "Escape", ToggleGraphView);
+    bind(kb, GraphView, "L", ToggleGraphView);
+
+    // Quit
+    bind(kb, GraphView, "q", Quit);
+}
+
 fn add_interactive_mode(kb: &mut Keybindings) {
     use Action::*;
     use KeybindingMode::Interactive;
@@ -201,9 +327,13 @@ fn add_interactive_mode(kb: &mut Keybindings) {
     // Page navigation
     bind(kb, Interactive, "d", PageDown);
     bind(kb, Interactive, "PageDown", PageDown);
-    bind(kb, Interactive, "u", PageUp);
+    bind(kb, Interactive, "Ctrl+u", PageUp);
     bind(kb, Interactive, "PageUp", PageUp);
 
+    // Undo/redo checkbox toggles and cell edits
+    bind(kb, Interactive, "u", UndoEdit);
+    bind(kb, Interactive, "Ctrl+r", RedoEdit);
+
     // Document search from interactive mode
     bind(kb, Interactive, "/", EnterDocSearch);
 
@@ -214,6 +344,12 @@ fn add_interactive_mode(kb: &mut Keybindings) {
     // Clipboard
     bind(kb, Interactive, "y", CopyContent);
 
+    // Code blocks
+    bind(kb, Interactive, "x", RunCodeBlock);
+    bind(kb, Interactive, "o", ToggleCodeOutput);
+    bind(kb, Interactive, "e", EditCodeBlock);
+    bind(kb, Interactive, "v", ToggleDiagramSource);
+
     // Quit
     bind(kb, Interactive, "q", Quit);
 }
@@ -234,10 +370,36 @@ fn add_interactive_table_mode(kb: &mut Keybindings) {
     bind(kb, InteractiveTable, "Down", InteractiveNext);
     bind(kb, InteractiveTable, "k", InteractivePrevious);
     bind(kb, InteractiveTable, "Up", InteractivePrevious);
+    bind(kb, InteractiveTable, "H", TableScrollLeft);
+    bind(kb, InteractiveTable, "L", TableScrollRight);
+
+    // View (sort, filter, wrap) - never modifies the file
+    bind(kb, InteractiveTable, "s", TableSort);
+    bind(kb, InteractiveTable, "/", TableFilter);
+    bind(kb, InteractiveTable, "w", TableToggleWrap);
+
+    // Structure (rows, columns, alignment) - rewrites the table in the file
+    bind(kb, InteractiveTable, "o", TableInsertRowBelow);
+    bind(kb, InteractiveTable, "O", TableInsertRowAbove);
+    bind(kb, InteractiveTable, "D", TableDeleteRow);
+    bind(kb, InteractiveTable, "J", TableMoveRowDown);
+    bind(kb, InteractiveTable, "K", TableMoveRowUp);
+    bind(kb, InteractiveTable, "a", TableInsertColumnRight);
+    bind(kb, InteractiveTable, "A", TableInsertColumnLeft);
+    bind(kb, InteractiveTable, "X", TableDeleteColumn);
+    bind(kb, InteractiveTable, "<", TableMoveColumnLeft);
+    bind(kb, InteractiveTable, ">", TableMoveColumnRight);
+    bind(kb, InteractiveTable, "=", TableCycleAlignment);
 
     // Clipboard
     bind(kb, InteractiveTable, "y", CopyContent);
     bind(kb, InteractiveTable, "Y", CopyAnchor);
+    bind(kb, InteractiveTable, "c", CopyTableCsv);
+    bind(kb, InteractiveTable, "t", CopyTableTsv);
+    bind(kb, InteractiveTable, "I", ImportTable);
+
+    // Command line (`:import` replaces this table; other commands leave table mode)
+    bind(kb, InteractiveTable, ":", OpenCommandPalette);
 
     // View toggle
     bind(kb, InteractiveTable, "r", ToggleRawSource);
@@ -245,6 +407,10 @@ fn add_interactive_table_mode(kb: &mut Keybindings) {
     // Activate (follow link or edit cell)
     bind(kb, InteractiveTable, "Enter", InteractiveActivate);
 
+    // Undo/redo cell edits
+    bind(kb, InteractiveTable, "u", UndoEdit);
+    bind(kb, InteractiveTable, "Ctrl+r", RedoEdit);
+
     // Quit
     bind(kb, InteractiveTable, "q", Quit);
 }
@@ -360,10 +526,14 @@ fn add_command_palette_mode(kb: &mut Keybindings) {
 
     // Navigation
     bind(kb, CommandPalette, "Down", CommandPaletteNext);
-    bind(kb, CommandPalette, "Tab", CommandPaletteNext);
     bind(kb, CommandPalette, "Up", CommandPalettePrev);
     bind(kb, CommandPalette, "Shift+Tab", CommandPalettePrev);
 
+    // Completion and history
+    bind(kb, CommandPalette, "Tab", CommandPaletteComplete);
+    bind(kb, CommandPalette, "Ctrl+p", CommandHistoryPrev);
+    bind(kb, CommandPalette, "Ctrl+n", CommandHistoryNext);
+
     // Delete character
     bind(kb, CommandPalette, "Backspace", SearchBackspace);
 }
@@ -383,6 +553,20 @@ fn add_confirm_dialog_mode(kb: &mut Keybindings) {
     bind(kb, ConfirmDialog, "Escape", CancelAction);
 }
 
+fn add_conflict_dialog_mode(kb: &mut Keybindings) {
+    use Action::*;
+    use KeybindingMode::ConflictDialog;
+
+    // Resolve
+    bind(kb, ConflictDialog, "r", ConflictReload);
+    bind(kb, ConflictDialog, "o", ConflictOverwrite);
+    bind(kb, ConflictDialog, "m", ConflictMerge);
+
+    // Cancel
+    bind(kb, ConflictDialog, "n", CancelAction);
+    bind(kb, ConflictDialog, "Escape", CancelAction);
+}
+
 fn add_cell_edit_mode(kb: &mut Keybindings) {
     use Action::*;
     use KeybindingMode::CellEdit;
@@ -397,6 +581,20 @@ fn add_cell_edit_mode(kb: &mut Keybindings) {
     bind(kb, CellEdit, "Backspace", SearchBackspace);
 }
 
+fn add_table_filter_mode(kb: &mut Keybindings) {
+    use Action::*;
+    use KeybindingMode::TableFilter;
+
+    // Clear the filter
+    bind(kb, TableFilter, "Escape", CancelAction);
+
+    // Keep the filter and go back to navigating
+    bind(kb, TableFilter, "Enter", ConfirmAction);
+
+    // Delete character
+    bind(kb, TableFilter, "Backspace", SearchBackspace);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -464,6 +662,20 @@ mod tests {
             ),
             Some(Action::InteractiveNextLink)
         );
+        assert_eq!(
+            kb.dispatch(
+                KeybindingMode::Interactive,
+                make_key_event(KeyCode::Char('u'), KeyModifiers::NONE)
+            ),
+            Some(Action::UndoEdit)
+        );
+        assert_eq!(
+            kb.dispatch(
+                KeybindingMode::InteractiveTable,
+                make_key_event(KeyCode::Char('r'), KeyModifiers::CONTROL)
+            ),
+            Some(Action::RedoEdit)
+        );
     }
 
     #[test]
@@ -482,7 +694,9 @@ mod tests {
             KeybindingMode::DocSearch,
             KeybindingMode::CommandPalette,
             KeybindingMode::ConfirmDialog,
+            KeybindingMode::ConflictDialog,
             KeybindingMode::CellEdit,
+            KeybindingMode::TableFilter,
         ];
 
         for mode in modes {
@@ -44,6 +44,14 @@ pub enum KeybindingMode {
     Help,
     /// Theme picker is shown
     ThemePicker,
+    /// Task list popup is shown
+    TaskList,
+    /// Choosing between notes an ambiguous wikilink matches
+    NotePicker,
+    /// Tag browser popup is shown
+    TagBrowser,
+    /// Link graph popup is shown
+    GraphView,
     /// Interactive element navigation
     Interactive,
     /// Table cell navigation within interactive mode
@@ -60,8 +68,12 @@ pub enum KeybindingMode {
     CommandPalette,
     /// Cell editing mode (for tables)
     CellEdit,
+    /// Typing a table row filter
+    TableFilter,
     /// Confirmation dialog
     ConfirmDialog,
+    /// File changed on disk before an edit could be written
+    ConflictDialog,
 }
 
 impl KeybindingMode {
@@ -71,6 +83,10 @@ impl KeybindingMode {
             KeybindingMode::Normal => "Normal",
             KeybindingMode::Help => "Help",
             KeybindingMode::ThemePicker => "Theme Picker",
+            KeybindingMode::TaskList => "Task List",
+            KeybindingMode::NotePicker => "Note Picker",
+            KeybindingMode::TagBrowser => "Tag Browser",
+            KeybindingMode::GraphView => "Link Graph",
             KeybindingMode::Interactive => "Interactive",
             KeybindingMode::InteractiveTable => "Table Navigation",
             KeybindingMode::LinkFollow => "Link Follow",
@@ -79,7 +95,9 @@ impl KeybindingMode {
             KeybindingMode::DocSearch => "Doc Search",
             KeybindingMode::CommandPalette => "Command Palette",
             KeybindingMode::CellEdit => "Cell Edit",
+            KeybindingMode::TableFilter => "Table Filter",
             KeybindingMode::ConfirmDialog => "Confirm",
+            KeybindingMode::ConflictDialog => "Conflict",
         }
     }
 }
@@ -374,6 +392,10 @@ mod tests {
             KeybindingMode::Normal,
             KeybindingMode::Help,
             KeybindingMode::ThemePicker,
+            KeybindingMode::TaskList,
+            KeybindingMode::NotePicker,
+            KeybindingMode::TagBrowser,
+            KeybindingMode::GraphView,
             KeybindingMode::Interactive,
             KeybindingMode::InteractiveTable,
             KeybindingMode::LinkFollow,
@@ -382,7 +404,9 @@ mod tests {
             KeybindingMode::DocSearch,
             KeybindingMode::CommandPalette,
             KeybindingMode::ConfirmDialog,
+            KeybindingMode::ConflictDialog,
             KeybindingMode::CellEdit,
+            KeybindingMode::TableFilter,
         ];
 
         for mode in modes {
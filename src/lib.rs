@@ -52,6 +52,11 @@
 /// Provides configuration management for theme choices, UI settings, and terminal preferences.
 pub mod config;
 
+/// Writing edited files back to disk.
+///
+/// Provides the atomic write used by both TUI edits and CLI commands.
+pub mod edit;
+
 /// Input handling module for stdin and file sources.
 ///
 /// Provides robust input reading, format detection, and tree output parsing.
@@ -89,6 +94,30 @@ pub mod query;
 /// keyboard shortcuts via configuration files.
 pub mod keybindings;
 
+/// Vault-wide note lookup for wikilinks.
+///
+/// Resolves `[[note]]` targets across a folder of notes by path suffix or
+/// front matter alias.
+pub mod vault;
+
+/// Link graph between the notes of a vault.
+///
+/// Resolves wikilinks, embeds and relative links between notes, and finds
+/// orphans, dead ends and unresolved links.
+pub mod graph;
+
+/// Heading and note renames that rewrite the links pointing at them.
+///
+/// Plans the link changes across the workspace for a preview, then writes
+/// them atomically.
+pub mod rename;
+
+/// Moving, re-levelling, deleting and extracting whole sections.
+///
+/// Works on the byte ranges of each heading's section and returns the new
+/// document text.
+pub mod section;
+
 // Re-export commonly used types for convenience
 pub use config::Config;
 pub use parser::{Document, Heading, HeadingNode, parse_file, parse_markdown};
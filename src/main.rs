@@ -35,7 +35,7 @@ use cli::{Cli, OutputFormat};
 use color_eyre::Result;
 use std::collections::HashMap;
 use std::process;
-use treemd::{Document, parser};
+use treemd::{Document, parser, section};
 
 fn main() -> Result<()> {
     color_eyre::install()?;
@@ -69,6 +69,36 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Subcommands that read their own input
+    if let Some(cli::Command::Tasks(ref tasks_args)) = args.command {
+        if let Err(e) = cli::tasks::run(tasks_args) {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+        return Ok(());
+    }
+    if let Some(cli::Command::Graph(ref graph_args)) = args.command {
+        if let Err(e) = cli::graph::run(graph_args) {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+        return Ok(());
+    }
+    if let Some(cli::Command::Rename(ref rename_args)) = args.command {
+        if let Err(e) = cli::rename::run(rename_args) {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+        return Ok(());
+    }
+    if let Some(cli::Command::Section(ref section_command)) = args.command {
+        if let Err(e) = cli::section::run(section_command) {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
     // For TUI mode with piped stdin, we'll read stdin first, then open TUI
     // This allows elegant piping: tree | treemd
     //
@@ -122,8 +152,9 @@ fn main() -> Result<()> {
     // If no flags, launch TUI
     if !args.list
         && !args.tree
+        && !args.render
         && !args.count
-        && args.section.is_none()
+        && args.section.is_empty()
         && args.command.is_none()
         && !args.setup_completions
     {
@@ -211,7 +242,13 @@ fn main() -> Result<()> {
             ("stdin".to_string(), std::path::PathBuf::from("<stdin>"))
         };
 
-        let app = treemd::App::new(doc, filename, file_path, config, color_mode);
+        let mut app = treemd::App::new(doc, filename, file_path, config, color_mode);
+
+        // CLI flags can only tighten the restrictions from the config file
+        app.restrict_access(treemd::tui::AccessMode::from_flags(
+            args.read_only,
+            args.safe,
+        ));
         let result = treemd::tui::run(&mut terminal, app);
 
         // Cleanup terminal state
@@ -242,8 +279,13 @@ fn handle_cli_mode(args: &Cli, doc: &Document) {
         print_heading_counts(doc);
     } else if args.tree {
         print_tree(doc, &args.output);
-    } else if let Some(ref section_name) = args.section {
-        extract_section(doc, section_name);
+    } else if !args.section.is_empty() {
+        extract_sections(doc, &args.section, args.render);
+    } else if args.render {
+        println!(
+            "{}",
+            parser::mermaid::render_mermaid_blocks(&doc.content).trim_end()
+        );
     } else if args.list {
         print_headings(&headings, &args.output, doc);
     }
@@ -306,37 +348,26 @@ fn print_heading_counts(doc: &Document) {
     println!("\nTotal: {}", doc.headings.len());
 }
 
-fn extract_section(doc: &Document, section_name: &str) {
-    let heading = match doc.find_heading(section_name) {
-        Some(h) => h,
-        None => {
-            eprintln!("Section '{}' not found", section_name);
+fn extract_sections(doc: &Document, addresses: &[String], render: bool) {
+    let sections = addresses
+        .iter()
+        .map(|address| section::find(doc, address).map(|idx| section::text(doc, idx)))
+        .collect::<Result<Vec<_>, _>>();
+    let sections = match sections {
+        Ok(sections) => sections.join("\n\n"),
+        Err(e) => {
+            eprintln!("{}", e);
             process::exit(1);
         }
     };
 
-    // Find the section in content
-    // This is a simple implementation - could be improved
-    let search = format!("{} {}", "#".repeat(heading.level), heading.text);
-    if let Some(start) = doc.content.find(&search) {
-        // Find next heading at same or higher level
-        let after = &doc.content[start..];
-        let section_level = heading.level;
-
-        // Find end of section
-        let end_pos = doc
-            .headings
-            .iter()
-            .skip_while(|h| h.text != heading.text)
-            .skip(1)
-            .find(|h| h.level <= section_level)
-            .and_then(|next_heading| {
-                let search = format!("{} {}", "#".repeat(next_heading.level), next_heading.text);
-                after.find(&search)
-            })
-            .unwrap_or(after.len());
-
-        println!("{}", &after[..end_pos].trim());
+    if render {
+        println!(
+            "{}",
+            parser::mermaid::render_mermaid_blocks(&sections).trim_end()
+        );
+    } else {
+        println!("{}", sections);
     }
 }
 
@@ -387,6 +418,13 @@ ELEMENT SELECTORS
     .img            All images
     .table          All tables
     .list           All lists
+    .task, .todo    All task list items ([ ] / [x])
+    .task[open]     Unchecked tasks (.task[done]: checked ones)
+    .callout        Callouts / GitHub alerts (> [!NOTE])
+    .callout[tip]   Callouts of one kind (aliases match too)
+    .footnote       Footnote definitions ([^1]: ...)
+    .math           LaTeX math ($...$, $$...$$)
+    .tag            Tags (#tag, front matter tags:) with counts
     .blockquote     All blockquotes
 
 FILTERS & INDEXING
@@ -400,6 +438,7 @@ FILTERS & INDEXING
 HIERARCHY
     .h1 > .h2           Direct child h2s under h1s
     .h1 >> .code        Code blocks anywhere under h1s
+    .h2 > .task         Tasks directly under each h2 (>> includes subsections)
 
 PIPES
     .h2 | text          Get heading text (strips ##)
@@ -446,6 +485,14 @@ CONTENT FUNCTIONS
     md                  Raw markdown
     url, href, src      Get URL/link/image source
     lang                Code block language
+    .checked, .line     Task state and source line (also .depth, .section, .path)
+    .due, .priority     Obsidian Tasks due date (📅 YYYY-MM-DD) and priority (⏫ ...)
+
+TABLE FUNCTIONS
+    rows                One object per row, keyed by header
+    column(c)           Array of a column's values (c: header or index)
+    cell(r; c)          Single cell (r: 0-based data row, negative from end)
+    Numeric cells become numbers when they parse cleanly
 
 AGGREGATION FUNCTIONS
     stats               Document statistics
@@ -489,6 +536,15 @@ EXAMPLES
     # JSON output
     treemd -q '.h2' --query-output json doc.md
 
+    # Names of finished tasks in the first table
+    treemd -q '.table[0] | rows | select(.Status == "done") | .Name' doc.md
+
+    # Open tasks with their line numbers
+    treemd -q '.task[open] | {text: .text, line: .line}' doc.md
+
+    # First table as CSV
+    treemd -q '.table[0]' --query-output csv doc.md
+
 OUTPUT FORMATS (--query-output)
     plain       Human-readable text (default)
     json        Compact JSON
@@ -496,6 +552,8 @@ OUTPUT FORMATS (--query-output)
     jsonl       Line-delimited JSON (one per line)
     md          Raw markdown
     tree        Tree structure
+    csv         Comma-separated values (tables as header + rows)
+    tsv         Tab-separated values (paste into spreadsheets)
 
 For more details, see: https://github.com/epistates/treemd
 "#;
@@ -0,0 +1,262 @@
+//! Obsidian callouts and GitHub alerts (`> [!NOTE]`, `> [!tip]- Title`).
+//!
+//! The block parser only knows plain blockquotes, so before parsing each
+//! top-level callout is rewritten into a `<details>` block whose summary keeps
+//! the callout header. It then comes out as a `Block::Details` with properly
+//! parsed contents, and [`Callout::from_summary`] recovers the kind, title and
+//! fold state from the summary.
+
+use super::utils::FenceTracker;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Callout header line: `> [!kind]`, an optional fold marker, then a title
+static CALLOUT_HEADER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^ {0,3}> ?\[!([A-Za-z][\w-]*)\]([+-]?)[ \t]*(.*)$").unwrap());
+
+/// Callout summary as written by [`rewrite_callouts`]
+static CALLOUT_SUMMARY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\[!([A-Za-z][\w-]*)\]([+-]?)\s*(.*)$").unwrap());
+
+/// Whether (and how) a callout can be folded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalloutFold {
+    /// Always shown
+    None,
+    /// `+`: foldable, expanded by default
+    Open,
+    /// `-`: foldable, collapsed by default
+    Closed,
+}
+
+/// Color group a callout kind is drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalloutStyle {
+    Note,
+    Tip,
+    Important,
+    Warning,
+    Caution,
+    Quote,
+}
+
+/// A callout recovered from a details block summary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Callout {
+    /// Canonical kind, e.g. `warning` for `[!attention]`
+    pub kind: String,
+    /// Title, or the written kind capitalized when there is none
+    pub title: String,
+    pub fold: CalloutFold,
+}
+
+impl Callout {
+    /// Parse a details summary produced by [`rewrite_callouts`]
+    pub fn from_summary(summary: &str) -> Option<Self> {
+        let caps = CALLOUT_SUMMARY.captures(summary.trim())?;
+        let written_kind = caps[1].to_lowercase();
+        let fold = match &caps[2] {
+            "+" => CalloutFold::Open,
+            "-" => CalloutFold::Closed,
+            _ => CalloutFold::None,
+        };
+        let title = match caps[3].trim() {
+            "" => capitalize(&written_kind),
+            title => title.to_string(),
+        };
+
+        Some(Self {
+            kind: canonical_kind(&written_kind).to_string(),
+            title,
+            fold,
+        })
+    }
+
+    pub fn is_foldable(&self) -> bool {
+        self.fold != CalloutFold::None
+    }
+
+    pub fn style(&self) -> CalloutStyle {
+        match self.kind.as_str() {
+            "tip" | "success" => CalloutStyle::Tip,
+            "important" | "example" => CalloutStyle::Important,
+            "warning" | "question" => CalloutStyle::Warning,
+            "caution" | "danger" | "failure" | "bug" => CalloutStyle::Caution,
+            "quote" => CalloutStyle::Quote,
+            _ => CalloutStyle::Note,
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self.kind.as_str() {
+            "abstract" => "≡",
+            "info" => "ℹ",
+            "todo" => "☐",
+            "tip" => "★",
+            "important" => "‼",
+            "success" => "✓",
+            "question" => "?",
+            "warning" => "⚠",
+            "caution" | "failure" => "✗",
+            "danger" => "⚡",
+            "bug" => "✱",
+            "example" => "»",
+            "quote" => "❝",
+            _ => "✎",
+        }
+    }
+}
+
+/// Kinds with their own icon and color; other kinds are drawn like notes
+const KINDS: &[&str] = &[
+    "note",
+    "abstract",
+    "info",
+    "todo",
+    "tip",
+    "success",
+    "question",
+    "warning",
+    "failure",
+    "danger",
+    "bug",
+    "example",
+    "quote",
+    "important",
+    "caution",
+];
+
+/// Map Obsidian's alias kinds to the kind they share a look with.
+/// Unknown kinds are kept as they are.
+pub fn canonical_kind(kind: &str) -> &str {
+    match kind {
+        "summary" | "tldr" => "abstract",
+        "hint" => "tip",
+        "check" | "done" => "success",
+        "help" | "faq" => "question",
+        "attention" => "warning",
+        "fail" | "missing" => "failure",
+        "error" => "danger",
+        "cite" => "quote",
+        other => other,
+    }
+}
+
+/// Whether `kind` (lowercase) is a built-in kind or one of its aliases
+pub fn is_known_kind(kind: &str) -> bool {
+    KINDS.contains(&canonical_kind(kind))
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Rewrite top-level callouts into `<details>` blocks.
+///
+/// A callout runs from its header to the first line that doesn't start with
+/// `>`. Blockquotes without a `[!kind]` header and anything inside fenced
+/// code blocks are left alone.
+pub fn rewrite_callouts(markdown: &str) -> String {
+    if !markdown.contains("[!") {
+        return markdown.to_string();
+    }
+
+    let mut result = String::with_capacity(markdown.len());
+    let mut lines = markdown.split_inclusive('\n').peekable();
+    let mut fences = FenceTracker::default();
+
+    while let Some(raw_line) = lines.next() {
+        let line = raw_line.trim_end_matches(['\n', '\r']);
+
+        if fences.line(line).is_code() {
+            result.push_str(raw_line);
+            continue;
+        }
+
+        let Some(caps) = CALLOUT_HEADER.captures(line) else {
+            result.push_str(raw_line);
+            continue;
+        };
+
+        let mut body = Vec::new();
+        while let Some(next) = lines.peek() {
+            let next = next.trim_end_matches(['\n', '\r']);
+            let Some(quoted) = quoted_text(next) else {
+                break;
+            };
+            body.push(quoted);
+            lines.next();
+        }
+
+        result.push_str("\n<details>\n<summary>[!");
+        result.push_str(&caps[1]);
+        result.push(']');
+        result.push_str(&caps[2]);
+        let title = caps[3].trim();
+        if !title.is_empty() {
+            result.push(' ');
+            result.push_str(title);
+        }
+        result.push_str("</summary>\n\n");
+        result.push_str(&body.join("\n"));
+        result.push_str("\n\n</details>\n");
+    }
+
+    result
+}
+
+/// Text of a blockquote line with its `>` marker removed
+fn quoted_text(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let rest = trimmed.strip_prefix('>')?;
+    Some(rest.strip_prefix(' ').unwrap_or(rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_callouts() {
+        let markdown = "> [!WARNING]\n> Back up **first**.\n>\n> - one\n\n> plain quote\n\n```md\n> [!note] in code\n```\n\n> [!faq]- Why?\n> Because.\nafter\n";
+        let rewritten = rewrite_callouts(markdown);
+
+        assert!(rewritten.starts_with(
+            "\n<details>\n<summary>[!WARNING]</summary>\n\nBack up **first**.\n\n- one\n\n</details>\n\n> plain quote\n"
+        ));
+        assert!(rewritten.contains("```md\n> [!note] in code\n```\n"));
+        assert!(
+            rewritten
+                .contains("<summary>[!faq]- Why?</summary>\n\nBecause.\n\n</details>\nafter\n")
+        );
+        assert_eq!(rewrite_callouts("> quote\n"), "> quote\n");
+    }
+
+    #[test]
+    fn test_callout_from_summary() {
+        let alert = Callout::from_summary("[!WARNING]").unwrap();
+        assert_eq!(alert.kind, "warning");
+        assert_eq!(alert.title, "Warning");
+        assert_eq!(alert.fold, CalloutFold::None);
+        assert_eq!(alert.style(), CalloutStyle::Warning);
+
+        let faq = Callout::from_summary("[!faq]- Why bother?").unwrap();
+        assert_eq!(faq.kind, "question");
+        assert_eq!(faq.title, "Why bother?");
+        assert_eq!(faq.fold, CalloutFold::Closed);
+        assert!(is_known_kind("help") && !is_known_kind("custom"));
+
+        assert_eq!(
+            Callout::from_summary("[!custom]+").unwrap().style(),
+            CalloutStyle::Note
+        );
+        assert_eq!(Callout::from_summary("Click to expand"), None);
+    }
+}
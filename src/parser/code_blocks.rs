@@ -0,0 +1,204 @@
+//! Fenced code blocks with their source positions.
+//!
+//! Parsed code blocks only carry their content, so editing one in place means
+//! finding it again in the source. Blocks nested in list items keep the
+//! indentation of their fence, which is removed from the content here and
+//! restored when the content is replaced.
+
+use super::utils::{FenceLine, FenceTracker};
+use std::ops::Range;
+
+/// A closed fenced code block in the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FencedBlock {
+    pub language: Option<String>,
+    /// Content with the fence's indentation removed
+    pub content: String,
+    /// Byte range of the lines between the fences
+    pub body: Range<usize>,
+    /// Leading whitespace of the opening fence
+    pub indent: String,
+    /// 1-indexed line of the opening fence
+    pub line: usize,
+}
+
+/// All closed fenced code blocks in markdown source
+pub fn find_fenced_blocks(source: &str) -> Vec<FencedBlock> {
+    let mut blocks = Vec::new();
+    let mut fences = FenceTracker::default();
+    // (indent, language, body start, fence line) of the open block
+    let mut open: Option<(String, Option<String>, usize, usize)> = None;
+    let mut offset = 0;
+
+    for (idx, raw_line) in source.split_inclusive('\n').enumerate() {
+        let line_start = offset;
+        offset += raw_line.len();
+        let line = raw_line.trim_end_matches(['\n', '\r']);
+
+        match fences.line(line) {
+            FenceLine::Open => {
+                let trimmed = line.trim_start_matches(' ');
+                let info = trimmed.trim_start_matches(&trimmed[..1]).trim();
+                let language = info
+                    .split_whitespace()
+                    .next()
+                    .map(|lang| lang.trim_start_matches('{').trim_end_matches('}'))
+                    .filter(|lang| !lang.is_empty())
+                    .map(String::from);
+                let indent = line[..line.len() - trimmed.len()].to_string();
+                open = Some((indent, language, offset, idx + 1));
+            }
+            FenceLine::Close => {
+                if let Some((indent, language, body_start, fence_line)) = open.take() {
+                    let body = body_start..line_start;
+                    blocks.push(FencedBlock {
+                        language,
+                        content: dedent(&source[body.clone()], indent.len()),
+                        body,
+                        indent,
+                        line: fence_line,
+                    });
+                }
+            }
+            FenceLine::Text | FenceLine::Code => {}
+        }
+    }
+
+    blocks
+}
+
+/// Remove up to `width` leading whitespace characters from each line
+fn dedent(text: &str, width: usize) -> String {
+    text.split_inclusive('\n')
+        .map(|line| {
+            let strip = line
+                .char_indices()
+                .take(width)
+                .take_while(|(_, c)| *c == ' ' || *c == '\t')
+                .count();
+            &line[strip..]
+        })
+        .collect::<String>()
+        .replace("\r\n", "\n")
+}
+
+/// Find the block whose content matches `content` (ignoring trailing
+/// newlines), preferring the first at or after `line_hint` (1-indexed)
+pub fn find_block_by_content<'a>(
+    blocks: &'a [FencedBlock],
+    content: &str,
+    line_hint: Option<usize>,
+) -> Option<&'a FencedBlock> {
+    let wanted = content.trim_end_matches('\n');
+    let mut candidates = blocks
+        .iter()
+        .filter(|block| block.content.trim_end_matches('\n') == wanted)
+        .peekable();
+    let first = candidates.peek().copied();
+    let hint = line_hint.unwrap_or(0);
+    candidates.find(|block| block.line >= hint).or(first)
+}
+
+/// Replace a block's content in `source` (which it must have been found in),
+/// indenting the new lines like the fence
+pub fn replace_block_content(source: &str, block: &FencedBlock, new_content: &str) -> String {
+    let newline = if source[block.body.clone()].contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+
+    let mut body = String::new();
+    let new_content = new_content.strip_suffix('\n').unwrap_or(new_content);
+    if !new_content.is_empty() {
+        for line in new_content.split('\n') {
+            let line = line.trim_end_matches('\r');
+            if !line.is_empty() {
+                body.push_str(&block.indent);
+                body.push_str(line);
+            }
+            body.push_str(newline);
+        }
+    }
+
+    let mut result = String::with_capacity(source.len() + body.len());
+    result.push_str(&source[..block.body.start]);
+    result.push_str(&body);
+    result.push_str(&source[block.body.end..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "# Setup\n\n```bash\necho one\n```\n\n- step\n  ```python title=\"x\"\n  print(1)\n\n    print(2)\n  ```\n\n````md\n```\nnot a fence\n```\n````\n\n```\nunclosed\n";
+
+    #[test]
+    fn test_find_fenced_blocks() {
+        let blocks = find_fenced_blocks(DOC);
+        let summary: Vec<(Option<&str>, &str, &str, usize)> = blocks
+            .iter()
+            .map(|b| {
+                (
+                    b.language.as_deref(),
+                    b.content.as_str(),
+                    b.indent.as_str(),
+                    b.line,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (Some("bash"), "echo one\n", "", 3),
+                (Some("python"), "print(1)\n\n  print(2)\n", "  ", 8),
+                (Some("md"), "```\nnot a fence\n```\n", "", 14),
+            ]
+        );
+        assert_eq!(
+            find_block_by_content(&blocks, "echo one", Some(1)).map(|b| b.line),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn test_replace_keeps_list_indentation() {
+        let blocks = find_fenced_blocks(DOC);
+        let replaced = replace_block_content(
+            DOC,
+            &blocks[1],
+            "for i in range(2):\n    print(i)\n\nprint('done')\n",
+        );
+
+        assert!(replaced.contains(
+            "  ```python title=\"x\"\n  for i in range(2):\n      print(i)\n\n  print('done')\n  ```\n"
+        ));
+        assert_eq!(
+            find_fenced_blocks(&replaced)[1].content,
+            "for i in range(2):\n    print(i)\n\nprint('done')\n"
+        );
+
+        let emptied = replace_block_content(DOC, &blocks[0], "");
+        assert!(emptied.starts_with("# Setup\n\n```bash\n```\n"));
+    }
+
+    #[test]
+    fn test_fences_deep_in_list_items() {
+        let doc = "- item\n  - nested\n\n    ```sh\n    ls\n    ```\n\n1. step\n\n     ~~~mermaid\n     graph TD\n     ~~~\n";
+        let blocks = find_fenced_blocks(doc);
+        let summary: Vec<(Option<&str>, &str, &str)> = blocks
+            .iter()
+            .map(|b| (b.language.as_deref(), b.content.as_str(), b.indent.as_str()))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (Some("sh"), "ls\n", "    "),
+                (Some("mermaid"), "graph TD\n", "     "),
+            ]
+        );
+    }
+}
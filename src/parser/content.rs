@@ -8,6 +8,9 @@
 //! - Wikilink preprocessing: `[[target]]` → `[target](wikilink:target)`
 //! - Links with spaces: `[text](url with spaces)` → `[text](<url with spaces>)`
 //! - Details block extraction: `<details><summary>` HTML blocks
+//! - Callouts: `> [!NOTE]` blockquotes become details blocks (see [`super::callouts`])
+//! - Footnotes: `[^1]` → `[¹](footnote:1)`, definitions removed (see [`super::footnotes`])
+//! - Math: `$x^2$` → `x²`, `$$...$$` → ```` ```math ```` blocks (see [`super::math`])
 //! - Full GFM support: tables, strikethrough, task lists
 
 use super::output::Block;
@@ -26,7 +29,10 @@ use super::output::Block;
 ///
 /// A vector of parsed content blocks.
 pub fn parse_content(markdown: &str, start_line: usize) -> Vec<Block> {
-    turbovault_parser::parse_blocks_from_line(markdown, start_line)
+    let markdown = super::callouts::rewrite_callouts(markdown);
+    let markdown = super::footnotes::rewrite_footnotes(&markdown);
+    let markdown = super::math::rewrite_math(&markdown);
+    turbovault_parser::parse_blocks_from_line(&markdown, start_line)
 }
 
 /// Generate URL-friendly slug from heading text.
@@ -211,6 +217,41 @@ Also [[Another Valid]]
         }
     }
 
+    #[test]
+    fn test_callout_parsed_as_details() {
+        let markdown = "Intro\n\n> [!tip] Shortcut\n> Press **q**.\n>\n> - one\n> - two\n\nOutro";
+        let blocks = parse_content(markdown, 0);
+
+        assert_eq!(blocks.len(), 3);
+        if let Block::Details {
+            summary, blocks, ..
+        } = &blocks[1]
+        {
+            assert_eq!(summary, "[!tip] Shortcut");
+            assert!(matches!(blocks[0], Block::Paragraph { .. }));
+            assert!(matches!(&blocks[1], Block::List { items, .. } if items.len() == 2));
+        } else {
+            panic!("Expected Details block");
+        }
+    }
+
+    #[test]
+    fn test_math_rendered_as_unicode() {
+        let markdown = "Energy $E = mc^2$ with $a_i * b$.\n\n$$\n\\frac{1}{n}\n$$";
+        let blocks = parse_content(markdown, 0);
+
+        assert_eq!(blocks.len(), 2);
+        if let Block::Paragraph { content, .. } = &blocks[0] {
+            assert_eq!(content, "Energy E = mc² with aᵢ * b.");
+        } else {
+            panic!("Expected Paragraph block");
+        }
+        assert!(matches!(
+            &blocks[1],
+            Block::Code { language: Some(lang), content, .. } if lang == "math" && content == "\\frac{1}{n}"
+        ));
+    }
+
     #[test]
     fn test_slugify() {
         assert_eq!(slugify("Hello World"), "hello-world");
@@ -0,0 +1,223 @@
+//! CSV/TSV conversion for tables.
+//!
+//! Writing quotes fields the way spreadsheets expect (RFC 4180 for CSV; TSV
+//! has no quoting, so tabs and newlines inside cells become spaces). Reading
+//! accepts quoted fields with embedded delimiters, quotes and newlines in
+//! both formats, since spreadsheets quote TSV too.
+
+use std::path::Path;
+
+/// Field separator of a delimited text format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    Comma,
+    Tab,
+}
+
+impl Delimiter {
+    pub fn as_char(self) -> char {
+        match self {
+            Delimiter::Comma => ',',
+            Delimiter::Tab => '\t',
+        }
+    }
+
+    /// Format name for messages ("CSV" / "TSV")
+    pub fn name(self) -> &'static str {
+        match self {
+            Delimiter::Comma => "CSV",
+            Delimiter::Tab => "TSV",
+        }
+    }
+
+    /// Delimiter implied by a file extension (`.csv`, `.tsv`, `.tab`)
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "csv" => Some(Delimiter::Comma),
+            "tsv" | "tab" => Some(Delimiter::Tab),
+            _ => None,
+        }
+    }
+
+    /// Guess the delimiter of pasted text: tabs on the first line mean TSV
+    /// (what spreadsheets put on the clipboard), anything else is CSV.
+    pub fn detect(text: &str) -> Self {
+        let first_line = text.lines().next().unwrap_or_default();
+        if first_line.contains('\t') {
+            Delimiter::Tab
+        } else {
+            Delimiter::Comma
+        }
+    }
+}
+
+/// Write records as delimited text, one line per record (no trailing newline)
+pub fn write<'a, I>(records: I, delimiter: Delimiter) -> String
+where
+    I: IntoIterator<Item = &'a [String]>,
+{
+    records
+        .into_iter()
+        .map(|record| {
+            record
+                .iter()
+                .map(|field| write_field(field, delimiter))
+                .collect::<Vec<_>>()
+                .join(&delimiter.as_char().to_string())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Write a table (header row first) as delimited text
+pub fn write_table(headers: &[String], rows: &[Vec<String>], delimiter: Delimiter) -> String {
+    write(
+        std::iter::once(headers).chain(rows.iter().map(Vec::as_slice)),
+        delimiter,
+    )
+}
+
+fn write_field(field: &str, delimiter: Delimiter) -> String {
+    match delimiter {
+        Delimiter::Comma => {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        }
+        Delimiter::Tab => field.replace(['\t', '\n', '\r'], " "),
+    }
+}
+
+/// Parse delimited text into records.
+///
+/// Blank lines are skipped and records may have different lengths.
+pub fn parse(text: &str, delimiter: Delimiter) -> Vec<Vec<String>> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let separator = delimiter.as_char();
+
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    // Whether the current field has any content, quoted or not (so `""` is
+    // kept as an empty field rather than a blank line)
+    let mut field_started = false;
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => {
+                in_quotes = true;
+                field_started = true;
+            }
+            c if c == separator => {
+                record.push(std::mem::take(&mut field));
+                field_started = true;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                finish_record(&mut records, &mut record, &mut field, field_started);
+                field_started = false;
+            }
+            _ => {
+                field.push(c);
+                field_started = true;
+            }
+        }
+    }
+    finish_record(&mut records, &mut record, &mut field, field_started);
+
+    records
+}
+
+fn finish_record(
+    records: &mut Vec<Vec<String>>,
+    record: &mut Vec<String>,
+    field: &mut String,
+    field_started: bool,
+) {
+    if field_started || !record.is_empty() {
+        record.push(std::mem::take(field));
+        records.push(std::mem::take(record));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(fields: &[&str]) -> Vec<String> {
+        fields.iter().map(|f| f.to_string()).collect()
+    }
+
+    #[test]
+    fn test_write_quotes_csv_and_flattens_tsv() {
+        let headers = strings(&["Name", "Note"]);
+        let rows = vec![
+            strings(&["a,b", "say \"hi\""]),
+            strings(&["tab\there", "x | y"]),
+        ];
+
+        assert_eq!(
+            write_table(&headers, &rows, Delimiter::Comma),
+            "Name,Note\n\"a,b\",\"say \"\"hi\"\"\"\ntab\there,x | y"
+        );
+        assert_eq!(
+            write_table(&headers, &rows, Delimiter::Tab),
+            "Name\tNote\na,b\tsay \"hi\"\ntab here\tx | y"
+        );
+    }
+
+    #[test]
+    fn test_parse_quoted_and_ragged_records() {
+        let text = "\u{feff}a,b,c\r\n\"1,5\",\"two\nlines\",\"\"\"q\"\"\"\n\nonly\n,\n";
+        assert_eq!(
+            parse(text, Delimiter::Comma),
+            vec![
+                strings(&["a", "b", "c"]),
+                strings(&["1,5", "two\nlines", "\"q\""]),
+                strings(&["only"]),
+                strings(&["", ""]),
+            ]
+        );
+
+        assert_eq!(
+            parse("x\ty\n1\t\"2\t3\"", Delimiter::Tab),
+            vec![strings(&["x", "y"]), strings(&["1", "2\t3"])]
+        );
+    }
+
+    #[test]
+    fn test_round_trip_and_detect() {
+        let headers = strings(&["k", "v"]);
+        let rows = vec![strings(&["multi\nline", "\"quoted\", too"])];
+        let csv = write_table(&headers, &rows, Delimiter::Comma);
+        assert_eq!(
+            parse(&csv, Delimiter::Comma),
+            vec![headers, rows[0].clone()]
+        );
+
+        assert_eq!(Delimiter::detect("a\tb\n1\t2"), Delimiter::Tab);
+        assert_eq!(Delimiter::detect("a,b"), Delimiter::Comma);
+        assert_eq!(
+            Delimiter::from_path(Path::new("data.TSV")),
+            Some(Delimiter::Tab)
+        );
+        assert_eq!(Delimiter::from_path(Path::new("notes.md")), None);
+    }
+}
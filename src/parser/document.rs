@@ -103,6 +103,12 @@ impl Document {
     ///
     /// Uses stored byte offsets for fast, accurate extraction without string searching.
     pub fn extract_section(&self, heading_text: &str) -> Option<String> {
+        self.section_range(heading_text)
+            .map(|range| self.content[range].to_string())
+    }
+
+    /// Byte range of a section's content (as returned by [`Self::extract_section`]).
+    pub fn section_range(&self, heading_text: &str) -> Option<std::ops::Range<usize>> {
         // Find the heading (O(n) scan of headings list)
         let heading_idx = self
             .headings
@@ -130,8 +136,11 @@ impl Document {
             .map(|h| h.offset)
             .unwrap_or(self.content.len());
 
-        // Extract section content
-        Some(self.content[content_start..end].trim().to_string())
+        // Trim surrounding whitespace
+        let section = &self.content[content_start..end];
+        let leading = section.len() - section.trim_start().len();
+        let trimmed_len = section.trim().len();
+        Some(content_start + leading..content_start + leading + trimmed_len)
     }
 }
 
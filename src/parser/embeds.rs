@@ -0,0 +1,186 @@
+//! Obsidian embeds: `![[note]]`, `![[note#Heading]]` and `![[note#^block-id]]`.
+//!
+//! The block parser reads `![[x]]` as an image whose source is `wikilink:x`.
+//! A paragraph holding just such an image is drawn as the embedded note,
+//! section or block; these helpers pick that part out of the note's source.
+
+use super::parse_markdown;
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+/// Block id marker ending a line: `text ^block-id`
+static BLOCK_ID: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|\s)\^([A-Za-z0-9-]+)\s*$").unwrap());
+
+/// File extensions embedded as images rather than notes
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp", "svg"];
+
+/// How deep embeds inside embedded notes are followed
+pub const MAX_EMBED_DEPTH: usize = 3;
+
+/// The part of a note an embed shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbedPart<'a> {
+    Note,
+    Heading(&'a str),
+    Block(&'a str),
+}
+
+/// A parsed embed target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Embed<'a> {
+    /// Wikilink file part; empty for the current note (`![[#Heading]]`)
+    pub file: &'a str,
+    pub part: EmbedPart<'a>,
+}
+
+impl<'a> Embed<'a> {
+    /// The embed an image source stands for, if it came from `![[...]]`
+    pub fn from_src(src: &'a str) -> Option<Self> {
+        src.strip_prefix("wikilink:").map(Self::parse)
+    }
+
+    /// Split `note#Heading` / `note#^block-id` into file and part
+    pub fn parse(target: &'a str) -> Self {
+        let (file, part) = match target.split_once('#') {
+            Some((file, anchor)) => match anchor.strip_prefix('^') {
+                Some(id) => (file, EmbedPart::Block(id.trim())),
+                None => (file, EmbedPart::Heading(anchor.trim())),
+            },
+            None => (target, EmbedPart::Note),
+        };
+        Self {
+            file: file.trim(),
+            part,
+        }
+    }
+
+    /// Whether this embeds an image file (`![[diagram.png]]`)
+    pub fn is_image(&self) -> bool {
+        self.file
+            .rsplit_once('.')
+            .is_some_and(|(_, ext)| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+    }
+
+    /// The embedded markdown out of the note's source, or an error naming
+    /// the missing heading or block
+    pub fn extract(&self, content: &str) -> Result<String, String> {
+        match self.part {
+            EmbedPart::Note => Ok(strip_frontmatter(content).to_string()),
+            EmbedPart::Heading(heading) => parse_markdown(content)
+                .extract_section(heading)
+                .ok_or_else(|| format!("heading '{}' not found", heading)),
+            EmbedPart::Block(id) => find_block(content, id)
+                .map(|lines| {
+                    content
+                        .lines()
+                        .skip(lines.start)
+                        .take(lines.len())
+                        .map(|line| match BLOCK_ID.captures(line) {
+                            Some(caps) if &caps[1] == id => {
+                                line[..caps.get(0).unwrap().start()].trim_end()
+                            }
+                            _ => line,
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .ok_or_else(|| format!("block ^{} not found", id)),
+        }
+    }
+}
+
+/// 0-indexed line range of the block marked `^id`.
+///
+/// The block is the run of non-blank lines holding the marker; a marker on a
+/// line of its own refers to the block above it (a list or table).
+pub fn find_block(content: &str, id: &str) -> Option<Range<usize>> {
+    let lines: Vec<&str> = content.lines().collect();
+    let marker = lines
+        .iter()
+        .position(|line| BLOCK_ID.captures(line).is_some_and(|caps| &caps[1] == id))?;
+
+    let is_blank = |idx: usize| lines[idx].trim().is_empty();
+    let mut end = marker + 1;
+    let mut start = marker;
+    if lines[marker].trim() == format!("^{}", id) {
+        // Standalone marker: the block ends above it, past any blank lines
+        end = marker;
+        while end > 0 && is_blank(end - 1) {
+            end -= 1;
+        }
+        start = end;
+    } else {
+        while end < lines.len() && !is_blank(end) {
+            end += 1;
+        }
+    }
+    while start > 0 && !is_blank(start - 1) {
+        start -= 1;
+    }
+    (start < end).then_some(start..end)
+}
+
+/// Content without a leading YAML front matter block
+fn strip_frontmatter(content: &str) -> &str {
+    let Some(rest) = content.strip_prefix("---\n") else {
+        return content;
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        offset += line.len();
+        if line.trim_end() == "---" {
+            return rest[offset..].trim_start_matches('\n');
+        }
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_targets() {
+        assert_eq!(
+            Embed::from_src("wikilink:notes/a#Setup"),
+            Some(Embed {
+                file: "notes/a",
+                part: EmbedPart::Heading("Setup")
+            })
+        );
+        assert_eq!(Embed::parse("a#^x1").part, EmbedPart::Block("x1"));
+        assert_eq!(Embed::parse("#Intro").file, "");
+        assert!(Embed::parse("img/Chart.PNG").is_image());
+        assert!(!Embed::parse("v1.2 notes").is_image());
+        assert_eq!(Embed::from_src("pic.png"), None);
+    }
+
+    #[test]
+    fn test_extract_heading_and_note() {
+        let note = "---\ntags: [a]\n---\n# A\n\nIntro\n\n## Setup\n\nSteps\n\n## Other\n";
+        let setup = Embed::parse("a#setup").extract(note).unwrap();
+        assert_eq!(setup.trim(), "Steps");
+        assert!(Embed::parse("a").extract(note).unwrap().starts_with("# A"));
+        assert_eq!(
+            Embed::parse("a#Missing").extract(note),
+            Err("heading 'Missing' not found".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_blocks() {
+        let note = "Intro\n\nFirst line\nkey point ^p1\n\n- one\n- two\n\n^list\n";
+        assert_eq!(find_block(note, "p1"), Some(2..4));
+        assert_eq!(
+            Embed::parse("a#^p1").extract(note).unwrap(),
+            "First line\nkey point"
+        );
+        assert_eq!(
+            Embed::parse("a#^list").extract(note).unwrap(),
+            "- one\n- two"
+        );
+        assert_eq!(find_block(note, "nope"), None);
+    }
+}
@@ -0,0 +1,264 @@
+//! Footnote references (`[^1]`) and definitions (`[^1]: text`).
+//!
+//! The block parser doesn't know footnotes, so before parsing, references are
+//! rewritten into links with a `footnote:` URL (like wikilinks become
+//! `wikilink:` links) and definitions are removed; they are shown together at
+//! the end of a rendered section instead. Code blocks and code spans are
+//! skipped.
+
+use super::utils::{FenceLine, FenceTracker, code_span_ranges};
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+/// Definition line: `[^label]: text`
+static DEFINITION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^ {0,3}\[\^([^\]\s()<>^]+)\]:[ \t]*(.*)$").unwrap());
+
+/// Reference: `[^label]`
+static REFERENCE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[\^([^\]\s()<>^]+)\]").unwrap());
+
+/// A footnote definition in the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FootnoteDef {
+    pub label: String,
+    /// Text with continuation lines dedented
+    pub content: String,
+    /// 1-indexed line of the `[^label]:` marker
+    pub line: usize,
+    /// Byte range of the definition's lines
+    range: Range<usize>,
+}
+
+/// A footnote reference in the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FootnoteRef {
+    pub label: String,
+    /// Byte offset of the `[^`
+    pub offset: usize,
+    /// 1-indexed line
+    pub line: usize,
+    len: usize,
+}
+
+/// All footnote definitions and references in markdown source
+pub fn scan_footnotes(source: &str) -> (Vec<FootnoteDef>, Vec<FootnoteRef>) {
+    let mut defs: Vec<FootnoteDef> = Vec::new();
+    let mut refs = Vec::new();
+    if !source.contains("[^") {
+        return (defs, refs);
+    }
+
+    let mut fences = FenceTracker::default();
+    // Whether the last definition can still take continuation lines
+    let mut in_definition = false;
+    let mut offset = 0;
+
+    for (idx, raw_line) in source.split_inclusive('\n').enumerate() {
+        let line_start = offset;
+        offset += raw_line.len();
+        let line = raw_line.trim_end_matches(['\n', '\r']);
+
+        match fences.line(line) {
+            FenceLine::Text => {}
+            FenceLine::Code => continue,
+            FenceLine::Open | FenceLine::Close => {
+                in_definition = false;
+                continue;
+            }
+        }
+
+        if let Some(caps) = DEFINITION.captures(line) {
+            defs.push(FootnoteDef {
+                label: caps[1].to_string(),
+                content: caps[2].trim().to_string(),
+                line: idx + 1,
+                range: line_start..offset,
+            });
+            in_definition = true;
+            continue;
+        }
+
+        if in_definition && let Some(def) = defs.last_mut() {
+            let indented = line.starts_with("    ") || line.starts_with('\t');
+            if line.trim().is_empty() || indented {
+                // Blank lines only belong to the definition if indented text follows
+                if indented {
+                    let gap = &source[def.range.end..line_start];
+                    def.content
+                        .push_str(if gap.trim().is_empty() && !gap.is_empty() {
+                            "\n\n"
+                        } else {
+                            "\n"
+                        });
+                    def.content.push_str(line.trim());
+                    def.range.end = offset;
+                }
+                continue;
+            }
+            in_definition = false;
+        }
+
+        let code_spans = code_span_ranges(line);
+        for m in REFERENCE.find_iter(line) {
+            if code_spans.iter().any(|span| span.contains(&m.start())) {
+                continue;
+            }
+            refs.push(FootnoteRef {
+                label: line[m.start() + 2..m.end() - 1].to_string(),
+                offset: line_start + m.start(),
+                line: idx + 1,
+                len: m.len(),
+            });
+        }
+    }
+
+    (defs, refs)
+}
+
+/// All footnote definitions in markdown source
+pub fn find_definitions(source: &str) -> Vec<FootnoteDef> {
+    scan_footnotes(source).0
+}
+
+/// Labels referenced in `source`, in order of first reference
+pub fn referenced_labels(source: &str) -> Vec<String> {
+    let mut labels: Vec<String> = Vec::new();
+    for reference in scan_footnotes(source).1 {
+        if !labels.contains(&reference.label) {
+            labels.push(reference.label);
+        }
+    }
+    labels
+}
+
+/// Rewrite references into `[¹](footnote:label)` links and drop definitions
+pub fn rewrite_footnotes(markdown: &str) -> String {
+    let (defs, refs) = scan_footnotes(markdown);
+    if defs.is_empty() && refs.is_empty() {
+        return markdown.to_string();
+    }
+
+    // Both lists are in source order and never overlap
+    let mut edits: Vec<(Range<usize>, String)> = defs
+        .iter()
+        .map(|def| (def.range.clone(), String::new()))
+        .chain(refs.iter().map(|r| {
+            let link = format!("[{}](footnote:{})", marker(&r.label), r.label);
+            (r.offset..r.offset + r.len, link)
+        }))
+        .collect();
+    edits.sort_by_key(|(range, _)| range.start);
+
+    let mut result = String::with_capacity(markdown.len());
+    let mut pos = 0;
+    for (range, replacement) in edits {
+        result.push_str(&markdown[pos..range.start]);
+        result.push_str(&replacement);
+        pos = range.end;
+    }
+    result.push_str(&markdown[pos..]);
+    result
+}
+
+/// Superscript form of a label (`1` → `¹`), or `⁽label⁾` when some
+/// character has no superscript. Brackets would turn the rewritten
+/// `[marker](footnote:label)` into a wikilink.
+pub fn marker(label: &str) -> String {
+    label
+        .chars()
+        .map(superscript)
+        .collect::<Option<String>>()
+        .unwrap_or_else(|| format!("⁽{}⁾", label))
+}
+
+fn superscript(c: char) -> Option<char> {
+    let sup = match c.to_ascii_lowercase() {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        '-' => '⁻',
+        'a' => 'ᵃ',
+        'b' => 'ᵇ',
+        'c' => 'ᶜ',
+        'd' => 'ᵈ',
+        'e' => 'ᵉ',
+        'f' => 'ᶠ',
+        'g' => 'ᵍ',
+        'h' => 'ʰ',
+        'i' => 'ⁱ',
+        'j' => 'ʲ',
+        'k' => 'ᵏ',
+        'l' => 'ˡ',
+        'm' => 'ᵐ',
+        'n' => 'ⁿ',
+        'o' => 'ᵒ',
+        'p' => 'ᵖ',
+        'r' => 'ʳ',
+        's' => 'ˢ',
+        't' => 'ᵗ',
+        'u' => 'ᵘ',
+        'v' => 'ᵛ',
+        'w' => 'ʷ',
+        'x' => 'ˣ',
+        'y' => 'ʸ',
+        'z' => 'ᶻ',
+        _ => return None,
+    };
+    Some(sup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "# Notes\n\nA claim[^1] and `[^code]` and another[^note].\n\n[^1]: First.\n[^note]: Named,\n    continued.\n\n    Second paragraph.\n\n```\n[^2]\n```\nAgain[^1].\n";
+
+    #[test]
+    fn test_scan_footnotes() {
+        let (defs, refs) = scan_footnotes(DOC);
+
+        let defs: Vec<(&str, &str, usize)> = defs
+            .iter()
+            .map(|d| (d.label.as_str(), d.content.as_str(), d.line))
+            .collect();
+        assert_eq!(
+            defs,
+            vec![
+                ("1", "First.", 5),
+                ("note", "Named,\ncontinued.\n\nSecond paragraph.", 6)
+            ]
+        );
+
+        let refs: Vec<(&str, usize)> = refs.iter().map(|r| (r.label.as_str(), r.line)).collect();
+        assert_eq!(refs, vec![("1", 3), ("note", 3), ("1", 14)]);
+        assert_eq!(referenced_labels(DOC), vec!["1", "note"]);
+    }
+
+    #[test]
+    fn test_rewrite_footnotes() {
+        let rewritten = rewrite_footnotes(DOC);
+
+        assert!(rewritten.contains(
+            "A claim[¹](footnote:1) and `[^code]` and another[ⁿᵒᵗᵉ](footnote:note).\n\n\n```\n[^2]\n```\nAgain[¹](footnote:1).\n"
+        ));
+        assert!(!rewritten.contains("First."));
+        assert_eq!(marker("12"), "¹²");
+        assert_eq!(marker("Quote"), "⁽Quote⁾");
+    }
+
+    #[test]
+    fn test_rewrite_label_without_superscript() {
+        let rewritten = rewrite_footnotes("Text[^my_note].\n\n[^my_note]: Body.\n");
+
+        assert!(rewritten.contains("Text[⁽my_note⁾](footnote:my_note)."));
+        assert!(turbovault_parser::parse_wikilinks(&rewritten).is_empty());
+    }
+}
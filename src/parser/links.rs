@@ -7,7 +7,7 @@
 //! All parsing is delegated to `turbovault-parser` for unified, code-block-aware
 //! link extraction.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use turbovault_parser::LinkType;
 
 /// Represents a link found in markdown content.
@@ -41,6 +41,9 @@ pub enum LinkTarget {
 
     /// External URL (e.g., `https://example.com`)
     External(String),
+
+    /// Footnote reference (e.g., `[^1]`), by label
+    Footnote(String),
 }
 
 impl LinkTarget {
@@ -63,6 +66,7 @@ impl LinkTarget {
                 }
             }
             LinkTarget::External(url) => url.clone(),
+            LinkTarget::Footnote(label) => format!("[^{}]", label),
         }
     }
 }
@@ -89,6 +93,7 @@ impl Link {
 /// - Wikilinks: `[[target]]` or `[[target|alias]]`
 /// - Anchor links: `[text](#section)`
 /// - External links: `[text](https://...)`
+/// - Footnote references: `[^label]`
 ///
 /// # Arguments
 ///
@@ -124,6 +129,15 @@ pub fn extract_links(content: &str) -> Vec<Link> {
         ));
     }
 
+    // Footnote references (not links to turbovault-parser)
+    for reference in super::footnotes::scan_footnotes(content).1 {
+        links.push(Link::new(
+            format!("[^{}]", reference.label),
+            LinkTarget::Footnote(reference.label),
+            reference.offset,
+        ));
+    }
+
     // Sort by offset for consistent ordering
     links.sort_by_key(|l| l.offset);
 
@@ -171,6 +185,62 @@ fn convert_link_type(link_type: &LinkType, target: &str) -> LinkTarget {
     }
 }
 
+/// Files a wikilink's file part (`note` in `[[note#Heading]]`) may refer to,
+/// relative to the linking document's directory, in the order they're tried.
+///
+/// # Errors
+///
+/// Rejects path traversal and absolute paths.
+pub fn wikilink_candidates(file_target: &str) -> Result<Vec<String>, String> {
+    // Security: Reject path traversal attempts
+    if file_target.contains("..") {
+        return Err("WikiLinks cannot contain path traversal (..)".to_string());
+    }
+
+    // Security: Reject absolute paths
+    if file_target.starts_with('/') {
+        return Err("WikiLinks cannot be absolute paths".to_string());
+    }
+
+    // Security: Reject Windows absolute paths (drive letters)
+    #[cfg(windows)]
+    if file_target.len() >= 2 && file_target.chars().nth(1) == Some(':') {
+        return Err("WikiLinks cannot be absolute paths".to_string());
+    }
+
+    // Normalize backslashes to forward slashes for cross-platform compatibility
+    let file_target = file_target.replace('\\', "/");
+
+    // Check if target already has a markdown extension
+    let file_target_lower = file_target.to_lowercase();
+    let has_md_extension = file_target_lower.ends_with(".md")
+        || file_target_lower.ends_with(".markdown")
+        || file_target_lower.ends_with(".mdown");
+
+    // Try various extensions (only add extensions if target doesn't already have one)
+    Ok(if has_md_extension {
+        vec![file_target]
+    } else {
+        vec![
+            format!("{}.md", file_target),
+            format!("{}.markdown", file_target),
+            file_target,
+        ]
+    })
+}
+
+/// The first existing file among [`wikilink_candidates`], relative to `dir`.
+/// Symlinks are skipped.
+pub fn resolve_wikilink(dir: &Path, file_target: &str) -> Result<Option<PathBuf>, String> {
+    Ok(wikilink_candidates(file_target)?
+        .into_iter()
+        .map(PathBuf::from)
+        .find(|candidate| {
+            let path = dir.join(candidate);
+            !path.is_symlink() && path.exists()
+        }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -313,6 +383,17 @@ Visit [GitHub](https://github.com/user/repo) for source.
         assert_eq!(links.len(), 0); // Should not extract malformed links
     }
 
+    #[test]
+    fn test_wikilink_candidates() {
+        assert_eq!(
+            wikilink_candidates("notes\\todo").unwrap(),
+            vec!["notes/todo.md", "notes/todo.markdown", "notes/todo"]
+        );
+        assert_eq!(wikilink_candidates("Guide.MD").unwrap(), vec!["Guide.MD"]);
+        assert!(wikilink_candidates("../secret").is_err());
+        assert!(wikilink_candidates("/etc/passwd").is_err());
+    }
+
     #[test]
     fn test_wikilinks_excluded_from_code_blocks() {
         // Wikilinks inside code blocks should NOT be extracted
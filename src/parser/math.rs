@@ -0,0 +1,1071 @@
+//! LaTeX math (`$...$` inline, `$$...$$` display) rendered as Unicode.
+//!
+//! Like callouts and footnotes, math is rewritten before block parsing:
+//! inline math becomes plain Unicode text (`$\alpha^2$` → `α²`) and display
+//! math becomes a ```` ```math ```` code block, which the renderer draws with
+//! [`render_display`] (stacked fractions, limits above and below big
+//! operators). Math using anything not handled here (environments, unknown
+//! commands) is left as written.
+
+use super::utils::{FenceTracker, code_span_ranges};
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
+
+/// Math in markdown source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MathSpan {
+    /// TeX source without the `$` delimiters
+    pub tex: String,
+    /// `$$...$$` starting a line and ending one
+    pub display: bool,
+    /// 1-indexed line of the opening delimiter
+    pub line: usize,
+    /// Byte range including the delimiters (whole lines for display math)
+    range: Range<usize>,
+}
+
+/// All math in markdown source, skipping code blocks and code spans.
+///
+/// Inline math follows Pandoc's rules so prices aren't mistaken for math: the
+/// opening `$` must be followed by a non-space, and the closing `$` preceded
+/// by a non-space and not followed by a digit.
+pub fn find_math(source: &str) -> Vec<MathSpan> {
+    let mut spans = Vec::new();
+    if !source.contains('$') {
+        return spans;
+    }
+
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for raw_line in source.split_inclusive('\n') {
+        lines.push((offset, raw_line.trim_end_matches(['\n', '\r'])));
+        offset += raw_line.len();
+    }
+
+    let mut fences = FenceTracker::default();
+    let mut idx = 0;
+    while idx < lines.len() {
+        let (start, line) = lines[idx];
+
+        if fences.line(line).is_code() {
+            idx += 1;
+            continue;
+        }
+
+        if let Some((tex, last)) = display_math(&lines, idx) {
+            let (last_start, last_line) = lines[last];
+            spans.push(MathSpan {
+                tex,
+                display: true,
+                line: idx + 1,
+                range: start..last_start + last_line.len(),
+            });
+            idx = last + 1;
+            continue;
+        }
+
+        inline_math(line, start, idx + 1, &mut spans);
+        idx += 1;
+    }
+
+    spans
+}
+
+/// Display math opening at `lines[idx]`: its TeX and the index of its last line
+fn display_math(lines: &[(usize, &str)], idx: usize) -> Option<(String, usize)> {
+    let line = lines[idx].1;
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let rest = trimmed.strip_prefix("$$")?.trim_end();
+
+    // `$$ x $$` on one line
+    if let Some(tex) = rest.strip_suffix("$$") {
+        return (!tex.trim().is_empty() && !tex.contains('$')).then(|| (tex.to_string(), idx));
+    }
+    if rest.contains('$') {
+        return None;
+    }
+
+    let mut tex = vec![rest];
+    for (last, &(_, next)) in lines.iter().enumerate().skip(idx + 1) {
+        let next = next.trim_end();
+        if next.trim().is_empty() {
+            return None;
+        }
+        if let Some(end) = next.strip_suffix("$$") {
+            tex.push(end);
+            return Some((tex.join("\n"), last));
+        }
+        tex.push(next);
+    }
+    None
+}
+
+/// Collect the inline math of one line
+fn inline_math(line: &str, line_start: usize, line_no: usize, spans: &mut Vec<MathSpan>) {
+    let bytes = line.as_bytes();
+    let code_spans = code_span_ranges(line);
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if let Some(span) = code_spans.iter().find(|span| span.contains(&i)) {
+            i = span.end;
+            continue;
+        }
+        match bytes[i] {
+            b'\\' => {
+                i += 2;
+                continue;
+            }
+            b'$' => {}
+            _ => {
+                i += 1;
+                continue;
+            }
+        }
+
+        let double = bytes.get(i + 1) == Some(&b'$');
+        let open_end = i + if double { 2 } else { 1 };
+        if bytes.get(open_end).is_none_or(|b| b.is_ascii_whitespace()) {
+            i = open_end;
+            continue;
+        }
+
+        let mut j = open_end;
+        let mut close = None;
+        while j < bytes.len() {
+            match bytes[j] {
+                b'\\' => j += 1,
+                b'$' if double && bytes.get(j + 1) == Some(&b'$') => {
+                    close = Some(j);
+                    break;
+                }
+                b'$' if double => {}
+                b'$' => {
+                    if !bytes[j - 1].is_ascii_whitespace()
+                        && !bytes.get(j + 1).is_some_and(u8::is_ascii_digit)
+                    {
+                        close = Some(j);
+                    }
+                    break;
+                }
+                _ => {}
+            }
+            j += 1;
+        }
+
+        match close {
+            Some(close) => {
+                let end = close + if double { 2 } else { 1 };
+                spans.push(MathSpan {
+                    tex: line[open_end..close].to_string(),
+                    display: false,
+                    line: line_no,
+                    range: line_start + i..line_start + end,
+                });
+                i = end;
+            }
+            None => i = open_end,
+        }
+    }
+}
+
+/// Rewrite inline math into Unicode text and display math into
+/// ```` ```math ```` blocks
+pub fn rewrite_math(markdown: &str) -> String {
+    let spans = find_math(markdown);
+    if spans.is_empty() {
+        return markdown.to_string();
+    }
+
+    let mut result = String::with_capacity(markdown.len());
+    let mut pos = 0;
+    for span in spans {
+        let source = &markdown[span.range.clone()];
+        result.push_str(&markdown[pos..span.range.start]);
+        if span.display {
+            let indent = &source[..source.len() - source.trim_start_matches(' ').len()];
+            result.push_str(indent);
+            result.push_str("```math\n");
+            for line in span.tex.trim().lines() {
+                result.push_str(indent);
+                result.push_str(line.trim());
+                result.push('\n');
+            }
+            result.push_str(indent);
+            result.push_str("```");
+        } else {
+            match to_unicode(&span.tex) {
+                Some(text) => result.push_str(&escape_markdown(&text)),
+                None => result.push_str(source),
+            }
+        }
+        pos = span.range.end;
+    }
+    result.push_str(&markdown[pos..]);
+    result
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '~'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// TeX as a single line of Unicode, or `None` if it uses anything unsupported
+pub fn to_unicode(tex: &str) -> Option<String> {
+    let nodes = Parser::new(tex).parse()?;
+    let text: String = nodes.iter().map(Node::inline).collect();
+    Some(text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// TeX laid out over several lines, with fractions stacked and limits above
+/// and below big operators. `None` if it uses anything unsupported.
+pub fn render_display(tex: &str) -> Option<Vec<String>> {
+    let nodes = Parser::new(tex).parse()?;
+    let layout = MathBox::beside(nodes.iter().map(Node::layout).collect());
+    Some(
+        layout
+            .lines
+            .iter()
+            .map(|line| line.trim_end().to_string())
+            .collect(),
+    )
+}
+
+/// Parsed TeX
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    /// Drawn as is
+    Text(String),
+    /// `{...}`
+    Group(Vec<Node>),
+    /// `\sum`, `\int`, `\lim`, ...: scripts become limits in display math
+    BigOp(String),
+    Scripts {
+        base: Box<Node>,
+        sub: Option<Box<Node>>,
+        sup: Option<Box<Node>>,
+    },
+    Frac(Box<Node>, Box<Node>),
+    Sqrt {
+        index: Option<Box<Node>>,
+        arg: Box<Node>,
+    },
+}
+
+impl Node {
+    /// Single-line form
+    fn inline(&self) -> String {
+        match self {
+            Node::Text(text) | Node::BigOp(text) => text.clone(),
+            Node::Group(nodes) => nodes.iter().map(Node::inline).collect(),
+            Node::Scripts { base, sub, sup } => {
+                let mut text = base.inline();
+                if let Some(sub) = sub {
+                    text.push_str(&script(&sub.inline(), subscript, '_'));
+                }
+                if let Some(sup) = sup {
+                    text.push_str(&script(&sup.inline(), superscript, '^'));
+                }
+                text
+            }
+            Node::Frac(num, den) => {
+                let (num, den) = (num.inline(), den.inline());
+                match vulgar_fraction(num.trim(), den.trim()) {
+                    Some(fraction) => fraction.to_string(),
+                    None => format!("{}/{}", parenthesize(&num), parenthesize(&den)),
+                }
+            }
+            Node::Sqrt { index, arg } => {
+                let root = match index.as_ref().map(|index| index.inline()).as_deref() {
+                    None => "√".to_string(),
+                    Some("3") => "∛".to_string(),
+                    Some("4") => "∜".to_string(),
+                    Some(index) => format!("{}√", script(index, superscript, '^')),
+                };
+                format!("{}{}", root, parenthesize(&arg.inline()))
+            }
+        }
+    }
+
+    /// Multi-line form
+    fn layout(&self) -> MathBox {
+        match self {
+            Node::Group(nodes) => MathBox::beside(nodes.iter().map(Node::layout).collect()),
+            Node::Frac(num, den) => {
+                let (num, den) = (num.layout(), den.layout());
+                let width = num.width().max(den.width());
+                MathBox::stack(num, MathBox::text("─".repeat(width)), den)
+            }
+            Node::Scripts { base, sub, sup } if matches!(**base, Node::BigOp(_)) => {
+                let empty = || MathBox::text(String::new());
+                MathBox::stack(
+                    sup.as_ref().map_or_else(empty, |sup| sup.layout()),
+                    base.layout(),
+                    sub.as_ref().map_or_else(empty, |sub| sub.layout()),
+                )
+            }
+            Node::Sqrt { index: None, arg } if arg.is_tall() => {
+                MathBox::beside(vec![MathBox::text("√".to_string()), arg.layout()])
+            }
+            // Scripts too tall for Unicode sub/superscripts go beside the base
+            Node::Scripts { base, sub, sup } if self.is_tall() => {
+                let mut parts = vec![base.layout()];
+                for (marker, script) in [("_", sub), ("^", sup)] {
+                    if let Some(script) = script {
+                        parts.push(MathBox::text(marker.to_string()));
+                        parts.push(script.layout());
+                    }
+                }
+                MathBox::beside(parts)
+            }
+            _ => MathBox::text(self.inline()),
+        }
+    }
+
+    /// Whether the display form takes more than one line
+    fn is_tall(&self) -> bool {
+        match self {
+            Node::Text(_) | Node::BigOp(_) => false,
+            Node::Frac(..) => true,
+            Node::Group(nodes) => nodes.iter().any(Node::is_tall),
+            Node::Scripts { base, sub, sup } => {
+                (matches!(**base, Node::BigOp(_)) && (sub.is_some() || sup.is_some()))
+                    || base.is_tall()
+                    || sub.as_ref().is_some_and(|sub| sub.is_tall())
+                    || sup.as_ref().is_some_and(|sup| sup.is_tall())
+            }
+            Node::Sqrt { arg, .. } => arg.is_tall(),
+        }
+    }
+}
+
+/// Block of text lines with the row the surrounding line of math runs through
+#[derive(Debug, Clone)]
+struct MathBox {
+    lines: Vec<String>,
+    baseline: usize,
+}
+
+impl MathBox {
+    fn text(text: String) -> Self {
+        Self {
+            lines: vec![text],
+            baseline: 0,
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.lines
+            .iter()
+            .map(|line| line.width())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Boxes side by side, aligned on their baselines
+    fn beside(boxes: Vec<MathBox>) -> Self {
+        let above = boxes.iter().map(|b| b.baseline).max().unwrap_or(0);
+        let below = boxes
+            .iter()
+            .map(|b| b.lines.len() - b.baseline - 1)
+            .max()
+            .unwrap_or(0);
+
+        let mut lines = vec![String::new(); above + below + 1];
+        for b in &boxes {
+            let width = b.width();
+            let top = above - b.baseline;
+            for (row, line) in lines.iter_mut().enumerate() {
+                let text = row
+                    .checked_sub(top)
+                    .and_then(|i| b.lines.get(i))
+                    .map_or("", String::as_str);
+                line.push_str(text);
+                line.push_str(&" ".repeat(width - text.width()));
+            }
+        }
+
+        Self {
+            lines,
+            baseline: above,
+        }
+    }
+
+    /// Three boxes stacked and centered, with the baseline on the middle one
+    fn stack(top: MathBox, middle: MathBox, bottom: MathBox) -> Self {
+        let width = top.width().max(middle.width()).max(bottom.width());
+        let center = |line: &String| {
+            let left = (width - line.width()) / 2;
+            format!(
+                "{}{}{}",
+                " ".repeat(left),
+                line,
+                " ".repeat(width - left - line.width())
+            )
+        };
+
+        let top_lines: Vec<String> = top
+            .lines
+            .iter()
+            .filter(|line| !line.is_empty())
+            .map(center)
+            .collect();
+        let baseline = top_lines.len() + middle.baseline;
+        let lines = top_lines
+            .into_iter()
+            .chain(middle.lines.iter().map(center))
+            .chain(
+                bottom
+                    .lines
+                    .iter()
+                    .filter(|line| !line.is_empty())
+                    .map(center),
+            )
+            .collect();
+
+        Self { lines, baseline }
+    }
+}
+
+/// Recursive-descent parser over TeX source
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(tex: &'a str) -> Self {
+        Self {
+            chars: tex.chars().peekable(),
+        }
+    }
+
+    fn parse(mut self) -> Option<Vec<Node>> {
+        self.sequence(false)
+    }
+
+    /// Nodes up to the end of input, or up to `}` inside a group
+    fn sequence(&mut self, in_group: bool) -> Option<Vec<Node>> {
+        let mut nodes = Vec::new();
+        loop {
+            match self.chars.peek() {
+                None if in_group => return None,
+                None => return Some(nodes),
+                Some('}') if in_group => {
+                    self.chars.next();
+                    return Some(nodes);
+                }
+                Some('}') => return None,
+                Some(&c @ ('^' | '_')) => {
+                    self.chars.next();
+                    let script = Box::new(self.argument()?);
+                    let base = nodes.pop().unwrap_or(Node::Text(String::new()));
+                    nodes.push(attach_script(base, c, script)?);
+                }
+                Some(_) => nodes.push(self.atom()?),
+            }
+        }
+    }
+
+    /// A `{group}` or a single atom, as taken by `^`, `_` and commands
+    fn argument(&mut self) -> Option<Node> {
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'{') {
+            self.chars.next();
+            return Some(Node::Group(self.sequence(true)?));
+        }
+        self.atom()
+    }
+
+    /// Raw text of a `{...}` argument, for `\text` and friends
+    fn raw_argument(&mut self) -> Option<String> {
+        self.skip_whitespace();
+        if self.chars.next()? != '{' {
+            return None;
+        }
+        let mut depth = 0;
+        let mut text = String::new();
+        loop {
+            match self.chars.next()? {
+                '{' => depth += 1,
+                '}' if depth == 0 => return Some(text),
+                '}' => depth -= 1,
+                c => text.push(c),
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn atom(&mut self) -> Option<Node> {
+        let c = self.chars.next()?;
+        let text = match c {
+            '{' => return Some(Node::Group(self.sequence(true)?)),
+            '\\' => return self.command(),
+            c if c.is_whitespace() => {
+                self.skip_whitespace();
+                " ".to_string()
+            }
+            // Alignment and comments only make sense in environments
+            '&' | '#' | '%' | '$' => return None,
+            '\'' => "′".to_string(),
+            '-' => "−".to_string(),
+            '~' => " ".to_string(),
+            c => c.to_string(),
+        };
+        Some(Node::Text(text))
+    }
+
+    fn command(&mut self) -> Option<Node> {
+        let mut name = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_alphabetic()) {
+            name.push(c);
+        }
+        if name.is_empty() {
+            name.push(self.chars.next()?);
+        }
+
+        let node = match name.as_str() {
+            "frac" | "dfrac" | "tfrac" => {
+                let num = self.argument()?;
+                let den = self.argument()?;
+                Node::Frac(Box::new(num), Box::new(den))
+            }
+            "sqrt" => {
+                self.skip_whitespace();
+                let index = if self.chars.next_if_eq(&'[').is_some() {
+                    let mut index = String::new();
+                    loop {
+                        match self.chars.next()? {
+                            ']' => break,
+                            c => index.push(c),
+                        }
+                    }
+                    Some(Box::new(Node::Group(Parser::new(&index).parse()?)))
+                } else {
+                    None
+                };
+                Node::Sqrt {
+                    index,
+                    arg: Box::new(self.argument()?),
+                }
+            }
+            // Sized delimiters are drawn at normal size
+            "left" | "right" | "big" | "Big" | "bigg" | "Bigg" | "bigl" | "bigr" | "Bigl"
+            | "Bigr" | "biggl" | "biggr" | "Biggl" | "Biggr" => {
+                self.skip_whitespace();
+                match self.chars.peek()? {
+                    '.' => {
+                        self.chars.next();
+                        Node::Text(String::new())
+                    }
+                    _ => self.atom()?,
+                }
+            }
+            "text" | "textrm" | "textit" | "textbf" | "textsf" | "texttt" | "mbox" => {
+                Node::Text(self.raw_argument()?)
+            }
+            // Font and style changes are dropped
+            "mathrm" | "mathit" | "mathbf" | "mathsf" | "mathtt" | "boldsymbol" | "bm"
+            | "mathcal" | "operatorname" => self.argument()?,
+            "displaystyle" | "textstyle" => Node::Text(String::new()),
+            "mathbb" => Node::Text(
+                self.raw_argument()?
+                    .trim()
+                    .chars()
+                    .map(double_struck)
+                    .collect::<Option<String>>()?,
+            ),
+            "not" => Node::Text(format!("{}\u{0338}", self.argument()?.inline())),
+            _ => {
+                if let Some(accent) = accent(&name) {
+                    let base = self.argument()?.inline();
+                    return Some(Node::Text(base.chars().flat_map(|c| [c, accent]).collect()));
+                }
+                if let Some(op) = big_operator(&name) {
+                    return Some(Node::BigOp(op.to_string()));
+                }
+                Node::Text(symbol(&name)?.to_string())
+            }
+        };
+        Some(node)
+    }
+}
+
+/// Attach a `_` or `^` script to the node before it
+fn attach_script(base: Node, kind: char, script: Box<Node>) -> Option<Node> {
+    let (base, mut sub, mut sup) = match base {
+        Node::Scripts { base, sub, sup } => (base, sub, sup),
+        base => (Box::new(base), None, None),
+    };
+    let slot = if kind == '_' { &mut sub } else { &mut sup };
+    if slot.is_some() {
+        // `x^a^b` is an error in TeX
+        return None;
+    }
+    *slot = Some(script);
+    Some(Node::Scripts { base, sub, sup })
+}
+
+/// Script text as Unicode sub/superscripts, or `_x` / `^(...)` when some
+/// character has no such form
+fn script(text: &str, map: fn(char) -> Option<char>, marker: char) -> String {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    match text.chars().map(map).collect::<Option<String>>() {
+        Some(mapped) => mapped,
+        None if text.chars().count() == 1 => format!("{}{}", marker, text),
+        None => format!("{}({})", marker, text),
+    }
+}
+
+/// Wrap in parentheses unless it reads as a single term
+fn parenthesize(text: &str) -> String {
+    let text = text.trim();
+    if text.chars().count() <= 1 || text.chars().all(|c| c.is_alphanumeric() || c == '′') {
+        text.to_string()
+    } else {
+        format!("({})", text)
+    }
+}
+
+fn vulgar_fraction(num: &str, den: &str) -> Option<char> {
+    let fraction = match (num, den) {
+        ("1", "2") => '½',
+        ("1", "3") => '⅓',
+        ("2", "3") => '⅔',
+        ("1", "4") => '¼',
+        ("3", "4") => '¾',
+        ("1", "5") => '⅕',
+        ("1", "6") => '⅙',
+        ("1", "8") => '⅛',
+        ("3", "8") => '⅜',
+        ("1", "10") => '⅒',
+        _ => return None,
+    };
+    Some(fraction)
+}
+
+fn superscript(c: char) -> Option<char> {
+    let sup = match c {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        '+' => '⁺',
+        '-' | '−' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        '′' => '′',
+        'a' => 'ᵃ',
+        'b' => 'ᵇ',
+        'c' => 'ᶜ',
+        'd' => 'ᵈ',
+        'e' => 'ᵉ',
+        'f' => 'ᶠ',
+        'g' => 'ᵍ',
+        'h' => 'ʰ',
+        'i' => 'ⁱ',
+        'j' => 'ʲ',
+        'k' => 'ᵏ',
+        'l' => 'ˡ',
+        'm' => 'ᵐ',
+        'n' => 'ⁿ',
+        'o' => 'ᵒ',
+        'p' => 'ᵖ',
+        'r' => 'ʳ',
+        's' => 'ˢ',
+        't' => 'ᵗ',
+        'u' => 'ᵘ',
+        'v' => 'ᵛ',
+        'w' => 'ʷ',
+        'x' => 'ˣ',
+        'y' => 'ʸ',
+        'z' => 'ᶻ',
+        'A' => 'ᴬ',
+        'B' => 'ᴮ',
+        'D' => 'ᴰ',
+        'E' => 'ᴱ',
+        'G' => 'ᴳ',
+        'H' => 'ᴴ',
+        'I' => 'ᴵ',
+        'J' => 'ᴶ',
+        'K' => 'ᴷ',
+        'L' => 'ᴸ',
+        'M' => 'ᴹ',
+        'N' => 'ᴺ',
+        'O' => 'ᴼ',
+        'P' => 'ᴾ',
+        'R' => 'ᴿ',
+        'T' => 'ᵀ',
+        'U' => 'ᵁ',
+        'V' => 'ⱽ',
+        'W' => 'ᵂ',
+        'α' => 'ᵅ',
+        'β' => 'ᵝ',
+        'γ' => 'ᵞ',
+        'δ' => 'ᵟ',
+        'ε' => 'ᵋ',
+        'θ' => 'ᶿ',
+        'φ' => 'ᵠ',
+        'χ' => 'ᵡ',
+        _ => return None,
+    };
+    Some(sup)
+}
+
+fn subscript(c: char) -> Option<char> {
+    let sub = match c {
+        '0' => '₀',
+        '1' => '₁',
+        '2' => '₂',
+        '3' => '₃',
+        '4' => '₄',
+        '5' => '₅',
+        '6' => '₆',
+        '7' => '₇',
+        '8' => '₈',
+        '9' => '₉',
+        '+' => '₊',
+        '-' | '−' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'h' => 'ₕ',
+        'i' => 'ᵢ',
+        'j' => 'ⱼ',
+        'k' => 'ₖ',
+        'l' => 'ₗ',
+        'm' => 'ₘ',
+        'n' => 'ₙ',
+        'o' => 'ₒ',
+        'p' => 'ₚ',
+        'r' => 'ᵣ',
+        's' => 'ₛ',
+        't' => 'ₜ',
+        'u' => 'ᵤ',
+        'v' => 'ᵥ',
+        'x' => 'ₓ',
+        'β' => 'ᵦ',
+        'γ' => 'ᵧ',
+        'ρ' => 'ᵨ',
+        'φ' => 'ᵩ',
+        'χ' => 'ᵪ',
+        _ => return None,
+    };
+    Some(sub)
+}
+
+fn double_struck(c: char) -> Option<char> {
+    let letter = match c {
+        'C' => 'ℂ',
+        'H' => 'ℍ',
+        'N' => 'ℕ',
+        'P' => 'ℙ',
+        'Q' => 'ℚ',
+        'R' => 'ℝ',
+        'Z' => 'ℤ',
+        _ => return None,
+    };
+    Some(letter)
+}
+
+/// Combining character for an accent command
+fn accent(name: &str) -> Option<char> {
+    let mark = match name {
+        "hat" | "widehat" => '\u{0302}',
+        "tilde" | "widetilde" => '\u{0303}',
+        "bar" | "overline" => '\u{0304}',
+        "dot" => '\u{0307}',
+        "ddot" => '\u{0308}',
+        "vec" => '\u{20D7}',
+        _ => return None,
+    };
+    Some(mark)
+}
+
+/// Operators whose scripts are limits in display math
+fn big_operator(name: &str) -> Option<&'static str> {
+    let op = match name {
+        "sum" => "∑",
+        "prod" => "∏",
+        "coprod" => "∐",
+        "int" => "∫",
+        "iint" => "∬",
+        "iiint" => "∭",
+        "oint" => "∮",
+        "bigcup" => "⋃",
+        "bigcap" => "⋂",
+        "bigoplus" => "⨁",
+        "bigotimes" => "⨂",
+        "lim" => "lim",
+        "limsup" => "lim sup",
+        "liminf" => "lim inf",
+        "max" => "max",
+        "min" => "min",
+        "sup" => "sup",
+        "inf" => "inf",
+        "det" => "det",
+        "gcd" => "gcd",
+        "argmax" => "arg max",
+        "argmin" => "arg min",
+        _ => return None,
+    };
+    Some(op)
+}
+
+fn symbol(name: &str) -> Option<&'static str> {
+    let symbol = match name {
+        // Greek
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" => "ϵ",
+        "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" => "θ",
+        "vartheta" => "ϑ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "omicron" => "ο",
+        "pi" => "π",
+        "varpi" => "ϖ",
+        "rho" => "ρ",
+        "varrho" => "ϱ",
+        "sigma" => "σ",
+        "varsigma" => "ς",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" => "ϕ",
+        "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Upsilon" => "Υ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        // Operators and relations
+        "times" => "×",
+        "cdot" => "⋅",
+        "div" => "÷",
+        "pm" => "±",
+        "mp" => "∓",
+        "ast" => "∗",
+        "star" => "⋆",
+        "circ" => "∘",
+        "bullet" => "•",
+        "oplus" => "⊕",
+        "otimes" => "⊗",
+        "setminus" => "∖",
+        "cup" => "∪",
+        "cap" => "∩",
+        "wedge" | "land" => "∧",
+        "vee" | "lor" => "∨",
+        "neg" | "lnot" => "¬",
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "neq" | "ne" => "≠",
+        "ll" => "≪",
+        "gg" => "≫",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "simeq" => "≃",
+        "cong" => "≅",
+        "propto" => "∝",
+        "in" => "∈",
+        "notin" => "∉",
+        "ni" => "∋",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "supset" => "⊃",
+        "supseteq" => "⊇",
+        "mid" => "∣",
+        "parallel" => "∥",
+        "perp" => "⊥",
+        "vdash" => "⊢",
+        "models" => "⊨",
+        // Arrows
+        "to" | "rightarrow" => "→",
+        "leftarrow" | "gets" => "←",
+        "leftrightarrow" => "↔",
+        "Rightarrow" => "⇒",
+        "Leftarrow" => "⇐",
+        "Leftrightarrow" => "⇔",
+        "implies" => "⟹",
+        "iff" => "⟺",
+        "mapsto" => "↦",
+        "uparrow" => "↑",
+        "downarrow" => "↓",
+        // Misc symbols
+        "infty" => "∞",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "forall" => "∀",
+        "exists" => "∃",
+        "emptyset" | "varnothing" => "∅",
+        "angle" => "∠",
+        "degree" => "°",
+        "prime" => "′",
+        "hbar" => "ℏ",
+        "ell" => "ℓ",
+        "Re" => "ℜ",
+        "Im" => "ℑ",
+        "aleph" => "ℵ",
+        "top" => "⊤",
+        "bot" => "⊥",
+        "ldots" | "dots" => "…",
+        "cdots" => "⋯",
+        "vdots" => "⋮",
+        "ddots" => "⋱",
+        // Delimiters
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "vert" | "lvert" | "rvert" | "|" => "|",
+        "Vert" | "lVert" | "rVert" => "‖",
+        "{" | "lbrace" => "{",
+        "}" | "rbrace" => "}",
+        // Function names
+        "sin" => "sin",
+        "cos" => "cos",
+        "tan" => "tan",
+        "cot" => "cot",
+        "sec" => "sec",
+        "csc" => "csc",
+        "arcsin" => "arcsin",
+        "arccos" => "arccos",
+        "arctan" => "arctan",
+        "sinh" => "sinh",
+        "cosh" => "cosh",
+        "tanh" => "tanh",
+        "log" => "log",
+        "ln" => "ln",
+        "lg" => "lg",
+        "exp" => "exp",
+        "dim" => "dim",
+        "ker" => "ker",
+        "deg" => "deg",
+        "arg" => "arg",
+        "Pr" => "Pr",
+        "mod" | "bmod" => "mod",
+        // Spacing and escapes
+        "," | ":" | ";" | " " => " ",
+        "quad" => "  ",
+        "qquad" => "    ",
+        "!" => "",
+        "%" => "%",
+        "$" => "$",
+        "&" => "&",
+        "#" => "#",
+        "_" => "_",
+        _ => return None,
+    };
+    Some(symbol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_math() {
+        let md = "Mass $E = mc^2$ costs $5 and $10.\n\n`$x$` in code\n\n$$\n\\sum_{i=1}^n i\n$$\n\n```\n$y$\n```\nAlso $$a+b$$ inline.\n";
+        let spans = find_math(md);
+        let spans: Vec<(&str, bool, usize)> = spans
+            .iter()
+            .map(|m| (m.tex.as_str(), m.display, m.line))
+            .collect();
+
+        assert_eq!(
+            spans,
+            vec![
+                ("E = mc^2", false, 1),
+                ("\n\\sum_{i=1}^n i\n", true, 5),
+                ("a+b", false, 12),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_unicode() {
+        assert_eq!(to_unicode(r"E = mc^2").unwrap(), "E = mc²");
+        assert_eq!(to_unicode(r"\alpha_i + \beta^{n+1}").unwrap(), "αᵢ + βⁿ⁺¹");
+        assert_eq!(to_unicode(r"\frac{1}{2} \leq x").unwrap(), "½ ≤ x");
+        assert_eq!(to_unicode(r"\frac{a+b}{c}").unwrap(), "(a+b)/c");
+        assert_eq!(to_unicode(r"\sqrt{x^2 + y^2}").unwrap(), "√(x² + y²)");
+        assert_eq!(to_unicode(r"\sum_{i=1}^{n} x_i").unwrap(), "∑ᵢ₌₁ⁿ xᵢ");
+        assert_eq!(to_unicode(r"x \in \mathbb{R}").unwrap(), "x ∈ ℝ");
+        assert_eq!(
+            to_unicode(r"\lim_{x \to 0} f(x)").unwrap(),
+            "lim_(x→0) f(x)"
+        );
+        assert_eq!(to_unicode(r"\text{if } x > 0").unwrap(), "if x > 0");
+        assert_eq!(to_unicode(r"\begin{matrix} a \end{matrix}"), None);
+        assert_eq!(to_unicode(r"\unknown{x}"), None);
+        assert_eq!(to_unicode(r"\frac{1}{2"), None);
+    }
+
+    #[test]
+    fn test_render_display() {
+        assert_eq!(
+            render_display(r"x = \frac{-b \pm \sqrt{b^2 - 4ac}}{2a}").unwrap(),
+            vec![
+                "    −b ± √(b² − 4ac)",
+                "x = ────────────────",
+                "           2a",
+            ]
+        );
+        assert_eq!(
+            render_display(r"\sum_{i=1}^{n} i").unwrap(),
+            vec![" n", " ∑  i", "i=1"]
+        );
+    }
+
+    #[test]
+    fn test_rewrite_math() {
+        let md = "Let $x_1 * y$ and $\\foo$.\n\n$$\n\\frac{a}{b}\n$$\n";
+        assert_eq!(
+            rewrite_math(md),
+            "Let x₁ \\* y and $\\foo$.\n\n```math\n\\frac{a}{b}\n```\n"
+        );
+    }
+}
@@ -0,0 +1,1403 @@
+//! Text rendering of Mermaid diagrams.
+//!
+//! Flowcharts (`graph` / `flowchart`, any direction) and sequence diagrams
+//! are drawn with box-drawing characters. Other diagram types, and syntax
+//! this module doesn't understand, give `None` so callers can show the
+//! source instead.
+//!
+//! Flowcharts use a simple layered layout: each node goes in the layer of its
+//! longest path from a root, edges spanning several layers pass through
+//! placeholder slots, and layers are ordered by the average position of their
+//! neighbours. Edges that close a cycle aren't drawn but listed under the
+//! diagram.
+
+use super::code_blocks::{find_fenced_blocks, replace_block_content};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Render a Mermaid diagram, or `None` if its type or syntax isn't supported
+pub fn render_mermaid(source: &str) -> Option<Vec<String>> {
+    let mut statements = source
+        .lines()
+        .map(|line| line.split("%%").next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty());
+    let mut header = statements.next()?.split_whitespace();
+    match header.next()?.trim_end_matches(';') {
+        "graph" | "flowchart" => {
+            let direction = match header.next().unwrap_or("TD").trim_end_matches(';') {
+                "TD" | "TB" => Direction::Down,
+                "BT" => Direction::Up,
+                "LR" => Direction::Right,
+                "RL" => Direction::Left,
+                _ => return None,
+            };
+            Some(Flowchart::parse(direction, statements)?.render())
+        }
+        "sequenceDiagram" => Some(Sequence::parse(statements)?.render()),
+        _ => None,
+    }
+}
+
+/// Replace each ```` ```mermaid ```` block that can be rendered with a
+/// ```` ```text ```` block holding its diagram
+pub fn render_mermaid_blocks(markdown: &str) -> String {
+    let mut result = markdown.to_string();
+    // Back to front, so earlier offsets stay valid
+    for block in find_fenced_blocks(markdown).iter().rev() {
+        if block.language.as_deref() != Some("mermaid") {
+            continue;
+        }
+        let Some(diagram) = render_mermaid(&block.content) else {
+            continue;
+        };
+        result = replace_block_content(&result, block, &diagram.join("\n"));
+        let fence_start = result[..block.body.start - 1]
+            .rfind('\n')
+            .map_or(0, |i| i + 1);
+        let fence = result[fence_start..block.body.start].replacen("mermaid", "text", 1);
+        result.replace_range(fence_start..block.body.start, &fence);
+    }
+    result
+}
+
+// ---------------------------------------------------------------------------
+// Canvas
+// ---------------------------------------------------------------------------
+
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+
+/// Placeholder for the second cell of a double-width character
+const WIDE: char = '\0';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Stroke {
+    #[default]
+    Solid,
+    Dotted,
+    Thick,
+}
+
+/// A canvas cell: a fixed character, or line directions that are joined
+/// into box-drawing characters when rendered
+#[derive(Debug, Clone, Copy, Default)]
+struct Cell {
+    ch: Option<char>,
+    lines: u8,
+    stroke: Stroke,
+}
+
+/// Character grid that grows as it's drawn on
+#[derive(Debug, Default)]
+struct Canvas {
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Canvas {
+    fn cell(&mut self, x: usize, y: usize) -> &mut Cell {
+        if self.rows.len() <= y {
+            self.rows.resize_with(y + 1, Vec::new);
+        }
+        let row = &mut self.rows[y];
+        if row.len() <= x {
+            row.resize(x + 1, Cell::default());
+        }
+        &mut row[x]
+    }
+
+    fn is_empty(&self, x: usize, y: usize) -> bool {
+        self.rows
+            .get(y)
+            .and_then(|row| row.get(x))
+            .is_none_or(|cell| cell.ch.is_none() && cell.lines == 0)
+    }
+
+    fn width(&self) -> usize {
+        self.rows.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    fn put(&mut self, x: usize, y: usize, ch: char) {
+        self.cell(x, y).ch = Some(ch);
+    }
+
+    fn text(&mut self, x: usize, y: usize, text: &str) {
+        let mut x = x;
+        for ch in text.chars() {
+            let width = ch.width().unwrap_or(0);
+            if width == 0 {
+                continue;
+            }
+            self.put(x, y, ch);
+            for i in 1..width {
+                self.put(x + i, y, WIDE);
+            }
+            x += width;
+        }
+    }
+
+    /// Write `text` only if all of its cells are free
+    fn try_text(&mut self, x: usize, y: usize, text: &str) -> bool {
+        let free = (x..x + text.width()).all(|x| self.is_empty(x, y));
+        if free {
+            self.text(x, y, text);
+        }
+        free
+    }
+
+    /// Straight horizontal or vertical line, end points included
+    fn line(&mut self, from: (usize, usize), to: (usize, usize), stroke: Stroke) {
+        let ((x1, y1), (x2, y2)) = (from, to);
+        if y1 == y2 {
+            let (a, b) = (x1.min(x2), x1.max(x2));
+            for x in a..=b {
+                let lines = if x > a { LEFT } else { 0 } | if x < b { RIGHT } else { 0 };
+                self.add_lines(x, y1, lines, stroke);
+            }
+        } else {
+            let (a, b) = (y1.min(y2), y1.max(y2));
+            for y in a..=b {
+                let lines = if y > a { UP } else { 0 } | if y < b { DOWN } else { 0 };
+                self.add_lines(x1, y, lines, stroke);
+            }
+        }
+    }
+
+    fn polyline(&mut self, points: &[(usize, usize)], stroke: Stroke) {
+        for pair in points.windows(2) {
+            self.line(pair[0], pair[1], stroke);
+        }
+    }
+
+    fn add_lines(&mut self, x: usize, y: usize, lines: u8, stroke: Stroke) {
+        let cell = self.cell(x, y);
+        cell.lines |= lines;
+        if stroke != Stroke::Solid {
+            cell.stroke = stroke;
+        }
+    }
+
+    /// Draw a box around a one-line label, `label width + 4` cells wide
+    fn draw_box(&mut self, left: usize, top: usize, label: &str, shape: Shape) {
+        let inner = label.width() + 2;
+        let (top_left, top_right, bottom_left, bottom_right, side_left, side_right) = match shape {
+            Shape::Rect => ('┌', '┐', '└', '┘', '│', '│'),
+            Shape::Round => ('╭', '╮', '╰', '╯', '│', '│'),
+            Shape::Diamond => ('╱', '╲', '╲', '╱', '<', '>'),
+        };
+        let right = left + inner + 1;
+        self.put(left, top, top_left);
+        self.put(right, top, top_right);
+        self.put(left, top + 2, bottom_left);
+        self.put(right, top + 2, bottom_right);
+        for x in left + 1..right {
+            self.put(x, top, '─');
+            self.put(x, top + 2, '─');
+            self.put(x, top + 1, ' ');
+        }
+        self.put(left, top + 1, side_left);
+        self.put(right, top + 1, side_right);
+        self.text(left + 2, top + 1, label);
+    }
+
+    fn into_lines(self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let line: String = row
+                    .iter()
+                    .filter(|cell| cell.ch != Some(WIDE))
+                    .map(|cell| {
+                        cell.ch
+                            .or_else(|| line_char(cell.lines, cell.stroke))
+                            .unwrap_or(' ')
+                    })
+                    .collect();
+                line.trim_end().to_string()
+            })
+            .collect();
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+        lines
+    }
+}
+
+/// Box-drawing character joining the given line directions
+fn line_char(lines: u8, stroke: Stroke) -> Option<char> {
+    let up = lines & UP != 0;
+    let down = lines & DOWN != 0;
+    let left = lines & LEFT != 0;
+    let right = lines & RIGHT != 0;
+    let ch = match (up, down, left, right) {
+        (false, false, false, false) => return None,
+        (_, _, false, false) => match stroke {
+            Stroke::Solid => '│',
+            Stroke::Dotted => '┆',
+            Stroke::Thick => '┃',
+        },
+        (false, false, _, _) => match stroke {
+            Stroke::Solid => '─',
+            Stroke::Dotted => '╌',
+            Stroke::Thick => '━',
+        },
+        (false, true, false, true) => '┌',
+        (false, true, true, false) => '┐',
+        (true, false, false, true) => '└',
+        (true, false, true, false) => '┘',
+        (true, true, false, true) => '├',
+        (true, true, true, false) => '┤',
+        (false, true, true, true) => '┬',
+        (true, false, true, true) => '┴',
+        _ => '┼',
+    };
+    Some(ch)
+}
+
+// ---------------------------------------------------------------------------
+// Flowcharts
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Down,
+    Up,
+    Right,
+    Left,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    Rect,
+    Round,
+    Diamond,
+}
+
+#[derive(Debug, Clone)]
+struct FlowNode {
+    label: String,
+    shape: Shape,
+}
+
+#[derive(Debug, Clone)]
+struct FlowEdge {
+    from: usize,
+    to: usize,
+    label: Option<String>,
+    stroke: Stroke,
+    arrow: bool,
+}
+
+#[derive(Debug)]
+struct Flowchart {
+    direction: Direction,
+    ids: HashMap<String, usize>,
+    nodes: Vec<FlowNode>,
+    edges: Vec<FlowEdge>,
+}
+
+/// Statements that only affect styling or grouping
+const IGNORED: &[&str] = &[
+    "subgraph",
+    "end",
+    "classDef",
+    "class",
+    "style",
+    "linkStyle",
+    "click",
+    "direction",
+];
+
+/// Node shapes as (opening, closing) delimiters, longest first
+const SHAPES: &[(&str, &str, Shape)] = &[
+    ("(((", ")))", Shape::Round),
+    ("((", "))", Shape::Round),
+    ("([", "])", Shape::Round),
+    ("[(", ")]", Shape::Rect),
+    ("[[", "]]", Shape::Rect),
+    ("{{", "}}", Shape::Diamond),
+    ("[/", "/]", Shape::Rect),
+    ("[/", "\\]", Shape::Rect),
+    ("[\\", "\\]", Shape::Rect),
+    ("[\\", "/]", Shape::Rect),
+    ("[", "]", Shape::Rect),
+    ("(", ")", Shape::Round),
+    ("{", "}", Shape::Diamond),
+    (">", "]", Shape::Rect),
+];
+
+/// Link with its label between the dashes: `-- text -->`, `-. text .->`
+static LABELED_LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^<?(?:--|==|-\.)\s+(.+?)\s*(-{2,}>|-{3,}|={2,}>|={3,}|\.-+>|\.-+)").unwrap()
+});
+
+/// Link without a label between the dashes: `-->`, `---`, `-.->`, `==>`
+static LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^<?(?:-{2,}|={2,}|-\.+-)([>xo]?)").unwrap());
+
+/// A parsed link between nodes
+struct Link {
+    label: Option<String>,
+    stroke: Stroke,
+    arrow: bool,
+}
+
+impl Flowchart {
+    fn parse<'a>(direction: Direction, statements: impl Iterator<Item = &'a str>) -> Option<Self> {
+        let mut chart = Self {
+            direction,
+            ids: HashMap::new(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+        for line in statements {
+            for statement in line.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+                let keyword = statement.split_whitespace().next().unwrap_or("");
+                if IGNORED.contains(&keyword) {
+                    continue;
+                }
+                chart.parse_statement(statement)?;
+            }
+        }
+        (!chart.nodes.is_empty()).then_some(chart)
+    }
+
+    /// A chain of node groups joined by links: `A & B --> C -- yes --> D`
+    fn parse_statement(&mut self, statement: &str) -> Option<()> {
+        let (mut sources, mut rest) = self.parse_node_group(statement)?;
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                return Some(());
+            }
+            let (link, after) = parse_link(rest)?;
+            let (targets, after) = self.parse_node_group(after)?;
+            for &from in &sources {
+                for &to in &targets {
+                    self.edges.push(FlowEdge {
+                        from,
+                        to,
+                        label: link.label.clone(),
+                        stroke: link.stroke,
+                        arrow: link.arrow,
+                    });
+                }
+            }
+            sources = targets;
+            rest = after;
+        }
+    }
+
+    fn parse_node_group<'s>(&mut self, text: &'s str) -> Option<(Vec<usize>, &'s str)> {
+        let mut nodes = Vec::new();
+        let mut rest = text;
+        loop {
+            let (node, after) = self.parse_node(rest.trim_start())?;
+            nodes.push(node);
+            rest = after.trim_start();
+            match rest.strip_prefix('&') {
+                Some(after) => rest = after,
+                None => return Some((nodes, rest)),
+            }
+        }
+    }
+
+    /// A node id with an optional shape and label, e.g. `A{Ready?}`
+    fn parse_node<'s>(&mut self, text: &'s str) -> Option<(usize, &'s str)> {
+        let id_len = text
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(text.len());
+        if id_len == 0 {
+            return None;
+        }
+        let (id, mut rest) = text.split_at(id_len);
+
+        let index = match self.ids.get(id) {
+            Some(&index) => index,
+            None => {
+                self.nodes.push(FlowNode {
+                    label: id.to_string(),
+                    shape: Shape::Rect,
+                });
+                self.ids.insert(id.to_string(), self.nodes.len() - 1);
+                self.nodes.len() - 1
+            }
+        };
+        if let Some((label, shape, after)) = parse_shape(rest) {
+            self.nodes[index] = FlowNode { label, shape };
+            rest = after;
+        }
+        // `:::class` styling
+        if let Some(after) = rest.strip_prefix(":::") {
+            rest = after.trim_start_matches(|c: char| c.is_alphanumeric() || c == '_');
+        }
+        Some((index, rest))
+    }
+
+    fn render(&self) -> Vec<String> {
+        let layout = self.layout();
+        let mut canvas = Canvas::default();
+        match self.direction {
+            Direction::Down | Direction::Up => self.draw_vertical(&layout, &mut canvas),
+            Direction::Right | Direction::Left => self.draw_horizontal(&layout, &mut canvas),
+        }
+        let mut lines = canvas.into_lines();
+
+        if !layout.back_edges.is_empty() {
+            lines.push(String::new());
+            for &index in &layout.back_edges {
+                let edge = &self.edges[index];
+                let mut line = format!(
+                    "↻ {} {} {}",
+                    self.nodes[edge.from].label,
+                    if edge.arrow { "→" } else { "─" },
+                    self.nodes[edge.to].label
+                );
+                if let Some(label) = &edge.label {
+                    line.push_str(": ");
+                    line.push_str(label);
+                }
+                lines.push(line);
+            }
+        }
+        lines
+    }
+
+    /// Layer of each node (longest path from a root) and the edges that
+    /// close cycles, which are left out of the layering
+    fn layer_nodes(&self) -> (Vec<usize>, Vec<bool>) {
+        let mut outgoing = vec![Vec::new(); self.nodes.len()];
+        for (index, edge) in self.edges.iter().enumerate() {
+            outgoing[edge.from].push(index);
+        }
+
+        // 0 = unvisited, 1 = on the DFS stack, 2 = done
+        fn visit(
+            node: usize,
+            edges: &[FlowEdge],
+            outgoing: &[Vec<usize>],
+            state: &mut [u8],
+            back: &mut [bool],
+        ) {
+            state[node] = 1;
+            for &index in &outgoing[node] {
+                let target = edges[index].to;
+                match state[target] {
+                    0 => visit(target, edges, outgoing, state, back),
+                    1 => back[index] = true,
+                    _ => {}
+                }
+            }
+            state[node] = 2;
+        }
+        let mut state = vec![0; self.nodes.len()];
+        let mut back = vec![false; self.edges.len()];
+        for node in 0..self.nodes.len() {
+            if state[node] == 0 {
+                visit(node, &self.edges, &outgoing, &mut state, &mut back);
+            }
+        }
+
+        let mut layer = vec![0; self.nodes.len()];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (index, edge) in self.edges.iter().enumerate() {
+                if !back[index] && layer[edge.to] < layer[edge.from] + 1 {
+                    layer[edge.to] = layer[edge.from] + 1;
+                    changed = true;
+                }
+            }
+        }
+        (layer, back)
+    }
+
+    fn layout(&self) -> Layout {
+        let (mut node_layer, back) = self.layer_nodes();
+        // Upward and leftward charts are laid out top-down with layers flipped
+        if matches!(self.direction, Direction::Up | Direction::Left) {
+            let max = node_layer.iter().copied().max().unwrap_or(0);
+            for layer in &mut node_layer {
+                *layer = max - *layer;
+            }
+        }
+
+        let mut slots: Vec<Slot> = (0..self.nodes.len()).map(Slot::Node).collect();
+        let mut slot_layer = node_layer.clone();
+        let mut segments = Vec::new();
+        for (index, edge) in self.edges.iter().enumerate() {
+            if back[index] {
+                continue;
+            }
+            let (upper, lower, head) = if node_layer[edge.from] < node_layer[edge.to] {
+                (edge.from, edge.to, End::Lower)
+            } else {
+                (edge.to, edge.from, End::Upper)
+            };
+            let mut chain = vec![upper];
+            for layer in node_layer[upper] + 1..node_layer[lower] {
+                slots.push(Slot::Pass(index));
+                slot_layer.push(layer);
+                chain.push(slots.len() - 1);
+            }
+            chain.push(lower);
+
+            let last = chain.len() - 2;
+            for (i, pair) in chain.windows(2).enumerate() {
+                let at_head = match head {
+                    End::Lower => i == last,
+                    End::Upper => i == 0,
+                };
+                segments.push(Segment {
+                    upper: pair[0],
+                    lower: pair[1],
+                    edge: index,
+                    head: (edge.arrow && at_head).then_some(head),
+                    labeled: at_head,
+                });
+            }
+        }
+
+        let depth = slot_layer.iter().max().map_or(0, |max| max + 1);
+        let mut layers = vec![Vec::new(); depth];
+        for (slot, &layer) in slot_layer.iter().enumerate() {
+            layers[layer].push(slot);
+        }
+        order_layers(&mut layers, &segments);
+
+        Layout {
+            slots,
+            slot_layer,
+            layers,
+            segments,
+            back_edges: (0..self.edges.len()).filter(|&i| back[i]).collect(),
+        }
+    }
+
+    fn slot_width(&self, slot: Slot) -> usize {
+        match slot {
+            Slot::Node(node) => self.nodes[node].label.width() + 4,
+            Slot::Pass(_) => 1,
+        }
+    }
+
+    /// Top-down layout: layers are rows of boxes, edges run through the rows
+    /// between them
+    fn draw_vertical(&self, layout: &Layout, canvas: &mut Canvas) {
+        const GAP: usize = 4;
+        let widths: Vec<usize> = layout.slots.iter().map(|&s| self.slot_width(s)).collect();
+        let layer_widths: Vec<usize> = layout
+            .layers
+            .iter()
+            .map(|layer| {
+                layer.iter().map(|&s| widths[s]).sum::<usize>()
+                    + GAP * layer.len().saturating_sub(1)
+            })
+            .collect();
+        let total = layer_widths.iter().copied().max().unwrap_or(0);
+
+        let mut left = vec![0; layout.slots.len()];
+        for (layer, slots) in layout.layers.iter().enumerate() {
+            // Centered on a shared column, so single boxes line up
+            let mut x = (total / 2).saturating_sub(layer_widths[layer] / 2);
+            for &slot in slots {
+                left[slot] = x;
+                x += widths[slot] + GAP;
+            }
+        }
+        let center = |slot: usize| left[slot] + widths[slot] / 2;
+
+        // Borders of boxes in the next layer, which isn't drawn yet
+        let mut joints = Vec::new();
+        let mut y = 0;
+        for (layer, slots) in layout.layers.iter().enumerate() {
+            for &slot in slots {
+                match layout.slots[slot] {
+                    Slot::Node(node) => {
+                        let node = &self.nodes[node];
+                        canvas.draw_box(left[slot], y, &node.label, node.shape);
+                    }
+                    Slot::Pass(edge) => {
+                        let x = center(slot);
+                        canvas.line((x, y), (x, y + 2), self.edges[edge].stroke);
+                    }
+                }
+            }
+
+            let segments: Vec<&Segment> = layout
+                .segments
+                .iter()
+                .filter(|segment| layout.slot_layer[segment.upper] == layer)
+                .collect();
+            // One row per source whose edges have to move sideways
+            let mut tracks: Vec<usize> = Vec::new();
+            for segment in &segments {
+                if center(segment.upper) != center(segment.lower)
+                    && !tracks.contains(&segment.upper)
+                {
+                    tracks.push(segment.upper);
+                }
+            }
+            tracks.sort_by_key(|&slot| center(slot));
+            let labeled = segments
+                .iter()
+                .any(|segment| segment.labeled && self.edges[segment.edge].label.is_some());
+
+            let first_gap_row = y + 3;
+            let arrow_row = first_gap_row + tracks.len() + usize::from(labeled) + 1;
+            for segment in segments {
+                let edge = &self.edges[segment.edge];
+                let (from_x, to_x) = (center(segment.upper), center(segment.lower));
+                let start = (from_x, y + 2);
+                let end = (to_x, arrow_row + 1);
+                match tracks.iter().position(|&slot| slot == segment.upper) {
+                    Some(track) if from_x != to_x => {
+                        let track_row = first_gap_row + 1 + track;
+                        canvas.polyline(
+                            &[start, (from_x, track_row), (to_x, track_row), end],
+                            edge.stroke,
+                        );
+                    }
+                    _ => canvas.line(start, end, edge.stroke),
+                }
+
+                if layout.slots[segment.upper].is_node() && segment.head != Some(End::Upper) {
+                    canvas.put(from_x, y + 2, '┬');
+                }
+                if layout.slots[segment.lower].is_node() && segment.head != Some(End::Lower) {
+                    joints.push((to_x, arrow_row + 1, '┴'));
+                }
+                match segment.head {
+                    Some(End::Lower) => canvas.put(to_x, arrow_row, '▼'),
+                    Some(End::Upper) => canvas.put(from_x, first_gap_row, '▲'),
+                    None => {}
+                }
+                if segment.labeled
+                    && let Some(label) = &edge.label
+                    && !canvas.try_text(to_x + 2, arrow_row - 1, label)
+                    && let Some(x) = to_x.checked_sub(label.width() + 1)
+                {
+                    canvas.try_text(x, arrow_row - 1, label);
+                }
+            }
+            y = arrow_row + 1;
+        }
+        for (x, y, joint) in joints {
+            canvas.put(x, y, joint);
+        }
+    }
+
+    /// Left-to-right layout: layers are columns of boxes, edges run through
+    /// the columns between them
+    fn draw_horizontal(&self, layout: &Layout, canvas: &mut Canvas) {
+        let widths: Vec<usize> = layout.slots.iter().map(|&s| self.slot_width(s)).collect();
+        let heights: Vec<usize> = layout
+            .slots
+            .iter()
+            .map(|slot| if slot.is_node() { 3 } else { 1 })
+            .collect();
+        let layer_heights: Vec<usize> = layout
+            .layers
+            .iter()
+            .map(|layer| {
+                layer.iter().map(|&s| heights[s]).sum::<usize>() + layer.len().saturating_sub(1)
+            })
+            .collect();
+        let total = layer_heights.iter().copied().max().unwrap_or(0);
+
+        let mut top = vec![0; layout.slots.len()];
+        for (layer, slots) in layout.layers.iter().enumerate() {
+            let mut y = (total - layer_heights[layer]) / 2;
+            for &slot in slots {
+                top[slot] = y;
+                y += heights[slot] + 1;
+            }
+        }
+        let middle = |slot: usize| top[slot] + heights[slot] / 2;
+
+        // Borders of boxes in the next layer, which isn't drawn yet
+        let mut joints = Vec::new();
+        let mut x = 0;
+        for (layer, slots) in layout.layers.iter().enumerate() {
+            let layer_width = slots.iter().map(|&s| widths[s]).max().unwrap_or(1);
+            for &slot in slots {
+                match layout.slots[slot] {
+                    Slot::Node(node) => {
+                        let node = &self.nodes[node];
+                        canvas.draw_box(x, top[slot], &node.label, node.shape);
+                    }
+                    Slot::Pass(edge) => {
+                        let y = middle(slot);
+                        canvas.line((x, y), (x + layer_width - 1, y), self.edges[edge].stroke);
+                    }
+                }
+            }
+
+            let segments: Vec<&Segment> = layout
+                .segments
+                .iter()
+                .filter(|segment| layout.slot_layer[segment.upper] == layer)
+                .collect();
+            // One column per source whose edges have to move up or down
+            let mut tracks: Vec<usize> = Vec::new();
+            for segment in &segments {
+                if middle(segment.upper) != middle(segment.lower)
+                    && !tracks.contains(&segment.upper)
+                {
+                    tracks.push(segment.upper);
+                }
+            }
+            tracks.sort_by_key(|&slot| middle(slot));
+            let label_width = segments
+                .iter()
+                .filter(|segment| segment.labeled)
+                .filter_map(|segment| self.edges[segment.edge].label.as_ref())
+                .map(|label| label.width() + 2)
+                .max()
+                .unwrap_or(0);
+
+            let first_gap_column = x + layer_width;
+            let arrow_column = first_gap_column + tracks.len() + label_width + 2;
+            for segment in segments {
+                let edge = &self.edges[segment.edge];
+                let (from_y, to_y) = (middle(segment.upper), middle(segment.lower));
+                let exit_x = x + widths[segment.upper] - 1;
+                let start = (exit_x, from_y);
+                let end = (arrow_column + 1, to_y);
+                match tracks.iter().position(|&slot| slot == segment.upper) {
+                    Some(track) if from_y != to_y => {
+                        let track_column = first_gap_column + 1 + track;
+                        canvas.polyline(
+                            &[start, (track_column, from_y), (track_column, to_y), end],
+                            edge.stroke,
+                        );
+                    }
+                    _ => canvas.line(start, end, edge.stroke),
+                }
+
+                if layout.slots[segment.upper].is_node() && segment.head != Some(End::Upper) {
+                    canvas.put(exit_x, from_y, '├');
+                }
+                if layout.slots[segment.lower].is_node() && segment.head != Some(End::Lower) {
+                    joints.push((arrow_column + 1, to_y, '┤'));
+                }
+                match segment.head {
+                    Some(End::Lower) => canvas.put(arrow_column, to_y, '►'),
+                    Some(End::Upper) => canvas.put(exit_x + 1, from_y, '◄'),
+                    None => {}
+                }
+                if segment.labeled
+                    && let Some(label) = &edge.label
+                {
+                    canvas.text(arrow_column - 1 - label.width(), to_y, label);
+                }
+            }
+            x = arrow_column + 1;
+        }
+        for (x, y, joint) in joints {
+            canvas.put(x, y, joint);
+        }
+    }
+}
+
+/// Parse a node shape and label following its id
+fn parse_shape(text: &str) -> Option<(String, Shape, &str)> {
+    for &(open, close, shape) in SHAPES {
+        let Some(inner) = text.strip_prefix(open) else {
+            continue;
+        };
+        // A quoted label may contain the closing delimiter
+        let search_from = match inner.strip_prefix('"') {
+            Some(quoted) => quoted.find('"').map_or(0, |end| end + 2),
+            None => 0,
+        };
+        if let Some(end) = inner[search_from..].find(close) {
+            let end = search_from + end;
+            return Some((
+                clean_label(&inner[..end]),
+                shape,
+                &inner[end + close.len()..],
+            ));
+        }
+    }
+    None
+}
+
+/// Label text without quotes, with `<br>` line breaks flattened to spaces
+fn clean_label(label: &str) -> String {
+    let label = label.trim();
+    let label = label
+        .strip_prefix('"')
+        .and_then(|l| l.strip_suffix('"'))
+        .unwrap_or(label);
+    label
+        .replace("<br/>", " ")
+        .replace("<br />", " ")
+        .replace("<br>", " ")
+}
+
+fn parse_link(text: &str) -> Option<(Link, &str)> {
+    let (link, label, mut rest) = if let Some(caps) = LABELED_LINK.captures(text) {
+        let whole = caps.get(0).unwrap();
+        (
+            whole.as_str(),
+            Some(caps[1].trim().to_string()),
+            &text[whole.end()..],
+        )
+    } else {
+        let caps = LINK.captures(text)?;
+        let mut end = caps.get(0).unwrap().end();
+        // `A --o B` is a circle head, but in `A---oB` the `o` starts the node
+        let head = caps.get(1).unwrap();
+        if matches!(head.as_str(), "x" | "o")
+            && text[end..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_')
+        {
+            end = head.start();
+        }
+        (&text[..end], None, &text[end..])
+    };
+
+    let stroke = if link.contains('=') {
+        Stroke::Thick
+    } else if link.contains('.') {
+        Stroke::Dotted
+    } else {
+        Stroke::Solid
+    };
+    let arrow = link.ends_with(['>', 'x', 'o']) || link.starts_with('<');
+
+    // `-->|label|`
+    let mut label = label;
+    if let Some(after) = rest.trim_start().strip_prefix('|') {
+        let end = after.find('|')?;
+        label = Some(clean_label(&after[..end]));
+        rest = &after[end + 1..];
+    }
+
+    Some((
+        Link {
+            label: label.filter(|l| !l.is_empty()),
+            stroke,
+            arrow,
+        },
+        rest,
+    ))
+}
+
+/// An entry in a layer: a node, or a point an edge passes through
+#[derive(Debug, Clone, Copy)]
+enum Slot {
+    Node(usize),
+    /// Passing edge's index
+    Pass(usize),
+}
+
+impl Slot {
+    fn is_node(&self) -> bool {
+        matches!(self, Slot::Node(_))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum End {
+    Upper,
+    Lower,
+}
+
+/// Part of an edge between two slots in adjacent layers
+#[derive(Debug)]
+struct Segment {
+    upper: usize,
+    lower: usize,
+    edge: usize,
+    /// End with the arrowhead, if this segment has it
+    head: Option<End>,
+    /// Whether the edge's label goes on this segment
+    labeled: bool,
+}
+
+#[derive(Debug)]
+struct Layout {
+    slots: Vec<Slot>,
+    slot_layer: Vec<usize>,
+    /// Slots in each layer, in drawing order
+    layers: Vec<Vec<usize>>,
+    segments: Vec<Segment>,
+    back_edges: Vec<usize>,
+}
+
+/// Order each layer by the average position of its neighbours in the layer
+/// before it, sweeping down, up and down again
+fn order_layers(layers: &mut [Vec<usize>], segments: &[Segment]) {
+    for sweep in 0..3 {
+        let downward = sweep % 2 == 0;
+        let order: Vec<usize> = if downward {
+            (1..layers.len()).collect()
+        } else {
+            (0..layers.len().saturating_sub(1)).rev().collect()
+        };
+        for layer in order {
+            let reference = if downward { layer - 1 } else { layer + 1 };
+            let position: HashMap<usize, usize> = layers[reference]
+                .iter()
+                .enumerate()
+                .map(|(i, &slot)| (slot, i))
+                .collect();
+            let mut keyed: Vec<(f64, usize)> = layers[layer]
+                .iter()
+                .enumerate()
+                .map(|(i, &slot)| {
+                    let neighbours: Vec<usize> = segments
+                        .iter()
+                        .filter_map(|segment| match downward {
+                            true if segment.lower == slot => Some(segment.upper),
+                            false if segment.upper == slot => Some(segment.lower),
+                            _ => None,
+                        })
+                        .filter_map(|neighbour| position.get(&neighbour).copied())
+                        .collect();
+                    let key = if neighbours.is_empty() {
+                        i as f64
+                    } else {
+                        neighbours.iter().sum::<usize>() as f64 / neighbours.len() as f64
+                    };
+                    (key, slot)
+                })
+                .collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            layers[layer] = keyed.into_iter().map(|(_, slot)| slot).collect();
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Sequence diagrams
+// ---------------------------------------------------------------------------
+
+static PARTICIPANT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:participant|actor)\s+(.+?)(?:\s+as\s+(.+))?$").unwrap());
+
+static MESSAGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^([^\s:][^:]*?)\s*(-->>|->>|-->|->|--x|-x|--\)|-\))\s*[+-]?\s*([^:]+?)\s*(?::\s*(.*))?$",
+    )
+    .unwrap()
+});
+
+static NOTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[Nn]ote\s+(left of|right of|over)\s+([^:]+?)\s*:\s*(.*)$").unwrap()
+});
+
+/// Keywords that open a framed section closed by `end`
+const FRAGMENTS: &[&str] = &["loop", "alt", "opt", "par", "critical", "break", "rect"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Head {
+    Arrow,
+    None,
+    Cross,
+    Async,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placement {
+    Left,
+    Right,
+    Over,
+}
+
+#[derive(Debug)]
+enum Event {
+    Message {
+        from: usize,
+        to: usize,
+        text: String,
+        stroke: Stroke,
+        head: Head,
+    },
+    Note {
+        first: usize,
+        last: usize,
+        placement: Placement,
+        text: String,
+    },
+    /// Start of a fragment, or an `else` / `and` divider in one
+    Divider(String),
+    End,
+}
+
+#[derive(Debug, Default)]
+struct Sequence {
+    participants: Vec<(String, String)>,
+    events: Vec<Event>,
+}
+
+impl Sequence {
+    fn parse<'a>(statements: impl Iterator<Item = &'a str>) -> Option<Self> {
+        let mut diagram = Self::default();
+        let mut autonumber = None;
+        // Whether each open `end`-terminated block is a participant `box`
+        let mut blocks: Vec<bool> = Vec::new();
+
+        for line in statements {
+            let line = line.trim_end_matches(';');
+            let keyword = line.split_whitespace().next().unwrap_or("");
+            let rest = line[keyword.len()..].trim();
+            if let Some(caps) = PARTICIPANT.captures(line) {
+                let label = caps.get(2).map_or(&caps[1], |alias| alias.as_str());
+                let index = diagram.participant(&caps[1]);
+                diagram.participants[index].1 = label.trim().to_string();
+            } else if let Some(caps) = NOTE.captures(line) {
+                let mut names = caps[2].split(',');
+                let first = diagram.participant(names.next()?.trim());
+                let last = names
+                    .next()
+                    .map_or(first, |name| diagram.participant(name.trim()));
+                diagram.events.push(Event::Note {
+                    first: first.min(last),
+                    last: first.max(last),
+                    placement: match &caps[1] {
+                        "left of" => Placement::Left,
+                        "right of" => Placement::Right,
+                        _ => Placement::Over,
+                    },
+                    text: caps[3].trim().to_string(),
+                });
+            } else if FRAGMENTS.contains(&keyword) {
+                blocks.push(false);
+                diagram.events.push(Event::Divider(
+                    format!("{} {}", keyword, rest).trim().to_string(),
+                ));
+            } else if matches!(keyword, "else" | "and" | "option") {
+                diagram.events.push(Event::Divider(
+                    format!("{} {}", keyword, rest).trim().to_string(),
+                ));
+            } else if keyword == "end" {
+                if !blocks.pop()? {
+                    diagram.events.push(Event::End);
+                }
+            } else if keyword == "box" {
+                blocks.push(true);
+            } else if keyword == "autonumber" {
+                autonumber = Some(1);
+            } else if matches!(keyword, "activate" | "deactivate" | "title") {
+                continue;
+            } else if let Some(caps) = MESSAGE.captures(line) {
+                let from = diagram.participant(caps[1].trim());
+                let to = diagram.participant(caps[3].trim_start_matches(['+', '-']).trim());
+                let arrow = &caps[2];
+                let mut text = caps.get(4).map_or("", |m| m.as_str()).trim().to_string();
+                if let Some(number) = autonumber.as_mut() {
+                    text = format!("{}. {}", number, text).trim().to_string();
+                    *number += 1;
+                }
+                diagram.events.push(Event::Message {
+                    from,
+                    to,
+                    text,
+                    stroke: if arrow.starts_with("--") {
+                        Stroke::Dotted
+                    } else {
+                        Stroke::Solid
+                    },
+                    head: match arrow.trim_start_matches('-') {
+                        ">>" => Head::Arrow,
+                        "x" => Head::Cross,
+                        ")" => Head::Async,
+                        _ => Head::None,
+                    },
+                });
+            } else {
+                return None;
+            }
+        }
+        (!diagram.participants.is_empty()).then_some(diagram)
+    }
+
+    fn participant(&mut self, name: &str) -> usize {
+        match self.participants.iter().position(|(id, _)| id == name) {
+            Some(index) => index,
+            None => {
+                self.participants.push((name.to_string(), name.to_string()));
+                self.participants.len() - 1
+            }
+        }
+    }
+
+    /// Lifeline column of each participant, far enough apart for the
+    /// boxes, messages and notes between them
+    fn columns(&self) -> Vec<usize> {
+        let count = self.participants.len();
+        let box_widths: Vec<usize> = self
+            .participants
+            .iter()
+            .map(|(_, label)| label.width() + 4)
+            .collect();
+
+        // (left participant, right participant, minimum distance)
+        let mut needs: Vec<(usize, usize, usize)> = (1..count)
+            .map(|i| {
+                let half_left = box_widths[i - 1] - box_widths[i - 1] / 2;
+                (i - 1, i, half_left + box_widths[i] / 2 + 2)
+            })
+            .collect();
+        let mut first = box_widths[0] / 2;
+        for event in &self.events {
+            match event {
+                Event::Message { from, to, text, .. } if from != to => {
+                    needs.push(((*from).min(*to), (*from).max(*to), text.width() + 4));
+                }
+                Event::Message { from, text, .. } if from + 1 < count => {
+                    needs.push((*from, from + 1, text.width() + 7));
+                }
+                Event::Note {
+                    first: participant,
+                    placement: Placement::Right,
+                    text,
+                    ..
+                } if participant + 1 < count => {
+                    needs.push((*participant, participant + 1, text.width() + 7));
+                }
+                Event::Note {
+                    first: participant,
+                    placement: Placement::Left,
+                    text,
+                    ..
+                } => match participant {
+                    0 => first = first.max(text.width() + 6),
+                    _ => needs.push((participant - 1, *participant, text.width() + 7)),
+                },
+                Event::Note {
+                    first: 0,
+                    placement: Placement::Over,
+                    text,
+                    ..
+                } => first = first.max((text.width() + 4) / 2),
+                _ => {}
+            }
+        }
+
+        let mut columns = vec![first; count];
+        for i in 1..count {
+            columns[i] = needs
+                .iter()
+                .filter(|&&(_, right, _)| right == i)
+                .map(|&(left, _, distance)| columns[left] + distance)
+                .max()
+                .unwrap_or(0);
+        }
+        columns
+    }
+
+    fn render(&self) -> Vec<String> {
+        let columns = self.columns();
+        let mut canvas = Canvas::default();
+
+        let draw_boxes = |canvas: &mut Canvas, top: usize, joint: char| {
+            for ((_, label), &x) in self.participants.iter().zip(&columns) {
+                let width = label.width() + 4;
+                canvas.draw_box(x - width / 2, top, label, Shape::Rect);
+                let joint_row = if joint == '┬' { top + 2 } else { top };
+                canvas.put(x, joint_row, joint);
+            }
+        };
+        draw_boxes(&mut canvas, 0, '┬');
+
+        let mut y = 3;
+        let mut dividers: Vec<(usize, Option<&str>)> = Vec::new();
+        for event in &self.events {
+            match event {
+                Event::Message {
+                    from,
+                    to,
+                    text,
+                    stroke,
+                    head,
+                } if from != to => {
+                    let (from_x, to_x) = (columns[*from], columns[*to]);
+                    if !text.is_empty() {
+                        canvas.text(from_x.min(to_x) + 2, y, text);
+                        y += 1;
+                    }
+                    let head_x = if to_x > from_x { to_x - 1 } else { to_x + 1 };
+                    let line_end = if *head == Head::None { to_x } else { head_x };
+                    canvas.line((from_x, y), (line_end, y), *stroke);
+                    let rightward = to_x > from_x;
+                    match head {
+                        Head::Arrow => canvas.put(head_x, y, if rightward { '►' } else { '◄' }),
+                        Head::Cross => canvas.put(head_x, y, '×'),
+                        Head::Async => canvas.put(head_x, y, if rightward { '▷' } else { '◁' }),
+                        Head::None => {}
+                    }
+                    y += 1;
+                }
+                Event::Message {
+                    from, text, stroke, ..
+                } => {
+                    let x = columns[*from];
+                    if !text.is_empty() {
+                        canvas.text(x + 2, y, text);
+                        y += 1;
+                    }
+                    canvas.polyline(
+                        &[(x, y), (x + 3, y), (x + 3, y + 1), (x + 1, y + 1)],
+                        *stroke,
+                    );
+                    canvas.put(x + 1, y + 1, '◄');
+                    y += 2;
+                }
+                Event::Note {
+                    first,
+                    last,
+                    placement,
+                    text,
+                } => {
+                    let width = text.width() + 4;
+                    let left = match placement {
+                        Placement::Right => columns[*first] + 2,
+                        Placement::Left => (columns[*first] + 1).saturating_sub(width + 2),
+                        Placement::Over => {
+                            let middle = (columns[*first] + columns[*last]) / 2;
+                            middle.saturating_sub(width / 2)
+                        }
+                    };
+                    // A note over several participants spans their lifelines
+                    let text = match placement {
+                        Placement::Over if first != last => {
+                            let span = columns[*last] - columns[*first] + 4;
+                            let padding = span.saturating_sub(width);
+                            format!("{}{}", text, " ".repeat(padding))
+                        }
+                        _ => text.clone(),
+                    };
+                    let left = match placement {
+                        Placement::Over if first != last => columns[*first] - 2,
+                        _ => left,
+                    };
+                    canvas.draw_box(left, y, &text, Shape::Rect);
+                    y += 3;
+                }
+                Event::Divider(label) => {
+                    dividers.push((y, Some(label)));
+                    y += 1;
+                }
+                Event::End => {
+                    dividers.push((y, None));
+                    y += 1;
+                }
+            }
+        }
+
+        for &x in &columns {
+            canvas.line((x, 2), (x, y), Stroke::Dotted);
+        }
+        draw_boxes(&mut canvas, y, '┴');
+
+        let width = canvas.width();
+        for (row, label) in dividers {
+            for x in 0..width {
+                if canvas.is_empty(x, row) {
+                    canvas.put(x, row, '╌');
+                }
+            }
+            if let Some(label) = label {
+                canvas.text(1, row, &format!("[{}]", label));
+            }
+        }
+        canvas.into_lines()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flowchart_top_down() {
+        let diagram =
+            render_mermaid("graph TD\n  A[Start] --> B{Ready?}\n  B -- yes --> C(Done)\n").unwrap();
+        assert_eq!(
+            diagram,
+            vec![
+                " ┌───────┐",
+                " │ Start │",
+                " └───┬───┘",
+                "     │",
+                "     ▼",
+                "╱────────╲",
+                "< Ready? >",
+                "╲────┬───╱",
+                "     │",
+                "     │ yes",
+                "     ▼",
+                " ╭──────╮",
+                " │ Done │",
+                " ╰──────╯",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flowchart_left_right_with_branches_and_cycles() {
+        let diagram = render_mermaid("flowchart LR\n  a --> b & c\n  c -.-> a\n").unwrap();
+        assert_eq!(
+            diagram,
+            vec![
+                "         ┌───┐",
+                "      ┌─►│ b │",
+                "┌───┐ │  └───┘",
+                "│ a ├─┤",
+                "└───┘ │  ┌───┐",
+                "      └─►│ c │",
+                "         └───┘",
+                "",
+                "↻ c → a",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sequence_diagram() {
+        let diagram = render_mermaid(
+            "sequenceDiagram\n  participant A as Alice\n  A->>Bob: Hi\n  Bob-->>A: Hey\n",
+        )
+        .unwrap();
+        assert_eq!(
+            diagram,
+            vec![
+                "┌───────┐  ┌─────┐",
+                "│ Alice │  │ Bob │",
+                "└───┬───┘  └──┬──┘",
+                "    ┆ Hi      ┆",
+                "    ├────────►┆",
+                "    ┆ Hey     ┆",
+                "    ┆◄╌╌╌╌╌╌╌╌┤",
+                "┌───┴───┐  ┌──┴──┐",
+                "│ Alice │  │ Bob │",
+                "└───────┘  └─────┘",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_diagrams() {
+        assert_eq!(render_mermaid("pie\n  \"a\": 1"), None);
+        assert_eq!(render_mermaid("graph TD\n  A --> B --> ???"), None);
+        assert_eq!(render_mermaid(""), None);
+    }
+
+    #[test]
+    fn test_render_mermaid_blocks() {
+        let markdown = "Intro\n\n```mermaid\ngraph LR\n  A --> B\n```\n\n```mermaid\npie\n```\n";
+        let rendered = render_mermaid_blocks(markdown);
+        assert!(rendered.starts_with("Intro\n\n```text\n┌───┐   ┌───┐\n│ A ├──►│ B │\n"));
+        assert!(rendered.ends_with("```\n\n```mermaid\npie\n```\n"));
+    }
+}
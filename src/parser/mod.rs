@@ -7,10 +7,19 @@
 //! OFM (Obsidian Flavored Markdown) support.
 
 pub mod builder;
+pub mod callouts;
+pub mod code_blocks;
 pub mod content;
+pub mod delimited;
 mod document;
+pub mod embeds;
+pub mod footnotes;
 pub mod links;
+pub mod math;
+pub mod mermaid;
 pub mod output;
+pub mod tags;
+pub mod tasks;
 pub mod utils;
 
 pub use builder::build_json_output;
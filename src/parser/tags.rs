@@ -0,0 +1,124 @@
+//! Obsidian tags: `#tag`, `#nested/tag` and `tags:` in front matter.
+//!
+//! Inline tags come from turbovault-parser, which skips code blocks, inline
+//! code and the front matter itself. A `#` that starts a link anchor
+//! (`[text](#section)`, `[[#Heading]]`) is not a tag.
+
+use turbovault_parser::{ParseOptions, ParsedContent};
+
+/// A tag occurrence in a note
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    /// Name without the `#`
+    pub name: String,
+    /// 1-indexed source line (the `tags:` line for front matter tags)
+    pub line: usize,
+    /// Whether it came from the front matter
+    pub frontmatter: bool,
+}
+
+/// All tags in a note, front matter tags first, then in source order
+pub fn extract_tags(content: &str) -> Vec<Tag> {
+    let mut tags = frontmatter_tags(content);
+    tags.extend(
+        turbovault_parser::parse_tags(content)
+            .into_iter()
+            .filter(|tag| {
+                let before = &content[..tag.position.offset];
+                !before.ends_with("](") && !before.ends_with("[[")
+            })
+            .map(|tag| Tag {
+                name: tag.name,
+                line: tag.position.line,
+                frontmatter: false,
+            }),
+    );
+    tags
+}
+
+/// Distinct tags with their occurrences, most used first. Tags differing
+/// only in case are the same tag, named as first written.
+pub fn group_tags<T>(tags: impl IntoIterator<Item = (Tag, T)>) -> Vec<(String, Vec<(Tag, T)>)> {
+    let mut groups: Vec<(String, Vec<(Tag, T)>)> = Vec::new();
+    for (tag, item) in tags {
+        let key = tag.name.to_lowercase();
+        match groups
+            .iter_mut()
+            .find(|(name, _)| name.to_lowercase() == key)
+        {
+            Some((_, occurrences)) => occurrences.push((tag, item)),
+            None => groups.push((tag.name.clone(), vec![(tag, item)])),
+        }
+    }
+    groups.sort_by(|(a_name, a), (b_name, b)| {
+        b.len()
+            .cmp(&a.len())
+            .then_with(|| a_name.to_lowercase().cmp(&b_name.to_lowercase()))
+    });
+    groups
+}
+
+/// `tags:` from the front matter, as a list or a single string
+fn frontmatter_tags(content: &str) -> Vec<Tag> {
+    if !content.starts_with("---") {
+        return Vec::new();
+    }
+    let Some(frontmatter) =
+        ParsedContent::parse_with_options(content, ParseOptions::none().with_frontmatter())
+            .frontmatter
+    else {
+        return Vec::new();
+    };
+    let line = content
+        .lines()
+        .position(|line| line.starts_with("tags:"))
+        .map_or(1, |idx| idx + 1);
+    frontmatter
+        .tags()
+        .into_iter()
+        // `tags: a, b` is one string in YAML
+        .flat_map(|value| {
+            value
+                .split([',', ' '])
+                .map(|name| name.trim().trim_start_matches('#').to_string())
+                .filter(|name| !name.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .map(|name| Tag {
+            name,
+            line,
+            frontmatter: true,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_tags() {
+        let md = "---\ntitle: x\ntags: [project, Rust]\n---\n# Notes #draft\n\nSee #project/alpha and [intro](#intro) or [[#Notes]].\n\n```\n#not-a-tag\n```\n\nInline `#code` too, #Draft again.\n";
+        let tags = extract_tags(md);
+        let names: Vec<&str> = tags.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["project", "Rust", "draft", "project/alpha", "Draft"]
+        );
+        assert_eq!(tags[0].line, 3);
+        assert!(tags[1].frontmatter);
+        assert_eq!(tags[3].line, 7);
+        assert_eq!(tags[4].line, 13);
+    }
+
+    #[test]
+    fn test_group_tags() {
+        let md = "---\ntags: a, b\n---\n#b #B #c";
+        let groups = group_tags(extract_tags(md).into_iter().map(|t| (t, ())));
+        let counts: Vec<(&str, usize)> = groups
+            .iter()
+            .map(|(name, occurrences)| (name.as_str(), occurrences.len()))
+            .collect();
+        assert_eq!(counts, vec![("b", 3), ("a", 1), ("c", 1)]);
+    }
+}
@@ -0,0 +1,340 @@
+//! Task list items (`- [ ]` / `- [x]`) with their source positions.
+//!
+//! Parsed list items don't carry line numbers, so tasks are found by scanning
+//! the source. Fenced and indented code blocks are skipped, so a `- [ ]` in
+//! a code sample is never reported (or toggled).
+//!
+//! Due dates and priorities in the [Obsidian Tasks] emoji format
+//! (`📅 2025-01-31`, `⏫`) are picked up from the task text.
+//!
+//! [Obsidian Tasks]: https://publish.obsidian.md/tasks/
+
+use super::document::{Document, Heading};
+use super::utils::{FenceTracker, strip_markdown_inline};
+use regex::Regex;
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+/// A list item, optional checkbox, and the text after it
+static LIST_ITEM: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\s*)(?:[-*+]|\d{1,9}[.)])(?:\s+\[([ xX])\](?:\s+(.*))?|\s.*)?$").unwrap()
+});
+
+/// Obsidian Tasks due date: `📅 YYYY-MM-DD`
+static DUE_DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"📅\uFE0F?\s*(\d{4}-\d{2}-\d{2})").unwrap());
+
+/// A task list item in the source
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Task {
+    /// Text after the checkbox, with inline markdown stripped
+    pub text: String,
+    pub checked: bool,
+    /// 1-indexed source line
+    pub line: usize,
+    /// List nesting depth (0 for a top-level item)
+    pub depth: usize,
+    /// Texts of the enclosing headings, outermost first
+    pub heading_path: Vec<String>,
+    /// Due date (`📅 YYYY-MM-DD`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due: Option<String>,
+    /// Priority emoji (`🔺 ⏫ 🔼 🔽 ⏬`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    /// Index into the document's headings of the innermost enclosing heading
+    #[serde(skip)]
+    pub heading_index: Option<usize>,
+    /// Byte offset of the state character between the brackets
+    #[serde(skip)]
+    pub marker_offset: usize,
+}
+
+/// Obsidian Tasks priority, lowest to highest (tasks without one sit
+/// between low and medium)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Lowest,
+    Low,
+    Medium,
+    High,
+    Highest,
+}
+
+impl Priority {
+    const EMOJI: [(&'static str, Priority); 5] = [
+        ("🔺", Priority::Highest),
+        ("⏫", Priority::High),
+        ("🔼", Priority::Medium),
+        ("🔽", Priority::Low),
+        ("⏬", Priority::Lowest),
+    ];
+
+    /// Priority marked in a task's text, if any
+    pub fn from_text(text: &str) -> Option<Self> {
+        Self::EMOJI
+            .iter()
+            .find(|(emoji, _)| text.contains(emoji))
+            .map(|&(_, priority)| priority)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Priority::Lowest => "lowest",
+            Priority::Low => "low",
+            Priority::Medium => "medium",
+            Priority::High => "high",
+            Priority::Highest => "highest",
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "lowest" => Ok(Priority::Lowest),
+            "low" => Ok(Priority::Low),
+            "medium" => Ok(Priority::Medium),
+            "high" => Ok(Priority::High),
+            "highest" => Ok(Priority::Highest),
+            _ => Err(format!(
+                "Unknown priority '{}' (expected lowest, low, medium, high or highest)",
+                s
+            )),
+        }
+    }
+}
+
+/// All tasks in a document, with their heading paths
+pub fn extract_tasks(doc: &Document) -> Vec<Task> {
+    let mut tasks = scan_tasks(&doc.content);
+    assign_headings(&mut tasks, &doc.headings);
+    tasks
+}
+
+/// All tasks in markdown source (heading paths are left empty)
+pub fn scan_tasks(content: &str) -> Vec<Task> {
+    let mut tasks = Vec::new();
+    let mut fences = FenceTracker::default();
+    // Indentation of the list items enclosing the current line
+    let mut list_indents: Vec<usize> = Vec::new();
+    let mut offset = 0;
+
+    for (idx, line) in content.split_inclusive('\n').enumerate() {
+        let line_start = offset;
+        offset += line.len();
+        let line = line.trim_end_matches(['\n', '\r']);
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        if fences.line(line).is_code() {
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let Some(caps) = LIST_ITEM.captures(line) else {
+            // Unindented text ends any list; indented text continues an item
+            if indent == 0 {
+                list_indents.clear();
+            }
+            continue;
+        };
+
+        // Indented code block (not inside a list)
+        if list_indents.is_empty() && indent >= 4 {
+            continue;
+        }
+
+        while list_indents.last().is_some_and(|&last| last >= indent) {
+            list_indents.pop();
+        }
+        let depth = list_indents.len();
+        list_indents.push(indent);
+
+        if let Some(state) = caps.get(2) {
+            let raw = caps.get(3).map_or("", |m| m.as_str());
+            tasks.push(Task {
+                text: strip_markdown_inline(raw.trim()),
+                checked: state.as_str() != " ",
+                line: idx + 1,
+                depth,
+                heading_path: Vec::new(),
+                due: DUE_DATE.captures(raw).map(|c| c[1].to_string()),
+                priority: Priority::from_text(raw),
+                heading_index: None,
+                marker_offset: line_start + state.start(),
+            });
+        }
+    }
+
+    tasks
+}
+
+/// Fill in heading paths from the document's headings
+fn assign_headings(tasks: &mut [Task], headings: &[Heading]) {
+    let mut stack: Vec<usize> = Vec::new();
+    let mut next = 0;
+
+    for task in tasks.iter_mut() {
+        while next < headings.len() && headings[next].offset < task.marker_offset {
+            let level = headings[next].level;
+            while stack.last().is_some_and(|&i| headings[i].level >= level) {
+                stack.pop();
+            }
+            stack.push(next);
+            next += 1;
+        }
+        task.heading_path = stack.iter().map(|&i| headings[i].text.clone()).collect();
+        task.heading_index = stack.last().copied();
+    }
+}
+
+/// Find a task by its text (inline markdown and any leading checkbox marker
+/// are ignored, as is anything after the first line).
+///
+/// `checked` restricts the match to tasks in that state. Among tasks with the
+/// same text, the first one at or after `line_hint` (1-indexed) wins.
+pub fn find_task_by_text<'a>(
+    tasks: &'a [Task],
+    text: &str,
+    checked: Option<bool>,
+    line_hint: Option<usize>,
+) -> Option<&'a Task> {
+    let clean_text = text
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim_start()
+        .trim_start_matches("[x]")
+        .trim_start_matches("[X]")
+        .trim_start_matches("[ ]")
+        .trim();
+    let clean_text = strip_markdown_inline(clean_text);
+
+    let mut candidates = tasks
+        .iter()
+        .filter(|task| checked.is_none_or(|c| task.checked == c) && task.text == clean_text)
+        .peekable();
+    let first = candidates.peek().copied();
+    let hint = line_hint.unwrap_or(0);
+    candidates.find(|task| task.line >= hint).or(first)
+}
+
+/// Set a task's checkbox in `content`, which must be the source it was scanned from
+pub fn set_task_checked(content: &str, task: &Task, checked: bool) -> String {
+    let marker = if checked { "x" } else { " " };
+    let mut result = String::with_capacity(content.len());
+    result.push_str(&content[..task.marker_offset]);
+    result.push_str(marker);
+    result.push_str(&content[task.marker_offset + 1..]);
+    result
+}
+
+/// Open and total task counts per heading (indexed like the document's
+/// headings), counting tasks under subheadings too
+pub fn task_counts_by_heading(doc: &Document, tasks: &[Task]) -> Vec<(usize, usize)> {
+    let mut counts = vec![(0, 0); doc.headings.len()];
+
+    for task in tasks {
+        let mut current = task.heading_index;
+        while let Some(idx) = current {
+            let (open, total) = &mut counts[idx];
+            *total += 1;
+            if !task.checked {
+                *open += 1;
+            }
+            // Walk up to the enclosing heading
+            let level = doc.headings[idx].level;
+            current = doc.headings[..idx].iter().rposition(|h| h.level < level);
+        }
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_markdown;
+
+    const DOC: &str = "# Plan\n\n- [ ] write **docs**\n  - [x] outline\n    * [ ] nested deeper\n- not a task\n\n```md\n- [ ] in a code block\n```\n\n## Later\n\n1. [X] shipped\n2. [ ]\n\nDone.\n\n    - [ ] indented code\n";
+
+    #[test]
+    fn test_scan_skips_code_and_tracks_depth() {
+        let tasks = scan_tasks(DOC);
+        let summary: Vec<(&str, bool, usize, usize)> = tasks
+            .iter()
+            .map(|t| (t.text.as_str(), t.checked, t.line, t.depth))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("write docs", false, 3, 0),
+                ("outline", true, 4, 1),
+                ("nested deeper", false, 5, 2),
+                ("shipped", true, 14, 0),
+                ("", false, 15, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_skips_fences_in_list_items() {
+        let doc = "- item\n\n    ```md\n    - [ ] example\n    ```\n\n1. step\n\n     ~~~\n     - [ ] example\n     ~~~\n- [ ] real\n";
+        let tasks = scan_tasks(doc);
+
+        assert_eq!(
+            tasks
+                .iter()
+                .map(|t| (t.text.as_str(), t.line))
+                .collect::<Vec<_>>(),
+            vec![("real", 12)]
+        );
+    }
+
+    #[test]
+    fn test_obsidian_metadata() {
+        let tasks =
+            scan_tasks("- [ ] file taxes ⏫ 📅 2025-04-15\n- [ ] water plants 🔽\n- [ ] read\n");
+
+        assert_eq!(tasks[0].due.as_deref(), Some("2025-04-15"));
+        assert_eq!(tasks[0].priority, Some(Priority::High));
+        assert_eq!(tasks[1].priority, Some(Priority::Low));
+        assert_eq!(tasks[2].due, None);
+        assert_eq!(tasks[2].priority, None);
+        assert_eq!("HIGH".parse::<Priority>(), Ok(Priority::High));
+    }
+
+    #[test]
+    fn test_heading_paths_counts_and_toggle() {
+        let doc = parse_markdown(DOC);
+        let tasks = extract_tasks(&doc);
+
+        assert_eq!(tasks[0].heading_path, vec!["Plan"]);
+        assert_eq!(tasks[3].heading_path, vec!["Plan", "Later"]);
+        assert_eq!(task_counts_by_heading(&doc, &tasks), vec![(3, 5), (1, 2)]);
+
+        let found = find_task_by_text(&tasks, "write **docs**", Some(false), None);
+        assert_eq!(found.map(|t| t.line), Some(3));
+        assert!(find_task_by_text(&tasks, "write docs", Some(true), None).is_none());
+
+        let toggled = set_task_checked(DOC, &tasks[0], true);
+        assert!(toggled.starts_with("# Plan\n\n- [x] write **docs**\n"));
+        assert_eq!(toggled.len(), DOC.len());
+    }
+}
@@ -79,6 +79,185 @@ pub fn get_heading_level(line: &str) -> Option<usize> {
     None
 }
 
+/// 1-based line number of byte `offset` in `content`
+pub fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+/// Fence character and run length if the line opens or closes a code fence
+pub fn fence_run(line: &str) -> Option<(char, usize)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let fence_char = trimmed.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let run = trimmed.chars().take_while(|&c| c == fence_char).count();
+    (run >= 3).then_some((fence_char, run))
+}
+
+/// What a line is with respect to fenced code blocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FenceLine {
+    /// Outside any code block
+    Text,
+    /// Opening fence
+    Open,
+    /// Inside a code block
+    Code,
+    /// Closing fence
+    Close,
+}
+
+impl FenceLine {
+    /// Whether the line belongs to a code block, fences included
+    pub fn is_code(self) -> bool {
+        self != FenceLine::Text
+    }
+}
+
+/// Follows fenced code blocks line by line.
+///
+/// A fence is three or more backticks or tildes indented by at most three
+/// spaces past the content column of the list item it is in (column 0
+/// outside lists). It is closed by a run of the same character at least as
+/// long with nothing after it, and a backtick fence's info string can't
+/// contain backticks.
+///
+/// ```
+/// # use treemd::parser::utils::{FenceLine, FenceTracker};
+/// let mut fences = FenceTracker::default();
+/// let lines: Vec<_> = ["text", "````md", "```", "````", "    ```", "1. step", "    ```"]
+///     .into_iter()
+///     .map(|line| fences.line(line))
+///     .collect();
+/// assert_eq!(
+///     lines,
+///     [
+///         FenceLine::Text,
+///         FenceLine::Open,
+///         FenceLine::Code,
+///         FenceLine::Close,
+///         FenceLine::Text,
+///         FenceLine::Text,
+///         FenceLine::Open
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FenceTracker {
+    /// (fence char, fence length, container column) of the open code fence
+    open: Option<(char, usize, usize)>,
+    /// Content columns of the list items enclosing the current line
+    lists: Vec<usize>,
+}
+
+impl FenceTracker {
+    /// Classify the next line (without its line break)
+    pub fn line(&mut self, line: &str) -> FenceLine {
+        let indent = line.len() - line.trim_start_matches(' ').len();
+        if let Some((open_char, open_len, column)) = self.open {
+            let closes = fence_at(line, column).is_some_and(|(fence_char, run, rest)| {
+                fence_char == open_char && run >= open_len && rest.trim().is_empty()
+            });
+            if !closes {
+                return FenceLine::Code;
+            }
+            self.open = None;
+            return FenceLine::Close;
+        }
+        if line.trim().is_empty() {
+            return FenceLine::Text;
+        }
+
+        // Less indented text ends the list items it isn't part of
+        while self.lists.last().is_some_and(|&column| column > indent) {
+            self.lists.pop();
+        }
+        if let Some(column) = list_content_column(line) {
+            self.lists.push(column);
+            return FenceLine::Text;
+        }
+
+        let column = self.lists.last().copied().unwrap_or(0);
+        match fence_at(line, column) {
+            Some(('`', _, rest)) if rest.contains('`') => FenceLine::Text,
+            Some((fence_char, run, _)) => {
+                self.open = Some((fence_char, run, column));
+                FenceLine::Open
+            }
+            None => FenceLine::Text,
+        }
+    }
+}
+
+/// Fence character, run length and the rest of the line, for a fence
+/// indented at most three spaces past `column`
+fn fence_at(line: &str, column: usize) -> Option<(char, usize, &str)> {
+    let strip = line.len() - line.trim_start_matches(' ').len();
+    let line = &line[strip.min(column)..];
+    let (fence_char, run) = fence_run(line)?;
+    Some((fence_char, run, &line.trim_start_matches(' ')[run..]))
+}
+
+/// Column where the content of a list item line starts (`None` if the line
+/// isn't a list item)
+fn list_content_column(line: &str) -> Option<usize> {
+    let rest = line.trim_start_matches(' ');
+    let indent = line.len() - rest.len();
+    let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+    let marker = match rest.as_bytes().get(digits)? {
+        b'-' | b'*' | b'+' if digits == 0 => 1,
+        b'.' | b')' if (1..=9).contains(&digits) => digits + 1,
+        _ => return None,
+    };
+    let after = &rest[marker..];
+    let spaces = after.len() - after.trim_start_matches(' ').len();
+    match spaces {
+        0 if !after.is_empty() => None,
+        // Blank after the marker, or indented code inside the item
+        0 | 5.. => Some(indent + marker + 1),
+        _ => Some(indent + marker + spaces),
+    }
+}
+
+/// Byte ranges of inline code spans in a line
+pub fn code_span_ranges(line: &str) -> Vec<std::ops::Range<usize>> {
+    let bytes = line.as_bytes();
+    let run_at = |i: usize| bytes[i..].iter().take_while(|&&b| b == b'`').count();
+
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'`' {
+            i += 1;
+            continue;
+        }
+        let open = run_at(i);
+        let mut j = i + open;
+        let mut close = None;
+        while j < bytes.len() {
+            if bytes[j] == b'`' {
+                let run = run_at(j);
+                if run == open {
+                    close = Some(j + run);
+                    break;
+                }
+                j += run;
+            } else {
+                j += 1;
+            }
+        }
+        match close {
+            Some(end) => {
+                spans.push(i..end);
+                i = end;
+            }
+            None => i += open,
+        }
+    }
+    spans
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,4 +302,34 @@ mod tests {
         assert_eq!(get_heading_level("####### Too many"), None);
         assert_eq!(get_heading_level("  ## Indented"), Some(2)); // Trimmed
     }
+
+    #[test]
+    fn test_fence_tracker() {
+        use FenceLine::*;
+        let classify = |text: &str| {
+            let mut fences = FenceTracker::default();
+            text.lines()
+                .map(|line| fences.line(line))
+                .collect::<Vec<_>>()
+        };
+
+        // Closed by a run at least as long, of the same character, with nothing after it
+        assert_eq!(
+            classify("~~~~\n~~~\n```\n~~~~~ x\n   ~~~~~\ntext"),
+            [Open, Code, Code, Code, Close, Text]
+        );
+        // At most three spaces of indentation
+        assert_eq!(classify("    ```\ntext\n   ```\n"), [Text, Text, Open]);
+        // Backtick fences can't have backticks in the info string
+        assert_eq!(classify("``` a`b\n~~~ a`b\n~~~"), [Text, Open, Close]);
+        // Inside list items the indent counts from the item's content
+        assert_eq!(
+            classify("- item\n  - sub\n\n      ```\n      x\n      ```\ntext\n    ```"),
+            [Text, Text, Text, Open, Code, Close, Text, Text]
+        );
+        assert_eq!(
+            classify("10. step\n\n       ~~~\n    ~~~\n        ```"),
+            [Text, Text, Open, Close, Text]
+        );
+    }
 }
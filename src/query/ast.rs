@@ -140,6 +140,16 @@ pub enum ElementKind {
     Table,
     /// List: `.list`
     List,
+    /// Task list item: `.task`
+    Task,
+    /// Callout / alert: `.callout`
+    Callout,
+    /// Footnote definition: `.footnote`
+    Footnote,
+    /// LaTeX math: `.math`
+    Math,
+    /// Tag (`#tag` or front matter `tags:`): `.tag`
+    Tag,
     /// Blockquote: `.blockquote`
     Blockquote,
     /// Paragraph: `.para`
@@ -177,6 +187,21 @@ impl ElementKind {
             // Lists
             "list" | "lists" | "ul" | "ol" => Some(ElementKind::List),
 
+            // Task list items
+            "task" | "tasks" | "todo" | "todos" => Some(ElementKind::Task),
+
+            // Callouts and GitHub alerts
+            "callout" | "callouts" | "alert" | "alerts" => Some(ElementKind::Callout),
+
+            // Footnotes
+            "footnote" | "footnotes" => Some(ElementKind::Footnote),
+
+            // Math
+            "math" | "equation" | "equations" | "latex" => Some(ElementKind::Math),
+
+            // Tags
+            "tag" | "tags" | "hashtag" | "hashtags" => Some(ElementKind::Tag),
+
             // Blockquotes
             "blockquote" | "blockquotes" | "quote" | "quotes" | "bq" => {
                 Some(ElementKind::Blockquote)
@@ -208,6 +233,11 @@ impl ElementKind {
             ElementKind::Image => "img",
             ElementKind::Table => "table",
             ElementKind::List => "list",
+            ElementKind::Task => "task",
+            ElementKind::Callout => "callout",
+            ElementKind::Footnote => "footnote",
+            ElementKind::Math => "math",
+            ElementKind::Tag => "tag",
             ElementKind::Blockquote => "blockquote",
             ElementKind::Paragraph => "para",
             ElementKind::FrontMatter => "frontmatter",
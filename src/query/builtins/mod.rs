@@ -5,10 +5,11 @@
 mod collection;
 mod string;
 
-use super::error::QueryError;
+use super::ast::Span;
+use super::error::{QueryError, QueryErrorKind};
 use super::eval::EvalContext;
 use super::registry::{Function, Registry};
-use super::value::Value;
+use super::value::{TableValue, Value};
 
 /// Register all built-in functions.
 pub fn register_all(registry: &mut Registry) {
@@ -56,6 +57,11 @@ pub fn register_all(registry: &mut Registry) {
     registry.register_function("url", Function::new(fn_url, 0..=0));
     registry.register_function("lang", Function::new(fn_lang, 0..=0));
 
+    // Table functions
+    registry.register_function("rows", Function::new(fn_rows, 0..=0));
+    registry.register_function("column", Function::new(fn_column, 1..=1));
+    registry.register_function("cell", Function::new(fn_cell, 2..=2));
+
     // Aggregation functions
     registry.register_function("stats", Function::new(fn_stats, 0..=0));
     registry.register_function("levels", Function::new(fn_levels, 0..=0));
@@ -456,6 +462,90 @@ fn fn_lang(args: &[Value], _ctx: &EvalContext) -> Result<Vec<Value>, QueryError>
     }
 }
 
+// ============================================================================
+// Table functions
+// ============================================================================
+
+/// Error for a table function applied to something else
+fn not_a_table(input: Option<&Value>, function: &str) -> QueryError {
+    QueryError::new(
+        QueryErrorKind::TypeError {
+            expected: "table",
+            found: input.map_or_else(|| "nothing".to_string(), |v| v.kind().to_string()),
+        },
+        Span::default(),
+        String::new(),
+    )
+    .with_help(format!(
+        "{} works on tables, e.g. .table[0] | {}",
+        function, function
+    ))
+}
+
+/// Error for a column that doesn't exist, listing the table's headers
+fn unknown_column(table: &TableValue, column: &Value) -> QueryError {
+    QueryError::new(
+        QueryErrorKind::PropertyNotFound {
+            property: column.to_text(),
+            on_type: "table".to_string(),
+        },
+        Span::default(),
+        String::new(),
+    )
+    .with_help(format!("columns: {}", table.headers.join(", ")))
+}
+
+#[allow(clippy::result_large_err)]
+fn fn_rows(args: &[Value], _ctx: &EvalContext) -> Result<Vec<Value>, QueryError> {
+    // One object per row, so `rows | select(.Status == "done")` filters rows
+    match args.first() {
+        Some(Value::Table(table)) => Ok(table.row_objects()),
+        other => Err(not_a_table(other, "rows")),
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn fn_column(args: &[Value], _ctx: &EvalContext) -> Result<Vec<Value>, QueryError> {
+    let Some(Value::Table(table)) = args.first() else {
+        return Err(not_a_table(args.first(), "column"));
+    };
+    let column = args.get(1).unwrap_or(&Value::Null);
+    let col = table
+        .column_index(column)
+        .ok_or_else(|| unknown_column(table, column))?;
+    let values = table
+        .rows
+        .iter()
+        .map(|row| TableValue::cell_value(row.get(col).map(String::as_str).unwrap_or_default()))
+        .collect();
+    Ok(vec![Value::Array(values)])
+}
+
+#[allow(clippy::result_large_err)]
+fn fn_cell(args: &[Value], _ctx: &EvalContext) -> Result<Vec<Value>, QueryError> {
+    let Some(Value::Table(table)) = args.first() else {
+        return Err(not_a_table(args.first(), "cell"));
+    };
+    let column = args.get(2).unwrap_or(&Value::Null);
+    let col = table
+        .column_index(column)
+        .ok_or_else(|| unknown_column(table, column))?;
+
+    // Row index is 0-based over data rows; negative counts from the end
+    let row = match args.get(1) {
+        Some(Value::Number(n)) if n.fract() == 0.0 => {
+            let len = table.rows.len() as i64;
+            let idx = if *n < 0.0 { len + *n as i64 } else { *n as i64 };
+            usize::try_from(idx).ok().and_then(|i| table.rows.get(i))
+        }
+        _ => None,
+    };
+
+    Ok(vec![row.map_or(Value::Null, |row| {
+        TableValue::cell_value(row.get(col).map(String::as_str).unwrap_or_default())
+    })])
+}
+
 // ============================================================================
 // Aggregation functions
 // ============================================================================
@@ -27,6 +27,16 @@ pub struct EvalContext {
     pub tables: Vec<TableValue>,
     /// All lists
     pub lists: Vec<ListValue>,
+    /// All task list items
+    pub tasks: Vec<TaskValue>,
+    /// All callouts
+    pub callouts: Vec<CalloutValue>,
+    /// All footnote definitions
+    pub footnotes: Vec<FootnoteValue>,
+    /// All math (`$...$`, `$$...$$` and ```` ```math ```` blocks)
+    pub math: Vec<MathValue>,
+    /// All tags, most used first
+    pub tags: Vec<TagValue>,
     /// Document metadata
     pub document: DocumentValue,
     /// Raw document content
@@ -38,6 +48,11 @@ impl EvalContext {
     pub fn from_document(doc: &Document) -> Self {
         let headings = extract_headings(doc);
         let (code_blocks, links, images, tables, lists) = extract_blocks(doc);
+        let tasks = extract_tasks(doc);
+        let callouts = extract_callouts(doc);
+        let footnotes = extract_footnotes(doc);
+        let math = extract_math(doc);
+        let tags = extract_tags(doc);
 
         let document = DocumentValue {
             content: doc.content.clone(),
@@ -53,6 +68,11 @@ impl EvalContext {
             images,
             tables,
             lists,
+            tasks,
+            callouts,
+            footnotes,
+            math,
+            tags,
             document,
             raw_content: doc.content.clone(),
         }
@@ -216,6 +236,29 @@ impl<'a> Engine<'a> {
                 .cloned()
                 .map(Value::List)
                 .collect(),
+            ElementKind::Task => self
+                .context
+                .tasks
+                .iter()
+                .cloned()
+                .map(Value::Task)
+                .collect(),
+            ElementKind::Callout => self
+                .context
+                .callouts
+                .iter()
+                .cloned()
+                .map(Value::Callout)
+                .collect(),
+            ElementKind::Footnote => self
+                .context
+                .footnotes
+                .iter()
+                .cloned()
+                .map(Value::Footnote)
+                .collect(),
+            ElementKind::Math => self.context.math.iter().cloned().map(Value::Math).collect(),
+            ElementKind::Tag => self.context.tags.iter().cloned().map(Value::Tag).collect(),
             ElementKind::Blockquote => {
                 // TODO: extract blockquotes
                 Vec::new()
@@ -254,6 +297,33 @@ impl<'a> Engine<'a> {
                 Ok(elements
                     .into_iter()
                     .filter(|v| {
+                        // `.task[open]` / `.task[done]` filter by status
+                        if let Value::Task(task) = v
+                            && !*exact
+                            && let Some(matches) = task.matches_status(&pattern_lower)
+                        {
+                            return matches;
+                        }
+                        // `.callout[warning]` filters by kind
+                        if let Value::Callout(callout) = v
+                            && !*exact
+                            && let Some(matches) = callout.matches_kind(&pattern_lower)
+                        {
+                            return matches;
+                        }
+                        // `.math[display]` / `.math[inline]` filter by placement
+                        if let Value::Math(math) = v
+                            && !*exact
+                            && let Some(matches) = math.matches_placement(&pattern_lower)
+                        {
+                            return matches;
+                        }
+                        // `.footnote["note"]` also matches the label
+                        if let Value::Footnote(footnote) = v
+                            && footnote.label.to_lowercase() == pattern_lower
+                        {
+                            return true;
+                        }
                         let text = v.to_text().to_lowercase();
                         if *exact {
                             text == pattern_lower
@@ -472,6 +542,25 @@ impl<'a> Engine<'a> {
                             results
                                 .extend(self.context.code_blocks.iter().cloned().map(Value::Code));
                         }
+                        ElementKind::Task => {
+                            // Tasks in this heading's section (or directly under it)
+                            let section_end = self.context.headings[parent_idx + 1..]
+                                .iter()
+                                .position(|h| h.level <= parent_level)
+                                .map_or(self.context.headings.len(), |i| parent_idx + 1 + i);
+                            results.extend(
+                                self.context
+                                    .tasks
+                                    .iter()
+                                    .filter(|t| match t.heading_index {
+                                        Some(idx) if direct => idx == parent_idx,
+                                        Some(idx) => (parent_idx..section_end).contains(&idx),
+                                        None => false,
+                                    })
+                                    .cloned()
+                                    .map(Value::Task),
+                            );
+                        }
                         _ => {
                             // Other element types under headings
                         }
@@ -645,6 +734,97 @@ fn extract_headings(doc: &Document) -> Vec<HeadingValue> {
         .collect()
 }
 
+fn extract_tasks(doc: &Document) -> Vec<TaskValue> {
+    crate::parser::tasks::extract_tasks(doc)
+        .into_iter()
+        .map(|task| TaskValue {
+            text: task.text,
+            checked: task.checked,
+            line: task.line,
+            depth: task.depth,
+            heading_path: task.heading_path,
+            due: task.due,
+            priority: task.priority.map(|p| p.to_string()),
+            heading_index: task.heading_index,
+        })
+        .collect()
+}
+
+fn extract_callouts(doc: &Document) -> Vec<CalloutValue> {
+    use crate::parser::callouts::{Callout, CalloutFold};
+    use crate::parser::content::parse_content;
+    use crate::parser::output::Block;
+
+    parse_content(&doc.content, 1)
+        .into_iter()
+        .filter_map(|block| match block {
+            Block::Details {
+                summary, content, ..
+            } => Callout::from_summary(&summary).map(|callout| CalloutValue {
+                foldable: callout.is_foldable(),
+                collapsed: callout.fold == CalloutFold::Closed,
+                kind: callout.kind,
+                title: callout.title,
+                content,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+fn extract_footnotes(doc: &Document) -> Vec<FootnoteValue> {
+    let (defs, refs) = crate::parser::footnotes::scan_footnotes(&doc.content);
+
+    defs.into_iter()
+        .map(|def| FootnoteValue {
+            references: refs.iter().filter(|r| r.label == def.label).count(),
+            label: def.label,
+            content: def.content,
+            line: def.line,
+        })
+        .collect()
+}
+
+fn extract_math(doc: &Document) -> Vec<MathValue> {
+    use crate::parser::code_blocks::find_fenced_blocks;
+    use crate::parser::math::find_math;
+
+    let mut math: Vec<MathValue> = find_math(&doc.content)
+        .into_iter()
+        .map(|span| MathValue {
+            tex: span.tex.trim().to_string(),
+            display: span.display,
+            line: span.line,
+        })
+        .chain(
+            find_fenced_blocks(&doc.content)
+                .into_iter()
+                .filter(|block| block.language.as_deref() == Some("math"))
+                .map(|block| MathValue {
+                    tex: block.content.trim().to_string(),
+                    display: true,
+                    line: block.line,
+                }),
+        )
+        .collect();
+    math.sort_by_key(|m| m.line);
+    math
+}
+
+fn extract_tags(doc: &Document) -> Vec<TagValue> {
+    use crate::parser::tags::{extract_tags, group_tags};
+
+    let tags = extract_tags(&doc.content).into_iter().map(|tag| (tag, ()));
+    group_tags(tags)
+        .into_iter()
+        .map(|(name, occurrences)| TagValue {
+            name,
+            count: occurrences.len(),
+            lines: occurrences.iter().map(|(tag, _)| tag.line).collect(),
+        })
+        .collect()
+}
+
 fn extract_blocks(
     doc: &Document,
 ) -> (
@@ -675,6 +855,11 @@ fn extract_blocks(
     ) {
         for block in blocks {
             match block {
+                // Display math has its own selector
+                Block::Code {
+                    language: Some(language),
+                    ..
+                } if language == "math" => {}
                 Block::Code {
                     language,
                     content,
@@ -724,6 +909,10 @@ fn extract_blocks(
 
     for block in blocks {
         match block {
+            Block::Code {
+                language: Some(ref language),
+                ..
+            } if language == "math" => {}
             Block::Code {
                 language,
                 content,
@@ -783,9 +972,10 @@ fn extract_blocks(
         }
     }
 
+    // Footnote references have their own selector
     let link_values: Vec<LinkValue> = links
         .into_iter()
-        .map(|l| {
+        .filter_map(|l| {
             use crate::parser::links::LinkTarget;
             let (url, link_type) = match l.target {
                 LinkTarget::Anchor(s) => (format!("#{}", s), LinkType::Anchor),
@@ -799,13 +989,14 @@ fn extract_blocks(
                     (url, LinkType::Relative)
                 }
                 LinkTarget::WikiLink { target, .. } => (target, LinkType::WikiLink),
+                LinkTarget::Footnote(_) => return None,
             };
-            LinkValue {
+            Some(LinkValue {
                 text: l.text,
                 url,
                 link_type,
                 offset: l.offset,
-            }
+            })
         })
         .collect();
 
@@ -1075,4 +1266,159 @@ mod tests {
             assert!(c.content.contains("fn main"));
         }
     }
+
+    #[test]
+    fn test_table_rows_columns_and_cells() {
+        let md =
+            "| Name | Status | Hours |\n|---|---|---|\n| api | done | 12 |\n| db | open | 3.5 |";
+
+        let names: Vec<String> = eval(md, ".table[0] | rows | select(.Status == \"done\") | .Name")
+            .iter()
+            .map(Value::to_text)
+            .collect();
+        assert_eq!(names, vec!["api"]);
+
+        let heavy = eval(md, ".table[0] | rows | select(.Hours > 5) | .Name");
+        assert_eq!(heavy.len(), 1);
+
+        let hours = eval(md, ".table[0] | column(\"Hours\") | add");
+        assert!(matches!(hours[0], Value::Number(n) if n == 15.5));
+
+        let cell = eval(md, ".table[0] | cell(1; \"status\")");
+        assert_eq!(cell[0].to_text(), "open");
+        assert!(matches!(eval(md, ".table[0] | cell(5; 0)")[0], Value::Null));
+    }
+
+    #[test]
+    fn test_task_selector_filters_and_scoping() {
+        let md = "# Plan\n\n- [ ] write docs\n- [x] outline\n\n## Later\n\n- [ ] ship it\n  - [ ] tag release\n\n```\n- [ ] not a task\n```";
+
+        assert_eq!(eval(md, ".task").len(), 4);
+
+        let open: Vec<String> = eval(md, ".task[open]").iter().map(Value::to_text).collect();
+        assert_eq!(open, vec!["write docs", "ship it", "tag release"]);
+        assert_eq!(eval(md, ".task[done] | .line")[0].to_text(), "4");
+
+        assert_eq!(eval(md, ".h1 > .task").len(), 2);
+        assert_eq!(eval(md, ".h1 >> .task").len(), 4);
+        assert_eq!(eval(md, ".task[release] | .depth")[0].to_text(), "1");
+        assert_eq!(eval(md, ".task[ship] | .section")[0].to_text(), "Later");
+    }
+
+    #[test]
+    fn test_callout_selector() {
+        let md = "# Notes
+
+> [!WARNING]
+> Back up first.
+
+> [!attention]- Careful
+> Mind the warning above.
+
+> [!note] Aside
+> See the warning.
+
+> plain quote";
+
+        assert_eq!(eval(md, ".callout").len(), 3);
+        assert_eq!(eval(md, ".callout[warning]").len(), 2);
+        assert_eq!(eval(md, ".callout[attention]").len(), 2);
+        assert_eq!(
+            eval(md, ".callout[careful] | .kind")[0].to_text(),
+            "warning"
+        );
+        assert_eq!(eval(md, ".callout[aside] | .kind")[0].to_text(), "note");
+        assert_eq!(eval(md, ".callout[0] | .title")[0].to_text(), "Warning");
+        assert_eq!(
+            eval(md, ".callout | select(.foldable) | .content")[0].to_text(),
+            "Mind the warning above."
+        );
+    }
+
+    #[test]
+    fn test_callout_fold_marker_round_trips() {
+        use crate::query::{OutputFormat, output::format};
+
+        let md =
+            "> [!note]- Closed\n> hidden\n\n> [!tip]+ Open\n> shown\n\n> [!info] Plain\n> always";
+        let callouts = eval(md, ".callout");
+        assert_eq!(
+            format(&callouts, OutputFormat::Plain),
+            "> [!note]- Closed\n> hidden\n> [!tip]+ Open\n> shown\n> [!info] Plain\n> always"
+        );
+        assert_eq!(
+            eval(md, ".callout | select(.collapsed) | .title")[0].to_text(),
+            "Closed"
+        );
+    }
+
+    #[test]
+    fn test_footnote_selector() {
+        let md = "# Notes
+
+A claim[^1] and another[^note], again[^1].
+
+```
+[^2]: not a footnote
+```
+
+[^1]: First source.
+[^note]: Named,
+    continued.";
+
+        assert_eq!(eval(md, ".footnote").len(), 2);
+        assert_eq!(eval(md, ".footnote[0] | .references")[0].to_text(), "2");
+        assert_eq!(
+            eval(md, ".footnote[\"note\"] | .content")[0].to_text(),
+            "Named,\ncontinued."
+        );
+        assert_eq!(eval(md, ".footnote[source] | .label")[0].to_text(), "1");
+        assert_eq!(eval(md, ".footnote | .line")[1].to_text(), "10");
+    }
+
+    #[test]
+    fn test_math_selector() {
+        let md = r"# Physics
+
+Energy $E = mc^2$ costs $5 and $10.
+
+$$
+\frac{a}{b}
+$$
+
+```math
+\sqrt{x}
+```
+
+```python
+price = '$x$'
+```";
+
+        assert_eq!(eval(md, ".math").len(), 3);
+        assert_eq!(eval(md, ".math[inline] | .text")[0].to_text(), "E = mc²");
+        assert_eq!(eval(md, ".math[display]").len(), 2);
+        assert_eq!(eval(md, ".math[1] | .tex")[0].to_text(), r"\frac{a}{b}");
+        assert_eq!(eval(md, ".math[2] | .line")[0].to_text(), "9");
+        assert_eq!(eval(md, ".code").len(), 1);
+    }
+
+    #[test]
+    fn test_tag_selector() {
+        let md = "---
+tags: [rust]
+---
+# Plan #Rust
+
+Ship #release/v1 and #rust, see [setup](#setup).
+
+```
+#include <stdio.h>
+```";
+
+        assert_eq!(eval(md, ".tag").len(), 2);
+        assert_eq!(eval(md, ".tag[0] | .count")[0].to_text(), "3");
+        assert_eq!(eval(md, ".tag[0] | .lines | length")[0].to_text(), "3");
+        assert_eq!(eval(md, ".tag[release] | .nested")[0].to_text(), "true");
+        assert_eq!(eval(md, ".tag | .name")[1].to_text(), "release/v1");
+    }
 }
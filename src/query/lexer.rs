@@ -9,19 +9,20 @@ use super::error::{QueryError, QueryErrorKind};
 #[derive(Debug, Clone, PartialEq)]
 pub enum TokenKind {
     // Punctuation
-    Dot,      // .
-    Pipe,     // |
-    Comma,    // ,
-    Colon,    // :
-    LBracket, // [
-    RBracket, // ]
-    LParen,   // (
-    RParen,   // )
-    LBrace,   // {
-    RBrace,   // }
-    Gt,       // >
-    GtGt,     // >>
-    Question, // ?
+    Dot,       // .
+    Pipe,      // |
+    Comma,     // ,
+    Semicolon, // ;
+    Colon,     // :
+    LBracket,  // [
+    RBracket,  // ]
+    LParen,    // (
+    RParen,    // )
+    LBrace,    // {
+    RBrace,    // }
+    Gt,        // >
+    GtGt,      // >>
+    Question,  // ?
 
     // Operators
     Eq,         // ==
@@ -67,6 +68,7 @@ impl TokenKind {
             TokenKind::Dot => "'.'",
             TokenKind::Pipe => "'|'",
             TokenKind::Comma => "','",
+            TokenKind::Semicolon => "';'",
             TokenKind::Colon => "':'",
             TokenKind::LBracket => "'['",
             TokenKind::RBracket => "']'",
@@ -303,6 +305,7 @@ impl<'a> Lexer<'a> {
             '.' => Token::new(TokenKind::Dot, Span::new(start, self.pos)),
             '|' => Token::new(TokenKind::Pipe, Span::new(start, self.pos)),
             ',' => Token::new(TokenKind::Comma, Span::new(start, self.pos)),
+            ';' => Token::new(TokenKind::Semicolon, Span::new(start, self.pos)),
             ':' => Token::new(TokenKind::Colon, Span::new(start, self.pos)),
             '[' => Token::new(TokenKind::LBracket, Span::new(start, self.pos)),
             ']' => Token::new(TokenKind::RBracket, Span::new(start, self.pos)),
@@ -110,6 +110,10 @@ pub enum OutputFormat {
     Markdown,
     /// Tree structure with box-drawing
     Tree,
+    /// Comma-separated values (tables as header + rows)
+    Csv,
+    /// Tab-separated values (tables as header + rows)
+    Tsv,
 }
 
 impl std::str::FromStr for OutputFormat {
@@ -123,6 +127,8 @@ impl std::str::FromStr for OutputFormat {
             "jsonl" | "jsonlines" | "ndjson" => Ok(Self::JsonLines),
             "md" | "markdown" => Ok(Self::Markdown),
             "tree" => Ok(Self::Tree),
+            "csv" => Ok(Self::Csv),
+            "tsv" => Ok(Self::Tsv),
             _ => Err(format!("Unknown output format: {}", s)),
         }
     }
@@ -2,6 +2,7 @@
 
 use super::OutputFormat;
 use super::value::Value;
+use crate::parser::delimited::{self, Delimiter};
 
 /// Format query results according to the specified format.
 pub fn format(values: &[Value], format: OutputFormat) -> String {
@@ -12,6 +13,8 @@ pub fn format(values: &[Value], format: OutputFormat) -> String {
         OutputFormat::JsonLines => format_json_lines(values),
         OutputFormat::Markdown => format_markdown(values),
         OutputFormat::Tree => format_tree(values),
+        OutputFormat::Csv => format_delimited(values, Delimiter::Comma),
+        OutputFormat::Tsv => format_delimited(values, Delimiter::Tab),
     }
 }
 
@@ -93,6 +96,43 @@ fn format_plain_value(value: &Value) -> String {
             })
             .collect::<Vec<_>>()
             .join("\n"),
+        Value::Task(t) => {
+            let checkbox = if t.checked { "[x]" } else { "[ ]" };
+            format!("{}- {} {}", "  ".repeat(t.depth), checkbox, t.text)
+        }
+        Value::Callout(c) => {
+            let fold = match (c.foldable, c.collapsed) {
+                (false, _) => "",
+                (true, false) => "+",
+                (true, true) => "-",
+            };
+            let mut text = format!("> [!{}]{} {}", c.kind, fold, c.title);
+            for line in c.content.lines() {
+                text.push_str("\n>");
+                if !line.is_empty() {
+                    text.push(' ');
+                    text.push_str(line);
+                }
+            }
+            text
+        }
+        Value::Footnote(f) => {
+            // Continuation lines are indented, as in the source
+            let mut text = format!("[^{}]:", f.label);
+            for (i, line) in f.content.lines().enumerate() {
+                if i > 0 {
+                    text.push('\n');
+                }
+                if !line.is_empty() {
+                    text.push_str(if i == 0 { " " } else { "    " });
+                    text.push_str(line);
+                }
+            }
+            text
+        }
+        Value::Math(m) if m.display => format!("$$\n{}\n$$", m.tex),
+        Value::Math(m) => format!("${}$", m.tex),
+        Value::Tag(t) => format!("#{} ({})", t.name, t.count),
         Value::Blockquote(b) => b
             .content
             .lines()
@@ -201,6 +241,54 @@ fn value_to_json(value: &Value) -> serde_json::Value {
                 }).collect::<Vec<_>>(),
             })
         }
+        Value::Task(t) => {
+            serde_json::json!({
+                "type": "task",
+                "text": t.text,
+                "checked": t.checked,
+                "line": t.line,
+                "depth": t.depth,
+                "heading_path": t.heading_path,
+                "due": t.due,
+                "priority": t.priority,
+            })
+        }
+        Value::Callout(c) => {
+            serde_json::json!({
+                "type": "callout",
+                "kind": c.kind,
+                "title": c.title,
+                "content": c.content,
+                "foldable": c.foldable,
+                "collapsed": c.collapsed,
+            })
+        }
+        Value::Math(m) => {
+            serde_json::json!({
+                "type": "math",
+                "tex": m.tex,
+                "display": m.display,
+                "line": m.line,
+                "text": crate::parser::math::to_unicode(&m.tex),
+            })
+        }
+        Value::Tag(t) => {
+            serde_json::json!({
+                "type": "tag",
+                "name": t.name,
+                "count": t.count,
+                "lines": t.lines,
+            })
+        }
+        Value::Footnote(f) => {
+            serde_json::json!({
+                "type": "footnote",
+                "label": f.label,
+                "content": f.content,
+                "line": f.line,
+                "references": f.references,
+            })
+        }
         Value::Blockquote(b) => {
             serde_json::json!({
                 "type": "blockquote",
@@ -249,6 +337,39 @@ fn format_markdown_value(value: &Value) -> String {
     }
 }
 
+/// Tables become a header record plus one record per row (separated from
+/// other results by a blank line); arrays become one record; anything else
+/// is a single-field record.
+fn format_delimited(values: &[Value], delimiter: Delimiter) -> String {
+    let mut blocks: Vec<String> = Vec::new();
+    let mut records: Vec<Vec<String>> = Vec::new();
+
+    for value in values {
+        match value {
+            Value::Table(t) => {
+                if !records.is_empty() {
+                    blocks.push(delimited::write(
+                        records.iter().map(Vec::as_slice),
+                        delimiter,
+                    ));
+                    records.clear();
+                }
+                blocks.push(delimited::write_table(&t.headers, &t.rows, delimiter));
+            }
+            Value::Array(a) => records.push(a.iter().map(format_plain_value).collect()),
+            _ => records.push(vec![format_plain_value(value)]),
+        }
+    }
+    if !records.is_empty() {
+        blocks.push(delimited::write(
+            records.iter().map(Vec::as_slice),
+            delimiter,
+        ));
+    }
+
+    blocks.join("\n\n")
+}
+
 fn format_tree(values: &[Value]) -> String {
     let mut output = String::new();
 
@@ -304,7 +425,7 @@ fn format_tree_value(value: &Value, prefix: &str, is_last: bool, output: &mut St
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::query::value::HeadingValue;
+    use crate::query::value::{HeadingValue, TableValue};
 
     #[test]
     fn test_format_plain_heading() {
@@ -329,4 +450,20 @@ mod tests {
         assert!(output.contains("42"));
         assert!(output.contains("hello"));
     }
+
+    #[test]
+    fn test_format_csv_and_tsv() {
+        let table = Value::Table(TableValue {
+            headers: vec!["Name".to_string(), "Note".to_string()],
+            rows: vec![vec!["api".to_string(), "up, fast".to_string()]],
+            alignments: Vec::new(),
+        });
+
+        let output = format(std::slice::from_ref(&table), OutputFormat::Csv);
+        assert_eq!(output, "Name,Note\napi,\"up, fast\"");
+
+        let values = vec![Value::String("x".to_string()), table];
+        let output = format(&values, OutputFormat::Tsv);
+        assert_eq!(output, "x\n\nName\tNote\napi\tup, fast");
+    }
 }
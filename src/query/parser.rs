@@ -797,7 +797,8 @@ fn parse_function_args(p: &mut Parser) -> Result<Vec<Expr>, QueryError> {
     if !p.check(&TokenKind::RParen) {
         args.push(parse_piped_expr(p).map(Expr::from)?);
 
-        while p.matches(&[TokenKind::Comma]) {
+        // `;` separates arguments as in jq (`cell(0; "Name")`), `,` also works
+        while p.matches(&[TokenKind::Comma, TokenKind::Semicolon]) {
             args.push(parse_piped_expr(p).map(Expr::from)?);
         }
     }
@@ -50,6 +50,21 @@ pub enum Value {
     /// List element
     List(ListValue),
 
+    /// Task list item
+    Task(TaskValue),
+
+    /// Callout / alert
+    Callout(CalloutValue),
+
+    /// Footnote definition
+    Footnote(FootnoteValue),
+
+    /// LaTeX math
+    Math(MathValue),
+
+    /// Tag with its occurrences
+    Tag(TagValue),
+
     /// Blockquote element
     Blockquote(BlockquoteValue),
 
@@ -79,6 +94,11 @@ impl Value {
             Value::Image(_) => ValueKind::Image,
             Value::Table(_) => ValueKind::Table,
             Value::List(_) => ValueKind::List,
+            Value::Task(_) => ValueKind::Task,
+            Value::Callout(_) => ValueKind::Callout,
+            Value::Footnote(_) => ValueKind::Footnote,
+            Value::Math(_) => ValueKind::Math,
+            Value::Tag(_) => ValueKind::Tag,
             Value::Blockquote(_) => ValueKind::Blockquote,
             Value::Paragraph(_) => ValueKind::Paragraph,
             Value::Document(_) => ValueKind::Document,
@@ -151,6 +171,11 @@ impl Value {
             Value::Image(i) => i.get_property(name),
             Value::Table(t) => t.get_property(name),
             Value::List(l) => l.get_property(name),
+            Value::Task(t) => t.get_property(name),
+            Value::Callout(c) => c.get_property(name),
+            Value::Footnote(f) => f.get_property(name),
+            Value::Math(m) => m.get_property(name),
+            Value::Tag(t) => t.get_property(name),
             Value::Document(d) => d.get_property(name),
             Value::FrontMatter(fm) => fm.get(name).cloned(),
             _ => None,
@@ -185,6 +210,11 @@ impl Value {
                 .map(|i| i.content.clone())
                 .collect::<Vec<_>>()
                 .join("\n"),
+            Value::Task(t) => t.text.clone(),
+            Value::Callout(c) => format!("{}\n{}", c.title, c.content),
+            Value::Footnote(f) => f.content.clone(),
+            Value::Math(m) => m.tex.clone(),
+            Value::Tag(t) => t.name.clone(),
             Value::Blockquote(b) => b.content.clone(),
             Value::Paragraph(p) => p.content.clone(),
             Value::Document(d) => d.content.clone(),
@@ -282,6 +312,11 @@ pub enum ValueKind {
     Image,
     Table,
     List,
+    Task,
+    Callout,
+    Footnote,
+    Math,
+    Tag,
     Blockquote,
     Paragraph,
     Document,
@@ -303,6 +338,11 @@ impl fmt::Display for ValueKind {
             ValueKind::Image => "image",
             ValueKind::Table => "table",
             ValueKind::List => "list",
+            ValueKind::Task => "task",
+            ValueKind::Callout => "callout",
+            ValueKind::Footnote => "footnote",
+            ValueKind::Math => "math",
+            ValueKind::Tag => "tag",
             ValueKind::Blockquote => "blockquote",
             ValueKind::Paragraph => "paragraph",
             ValueKind::Document => "document",
@@ -472,6 +512,56 @@ impl TableValue {
             _ => None,
         }
     }
+
+    /// Rows as objects keyed by header, with numeric cells as numbers
+    pub fn row_objects(&self) -> Vec<Value> {
+        self.rows
+            .iter()
+            .map(|row| {
+                let object = self
+                    .headers
+                    .iter()
+                    .enumerate()
+                    .map(|(i, header)| {
+                        let cell = row.get(i).map(String::as_str).unwrap_or_default();
+                        (header.clone(), Self::cell_value(cell))
+                    })
+                    .collect();
+                Value::Object(object)
+            })
+            .collect()
+    }
+
+    /// Index of a column given by header name (case-insensitive) or 0-based number
+    pub fn column_index(&self, column: &Value) -> Option<usize> {
+        match column {
+            Value::Number(n) if n.fract() == 0.0 && *n >= 0.0 => {
+                Some(*n as usize).filter(|&i| i < self.headers.len())
+            }
+            _ => {
+                let name = column.to_text();
+                self.headers.iter().position(|h| h == &name).or_else(|| {
+                    self.headers
+                        .iter()
+                        .position(|h| h.eq_ignore_ascii_case(&name))
+                })
+            }
+        }
+    }
+
+    /// A cell as a value: a number if it parses cleanly, otherwise a string.
+    ///
+    /// Numbers with leading zeros (`007`, zip codes) stay strings.
+    pub fn cell_value(cell: &str) -> Value {
+        let trimmed = cell.trim();
+        let digits = trimmed.trim_start_matches(['-', '+']);
+        let leading_zero = digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.");
+
+        match trimmed.parse::<f64>() {
+            Ok(n) if n.is_finite() && !leading_zero => Value::Number(n),
+            _ => Value::String(cell.to_string()),
+        }
+    }
 }
 
 /// List element value.
@@ -505,6 +595,190 @@ pub struct ListItemValue {
     pub checked: Option<bool>,
 }
 
+/// Task list item value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskValue {
+    pub text: String,
+    pub checked: bool,
+    pub line: usize,
+    pub depth: usize,
+    /// Enclosing headings, outermost first
+    pub heading_path: Vec<String>,
+    /// Obsidian Tasks due date (`YYYY-MM-DD`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due: Option<String>,
+    /// Obsidian Tasks priority (`lowest` … `highest`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    /// Index of the innermost enclosing heading in the flat headings list
+    #[serde(skip)]
+    pub heading_index: Option<usize>,
+}
+
+impl TaskValue {
+    pub fn get_property(&self, name: &str) -> Option<Value> {
+        match name {
+            "text" | "content" => Some(Value::String(self.text.clone())),
+            "checked" | "done" => Some(Value::Bool(self.checked)),
+            "line" => Some(Value::Number(self.line as f64)),
+            "depth" => Some(Value::Number(self.depth as f64)),
+            "section" => Some(
+                self.heading_path
+                    .last()
+                    .cloned()
+                    .map(Value::String)
+                    .unwrap_or(Value::Null),
+            ),
+            "due" => Some(self.due.clone().map(Value::String).unwrap_or(Value::Null)),
+            "priority" => Some(
+                self.priority
+                    .clone()
+                    .map(Value::String)
+                    .unwrap_or(Value::Null),
+            ),
+            "path" | "heading_path" => Some(Value::Array(
+                self.heading_path
+                    .iter()
+                    .map(|h| Value::String(h.clone()))
+                    .collect(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Whether a `[open]` / `[done]` style filter word matches this task's
+    /// status (`None` if the word isn't a status)
+    pub fn matches_status(&self, word: &str) -> Option<bool> {
+        match word {
+            "open" | "todo" | "unchecked" | "pending" => Some(!self.checked),
+            "done" | "checked" | "complete" | "completed" => Some(self.checked),
+            _ => None,
+        }
+    }
+}
+
+/// Callout (`> [!warning] Title`) value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalloutValue {
+    /// Kind with aliases resolved (`[!faq]` is a `question`)
+    pub kind: String,
+    pub title: String,
+    /// Markdown body without the `>` markers
+    pub content: String,
+    pub foldable: bool,
+    /// Folded by default (`[!note]-`)
+    pub collapsed: bool,
+}
+
+impl CalloutValue {
+    pub fn get_property(&self, name: &str) -> Option<Value> {
+        match name {
+            "kind" | "type" => Some(Value::String(self.kind.clone())),
+            "title" => Some(Value::String(self.title.clone())),
+            "content" | "text" => Some(Value::String(self.content.clone())),
+            "foldable" => Some(Value::Bool(self.foldable)),
+            "collapsed" => Some(Value::Bool(self.collapsed)),
+            _ => None,
+        }
+    }
+
+    /// Whether a `[warning]` style filter word matches this callout's kind
+    /// (`None` if the word isn't a callout kind)
+    pub fn matches_kind(&self, word: &str) -> Option<bool> {
+        let kind = crate::parser::callouts::canonical_kind(word);
+        (kind == self.kind || crate::parser::callouts::is_known_kind(word))
+            .then(|| kind == self.kind)
+    }
+}
+
+/// Footnote definition (`[^label]: text`) value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FootnoteValue {
+    pub label: String,
+    pub content: String,
+    pub line: usize,
+    /// Number of `[^label]` references in the document
+    pub references: usize,
+}
+
+impl FootnoteValue {
+    pub fn get_property(&self, name: &str) -> Option<Value> {
+        match name {
+            "label" | "id" => Some(Value::String(self.label.clone())),
+            "content" | "text" => Some(Value::String(self.content.clone())),
+            "line" => Some(Value::Number(self.line as f64)),
+            "references" | "refs" => Some(Value::Number(self.references as f64)),
+            _ => None,
+        }
+    }
+}
+
+/// LaTeX math value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MathValue {
+    /// TeX source without the `$` delimiters
+    pub tex: String,
+    /// `$$...$$` or a ```` ```math ```` block
+    pub display: bool,
+    pub line: usize,
+}
+
+impl MathValue {
+    pub fn get_property(&self, name: &str) -> Option<Value> {
+        match name {
+            "tex" | "source" | "content" => Some(Value::String(self.tex.clone())),
+            "display" => Some(Value::Bool(self.display)),
+            "inline" => Some(Value::Bool(!self.display)),
+            "line" => Some(Value::Number(self.line as f64)),
+            "text" | "unicode" => Some(
+                crate::parser::math::to_unicode(&self.tex)
+                    .map(Value::String)
+                    .unwrap_or(Value::Null),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Whether a `[display]` / `[inline]` filter word matches this math
+    /// (`None` if the word isn't a placement)
+    pub fn matches_placement(&self, word: &str) -> Option<bool> {
+        match word {
+            "display" | "block" => Some(self.display),
+            "inline" => Some(!self.display),
+            _ => None,
+        }
+    }
+}
+
+/// Tag value: one per distinct tag, case-insensitively.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagValue {
+    /// Name without the `#`, as first written
+    pub name: String,
+    /// Number of occurrences, front matter included
+    pub count: usize,
+    /// 1-indexed lines of the occurrences
+    pub lines: Vec<usize>,
+}
+
+impl TagValue {
+    pub fn get_property(&self, name: &str) -> Option<Value> {
+        match name {
+            "name" | "text" | "tag" => Some(Value::String(self.name.clone())),
+            "count" => Some(Value::Number(self.count as f64)),
+            "lines" => Some(Value::Array(
+                self.lines
+                    .iter()
+                    .map(|&line| Value::Number(line as f64))
+                    .collect(),
+            )),
+            "line" => self.lines.first().map(|&line| Value::Number(line as f64)),
+            "nested" => Some(Value::Bool(self.name.contains('/'))),
+            _ => None,
+        }
+    }
+}
+
 /// Blockquote element value.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockquoteValue {
@@ -584,6 +858,34 @@ mod tests {
         assert!(Value::String("hello".into()).is_truthy());
     }
 
+    #[test]
+    fn test_table_cells_and_rows() {
+        assert!(matches!(TableValue::cell_value(" 42 "), Value::Number(n) if n == 42.0));
+        assert!(matches!(TableValue::cell_value("-1.5e3"), Value::Number(n) if n == -1500.0));
+        for text in ["007", "1,200", "NaN", "inf", "12 kg", ""] {
+            assert!(
+                matches!(TableValue::cell_value(text), Value::String(_)),
+                "{} should stay a string",
+                text
+            );
+        }
+
+        let table = TableValue {
+            headers: vec!["Name".to_string(), "Qty".to_string()],
+            rows: vec![vec!["bolts".to_string(), "12".to_string()]],
+            alignments: Vec::new(),
+        };
+        let rows = table.row_objects();
+        assert_eq!(rows[0].get_property("Name").unwrap().to_text(), "bolts");
+        assert!(matches!(
+            rows[0].get_property("Qty"),
+            Some(Value::Number(_))
+        ));
+        assert_eq!(table.column_index(&Value::String("qty".into())), Some(1));
+        assert_eq!(table.column_index(&Value::Number(0.0)), Some(0));
+        assert_eq!(table.column_index(&Value::Number(2.0)), None);
+    }
+
     #[test]
     fn test_slugify() {
         assert_eq!(slugify("Hello World"), "hello-world");
@@ -0,0 +1,709 @@
+//! Renaming headings and notes without breaking links to them.
+//!
+//! A rename is planned first: every note in the workspace (the vault, or the
+//! document's folder) is read and the links pointing at the renamed heading
+//! or note are rewritten in memory. The plan lists each changed line, so it
+//! can be shown as a preview, and is then applied with atomic writes.
+//!
+//! - Renaming a heading rewrites `[text](#anchor)`, `[text](note.md#anchor)`
+//!   and `[[note#Heading]]` links (embeds included). Anchors keep their
+//!   style: GitHub-style (`old-heading`) or turbovault-style slugs.
+//! - Renaming a note rewrites relative links and wikilinks to it, and the
+//!   relative links inside it when it moves to another folder.
+
+use crate::parser::content::slugify;
+use crate::parser::parse_markdown;
+use crate::parser::utils::line_of;
+use crate::vault::{Vault, is_note_extension, normalize_path};
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+
+/// A line changed by a rename
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineChange {
+    /// 1-indexed line number
+    pub line: usize,
+    pub before: String,
+    pub after: String,
+}
+
+/// New contents for a note
+#[derive(Debug, Clone)]
+pub struct FileRewrite {
+    pub path: PathBuf,
+    /// Contents the rewrite was planned against
+    pub original: String,
+    pub content: String,
+    pub changes: Vec<LineChange>,
+}
+
+/// Everything a rename will change, ready to preview or apply
+#[derive(Debug, Clone)]
+pub struct RenamePlan {
+    /// Workspace root, for showing paths
+    pub root: PathBuf,
+    /// Old and new text, when a heading is renamed
+    pub heading: Option<(String, String)>,
+    /// Old and new path, when a note is renamed
+    pub move_file: Option<(PathBuf, PathBuf)>,
+    pub rewrites: Vec<FileRewrite>,
+}
+
+impl RenamePlan {
+    /// Number of changed lines across all notes
+    pub fn change_count(&self) -> usize {
+        self.rewrites.iter().map(|r| r.changes.len()).sum()
+    }
+
+    /// `path` relative to the workspace root, if it is inside it
+    pub fn display_path<'a>(&self, path: &'a Path) -> std::path::Display<'a> {
+        path.strip_prefix(&self.root).unwrap_or(path).display()
+    }
+
+    /// Move the renamed note, then write the rewritten notes. A note that
+    /// changed since the plan was made is not overwritten: like a failed
+    /// write, it undoes the notes already written and the move.
+    pub fn apply(&self) -> Result<(), String> {
+        if let Some((from, to)) = &self.move_file {
+            if to.exists() {
+                return Err(format!("{} already exists", to.display()));
+            }
+            std::fs::rename(from, to)
+                .map_err(|e| format!("Failed to rename {}: {}", from.display(), e))?;
+        }
+
+        let mut originals = Vec::new();
+        for rewrite in &self.rewrites {
+            let path = match &self.move_file {
+                Some((from, to)) if rewrite.path == *from => to,
+                _ => &rewrite.path,
+            };
+            let written = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+                .and_then(|disk| {
+                    if disk != rewrite.original {
+                        return Err(format!(
+                            "{} changed since the rename was planned",
+                            self.display_path(path)
+                        ));
+                    }
+                    crate::edit::write_file_atomic(path, &rewrite.content)
+                });
+            match written {
+                Ok(()) => originals.push((path, rewrite.original.as_str())),
+                Err(e) => {
+                    self.roll_back(&originals);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Restore the notes changed so far and move the renamed note back
+    fn roll_back(&self, originals: &[(&PathBuf, &str)]) {
+        for (path, original) in originals.iter().rev() {
+            let _ = crate::edit::write_file_atomic(path, original);
+        }
+        if let Some((from, to)) = &self.move_file {
+            let _ = std::fs::rename(to, from);
+        }
+    }
+}
+
+/// The heading with this text (case-insensitive). An error lists the lines
+/// of all matches when there are several.
+pub fn find_heading(content: &str, text: &str) -> Result<usize, String> {
+    let matches: Vec<usize> = parse_markdown(content)
+        .headings
+        .into_iter()
+        .filter(|h| h.text.eq_ignore_ascii_case(text.trim()))
+        .map(|h| h.offset)
+        .collect();
+    match matches.as_slice() {
+        [offset] => Ok(*offset),
+        [] => Err(format!("Heading '{}' not found", text)),
+        _ => Err(format!(
+            "'{}' matches {} headings (lines {})",
+            text,
+            matches.len(),
+            matches
+                .iter()
+                .map(|&offset| line_of(content, offset).to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Plan renaming the heading at byte `offset` of `file` to `new_text`
+pub fn rename_heading(
+    workspace: &Vault,
+    file: &Path,
+    offset: usize,
+    new_text: &str,
+) -> Result<RenamePlan, String> {
+    let file = file
+        .canonicalize()
+        .map_err(|e| format!("Failed to open {}: {}", file.display(), e))?;
+    let content = read(&file)?;
+    let old_text = parse_markdown(&content)
+        .headings
+        .into_iter()
+        .find(|h| h.offset == offset)
+        .map(|h| h.text)
+        .ok_or_else(|| format!("No heading at byte {}", offset))?;
+    let new_text = new_text.trim();
+    if new_text.is_empty() || new_text.contains('\n') {
+        return Err("The new heading must be a single non-empty line".to_string());
+    }
+    if new_text == old_text {
+        return Err(format!("Heading is already '{}'", old_text));
+    }
+
+    let rewrite_anchor = |anchor: &str, wiki: bool| -> Option<String> {
+        if wiki {
+            // `[[note#Heading]]` or a nested `[[note#Parent#Heading]]`
+            let mut changed = false;
+            let parts: Vec<&str> = anchor
+                .split('#')
+                .map(|part| {
+                    if part.trim().eq_ignore_ascii_case(&old_text) {
+                        changed = true;
+                        new_text
+                    } else {
+                        part
+                    }
+                })
+                .collect();
+            return changed.then(|| parts.join("#"));
+        }
+        let anchor = anchor.to_lowercase();
+        [github_anchor, slugify]
+            .into_iter()
+            .find(|style| style(&old_text) == anchor)
+            .map(|style| style(new_text))
+    };
+
+    let mut rewrites = Vec::new();
+    for (path, content) in workspace_notes(workspace, &file) {
+        let mut edits = Vec::new();
+        if path == file {
+            edits.push(heading_edit(&content, offset, new_text));
+        }
+        for link in link_targets(&content) {
+            let (file_part, anchor) = link.split();
+            let Some(anchor) = anchor else {
+                continue;
+            };
+            if anchor.starts_with('^')
+                || resolve(workspace, &path, file_part, link.wiki).as_deref() != Some(&file)
+            {
+                continue;
+            }
+            if let Some(anchor) = rewrite_anchor(anchor, link.wiki) {
+                edits.push((link.range.clone(), format!("{}#{}", file_part, anchor)));
+            }
+        }
+        rewrites.extend(rewrite(path, &content, edits));
+    }
+
+    Ok(RenamePlan {
+        root: workspace.root().to_path_buf(),
+        heading: Some((old_text, new_text.to_string())),
+        move_file: None,
+        rewrites,
+    })
+}
+
+/// Plan renaming (or moving) the note `from` to `to`. Without an extension,
+/// `to` gets the one `from` has.
+pub fn rename_file(workspace: &Vault, from: &Path, to: &Path) -> Result<RenamePlan, String> {
+    let from = from
+        .canonicalize()
+        .map_err(|e| format!("Failed to open {}: {}", from.display(), e))?;
+    let mut to = to.to_path_buf();
+    if to.extension().is_none()
+        && let Some(ext) = from.extension()
+    {
+        to.set_extension(ext);
+    }
+    let to = match (to.parent(), to.file_name()) {
+        (Some(dir), Some(name)) => {
+            let dir = if dir.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                dir
+            };
+            dir.canonicalize()
+                .map_err(|_| format!("Folder {} doesn't exist", dir.display()))?
+                .join(name)
+        }
+        _ => return Err(format!("Invalid file name: {}", to.display())),
+    };
+    if to == from {
+        return Err("The new name is the same as the old one".to_string());
+    }
+    if to.exists() {
+        return Err(format!("{} already exists", to.display()));
+    }
+
+    let old_dir = dir_of(&from);
+    let new_dir = dir_of(&to);
+    let root = workspace.root();
+    // Bare names stay bare while no other note shares the new one
+    let new_stem = to.file_stem().map(|s| s.to_string_lossy().to_string());
+    let stem_is_unique = !workspace.notes().iter().any(|note| {
+        root.join(&note.path) != from
+            && note
+                .path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_lowercase())
+                == new_stem.as_ref().map(|s| s.to_lowercase())
+    });
+
+    let mut rewrites = Vec::new();
+    for (path, content) in workspace_notes(workspace, &from) {
+        let moved = path == from;
+        let dir = if moved {
+            new_dir.clone()
+        } else {
+            dir_of(&path)
+        };
+        let mut edits = Vec::new();
+        for link in link_targets(&content) {
+            let (file_part, anchor) = link.split();
+            if file_part.is_empty() {
+                continue;
+            }
+            let target = resolve(workspace, &path, file_part, link.wiki);
+            let new_target = if target.as_deref() == Some(&from) {
+                if link.wiki {
+                    Some(wikilink_name(file_part, &to, root, &dir, stem_is_unique))
+                } else {
+                    Some(relative_link(file_part, &to, &dir))
+                }
+            } else if moved && !link.wiki && old_dir != new_dir && !is_external(file_part) {
+                // The moved note's own relative links start from its new folder
+                normalize_path(&old_dir.join(file_part))
+                    .map(|target| relative_link(file_part, &target, &dir))
+            } else {
+                None
+            };
+            let Some(mut new_target) = new_target else {
+                continue;
+            };
+            if !link.wiki && new_target.contains(' ') && !content[..link.range.start].ends_with('<')
+            {
+                new_target = format!("<{}>", new_target);
+            }
+            if let Some(anchor) = anchor {
+                new_target = match new_target.strip_suffix('>') {
+                    Some(target) => format!("{}#{}>", target, anchor),
+                    None => format!("{}#{}", new_target, anchor),
+                };
+            }
+            edits.push((link.range.clone(), new_target));
+        }
+        rewrites.extend(rewrite(path, &content, edits));
+    }
+
+    Ok(RenamePlan {
+        root: root.to_path_buf(),
+        heading: None,
+        move_file: Some((from, to)),
+        rewrites,
+    })
+}
+
+/// A link's target text and where it is in the note
+struct LinkSpan {
+    range: Range<usize>,
+    target: String,
+    wiki: bool,
+}
+
+impl LinkSpan {
+    /// File part and anchor (`note.md` and `usage` in `note.md#usage`)
+    fn split(&self) -> (&str, Option<&str>) {
+        match self.target.split_once('#') {
+            Some((file, anchor)) => (file, Some(anchor)),
+            None => (self.target.as_str(), None),
+        }
+    }
+}
+
+/// Targets of the markdown links, wikilinks and embeds in a note
+fn link_targets(content: &str) -> Vec<LinkSpan> {
+    let mut spans = Vec::new();
+    for link in turbovault_parser::parse_markdown_links(content) {
+        let start = link.position.offset;
+        let raw = &content[start..start + link.position.length];
+        // The target follows `](` (or `](<`); the text before it may hold brackets
+        let Some(idx) = raw
+            .rfind(&format!("]({}", link.target))
+            .map(|idx| idx + 2)
+            .or_else(|| raw.rfind(&format!("](<{}", link.target)).map(|idx| idx + 3))
+        else {
+            continue;
+        };
+        let start = start + idx;
+        spans.push(LinkSpan {
+            range: start..start + link.target.len(),
+            target: link.target,
+            wiki: false,
+        });
+    }
+    let wikilinks = turbovault_parser::parse_wikilinks(content)
+        .into_iter()
+        .map(|link| (link, 2))
+        .chain(
+            turbovault_parser::parse_embeds(content)
+                .into_iter()
+                .map(|link| (link, 3)),
+        );
+    for (link, opening) in wikilinks {
+        let start = link.position.offset + opening;
+        if content[start..].starts_with(&link.target) {
+            spans.push(LinkSpan {
+                range: start..start + link.target.len(),
+                target: link.target,
+                wiki: true,
+            });
+        }
+    }
+    spans
+}
+
+/// Absolute path of the note a link's file part points at, from `source`.
+/// An empty file part is the note itself.
+fn resolve(workspace: &Vault, source: &Path, file_part: &str, wiki: bool) -> Option<PathBuf> {
+    if file_part.is_empty() {
+        return Some(source.to_path_buf());
+    }
+    if wiki {
+        return workspace.resolve(file_part, source).into_iter().next();
+    }
+    if is_external(file_part) {
+        return None;
+    }
+    let mut path = normalize_path(&dir_of(source).join(file_part))?;
+    if path.extension().is_none() {
+        path.set_extension("md");
+    }
+    Some(path)
+}
+
+/// Path and content of every note in the workspace, plus `file` itself
+fn workspace_notes(workspace: &Vault, file: &Path) -> Vec<(PathBuf, String)> {
+    let mut paths: Vec<PathBuf> = workspace
+        .notes()
+        .iter()
+        .map(|note| workspace.root().join(&note.path))
+        .collect();
+    if !paths.iter().any(|path| path == file) {
+        paths.push(file.to_path_buf());
+    }
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(&path).ok()?;
+            Some((path, content))
+        })
+        .collect()
+}
+
+/// Replace the text of the heading at `offset`, keeping its markers
+fn heading_edit(content: &str, offset: usize, new_text: &str) -> (Range<usize>, String) {
+    let end = content[offset..]
+        .find('\n')
+        .map_or(content.len(), |idx| offset + idx);
+    let line = content[offset..end].trim_end_matches('\r');
+    let hashes = line.trim_start().len() - line.trim_start().trim_start_matches('#').len();
+    if hashes == 0 {
+        // Setext heading: the whole line is the text
+        let indent = line.len() - line.trim_start().len();
+        return (offset + indent..offset + line.len(), new_text.to_string());
+    }
+
+    let text_start = line.len() - line.trim_start().trim_start_matches('#').trim_start().len();
+    // A closing sequence (`## Title ##`) stays
+    let body = line.trim_end();
+    let closing = body.trim_end_matches('#');
+    let text_end = if closing.len() < body.len() && closing.ends_with([' ', '\t']) {
+        closing.trim_end().len()
+    } else {
+        body.len()
+    };
+    (
+        offset + text_start..offset + text_end.max(text_start),
+        new_text.to_string(),
+    )
+}
+
+/// Apply edits to a note and list the lines they change. `None` if nothing
+/// changes.
+fn rewrite(
+    path: PathBuf,
+    content: &str,
+    mut edits: Vec<(Range<usize>, String)>,
+) -> Option<FileRewrite> {
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+    edits.dedup_by_key(|(range, _)| range.start);
+    let mut new_content = content.to_string();
+    for (range, text) in &edits {
+        new_content.replace_range(range.clone(), text);
+    }
+    if new_content == content {
+        return None;
+    }
+
+    let changes = content
+        .lines()
+        .zip(new_content.lines())
+        .enumerate()
+        .filter(|(_, (before, after))| before != after)
+        .map(|(idx, (before, after))| LineChange {
+            line: idx + 1,
+            before: before.to_string(),
+            after: after.to_string(),
+        })
+        .collect();
+    Some(FileRewrite {
+        path,
+        original: content.to_string(),
+        content: new_content,
+        changes,
+    })
+}
+
+/// A wikilink's new file part for a renamed note: the bare name if that
+/// still finds it, else a path from the vault root (or the linking note)
+fn wikilink_name(old: &str, to: &Path, root: &Path, dir: &Path, stem_is_unique: bool) -> String {
+    let keep_extension = Path::new(old).extension().is_some_and(is_note_extension);
+    let path = if keep_extension {
+        to.to_path_buf()
+    } else {
+        to.with_extension("")
+    };
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string());
+    if !old.contains('/')
+        && let Some(name) = name
+        && (stem_is_unique || to.parent() == Some(dir))
+    {
+        return name;
+    }
+    match path.strip_prefix(root) {
+        Ok(relative) => slash_path(relative),
+        Err(_) => slash_path(&relative_path(dir, &path)),
+    }
+}
+
+/// A relative link's new path to `target`, written the way the old one was
+fn relative_link(old: &str, target: &Path, dir: &Path) -> String {
+    let target = if Path::new(old).extension().is_none() {
+        target.with_extension("")
+    } else {
+        target.to_path_buf()
+    };
+    let path = slash_path(&relative_path(dir, &target));
+    if old.starts_with("./") && !path.starts_with("../") {
+        format!("./{}", path)
+    } else {
+        path
+    }
+}
+
+/// Path from directory `from` to `to`, both absolute
+fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from: Vec<Component> = from.components().collect();
+    let to: Vec<Component> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut path = PathBuf::new();
+    for _ in common..from.len() {
+        path.push("..");
+    }
+    for component in &to[common..] {
+        path.push(component);
+    }
+    path
+}
+
+fn slash_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+fn dir_of(path: &Path) -> PathBuf {
+    path.parent().unwrap_or(Path::new("/")).to_path_buf()
+}
+
+fn is_external(target: &str) -> bool {
+    target.contains("://") || target.starts_with("mailto:")
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+/// GitHub's heading anchor: lowercase, punctuation dropped, spaces to `-`
+fn github_anchor(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn workspace() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("projects")).unwrap();
+        fs::write(
+            root.join("guide.md"),
+            "# Guide\n\n## Getting Started ##\n\nSee [below](#getting-started) and [[#Getting Started]].\n\n[Plan](projects/plan.md)\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("projects/plan.md"),
+            "# Plan\n\nRead [the guide](../guide.md#getting-started), [[guide#Getting Started|start]].\n\n![[guide#getting started]] [[guide]] `[[guide#Getting Started]]`\n",
+        )
+        .unwrap();
+        (dir, root)
+    }
+
+    #[test]
+    fn test_rename_heading() {
+        let (_dir, root) = workspace();
+        let vault = Vault::open(root.clone());
+        let guide = root.join("guide.md");
+        let content = fs::read_to_string(&guide).unwrap();
+        let offset = find_heading(&content, "getting started").unwrap();
+
+        let plan = rename_heading(&vault, &guide, offset, "First Steps").unwrap();
+        assert_eq!(plan.change_count(), 4);
+        plan.apply().unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&guide).unwrap(),
+            "# Guide\n\n## First Steps ##\n\nSee [below](#first-steps) and [[#First Steps]].\n\n[Plan](projects/plan.md)\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("projects/plan.md")).unwrap(),
+            "# Plan\n\nRead [the guide](../guide.md#first-steps), [[guide#First Steps|start]].\n\n![[guide#First Steps]] [[guide]] `[[guide#Getting Started]]`\n"
+        );
+        assert!(
+            find_heading("# A\n## A\n", "a")
+                .unwrap_err()
+                .contains("lines 1, 2")
+        );
+    }
+
+    #[test]
+    fn test_rename_file() {
+        let (_dir, root) = workspace();
+        let vault = Vault::open(root.clone());
+
+        // Moving into another folder rewrites links to it and its own links
+        let plan = rename_file(
+            &vault,
+            &root.join("guide.md"),
+            &root.join("projects/Guide Book"),
+        )
+        .unwrap();
+        assert_eq!(
+            plan.move_file.as_ref().map(|(_, to)| to.clone()),
+            Some(root.join("projects/Guide Book.md"))
+        );
+        let preview: Vec<(String, usize)> = plan
+            .rewrites
+            .iter()
+            .flat_map(|r| {
+                r.changes
+                    .iter()
+                    .map(|c| (plan.display_path(&r.path).to_string(), c.line))
+            })
+            .collect();
+        assert_eq!(
+            preview,
+            vec![
+                ("guide.md".to_string(), 7),
+                ("projects/plan.md".to_string(), 3),
+                ("projects/plan.md".to_string(), 5)
+            ]
+        );
+        plan.apply().unwrap();
+
+        assert!(!root.join("guide.md").exists());
+        assert!(
+            fs::read_to_string(root.join("projects/Guide Book.md"))
+                .unwrap()
+                .ends_with("[Plan](plan.md)\n")
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("projects/plan.md")).unwrap(),
+            "# Plan\n\nRead [the guide](<Guide Book.md#getting-started>), [[Guide Book#Getting Started|start]].\n\n![[Guide Book#getting started]] [[Guide Book]] `[[guide#Getting Started]]`\n"
+        );
+        assert!(
+            rename_file(
+                &vault,
+                &root.join("projects/plan.md"),
+                &root.join("projects/Guide Book.md")
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_apply_rolls_back_on_failure() {
+        let (_dir, root) = workspace();
+        let vault = Vault::open(root.clone());
+        let guide = fs::read_to_string(root.join("guide.md")).unwrap();
+        let plan_note = fs::read_to_string(root.join("projects/plan.md")).unwrap();
+
+        let mut plan = rename_file(&vault, &root.join("guide.md"), &root.join("Manual")).unwrap();
+        plan.rewrites.push(FileRewrite {
+            path: root.join("missing/note.md"),
+            original: String::new(),
+            content: String::new(),
+            changes: Vec::new(),
+        });
+        assert!(plan.apply().unwrap_err().starts_with("Failed to read"));
+
+        // Nothing changed: the note is back and the links are untouched
+        assert!(!root.join("Manual.md").exists());
+        assert_eq!(fs::read_to_string(root.join("guide.md")).unwrap(), guide);
+        assert_eq!(
+            fs::read_to_string(root.join("projects/plan.md")).unwrap(),
+            plan_note
+        );
+    }
+
+    #[test]
+    fn test_apply_refuses_stale_plan() {
+        let (_dir, root) = workspace();
+        let vault = Vault::open(root.clone());
+        let guide = root.join("guide.md");
+        let original = fs::read_to_string(&guide).unwrap();
+        let offset = find_heading(&original, "getting started").unwrap();
+        let plan = rename_heading(&vault, &guide, offset, "First Steps").unwrap();
+
+        // Edited after the preview was made: the plan's contents are stale
+        let edited = "# Plan\n\nNew text, [[guide#Getting Started]].\n";
+        fs::write(root.join("projects/plan.md"), edited).unwrap();
+        assert_eq!(
+            plan.apply().unwrap_err(),
+            "projects/plan.md changed since the rename was planned"
+        );
+
+        assert_eq!(fs::read_to_string(&guide).unwrap(), original);
+        assert_eq!(
+            fs::read_to_string(root.join("projects/plan.md")).unwrap(),
+            edited
+        );
+    }
+}
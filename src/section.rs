@@ -0,0 +1,688 @@
+//! Structural edits on the sections of a document.
+//!
+//! A section runs from its heading line up to the next heading of the same or
+//! a higher level, so its subsections always travel with it. Every edit works
+//! on the byte ranges given by [`Heading::offset`](crate::parser::Heading) and
+//! returns the new document text; writing it back is up to the caller.
+
+use crate::parser::content::slugify;
+use crate::parser::utils::line_of;
+use crate::parser::{Document, parse_markdown};
+use std::ops::Range;
+
+/// Deepest heading level markdown has
+const MAX_LEVEL: usize = 6;
+
+/// New document text after an edit that keeps the section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionEdit {
+    pub content: String,
+    /// Byte offset of the edited section's heading in `content`
+    pub offset: usize,
+}
+
+/// Index of the first heading after the subtree of heading `idx`
+fn subtree_end(doc: &Document, idx: usize) -> usize {
+    let level = doc.headings[idx].level;
+    doc.headings[idx + 1..]
+        .iter()
+        .position(|h| h.level <= level)
+        .map_or(doc.headings.len(), |pos| idx + 1 + pos)
+}
+
+/// Byte range of the section under heading `idx`, from its heading line to
+/// the end of its last subsection
+pub fn span(doc: &Document, idx: usize) -> Range<usize> {
+    let end = doc
+        .headings
+        .get(subtree_end(doc, idx))
+        .map_or(doc.content.len(), |h| h.offset);
+    doc.headings[idx].offset..end
+}
+
+/// The heading before `idx` at the same level and under the same parent
+fn previous_sibling(doc: &Document, idx: usize) -> Option<usize> {
+    let level = doc.headings[idx].level;
+    let pos = doc.headings[..idx].iter().rposition(|h| h.level <= level)?;
+    (doc.headings[pos].level == level).then_some(pos)
+}
+
+/// The heading after `idx`'s subtree at the same level
+fn next_sibling(doc: &Document, idx: usize) -> Option<usize> {
+    let next = subtree_end(doc, idx);
+    doc.headings
+        .get(next)
+        .filter(|h| h.level == doc.headings[idx].level)
+        .map(|_| next)
+}
+
+/// Length of `text` without the blank lines trailing it
+fn trimmed_len(text: &str) -> usize {
+    let trimmed = text.trim_end().len();
+    trimmed
+        + text[trimmed..]
+            .find(['\r', '\n'])
+            .unwrap_or(text.len() - trimmed)
+}
+
+/// Swap section `idx` with its previous (`up`) or next sibling, subsections
+/// included. The blank lines between sections stay where they were.
+pub fn move_section(doc: &Document, idx: usize, up: bool) -> Result<SectionEdit, String> {
+    let (first, second) = if up {
+        let prev = previous_sibling(doc, idx).ok_or("Already the first section at its level")?;
+        (prev, idx)
+    } else {
+        let next = next_sibling(doc, idx).ok_or("Already the last section at its level")?;
+        (idx, next)
+    };
+
+    let (a, b) = (span(doc, first), span(doc, second));
+    let content = &doc.content;
+    let (a_end, b_end) = (
+        a.start + trimmed_len(&content[a.clone()]),
+        b.start + trimmed_len(&content[b.clone()]),
+    );
+    let moved = format!(
+        "{}{}{}{}{}{}",
+        &content[..a.start],
+        &content[b.start..b_end],
+        &content[a_end..b.start],
+        &content[a.start..a_end],
+        &content[b_end..b.end],
+        &content[b.end..]
+    );
+    let offset = if up {
+        a.start
+    } else {
+        a.start + (b_end - b.start) + (b.start - a_end)
+    };
+    Ok(SectionEdit {
+        content: moved,
+        offset,
+    })
+}
+
+/// Shift the level of section `idx` and all its subsections by `delta`
+/// (negative promotes)
+pub fn shift_section(doc: &Document, idx: usize, delta: isize) -> Result<SectionEdit, String> {
+    let range = span(doc, idx);
+    let shifted = shift_levels(&doc.content[range.clone()], delta)?;
+    Ok(SectionEdit {
+        content: format!(
+            "{}{}{}",
+            &doc.content[..range.start],
+            shifted,
+            &doc.content[range.end..]
+        ),
+        offset: range.start,
+    })
+}
+
+/// Shift every heading in `text` by `delta` levels. Headings in code blocks
+/// are left alone; setext headings deeper than level 2 become ATX ones.
+pub fn shift_levels(text: &str, delta: isize) -> Result<String, String> {
+    let doc = parse_markdown(text);
+    let mut shifted = text.to_string();
+    for heading in doc.headings.iter().rev() {
+        let level = heading
+            .level
+            .checked_add_signed(delta)
+            .filter(|level| (1..=MAX_LEVEL).contains(level))
+            .ok_or_else(|| {
+                if delta < 0 {
+                    format!("'{}' is already a top-level heading", heading.text)
+                } else {
+                    format!(
+                        "'{}' can't go deeper than level {}",
+                        heading.text, MAX_LEVEL
+                    )
+                }
+            })?;
+        let (range, marker) = heading_marker(text, heading.offset, level);
+        shifted.replace_range(range, &marker);
+    }
+    Ok(shifted)
+}
+
+/// The markers of the heading at `offset` and their replacement for `level`
+fn heading_marker(text: &str, offset: usize, level: usize) -> (Range<usize>, String) {
+    let line_end = |start: usize| {
+        text[start..]
+            .find('\n')
+            .map_or(text.len(), |idx| start + idx)
+    };
+    let end = line_end(offset);
+    let line = &text[offset..end];
+    let hashes = line.len() - line.trim_start_matches('#').len();
+    if hashes > 0 {
+        return (offset..offset + hashes, "#".repeat(level));
+    }
+
+    // Setext heading: the underline is the next line
+    let underline = (end + 1).min(text.len());
+    let underline_end = line_end(underline);
+    let indent =
+        text[underline..underline_end].len() - text[underline..underline_end].trim_start().len();
+    let marker_end = underline + indent + text[underline + indent..underline_end].trim_end().len();
+    match level {
+        1 | 2 => {
+            let ch = if level == 1 { "=" } else { "-" };
+            let width = marker_end - underline - indent;
+            (underline + indent..marker_end, ch.repeat(width))
+        }
+        _ => (
+            offset..marker_end,
+            format!(
+                "{} {}",
+                "#".repeat(level),
+                line.trim_end_matches('\r').trim()
+            ),
+        ),
+    }
+}
+
+/// End of the heading line of section `idx` (after the underline of a setext
+/// heading), before its line break
+fn heading_end(doc: &Document, idx: usize) -> usize {
+    let content = &doc.content;
+    let line_end = |from: usize| {
+        content[from..]
+            .find('\n')
+            .map_or(content.len(), |i| from + i)
+    };
+    let start = doc.headings[idx].offset;
+    let end = line_end(start);
+    if content[start..].starts_with('#') {
+        end
+    } else {
+        line_end((end + 1).min(content.len()))
+    }
+}
+
+/// Find the section `address` points at:
+///
+/// - `Install/Linux`: a heading, optionally preceded by some of its ancestors
+/// - `Errors[2]`: the second heading matching `Errors`
+/// - `#install-1`: a heading anchor, numbered like GitHub does for repeats
+/// - `@42`: the section containing line 42
+///
+/// Heading text matches case-insensitively. An address matching several
+/// headings is an error that lists them.
+pub fn find(doc: &Document, address: &str) -> Result<usize, String> {
+    let address = address.trim();
+    if let Some(line) = address.strip_prefix('@') {
+        let line: usize = line
+            .parse()
+            .map_err(|_| format!("Invalid line number '{}'", line))?;
+        return doc
+            .headings
+            .iter()
+            .rposition(|h| line_of(&doc.content, h.offset) <= line)
+            .ok_or_else(|| format!("No section contains line {}", line));
+    }
+
+    if let Some(anchor) = address.strip_prefix('#') {
+        let anchor = anchor.to_lowercase();
+        return anchors(doc)
+            .iter()
+            .position(|a| *a == anchor)
+            .ok_or_else(|| format!("No heading has the anchor '#{}'", anchor));
+    }
+
+    let found = find_by_text(doc, address);
+    // `Errors[2]` picks among repeats, unless a heading is literally named so
+    if found.is_empty()
+        && let Some((base, n)) = split_index(address)
+    {
+        let found = find_by_text(doc, base);
+        return match n.checked_sub(1).and_then(|i| found.get(i)) {
+            Some(&idx) => Ok(idx),
+            None if found.is_empty() => Err(format!("Section '{}' not found", base)),
+            None => Err(format!(
+                "'{}' matches only {} heading{}",
+                base,
+                found.len(),
+                if found.len() == 1 { "" } else { "s" }
+            )),
+        };
+    }
+
+    match found.as_slice() {
+        [] => Err(format!("Section '{}' not found", address)),
+        [idx] => Ok(*idx),
+        _ => {
+            let anchors = anchors(doc);
+            let candidates: Vec<String> = found
+                .iter()
+                .map(|&idx| {
+                    format!(
+                        "  @{}  #{}  {}",
+                        line_of(&doc.content, doc.headings[idx].offset),
+                        anchors[idx],
+                        heading_path(doc, idx)
+                    )
+                })
+                .collect();
+            Err(format!(
+                "'{}' matches {} headings (pick one with a path, '{}[N]', an anchor or @line):\n{}",
+                address,
+                found.len(),
+                address,
+                candidates.join("\n")
+            ))
+        }
+    }
+}
+
+/// Headings named `address`, or reached by it as a heading path
+fn find_by_text(doc: &Document, address: &str) -> Vec<usize> {
+    // Either the parsed text or the source matches, with or without markup
+    let names: Vec<[String; 2]> = (0..doc.headings.len())
+        .map(|idx| [plain(&doc.headings[idx].text), plain(source_text(doc, idx))])
+        .collect();
+    let matches = |idx: usize, text: &str| names[idx].contains(&plain(text));
+    // A heading whose text contains '/' is matched whole first
+    let found: Vec<usize> = (0..doc.headings.len())
+        .filter(|&idx| matches(idx, address))
+        .collect();
+    if !found.is_empty() || !address.contains('/') {
+        return found;
+    }
+
+    let segments: Vec<&str> = address.split('/').collect();
+    let (last, ancestors) = segments.split_last().unwrap_or((&"", &[]));
+    (0..doc.headings.len())
+        .filter(|&idx| matches(idx, last))
+        .filter(|&idx| {
+            // The other segments must be ancestors, in order
+            let chain = ancestry(doc, idx);
+            let mut chain = chain.iter();
+            ancestors
+                .iter()
+                .all(|segment| chain.any(|&a| matches(a, segment)))
+        })
+        .collect()
+}
+
+/// Heading `idx` as written, without its `#` markers
+fn source_text(doc: &Document, idx: usize) -> &str {
+    let start = doc.headings[idx].offset;
+    let line = doc.content[start..].lines().next().unwrap_or("");
+    let text = line.trim_start_matches('#').trim();
+    // A closing sequence (`## Title ##`) isn't part of the text
+    let open = text.trim_end_matches('#');
+    if open.len() < text.len() && (open.is_empty() || open.ends_with([' ', '\t'])) {
+        open.trim_end()
+    } else {
+        text
+    }
+}
+
+/// Lowercase `text` without inline markup and with single spaces, so that
+/// ``The `Config` *type*`` and `the config type` compare equal
+fn plain(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '`' | '*' | '_' | '~' | '[' => {}
+            // Keep a link's text, drop its target
+            ']' if chars.peek() == Some(&'(') => {
+                for c in chars.by_ref() {
+                    if c == ')' {
+                        break;
+                    }
+                }
+            }
+            ']' => {}
+            c => plain.extend(c.to_lowercase()),
+        }
+    }
+    plain.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Split `Errors[2]` into `Errors` and 2
+fn split_index(address: &str) -> Option<(&str, usize)> {
+    let (base, index) = address.strip_suffix(']')?.rsplit_once('[')?;
+    Some((base, index.trim().parse().ok()?))
+}
+
+/// The ancestors of heading `idx`, outermost first
+fn ancestry(doc: &Document, idx: usize) -> Vec<usize> {
+    let mut chain = Vec::new();
+    let mut level = doc.headings[idx].level;
+    for pos in (0..idx).rev() {
+        if doc.headings[pos].level < level {
+            chain.push(pos);
+            level = doc.headings[pos].level;
+        }
+    }
+    chain.reverse();
+    chain
+}
+
+/// Heading `idx` with its ancestors, as in `API/Errors`
+pub fn heading_path(doc: &Document, idx: usize) -> String {
+    ancestry(doc, idx)
+        .into_iter()
+        .chain([idx])
+        .map(|pos| doc.headings[pos].text.as_str())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Anchor of every heading; repeats get `-1`, `-2`, ... appended
+fn anchors(doc: &Document) -> Vec<String> {
+    let mut seen = std::collections::HashMap::new();
+    doc.headings
+        .iter()
+        .map(|h| {
+            let slug = slugify(&h.text);
+            let count = seen.entry(slug.clone()).or_insert(0);
+            let anchor = match *count {
+                0 => slug,
+                n => format!("{}-{}", slug, n),
+            };
+            *count += 1;
+            anchor
+        })
+        .collect()
+}
+
+/// The text of section `idx`: its heading, content and subsections
+pub fn text(doc: &Document, idx: usize) -> &str {
+    let range = span(doc, idx);
+    &doc.content[range.start..range.start + trimmed_len(&doc.content[range])]
+}
+
+/// Replace everything under the heading of section `idx` (subsections
+/// included) with `body`
+pub fn set_body(doc: &Document, idx: usize, body: &str) -> String {
+    let range = span(doc, idx);
+    let heading_end = heading_end(doc, idx);
+    let body_end = range.start + trimmed_len(&doc.content[range.clone()]);
+    let body = body.trim_end();
+    format!(
+        "{}{}{}{}",
+        &doc.content[..heading_end],
+        if body.is_empty() { "" } else { "\n\n" },
+        body,
+        &doc.content[body_end.max(heading_end)..]
+    )
+}
+
+/// Add `body` at the end of section `idx`, after its subsections
+pub fn append(doc: &Document, idx: usize, body: &str) -> String {
+    let range = span(doc, idx);
+    let body_end = range.start + trimmed_len(&doc.content[range]);
+    insert(&doc.content, body_end, &format!("\n\n{}", body.trim_end()))
+}
+
+/// Add `body` right under the heading of section `idx`
+pub fn prepend(doc: &Document, idx: usize, body: &str) -> String {
+    let range = span(doc, idx);
+    let heading_end = heading_end(doc, idx);
+    let under = &doc.content[heading_end..range.end];
+    if under.trim().is_empty() {
+        return set_body(doc, idx, body);
+    }
+    // Keep one blank line on each side of the new text
+    let rest = heading_end + under.len() - under.trim_start_matches(['\r', '\n']).len();
+    format!(
+        "{}\n\n{}\n\n{}",
+        &doc.content[..heading_end],
+        body.trim_end(),
+        &doc.content[rest..]
+    )
+}
+
+/// Move section `idx` right before section `target`, or after it (and its
+/// subsections) with `after`, shifting its headings by `delta` levels on the
+/// way. The moved section is set off by one blank line on each side.
+pub fn move_to(
+    doc: &Document,
+    idx: usize,
+    target: usize,
+    after: bool,
+    delta: isize,
+) -> Result<String, String> {
+    let range = span(doc, idx);
+    let target_range = span(doc, target);
+    if range.contains(&target_range.start) {
+        return Err("Can't move a section next to itself or its subsections".to_string());
+    }
+    if target_range.contains(&range.start) {
+        return Err("Can't move a section next to its own parent section".to_string());
+    }
+
+    let moved = match delta {
+        0 => text(doc, idx).to_string(),
+        _ => shift_levels(text(doc, idx), delta)?,
+    };
+    let at = if after {
+        target_range.start + trimmed_len(&doc.content[target_range])
+    } else {
+        target_range.start
+    };
+    // The spans don't overlap, so cutting only shifts a later insertion point
+    let at = if at >= range.end {
+        at - range.len()
+    } else {
+        at
+    };
+    let cut = format!(
+        "{}{}",
+        &doc.content[..range.start],
+        &doc.content[range.end..]
+    );
+
+    let (before, rest) = (cut[..at].trim_end(), skip_blank_lines(&cut[at..]));
+    let mut content = String::new();
+    if !before.is_empty() {
+        content.push_str(before);
+        content.push_str("\n\n");
+    }
+    content.push_str(&moved);
+    if !rest.is_empty() {
+        content.push_str("\n\n");
+        content.push_str(rest);
+    }
+    Ok(tidy_end(content))
+}
+
+/// `text` without its leading blank lines
+fn skip_blank_lines(text: &str) -> &str {
+    let mut rest = text;
+    while let Some((line, next)) = rest.split_once('\n') {
+        if !line.trim().is_empty() {
+            break;
+        }
+        rest = next;
+    }
+    if rest.trim().is_empty() { "" } else { rest }
+}
+
+/// `content` with `text` inserted at byte `at`
+fn insert(content: &str, at: usize, text: &str) -> String {
+    format!("{}{}{}", &content[..at], text, &content[at..])
+}
+
+/// End the document with a single line break
+fn tidy_end(content: String) -> String {
+    format!("{}\n", content.trim_end())
+}
+
+/// Remove section `idx` and its subsections
+pub fn delete_section(doc: &Document, idx: usize) -> String {
+    let range = span(doc, idx);
+    let before = &doc.content[..range.start];
+    let after = &doc.content[range.end..];
+    if after.is_empty() && !before.trim().is_empty() {
+        // Don't leave the blank lines that separated it behind
+        return format!("{}\n", before.trim_end());
+    }
+    format!("{}{}", before, after)
+}
+
+/// Move the body of section `idx` out, leaving its heading with `link` under
+/// it. Returns the new document text and the extracted section, re-levelled
+/// so its heading is a title.
+pub fn extract_section(doc: &Document, idx: usize, link: &str) -> Result<(String, String), String> {
+    let range = span(doc, idx);
+    let body_end = range.start + trimmed_len(&doc.content[range.clone()]);
+    let heading_end = heading_end(doc, idx);
+
+    let extracted = shift_levels(
+        &format!("{}\n", &doc.content[range.start..body_end]),
+        1 - doc.headings[idx].level as isize,
+    )?;
+    let content = format!(
+        "{}\n\n{}{}",
+        doc.content[..heading_end].trim_end(),
+        link,
+        &doc.content[body_end.max(heading_end)..]
+    );
+    Ok((content, extracted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "# Title\n\nintro\n\n## A\n\na text\n\n### A1\n\nsub\n\n## B\n\nb text\n";
+
+    #[test]
+    fn test_move_section() {
+        let doc = parse_markdown(DOC);
+        let moved = move_section(&doc, 3, true).unwrap();
+        assert_eq!(
+            moved.content,
+            "# Title\n\nintro\n\n## B\n\nb text\n\n## A\n\na text\n\n### A1\n\nsub\n"
+        );
+        assert_eq!(&moved.content[moved.offset..moved.offset + 4], "## B");
+
+        let moved = move_section(&doc, 1, false).unwrap();
+        assert_eq!(&moved.content[moved.offset..moved.offset + 4], "## A");
+        assert!(moved.content.ends_with("## A\n\na text\n\n### A1\n\nsub\n"));
+
+        assert!(move_section(&doc, 1, true).is_err());
+        assert!(move_section(&doc, 2, true).is_err());
+    }
+
+    #[test]
+    fn test_shift_levels() {
+        let doc = parse_markdown(DOC);
+        let demoted = shift_section(&doc, 1, 1).unwrap();
+        assert_eq!(
+            demoted.content,
+            "# Title\n\nintro\n\n### A\n\na text\n\n#### A1\n\nsub\n\n## B\n\nb text\n"
+        );
+        assert!(shift_section(&doc, 0, -1).is_err());
+
+        assert_eq!(
+            shift_levels("Top\n===\n\n```\n# code\n```\n\nSub\n---\n", 1).unwrap(),
+            "Top\n---\n\n```\n# code\n```\n\n### Sub\n"
+        );
+        assert!(shift_levels("###### Deep\n", 1).is_err());
+    }
+
+    #[test]
+    fn test_delete_and_extract() {
+        let doc = parse_markdown(DOC);
+        assert_eq!(
+            delete_section(&doc, 1),
+            "# Title\n\nintro\n\n## B\n\nb text\n"
+        );
+        assert_eq!(
+            delete_section(&doc, 3),
+            "# Title\n\nintro\n\n## A\n\na text\n\n### A1\n\nsub\n"
+        );
+
+        let (content, extracted) = extract_section(&doc, 1, "[A](a.md)").unwrap();
+        assert_eq!(
+            content,
+            "# Title\n\nintro\n\n## A\n\n[A](a.md)\n\n## B\n\nb text\n"
+        );
+        assert_eq!(extracted, "# A\n\na text\n\n## A1\n\nsub\n");
+    }
+
+    #[test]
+    fn test_find() {
+        let doc = parse_markdown(
+            "# API\n\n## Errors\n\n### Codes\n\n# CLI\n\n## Errors\n\n## Input/Output\n",
+        );
+        assert_eq!(find(&doc, "api/errors"), Ok(1));
+        assert_eq!(find(&doc, "API/Codes"), Ok(2));
+        assert_eq!(find(&doc, "CLI/Errors"), Ok(4));
+        assert_eq!(find(&doc, "Input/Output"), Ok(5));
+        assert_eq!(find(&doc, "#errors-1"), Ok(4));
+        assert_eq!(find(&doc, "@6"), Ok(2));
+        assert_eq!(find(&doc, "Errors[2]"), Ok(4));
+        assert_eq!(find(&doc, "api/errors[1]"), Ok(1));
+        assert!(find(&doc, "Errors[3]").is_err());
+        assert_eq!(
+            find(&doc, "Errors"),
+            Err("'Errors' matches 2 headings (pick one with a path, 'Errors[N]', an anchor or @line):\n  \
+                 @3  #errors  API/Errors\n  @9  #errors-1  CLI/Errors"
+                .to_string())
+        );
+        assert!(find(&doc, "CLI/Codes").is_err());
+        assert!(find(&doc, "#missing").is_err());
+
+        let doc = parse_markdown("# The `Config` *type* ##\n\nSetext\n------\n");
+        assert_eq!(find(&doc, "the config type"), Ok(0));
+        assert_eq!(find(&doc, "The `Config` *type*"), Ok(0));
+        assert_eq!(
+            find(&doc, "Config type/setext"),
+            Err("Section 'Config type/setext' not found".to_string())
+        );
+        assert_eq!(find(&doc, "The Config Type/Setext"), Ok(1));
+    }
+
+    #[test]
+    fn test_body_edits() {
+        let doc = parse_markdown(DOC);
+        assert_eq!(text(&doc, 3), "## B\n\nb text");
+        assert_eq!(
+            set_body(&doc, 1, "new\n"),
+            "# Title\n\nintro\n\n## A\n\nnew\n\n## B\n\nb text\n"
+        );
+        assert_eq!(
+            append(&doc, 3, "more"),
+            "# Title\n\nintro\n\n## A\n\na text\n\n### A1\n\nsub\n\n## B\n\nb text\n\nmore\n"
+        );
+        assert_eq!(
+            prepend(&doc, 2, "first"),
+            "# Title\n\nintro\n\n## A\n\na text\n\n### A1\n\nfirst\n\nsub\n\n## B\n\nb text\n"
+        );
+        assert_eq!(
+            move_to(&doc, 3, 1, false, 0).unwrap(),
+            "# Title\n\nintro\n\n## B\n\nb text\n\n## A\n\na text\n\n### A1\n\nsub\n"
+        );
+        assert_eq!(
+            move_to(&doc, 1, 3, true, 0).unwrap(),
+            "# Title\n\nintro\n\n## B\n\nb text\n\n## A\n\na text\n\n### A1\n\nsub\n"
+        );
+        assert!(move_to(&doc, 1, 2, true, 0).is_err());
+        assert!(move_to(&doc, 2, 1, true, 0).is_err());
+        assert_eq!(
+            move_to(&doc, 2, 3, true, 0).unwrap(),
+            "# Title\n\nintro\n\n## A\n\na text\n\n## B\n\nb text\n\n### A1\n\nsub\n"
+        );
+        assert_eq!(
+            move_to(&doc, 3, 2, false, 0).unwrap(),
+            "# Title\n\nintro\n\n## A\n\na text\n\n## B\n\nb text\n\n### A1\n\nsub\n"
+        );
+
+        // Blank lines around the cut and the insertion point collapse to one
+        let doc = parse_markdown("# T\n## A\nx\n\n\n\n## B\ny\n\n\n## C\nz\n\n\n");
+        assert_eq!(
+            move_to(&doc, 1, 3, true, 0).unwrap(),
+            "# T\n## B\ny\n\n\n## C\nz\n\n## A\nx\n"
+        );
+        assert_eq!(
+            move_to(&doc, 3, 1, false, 0).unwrap(),
+            "# T\n\n## C\nz\n\n## A\nx\n\n\n\n## B\ny\n"
+        );
+    }
+}
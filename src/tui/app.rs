@@ -1,16 +1,35 @@
 use crate::config::Config;
+use crate::graph::LinkGraph;
 use crate::keybindings::{Action, KeybindingMode, Keybindings};
-use crate::parser::{Document, HeadingNode, Link, extract_links};
+use crate::parser::delimited::{self, Delimiter};
+use crate::parser::embeds::find_block;
+use crate::parser::footnotes::{FootnoteDef, find_definitions, referenced_labels};
+use crate::parser::links::{resolve_wikilink, wikilink_candidates};
+use crate::parser::{Document, HeadingNode, Link, LinkTarget, extract_links};
+use crate::rename::{self, RenamePlan};
+use crate::section::{self, SectionEdit};
+use crate::tui::code_runner::{CodeOutput, CodeRun, CodeRunRequest, RunStatus};
+use crate::tui::command_line::{self, CommandHistory, ExCommand};
+use crate::tui::conflict::{DiskSnapshot, WriteConflict};
+use crate::tui::edit_journal::{EditJournal, FileEdit};
+use crate::tui::graph_view::{DEFAULT_GRAPH_DEPTH, GraphView};
 use crate::tui::help_text;
-use crate::tui::interactive::InteractiveState;
+use crate::tui::images::ImagePreviews;
+use crate::tui::interactive::{ElementId, ElementType, InteractiveState, TableSort};
 use crate::tui::syntax::SyntaxHighlighter;
+use crate::tui::table_edit::{MarkdownTable, TableOp, find_tables};
+use crate::tui::tag_browser::TagBrowser;
 use crate::tui::terminal_compat::ColorMode;
 use crate::tui::theme::{Theme, ThemeName};
+use crate::vault::Vault;
 use crossterm::event::{KeyCode, KeyModifiers};
 use ratatui::widgets::{ListState, ScrollbarState};
-use std::collections::HashSet;
-use std::path::PathBuf;
-use std::time::{Duration, Instant};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use strum::IntoEnumIterator;
 
 /// Special marker for the document overview entry (shows entire file content)
 pub const DOCUMENT_OVERVIEW: &str = "(Document)";
@@ -24,6 +43,117 @@ pub enum ActionResult {
     Quit,
     /// Run an editor on a file, optionally at a specific line
     RunEditor(PathBuf, Option<u32>),
+    /// Run an editor on a code block's temp file, then write it back
+    EditCodeBlock(PathBuf),
+}
+
+/// File extension for a code block's language, so editors pick the right mode
+fn code_file_extension(language: &str) -> &str {
+    match language.to_lowercase().as_str() {
+        "rust" => "rs",
+        "python" | "python3" => "py",
+        "javascript" | "node" => "js",
+        "typescript" => "ts",
+        "ruby" => "rb",
+        "bash" | "shell" | "console" => "sh",
+        "markdown" => "md",
+        "yaml" => "yml",
+        "golang" => "go",
+        "c++" | "cpp" => "cpp",
+        "csharp" | "c#" => "cs",
+        "kotlin" => "kt",
+        "haskell" => "hs",
+        "perl" => "pl",
+        "text" | "plaintext" => "txt",
+        _ if !language.is_empty() && language.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            language
+        }
+        _ => "txt",
+    }
+}
+
+/// A code block being edited in `$EDITOR`
+struct CodeBlockEdit {
+    file: tempfile::NamedTempFile,
+    /// Opening fence line and content of the block when editing started
+    line: usize,
+    original: String,
+}
+
+/// Restrictions on what viewing a document may do to the machine
+///
+/// Ordered from least to most restrictive, so `max` combines config and CLI settings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AccessMode {
+    #[default]
+    Normal,
+    /// No file modification, file creation or `$EDITOR`
+    ReadOnly,
+    /// Read-only, links confined to the starting directory, no external URLs
+    Safe,
+}
+
+impl AccessMode {
+    pub fn from_flags(read_only: bool, safe: bool) -> Self {
+        if safe {
+            AccessMode::Safe
+        } else if read_only {
+            AccessMode::ReadOnly
+        } else {
+            AccessMode::Normal
+        }
+    }
+
+    pub fn is_read_only(self) -> bool {
+        self != AccessMode::Normal
+    }
+
+    /// Status bar badge for restricted modes
+    pub fn badge(self) -> Option<&'static str> {
+        match self {
+            AccessMode::Normal => None,
+            AccessMode::ReadOnly => Some("[READ-ONLY]"),
+            AccessMode::Safe => Some("[SAFE]"),
+        }
+    }
+}
+
+/// Fail if `path` lies outside the safe-mode `root`, after following `..`
+/// and symlinks
+pub fn ensure_within(root: &Path, path: &Path) -> Result<(), String> {
+    let canonical = path
+        .canonicalize()
+        .map_err(|_| format!("Safe mode: cannot resolve {}", path.display()))?;
+    if canonical.starts_with(root) {
+        Ok(())
+    } else {
+        Err(format!("Safe mode: links cannot leave {}", root.display()))
+    }
+}
+
+/// Notes drawn as embeds, re-read when their modification time changes
+#[derive(Debug, Default)]
+pub struct EmbeddedNotes {
+    notes: HashMap<PathBuf, (Option<SystemTime>, String)>,
+}
+
+impl EmbeddedNotes {
+    /// Content of the note at `path`
+    pub fn read(&mut self, path: &Path) -> Result<&str, String> {
+        let modified = std::fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok();
+        let stale = modified.is_none()
+            || self
+                .notes
+                .get(path)
+                .is_none_or(|(read_at, _)| *read_at != modified);
+        if stale {
+            let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+            self.notes.insert(path.to_path_buf(), (modified, content));
+        }
+        Ok(&self.notes[path].1)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -42,9 +172,18 @@ pub enum AppMode {
     Help,
     CellEdit,
     ConfirmFileCreate,
-    DocSearch,        // In-document search mode (n/N navigation)
-    CommandPalette,   // Fuzzy-searchable command palette
-    ConfirmSaveWidth, // Modal confirmation for saving outline width
+    DocSearch,            // In-document search mode (n/N navigation)
+    CommandPalette,       // Fuzzy-searchable command palette
+    ConfirmSaveWidth,     // Modal confirmation for saving outline width
+    ConfirmConflict,      // File changed on disk before an edit could be written
+    TableFilter,          // Typing a row filter for the table being navigated
+    TaskList,             // Popup listing the document's tasks
+    NotePicker,           // Choosing between notes an ambiguous wikilink matches
+    TagBrowser,           // Popup listing tags and where they are used
+    GraphView,            // Popup drawing the links around the current note
+    ConfirmRename,        // Preview of the links a rename rewrites
+    ConfirmDeleteSection, // Confirm deleting the selected section
+    ConfirmCodeRun,       // Confirm running the selected code block
 }
 
 /// Available commands in the command palette
@@ -54,9 +193,53 @@ pub enum CommandAction {
     ToggleOutline,
     ToggleHelp,
     ToggleRawSource,
+    TaskList,
+    TagBrowser,
+    GraphView,
     JumpToTop,
     JumpToBottom,
     Quit,
+    // Commands that take an argument (see `usage`)
+    Theme,
+    Goto,
+    Heading,
+    Level,
+    Open,
+    Query,
+    Set,
+    Import,
+    Rename,
+    Move,
+    Extract,
+}
+
+impl CommandAction {
+    /// Usage string for commands that take an argument, None otherwise
+    pub fn usage(&self) -> Option<&'static str> {
+        match self {
+            CommandAction::Theme => Some("theme <name>"),
+            CommandAction::Goto => Some("goto <line>"),
+            CommandAction::Heading => Some("heading <text>"),
+            CommandAction::Level => Some("level <1-6>"),
+            CommandAction::Open => Some("open <path>[#anchor]"),
+            CommandAction::Query => Some("query <expression>"),
+            CommandAction::Set => Some("set <option>[=<value>]"),
+            CommandAction::Import => Some("import [file.csv|file.tsv]"),
+            CommandAction::Rename => Some("rename <new heading>"),
+            CommandAction::Move => Some("move <new path>"),
+            CommandAction::Extract => Some("extract <new file>"),
+            _ => None,
+        }
+    }
+}
+
+/// An entry in the filtered command palette list
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaletteEntry {
+    /// Index into PALETTE_COMMANDS
+    Command(usize),
+    /// Keybinding action invoked by name
+    Action(Action),
 }
 
 /// A command in the palette
@@ -103,18 +286,7 @@ impl PaletteCommand {
         }
 
         // Fuzzy match: check if all query chars appear in order in name
-        let name_lower = self.name.to_lowercase();
-        let mut name_chars = name_lower.chars().peekable();
-        for qc in query_lower.chars() {
-            loop {
-                match name_chars.next() {
-                    Some(nc) if nc == qc => break,
-                    Some(_) => continue,
-                    None => return false,
-                }
-            }
-        }
-        true
+        fuzzy_matches(self.name, &query_lower)
     }
 
     /// Calculate match score (higher = better match)
@@ -153,12 +325,49 @@ impl PaletteCommand {
     }
 }
 
+/// Check if all (lowercase) query chars appear in order in `name`
+fn fuzzy_matches(name: &str, query_lower: &str) -> bool {
+    let name_lower = name.to_lowercase();
+    let mut name_chars = name_lower.chars();
+    for qc in query_lower.chars() {
+        loop {
+            match name_chars.next() {
+                Some(nc) if nc == qc => break,
+                Some(_) => continue,
+                None => return false,
+            }
+        }
+    }
+    true
+}
+
+/// Calculate match score of an action name (ranked below palette commands)
+fn action_match_score(action: Action, query: &str) -> Option<usize> {
+    if query.is_empty() {
+        return Some(50);
+    }
+    let query_lower = query.to_lowercase();
+    let name_lower = action.to_string().to_lowercase();
+
+    if name_lower == query_lower {
+        Some(900)
+    } else if name_lower.starts_with(&query_lower) {
+        Some(250)
+    } else if name_lower.contains(&query_lower) {
+        Some(150)
+    } else if fuzzy_matches(&name_lower, &query_lower) {
+        Some(50)
+    } else {
+        None
+    }
+}
+
 /// All available commands
 pub const PALETTE_COMMANDS: &[PaletteCommand] = &[
     PaletteCommand::new(
-        "Save width to config",
+        "Save config",
         &["w", "write", "save"],
-        "Save current outline width to config file",
+        "Save current outline width and theme to config file",
         CommandAction::SaveWidth,
     ),
     PaletteCommand::new(
@@ -179,6 +388,24 @@ pub const PALETTE_COMMANDS: &[PaletteCommand] = &[
         "Switch between rendered and raw markdown",
         CommandAction::ToggleRawSource,
     ),
+    PaletteCommand::new(
+        "Task list",
+        &["tasks", "todo"],
+        "List the document's open tasks by heading",
+        CommandAction::TaskList,
+    ),
+    PaletteCommand::new(
+        "Tag browser",
+        &["tags"],
+        "List tags in the document or workspace and where they are used",
+        CommandAction::TagBrowser,
+    ),
+    PaletteCommand::new(
+        "Link graph",
+        &["graph", "links"],
+        "Show the notes linking to and from this one",
+        CommandAction::GraphView,
+    ),
     PaletteCommand::new(
         "Jump to top",
         &["top", "first", "gg"],
@@ -197,8 +424,83 @@ pub const PALETTE_COMMANDS: &[PaletteCommand] = &[
         "Exit treemd",
         CommandAction::Quit,
     ),
+    PaletteCommand::new(
+        "Change theme",
+        &["theme", "colorscheme"],
+        "Switch and save the color theme",
+        CommandAction::Theme,
+    ),
+    PaletteCommand::new(
+        "Go to line",
+        &["goto", "line"],
+        "Jump to the section containing a source line",
+        CommandAction::Goto,
+    ),
+    PaletteCommand::new(
+        "Go to heading",
+        &["heading"],
+        "Jump to a heading by name",
+        CommandAction::Heading,
+    ),
+    PaletteCommand::new(
+        "Outline level",
+        &["level"],
+        "Collapse the outline below a heading level",
+        CommandAction::Level,
+    ),
+    PaletteCommand::new(
+        "Open file",
+        &["open", "e", "edit"],
+        "Open a markdown file relative to the current one",
+        CommandAction::Open,
+    ),
+    PaletteCommand::new(
+        "Run query",
+        &["query"],
+        "Run a query and copy the results to the clipboard",
+        CommandAction::Query,
+    ),
+    PaletteCommand::new(
+        "Set option",
+        &["set"],
+        "Change a setting for this session (outline_width, outline, raw, theme)",
+        CommandAction::Set,
+    ),
+    PaletteCommand::new(
+        "Import table",
+        &["import"],
+        "Replace the selected table (or add one to the section) from CSV/TSV; clipboard if no file",
+        CommandAction::Import,
+    ),
+    PaletteCommand::new(
+        "Rename heading",
+        &["rename"],
+        "Rename the selected heading and fix links to it across the workspace",
+        CommandAction::Rename,
+    ),
+    PaletteCommand::new(
+        "Move file",
+        &["move", "mv"],
+        "Rename or move this note and fix links to it across the workspace",
+        CommandAction::Move,
+    ),
+    PaletteCommand::new(
+        "Extract section",
+        &["extract"],
+        "Move the selected section to a new file, leaving a link behind",
+        CommandAction::Extract,
+    ),
 ];
 
+/// Notes an ambiguous wikilink matches, offered in a picker
+#[derive(Debug, Clone)]
+pub struct NotePicker {
+    pub target: String,
+    pub anchor: Option<String>,
+    pub notes: Vec<PathBuf>,
+    pub selected: usize,
+}
+
 /// A match found during search
 #[derive(Debug, Clone)]
 pub struct SearchMatch {
@@ -221,6 +523,8 @@ pub struct App {
     pub content_scroll: u16,
     pub content_scroll_state: ScrollbarState,
     pub content_height: u16,
+    /// Inner size of the content pane (width, height) as of the last frame
+    pub content_viewport: (u16, u16),
     pub show_help: bool,
     pub help_scroll: u16,
     pub show_search: bool,
@@ -241,10 +545,14 @@ pub struct App {
     pub theme_picker_selected: usize,
     pub theme_picker_original: Option<ThemeName>, // Original theme before picker opened (for cancel)
     previous_selection: Option<String>,           // Track previous selection to detect changes
+    pub task_list_selected: usize,                // Selected row in the task list popup
+    pub task_list_show_done: bool,                // Whether the task list includes completed tasks
 
     // Link following state
     pub mode: AppMode,
     pub current_file_path: PathBuf, // Path to current file for resolving relative links
+    pub access_mode: AccessMode,    // Read-only / safe restrictions
+    pub safe_root: Option<PathBuf>, // Links, embeds and images may not leave this directory in safe mode
     pub file_path_changed: bool,    // Flag to signal file watcher needs update
     pub suppress_file_watch: bool,  // Skip next file watch check (after internal save)
     pub links_in_view: Vec<Link>,   // Links in currently displayed content
@@ -265,6 +573,31 @@ pub struct App {
     pub cell_edit_row: usize,    // Row being edited
     pub cell_edit_col: usize,    // Column being edited
 
+    // Undo/redo journal for checkbox toggles and cell edits (survives reloads)
+    edit_journal: EditJournal,
+
+    // File contents at load time, used to detect external modification before writing
+    disk_snapshot: Option<DiskSnapshot>,
+    pub pending_conflict: Option<WriteConflict>,
+
+    // Code blocks run from interactive mode (output lives in interactive_state)
+    code_runs: HashMap<ElementId, CodeRun>,
+    pub pending_code_run: Option<CodeRunRequest>,
+    pub pending_rename: Option<RenamePlan>,
+    /// Heading of the section waiting for delete confirmation
+    pub pending_section_delete: Option<String>,
+    pending_code_edit: Option<CodeBlockEdit>,
+
+    // Footnotes: rendered line of each footnote in the current section (set by the renderer)
+    pub footnote_lines: HashMap<String, u16>,
+    footnote_return: Option<u16>, // Content scroll to restore after jumping to a footnote
+
+    // Vault of the current file (wikilinks resolve across it) and the picker for ambiguous links
+    pub vault: Option<Vault>,
+    pub note_picker: Option<NotePicker>,
+    pub tag_browser: Option<TagBrowser>,
+    pub graph_view: Option<GraphView>,
+
     // Persistent clipboard for Linux X11 compatibility
     // On Linux, the clipboard instance must stay alive to serve paste requests
     clipboard: Option<arboard::Clipboard>,
@@ -273,6 +606,10 @@ pub struct App {
     config: Config,
     color_mode: ColorMode,
 
+    // Inline image previews
+    pub images: ImagePreviews,
+    pub embedded_notes: EmbeddedNotes,
+
     // Pending file to open in external editor (set by link following, consumed by main loop)
     pub pending_editor_file: Option<PathBuf>,
 
@@ -293,8 +630,12 @@ pub struct App {
 
     // Command palette state
     pub command_query: String,
-    pub command_filtered: Vec<usize>, // Indices into PALETTE_COMMANDS
+    pub command_filtered: Vec<PaletteEntry>,
     pub command_selected: usize,
+    pub command_completions: Vec<String>, // Candidates from the last Tab press
+    pub command_completion_idx: Option<usize>,
+    command_history: CommandHistory,
+    command_from_table: bool, // Whether the palette was opened from table mode
 
     // Customizable keybindings
     pub keybindings: Keybindings,
@@ -315,6 +656,7 @@ pub struct FileState {
 pub struct OutlineItem {
     pub level: usize,
     pub text: String,
+    pub offset: usize, // Byte offset of the heading, telling repeated headings apart
     pub expanded: bool,
     pub has_children: bool, // Track if this heading has children in the tree
 }
@@ -339,6 +681,7 @@ impl App {
                 OutlineItem {
                     level: 0, // Level 0 for document overview (renders without # prefix)
                     text: DOCUMENT_OVERVIEW.to_string(),
+                    offset: 0,
                     expanded: true,
                     has_children: !outline_items.is_empty(),
                 },
@@ -368,8 +711,11 @@ impl App {
 
         // Load keybindings from config (before config is moved)
         let keybindings = config.keybindings();
+        let vault = Vault::detect(&file_path, config.vault.root.as_deref()).map(Vault::open);
+        let disk_snapshot = DiskSnapshot::capture(&file_path);
+        let access_mode = AccessMode::from_flags(config.security.read_only, config.security.safe);
 
-        Self {
+        let mut app = Self {
             document,
             filename,
             tree,
@@ -380,6 +726,7 @@ impl App {
             content_scroll: 0,
             content_scroll_state: ScrollbarState::new(content_lines),
             content_height: content_lines as u16,
+            content_viewport: (80, 20),
             show_help: false,
             help_scroll: 0,
             show_search: false,
@@ -397,10 +744,14 @@ impl App {
             theme_picker_selected: 0,
             theme_picker_original: None,
             previous_selection: None,
+            task_list_selected: 0,
+            task_list_show_done: false,
 
             // Link following state
             mode: AppMode::Normal,
             current_file_path: file_path,
+            access_mode: AccessMode::Normal,
+            safe_root: None,
             file_path_changed: false,
             suppress_file_watch: false,
             links_in_view: Vec::new(),
@@ -420,10 +771,29 @@ impl App {
             cell_edit_value: String::new(),
             cell_edit_row: 0,
             cell_edit_col: 0,
+            edit_journal: EditJournal::new(),
+            disk_snapshot,
+            pending_conflict: None,
+            code_runs: HashMap::new(),
+            pending_code_run: None,
+            pending_rename: None,
+            pending_section_delete: None,
+            pending_code_edit: None,
+            footnote_lines: HashMap::new(),
+            footnote_return: None,
+
+            vault,
+            note_picker: None,
+            tag_browser: None,
+            graph_view: None,
 
             // Initialize persistent clipboard (None if unavailable)
             clipboard: arboard::Clipboard::new().ok(),
 
+            // Inline image previews (before `config` moves in)
+            images: ImagePreviews::new(&config.terminal.images, color_mode),
+            embedded_notes: EmbeddedNotes::default(),
+
             // Configuration persistence
             config,
             color_mode,
@@ -448,13 +818,71 @@ impl App {
 
             // Command palette state
             command_query: String::new(),
-            command_filtered: (0..PALETTE_COMMANDS.len()).collect(),
+            command_filtered: Vec::new(),
             command_selected: 0,
+            command_completions: Vec::new(),
+            command_completion_idx: None,
+            command_history: CommandHistory::load(Config::history_path()),
+            command_from_table: false,
 
             // Customizable keybindings (loaded from config)
             // Note: keybindings() called before config is moved into struct
             keybindings,
+        };
+        app.set_access_mode(access_mode);
+        app
+    }
+
+    /// Set read-only / safe restrictions.
+    ///
+    /// Safe mode confines link following to the directory of the current file
+    /// (or the working directory when reading stdin).
+    pub fn set_access_mode(&mut self, mode: AccessMode) {
+        self.access_mode = mode;
+        self.safe_root = if mode == AccessMode::Safe {
+            let root = self
+                .current_file_path
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty() && p.is_dir())
+                .map(PathBuf::from)
+                .or_else(|| std::env::current_dir().ok());
+            root.map(|r| r.canonicalize().unwrap_or(r))
+        } else {
+            None
+        };
+    }
+
+    /// Tighten the restrictions to at least `mode`. Command-line flags go
+    /// through here, so they can't lift a mode set in the config file.
+    pub fn restrict_access(&mut self, mode: AccessMode) {
+        self.set_access_mode(self.access_mode.max(mode));
+    }
+
+    /// Fail if the document must not be modified
+    fn ensure_writable(&self) -> Result<(), String> {
+        if self.access_mode.is_read_only() {
+            Err("Read-only mode: editing is disabled".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Fail if `path` lies outside the safe-mode root directory
+    fn ensure_within_safe_root(&self, path: &Path) -> Result<(), String> {
+        match &self.safe_root {
+            Some(root) => ensure_within(root, path),
+            None => Ok(()),
+        }
+    }
+
+    /// Queue a non-markdown file to be opened in the external editor
+    fn open_in_external_editor(&mut self, path: PathBuf) -> Result<(), String> {
+        if self.access_mode.is_read_only() {
+            return Err("Read-only mode: opening files in an editor is disabled".to_string());
         }
+        self.ensure_within_safe_root(&path)?;
+        self.pending_editor_file = Some(path);
+        Ok(())
     }
 
     /// Get the current keybinding mode based on app state
@@ -499,9 +927,19 @@ impl App {
             AppMode::ThemePicker => KeybindingMode::ThemePicker,
             AppMode::Help => KeybindingMode::Help,
             AppMode::CellEdit => KeybindingMode::CellEdit,
-            AppMode::ConfirmFileCreate | AppMode::ConfirmSaveWidth => KeybindingMode::ConfirmDialog,
+            AppMode::ConfirmFileCreate
+            | AppMode::ConfirmSaveWidth
+            | AppMode::ConfirmCodeRun
+            | AppMode::ConfirmRename
+            | AppMode::ConfirmDeleteSection => KeybindingMode::ConfirmDialog,
+            AppMode::ConfirmConflict => KeybindingMode::ConflictDialog,
+            AppMode::TableFilter => KeybindingMode::TableFilter,
             AppMode::DocSearch => KeybindingMode::DocSearch,
             AppMode::CommandPalette => KeybindingMode::CommandPalette,
+            AppMode::TaskList => KeybindingMode::TaskList,
+            AppMode::NotePicker => KeybindingMode::NotePicker,
+            AppMode::TagBrowser => KeybindingMode::TagBrowser,
+            AppMode::GraphView => KeybindingMode::GraphView,
         }
     }
 
@@ -600,6 +1038,12 @@ impl App {
             LinkSearch => self.start_link_search(),
 
             // === Interactive Mode ===
+            InteractiveNext if self.interactive_state.is_in_table_mode() => {
+                self.table_navigate_down()
+            }
+            InteractivePrevious if self.interactive_state.is_in_table_mode() => {
+                self.table_navigate_up()
+            }
             InteractiveNext => {
                 self.interactive_state.next();
                 self.scroll_to_interactive_element(20);
@@ -610,6 +1054,11 @@ impl App {
                 self.scroll_to_interactive_element(20);
                 self.status_message = Some(self.interactive_state.status_text());
             }
+            InteractiveActivate if self.interactive_state.is_in_table_mode() => {
+                if let Err(e) = self.enter_cell_edit_mode() {
+                    self.status_message = Some(format!("✗ {}", e));
+                }
+            }
             InteractiveActivate => {
                 if let Err(e) = self.activate_interactive_element() {
                     self.status_message = Some(format!("✗ Error: {}", e));
@@ -626,22 +1075,78 @@ impl App {
                 self.scroll_to_interactive_element(20);
                 self.status_message = Some(self.interactive_state.status_text());
             }
+            RunCodeBlock => {
+                if let Err(e) = self.run_selected_code_block() {
+                    self.status_message = Some(format!("✗ {}", e));
+                }
+            }
+            EditCodeBlock => match self.start_code_block_edit() {
+                Ok(path) => return ActionResult::EditCodeBlock(path),
+                Err(e) => self.status_message = Some(format!("✗ {}", e)),
+            },
+            ToggleCodeOutput => {
+                if let Err(e) = self.toggle_code_output() {
+                    self.status_message = Some(format!("✗ {}", e));
+                }
+            }
+            ToggleDiagramSource => {
+                if let Err(e) = self.toggle_diagram_source() {
+                    self.status_message = Some(format!("✗ {}", e));
+                }
+            }
             InteractiveLeft => self.table_navigate_left(),
             InteractiveRight => self.table_navigate_right(),
+            TableSort => self.cycle_table_sort(),
+            TableFilter => self.start_table_filter(),
+            TableScrollLeft => self.scroll_table_columns(false),
+            TableScrollRight => self.scroll_table_columns(true),
+            TableToggleWrap => self.toggle_table_wrap(),
+            TableInsertRowBelow => self.report_table_edit(TableOp::InsertRow { below: true }),
+            TableInsertRowAbove => self.report_table_edit(TableOp::InsertRow { below: false }),
+            TableDeleteRow => self.report_table_edit(TableOp::DeleteRow),
+            TableMoveRowDown => self.report_table_edit(TableOp::MoveRow { down: true }),
+            TableMoveRowUp => self.report_table_edit(TableOp::MoveRow { down: false }),
+            TableInsertColumnRight => self.report_table_edit(TableOp::InsertColumn { right: true }),
+            TableInsertColumnLeft => self.report_table_edit(TableOp::InsertColumn { right: false }),
+            TableDeleteColumn => self.report_table_edit(TableOp::DeleteColumn),
+            TableMoveColumnLeft => self.report_table_edit(TableOp::MoveColumn { right: false }),
+            TableMoveColumnRight => self.report_table_edit(TableOp::MoveColumn { right: true }),
+            TableCycleAlignment => self.report_table_edit(TableOp::CycleAlignment),
 
             // === View ===
+            ToggleRawSource if self.interactive_state.is_in_table_mode() => {
+                self.report_table_copy(Self::copy_table_markdown)
+            }
             ToggleRawSource => self.toggle_raw_source(),
             ToggleHelp => self.toggle_help(),
             ToggleThemePicker => self.toggle_theme_picker(),
             ApplyTheme => self.apply_selected_theme(),
+            ToggleTaskList => self.toggle_task_list(),
+            ToggleImages => self.toggle_images(),
+            ToggleTagBrowser => self.toggle_tag_browser(),
+            ToggleGraphView => self.toggle_graph_view(),
 
             // === Clipboard ===
+            CopyContent if self.interactive_state.is_in_table_mode() => {
+                self.report_table_copy(Self::copy_table_cell)
+            }
+            CopyAnchor if self.interactive_state.is_in_table_mode() => {
+                self.report_table_copy(Self::copy_table_row)
+            }
             CopyContent => self.copy_content(),
             CopyAnchor => self.copy_anchor(),
+            CopyTableCsv => self.report_table_copy(Self::copy_table_csv),
+            CopyTableTsv => self.report_table_copy(Self::copy_table_tsv),
+            ImportTable => {
+                if let Err(e) = self.import_table(None) {
+                    self.status_message = Some(format!("✗ {}", e));
+                }
+            }
 
             // === File Operations ===
             GoBack => {
-                if self.go_back().is_ok() {
+                // Return from a footnote before going back through file history
+                if self.footnote_back().is_err() && self.go_back().is_ok() {
                     self.update_content_metrics();
                 }
             }
@@ -651,13 +1156,68 @@ impl App {
                 }
             }
             OpenInEditor => {
-                let line = self.selected_heading_source_line();
-                return ActionResult::RunEditor(self.current_file_path.clone(), line);
+                if self.access_mode.is_read_only() {
+                    self.status_message = Some("✗ Read-only mode: editor is disabled".to_string());
+                } else {
+                    let line = self.selected_heading_source_line();
+                    return ActionResult::RunEditor(self.current_file_path.clone(), line);
+                }
+            }
+            RenameHeading => self.start_heading_rename(),
+
+            // === Outline Editing ===
+            SectionMoveUp | SectionMoveDown | SectionPromote | SectionDemote => {
+                let result = match action {
+                    SectionMoveUp => self.edit_section("Moved section up", |doc, idx| {
+                        section::move_section(doc, idx, true)
+                    }),
+                    SectionMoveDown => self.edit_section("Moved section down", |doc, idx| {
+                        section::move_section(doc, idx, false)
+                    }),
+                    SectionPromote => self.edit_section("Promoted section", |doc, idx| {
+                        section::shift_section(doc, idx, -1)
+                    }),
+                    _ => self.edit_section("Demoted section", |doc, idx| {
+                        section::shift_section(doc, idx, 1)
+                    }),
+                };
+                if let Err(e) = result {
+                    self.set_status_message(&format!("✗ {}", e));
+                }
+            }
+            SectionDelete => {
+                if let Err(e) = self.start_section_delete() {
+                    self.set_status_message(&format!("✗ {}", e));
+                }
+            }
+            SectionExtract => self.start_section_extract(),
+            UndoEdit => {
+                if let Err(e) = self.undo_edit() {
+                    self.status_message = Some(format!("✗ {}", e));
+                }
+                self.update_content_metrics();
+            }
+            RedoEdit => {
+                if let Err(e) = self.redo_edit() {
+                    self.status_message = Some(format!("✗ {}", e));
+                }
+                self.update_content_metrics();
             }
 
             // === Dialog Actions ===
             ConfirmAction => self.handle_confirm_action(),
             CancelAction => self.handle_cancel_action(),
+            ConflictReload | ConflictOverwrite | ConflictMerge => {
+                let result = match action {
+                    ConflictReload => self.resolve_conflict_reload(),
+                    ConflictOverwrite => self.resolve_conflict_overwrite(),
+                    _ => self.resolve_conflict_merge(),
+                };
+                if let Err(e) = result {
+                    self.status_message = Some(format!("✗ {}", e));
+                }
+                self.update_content_metrics();
+            }
 
             // === Jump to Heading by Number ===
             JumpToHeading1 => self.jump_to_heading(0),
@@ -693,12 +1253,86 @@ impl App {
             ThemePickerNext => self.theme_picker_next(),
             ThemePickerPrevious => self.theme_picker_previous(),
 
+            // === Task List ===
+            TaskListNext => self.task_list_next(),
+            TaskListPrevious => self.task_list_previous(),
+            TaskListToggle => {
+                if let Err(e) = self.toggle_selected_task() {
+                    self.status_message = Some(format!("✗ {}", e));
+                }
+                self.update_content_metrics();
+            }
+            TaskListJump => self.jump_to_selected_task(),
+            TaskListToggleDone => self.toggle_task_list_done(),
+
+            // === Note Picker ===
+            NotePickerNext => self.note_picker_next(),
+            NotePickerPrevious => self.note_picker_previous(),
+
+            // === Tag Browser ===
+            TagBrowserNext => {
+                if let Some(browser) = &mut self.tag_browser {
+                    browser.next();
+                }
+            }
+            TagBrowserPrevious => {
+                if let Some(browser) = &mut self.tag_browser {
+                    browser.previous();
+                }
+            }
+            TagBrowserOpen => {
+                if let Err(e) = self.tag_browser_open() {
+                    self.status_message = Some(format!("✗ {}", e));
+                }
+            }
+            TagBrowserBack => {
+                if let Some(browser) = &mut self.tag_browser {
+                    browser.back();
+                }
+            }
+            TagBrowserToggleScope => self.toggle_tag_scope(),
+
+            // === Link Graph ===
+            GraphNext => {
+                if let Some(view) = &mut self.graph_view {
+                    view.next();
+                }
+            }
+            GraphPrevious => {
+                if let Some(view) = &mut self.graph_view {
+                    view.previous();
+                }
+            }
+            GraphOpen => {
+                if let Err(e) = self.graph_open() {
+                    self.status_message = Some(format!("✗ {}", e));
+                }
+            }
+            GraphRecenter => {
+                if let Some(view) = &mut self.graph_view {
+                    view.recenter();
+                }
+            }
+            GraphDeeper => {
+                if let Some(view) = &mut self.graph_view {
+                    view.change_depth(1);
+                }
+            }
+            GraphShallower => {
+                if let Some(view) = &mut self.graph_view {
+                    view.change_depth(-1);
+                }
+            }
+
             // === Search Input ===
             SearchBackspace => self.handle_search_backspace(),
 
             // === Command Palette ===
             CommandPaletteNext => self.command_palette_next(),
             CommandPalettePrev => self.command_palette_prev(),
+            CommandPaletteComplete => self.command_palette_complete(),
+            CommandHistoryPrev => self.command_history_prev(),
+            CommandHistoryNext => self.command_history_next(),
 
             // === Doc Search Navigation ===
             NextMatch => self.next_doc_match(),
@@ -720,6 +1354,10 @@ impl App {
         }
 
         match self.mode {
+            AppMode::Interactive if self.interactive_state.is_in_table_mode() => {
+                self.interactive_state.exit_table_mode();
+                self.status_message = Some(self.interactive_state.status_text());
+            }
             AppMode::Interactive => self.exit_interactive_mode(),
             AppMode::LinkFollow => {
                 if self.link_search_active {
@@ -747,6 +1385,14 @@ impl App {
                 self.mode = AppMode::Interactive;
                 self.status_message = Some("Editing cancelled".to_string());
             }
+            AppMode::TableFilter => self.clear_table_filter(),
+            AppMode::TaskList => self.mode = AppMode::Normal,
+            AppMode::NotePicker => {
+                self.note_picker = None;
+                self.mode = AppMode::Normal;
+            }
+            AppMode::TagBrowser => self.toggle_tag_browser(),
+            AppMode::GraphView => self.toggle_graph_view(),
             _ => {}
         }
     }
@@ -786,19 +1432,40 @@ impl App {
                 }
             }
             AppMode::ConfirmSaveWidth => self.confirm_save_outline_width(),
+            AppMode::ConfirmCodeRun => {
+                if let Err(e) = self.confirm_code_run() {
+                    self.status_message = Some(format!("✗ {}", e));
+                }
+            }
+            AppMode::ConfirmRename => {
+                if let Err(e) = self.confirm_rename() {
+                    self.status_message = Some(format!("✗ {}", e));
+                }
+            }
+            AppMode::ConfirmDeleteSection => {
+                if let Err(e) = self.confirm_section_delete() {
+                    self.status_message = Some(format!("✗ {}", e));
+                }
+            }
             AppMode::Search => self.show_search = false,
             AppMode::DocSearch => self.accept_doc_search(),
             AppMode::CommandPalette => {
-                // Execute command - Quit is handled separately
+                // Execute command - Quit and editor requests are handled by the main loop
                 let _ = self.execute_selected_command();
             }
             AppMode::CellEdit => {
                 if let Err(e) = self.save_edited_cell() {
                     self.status_message = Some(format!("✗ Error saving: {}", e));
-                } else {
+                } else if self.mode == AppMode::CellEdit {
                     self.mode = AppMode::Interactive;
                 }
             }
+            AppMode::TableFilter => self.accept_table_filter(),
+            AppMode::NotePicker => {
+                if let Err(e) = self.open_picked_note() {
+                    self.status_message = Some(format!("✗ {}", e));
+                }
+            }
             _ => {}
         }
     }
@@ -808,6 +1475,17 @@ impl App {
         match self.mode {
             AppMode::ConfirmFileCreate => self.cancel_file_create(),
             AppMode::ConfirmSaveWidth => self.cancel_save_width_confirmation(),
+            AppMode::ConfirmConflict => self.cancel_conflict(),
+            AppMode::ConfirmCodeRun => self.cancel_code_run(),
+            AppMode::ConfirmRename => {
+                self.pending_rename = None;
+                self.mode = AppMode::Normal;
+                self.set_status_message("Rename cancelled");
+            }
+            AppMode::ConfirmDeleteSection => {
+                self.pending_section_delete = None;
+                self.mode = AppMode::Normal;
+            }
             _ => self.exit_current_mode(),
         }
     }
@@ -828,6 +1506,7 @@ impl App {
             AppMode::CellEdit => {
                 self.cell_edit_value.pop();
             }
+            AppMode::TableFilter => self.table_filter_backspace(),
             _ => {}
         }
     }
@@ -841,6 +1520,7 @@ impl App {
         let (rows, cols) = self.get_table_dimensions();
         if cols > 0 {
             self.interactive_state.table_move_left();
+            self.keep_table_selection_visible();
             self.status_message = Some(self.interactive_state.table_status_text(rows + 1, cols));
         }
     }
@@ -854,75 +1534,447 @@ impl App {
         let (rows, cols) = self.get_table_dimensions();
         if cols > 0 {
             self.interactive_state.table_move_right(cols);
+            self.keep_table_selection_visible();
             self.status_message = Some(self.interactive_state.table_status_text(rows + 1, cols));
         }
     }
 
-    /// Get table dimensions for current interactive element
-    fn get_table_dimensions(&self) -> (usize, usize) {
-        if let Some(element) = self.interactive_state.current_element() {
-            if let crate::tui::interactive::ElementType::Table { rows, cols, .. } =
-                &element.element_type
-            {
-                return (*rows, *cols);
-            }
-        }
-        (0, 0)
+    /// Navigate table down (through the rows left by the filter)
+    fn table_navigate_down(&mut self) {
+        let (_, cols) = self.get_table_dimensions();
+        let shown = self.visible_table_rows().map_or(0, |visible| visible.len());
+        self.interactive_state.table_move_down(shown + 1);
+        self.keep_table_selection_visible();
+        self.status_message = Some(self.interactive_state.table_status_text(shown + 1, cols));
     }
 
-    /// Scroll content down by one line
-    fn scroll_content_down(&mut self) {
-        let new_scroll = self.content_scroll.saturating_add(1);
-        if new_scroll < self.content_height {
-            self.content_scroll = new_scroll;
-            self.content_scroll_state = self.content_scroll_state.position(new_scroll as usize);
-        }
-    }
+    /// Navigate table up
+    fn table_navigate_up(&mut self) {
+        let (rows, cols) = self.get_table_dimensions();
+        self.interactive_state.table_move_up();
+        self.keep_table_selection_visible();
+        self.status_message = Some(self.interactive_state.table_status_text(rows + 1, cols));
+    }
+
+    /// Source indices of the current table's rows, in display order
+    fn visible_table_rows(&self) -> Option<Vec<usize>> {
+        let (_, rows) = self.get_current_table_data()?;
+        let view = self
+            .interactive_state
+            .current_element()
+            .and_then(|element| self.interactive_state.table_view(element.id));
+        Some(match view {
+            Some(view) => view.visible_rows(&rows),
+            None => (0..rows.len()).collect(),
+        })
+    }
+
+    /// Scroll the table view so the selected cell is on screen
+    fn keep_table_selection_visible(&mut self) {
+        use crate::tui::ui::table::{
+            TABLE_GUTTER_WIDTH, table_column_widths, table_header_labels, table_rows_that_fit,
+            visible_table_columns,
+        };
 
-    /// Scroll content up by one line
-    fn scroll_content_up(&mut self) {
-        let new_scroll = self.content_scroll.saturating_sub(1);
-        self.content_scroll = new_scroll;
-        self.content_scroll_state = self.content_scroll_state.position(new_scroll as usize);
-    }
+        let Some((headers, rows)) = self.get_current_table_data() else {
+            return;
+        };
+        let Some((row, col)) = self.interactive_state.get_table_position() else {
+            return;
+        };
+        let available = (self.content_viewport.0 as usize).saturating_sub(TABLE_GUTTER_WIDTH);
+        let body_rows = table_rows_that_fit(self.content_viewport.1 as usize);
+        let Some(view) = self.interactive_state.current_table_view_mut() else {
+            return;
+        };
 
-    /// Jump to link by index in filtered list
-    fn jump_to_link(&mut self, idx: usize) {
-        if let Some(display_idx) = self.filtered_link_indices.iter().position(|&i| i == idx) {
-            self.selected_link_idx = Some(display_idx);
+        let widths = table_column_widths(
+            &table_header_labels(&headers, view.sort),
+            &rows,
+            Some(available),
+        );
+        if col < view.col_offset {
+            view.col_offset = col;
+        }
+        while view.col_offset < col
+            && !visible_table_columns(&widths, view.col_offset, available).contains(&col)
+        {
+            view.col_offset += 1;
+        }
+
+        // Row 0 is the header, which is always shown
+        if let Some(data_row) = row.checked_sub(1) {
+            if data_row < view.row_offset {
+                view.row_offset = data_row;
+            } else if data_row >= view.row_offset + body_rows {
+                view.row_offset = data_row + 1 - body_rows;
+            }
         }
     }
 
-    /// Toggle between raw source view and rendered markdown view
-    pub fn toggle_raw_source(&mut self) {
-        self.show_raw_source = !self.show_raw_source;
-        let msg = if self.show_raw_source {
-            "Raw source view enabled"
-        } else {
-            "Rendered view enabled"
+    /// Select `source_row` (0 = header) wherever it now appears in the view,
+    /// falling back to the header if it was filtered out
+    fn reselect_table_row(&mut self, source_row: Option<usize>) {
+        let Some((_, col)) = self.interactive_state.get_table_position() else {
+            return;
         };
-        self.set_status_message(msg);
-    }
+        let display_row = match (source_row, self.visible_table_rows()) {
+            (Some(source), Some(visible)) if source > 0 => visible
+                .iter()
+                .position(|&r| r == source - 1)
+                .map_or(0, |i| i + 1),
+            _ => 0,
+        };
+        self.interactive_state.set_table_position(display_row, col);
+        self.keep_table_selection_visible();
+    }
+
+    /// Apply a structural table edit, reporting failures in the status bar
+    fn report_table_edit(&mut self, op: TableOp) {
+        if let Err(e) = self.edit_table_structure(op) {
+            self.status_message = Some(format!("✗ {}", e));
+        }
+    }
+
+    /// Change the structure of the selected table and rewrite it in the file
+    fn edit_table_structure(&mut self, op: TableOp) -> Result<(), String> {
+        self.ensure_writable()?;
+
+        let (_, rows) = self
+            .get_current_table_data()
+            .ok_or_else(|| "No table selected".to_string())?;
+        let row = self
+            .interactive_state
+            .table_source_row(&rows)
+            .ok_or_else(|| "No cell selected".to_string())?;
+        let (_, col) = self
+            .interactive_state
+            .get_table_position()
+            .ok_or_else(|| "No cell selected".to_string())?;
+        let sort = self
+            .interactive_state
+            .current_table_view_mut()
+            .and_then(|view| view.sort);
+        if sort.is_some() && matches!(op, TableOp::MoveRow { .. }) {
+            return Err("Clear the sort (s) to move rows".to_string());
+        }
+
+        let file_content = self.loaded_file_content()?;
+        let range = self.current_table_range(&file_content)?;
+        let mut table = MarkdownTable::parse(&file_content[range.clone()])
+            .ok_or_else(|| "Could not parse table".to_string())?;
+        let ((new_row, new_col), label) = op.apply(&mut table, row, col)?;
+
+        let new_content = format!(
+            "{}{}{}",
+            &file_content[..range.start],
+            table.to_markdown(),
+            &file_content[range.end..]
+        );
+        if !self.write_and_record_edit(&file_content, &new_content, &label)? {
+            return Ok(());
+        }
+        self.reload_after_save()?;
 
-    /// Set a status message with automatic timeout tracking
-    pub fn set_status_message(&mut self, msg: &str) {
-        self.status_message = Some(msg.to_string());
-        self.status_message_time = Some(Instant::now());
+        // Keep sorting by the same column wherever it moved
+        if let Some(view) = self.interactive_state.current_table_view_mut() {
+            view.sort = view.sort.and_then(|sort| {
+                op.map_column(col, sort.column)
+                    .map(|column| TableSort { column, ..sort })
+            });
+        }
+        self.interactive_state.set_table_position(0, new_col);
+        self.reselect_table_row(Some(new_row));
+        self.status_message = Some(format!("✓ {} (u to undo)", label));
+        Ok(())
     }
 
-    /// Clear status message if it has expired (default 1 second timeout)
-    pub fn clear_expired_status_message(&mut self) {
-        const STATUS_MESSAGE_TIMEOUT: Duration = Duration::from_secs(1);
+    /// Byte range of the selected table in `content`, which must be the
+    /// contents the current document was parsed from
+    fn current_table_range(&self, content: &str) -> Result<Range<usize>, String> {
+        use crate::parser::content::parse_content;
+        use crate::parser::output::Block;
+
+        let element = self
+            .interactive_state
+            .current_element()
+            .ok_or_else(|| "No table selected".to_string())?;
+        let ElementType::Table { block_idx, .. } = element.element_type else {
+            return Err("No table selected".to_string());
+        };
+        if element.id.sub_idx.is_some() {
+            return Err("Tables nested in other blocks can't be edited".to_string());
+        }
+
+        let section = self
+            .selected_heading_text()
+            .and_then(|heading| self.document.section_range(heading))
+            .unwrap_or(0..content.len());
+        let section_content = content
+            .get(section.clone())
+            .ok_or_else(|| "Could not locate table in file".to_string())?;
+
+        // The selected table is the nth top-level table of its section
+        let blocks = parse_content(section_content, 0);
+        let selected = blocks
+            .get(block_idx)
+            .filter(|block| matches!(block, Block::Table { .. }))
+            .ok_or_else(|| "Could not locate table in file".to_string())?;
+        let nth = blocks[..block_idx]
+            .iter()
+            .filter(|block| matches!(block, Block::Table { .. }))
+            .count();
+        let candidates = find_tables(section_content);
+        let is_selected = |range: &&Range<usize>| {
+            parse_content(&section_content[(*range).clone()], 0).first() == Some(selected)
+        };
 
-        if let Some(time) = self.status_message_time {
-            if time.elapsed() >= STATUS_MESSAGE_TIMEOUT {
-                self.status_message = None;
-                self.status_message_time = None;
-            }
-        }
+        // If counting was thrown off (e.g. by a table inside a list item), only
+        // a table with exactly the same headers and cells will do
+        let range = match candidates.get(nth).filter(is_selected) {
+            Some(range) => range,
+            None => match candidates.iter().filter(is_selected).collect::<Vec<_>>()[..] {
+                [range] => range,
+                [] => return Err("Could not locate table in file".to_string()),
+                _ => return Err("Table appears more than once in this section".to_string()),
+            },
+        };
+        Ok(section.start + range.start..section.start + range.end)
     }
 
-    /// Check if the document has non-whitespace content before the first heading
+    /// Sort by the selected column: ascending, then descending, then off
+    fn cycle_table_sort(&mut self) {
+        let Some((headers, rows)) = self.get_current_table_data() else {
+            return;
+        };
+        let Some((_, col)) = self.interactive_state.get_table_position() else {
+            return;
+        };
+        let source_row = self.interactive_state.table_source_row(&rows);
+        let Some(view) = self.interactive_state.current_table_view_mut() else {
+            return;
+        };
+
+        view.sort = match view.sort {
+            Some(sort) if sort.column == col && sort.ascending => Some(TableSort {
+                column: col,
+                ascending: false,
+            }),
+            Some(sort) if sort.column == col => None,
+            _ => Some(TableSort {
+                column: col,
+                ascending: true,
+            }),
+        };
+        let header = headers.get(col).cloned().unwrap_or_default();
+        let message = match view.sort {
+            Some(sort) if sort.ascending => format!("✓ Sorted by '{}' ascending", header),
+            Some(_) => format!("✓ Sorted by '{}' descending", header),
+            None => "✓ Sort cleared (file order)".to_string(),
+        };
+
+        self.reselect_table_row(source_row);
+        self.status_message = Some(message);
+    }
+
+    /// Start typing a row filter for the current table
+    fn start_table_filter(&mut self) {
+        if !self.interactive_state.is_in_table_mode() {
+            return;
+        }
+        self.mode = AppMode::TableFilter;
+        self.update_table_filter(|_| {});
+    }
+
+    /// Add a character to the table filter
+    pub fn table_filter_input(&mut self, c: char) {
+        self.update_table_filter(|filter| filter.push(c));
+    }
+
+    /// Remove the last character from the table filter
+    fn table_filter_backspace(&mut self) {
+        self.update_table_filter(|filter| {
+            filter.pop();
+        });
+    }
+
+    /// Clear the table filter
+    pub fn clear_table_filter_input(&mut self) {
+        self.update_table_filter(String::clear);
+    }
+
+    /// Apply a change to the filter text and refresh the view as you type
+    fn update_table_filter(&mut self, change: impl FnOnce(&mut String)) {
+        let Some((_, rows)) = self.get_current_table_data() else {
+            return;
+        };
+        let source_row = self.interactive_state.table_source_row(&rows);
+        let Some(view) = self.interactive_state.current_table_view_mut() else {
+            return;
+        };
+        change(&mut view.filter);
+        let filter = view.filter.clone();
+
+        self.reselect_table_row(source_row);
+        let shown = self.visible_table_rows().map_or(0, |visible| visible.len());
+        self.status_message = Some(format!(
+            "Filter: {}_ ({} of {} rows) • Enter:Apply • Esc:Clear",
+            filter,
+            shown,
+            rows.len()
+        ));
+    }
+
+    /// Keep the typed filter and go back to navigating the table
+    fn accept_table_filter(&mut self) {
+        self.mode = AppMode::Interactive;
+        self.reindex_interactive_elements();
+        let (rows, cols) = self.get_table_dimensions();
+        self.status_message = Some(self.interactive_state.table_status_text(rows + 1, cols));
+    }
+
+    /// Drop the filter and go back to navigating the table
+    fn clear_table_filter(&mut self) {
+        self.clear_table_filter_input();
+        self.accept_table_filter();
+    }
+
+    /// Scroll the table one column left or right, keeping the selection on screen
+    fn scroll_table_columns(&mut self, right: bool) {
+        use crate::tui::ui::table::{
+            TABLE_GUTTER_WIDTH, table_column_widths, table_header_labels, visible_table_columns,
+        };
+
+        let Some((headers, rows)) = self.get_current_table_data() else {
+            return;
+        };
+        let Some((row, col)) = self.interactive_state.get_table_position() else {
+            return;
+        };
+        let available = (self.content_viewport.0 as usize).saturating_sub(TABLE_GUTTER_WIDTH);
+        let Some(view) = self.interactive_state.current_table_view_mut() else {
+            return;
+        };
+
+        let widths = table_column_widths(
+            &table_header_labels(&headers, view.sort),
+            &rows,
+            Some(available),
+        );
+        let last_start = (0..widths.len())
+            .find(|&start| visible_table_columns(&widths, start, available).end == widths.len())
+            .unwrap_or(0);
+        view.col_offset = if right {
+            (view.col_offset + 1).min(last_start)
+        } else {
+            view.col_offset.saturating_sub(1)
+        };
+        let columns = visible_table_columns(&widths, view.col_offset, available);
+        let col = col.clamp(columns.start, columns.end.saturating_sub(1));
+
+        self.interactive_state.set_table_position(row, col);
+        let cols = headers.len();
+        self.status_message = Some(
+            self.interactive_state
+                .table_status_text(rows.len() + 1, cols),
+        );
+    }
+
+    /// Toggle wrapping of long cells in the current table
+    fn toggle_table_wrap(&mut self) {
+        let Some(view) = self.interactive_state.current_table_view_mut() else {
+            return;
+        };
+        view.wrap = !view.wrap;
+        self.status_message = Some(if view.wrap {
+            "✓ Cell wrapping on".to_string()
+        } else {
+            "✓ Cell wrapping off (long cells truncated)".to_string()
+        });
+    }
+
+    /// Run a table copy command, reporting failures in the status bar
+    fn report_table_copy(&mut self, copy: fn(&mut Self) -> Result<(), String>) {
+        if let Err(e) = copy(self) {
+            self.status_message = Some(format!("✗ {}", e));
+        }
+    }
+
+    /// Get table dimensions for current interactive element
+    fn get_table_dimensions(&self) -> (usize, usize) {
+        if let Some(element) = self.interactive_state.current_element() {
+            if let crate::tui::interactive::ElementType::Table { rows, cols, .. } =
+                &element.element_type
+            {
+                return (*rows, *cols);
+            }
+        }
+        (0, 0)
+    }
+
+    /// Scroll content down by one line
+    fn scroll_content_down(&mut self) {
+        let new_scroll = self.content_scroll.saturating_add(1);
+        if new_scroll < self.content_height {
+            self.content_scroll = new_scroll;
+            self.content_scroll_state = self.content_scroll_state.position(new_scroll as usize);
+        }
+    }
+
+    /// Scroll content up by one line
+    fn scroll_content_up(&mut self) {
+        let new_scroll = self.content_scroll.saturating_sub(1);
+        self.content_scroll = new_scroll;
+        self.content_scroll_state = self.content_scroll_state.position(new_scroll as usize);
+    }
+
+    /// Jump to link by index in filtered list
+    fn jump_to_link(&mut self, idx: usize) {
+        if let Some(display_idx) = self.filtered_link_indices.iter().position(|&i| i == idx) {
+            self.selected_link_idx = Some(display_idx);
+        }
+    }
+
+    /// Toggle between raw source view and rendered markdown view
+    pub fn toggle_raw_source(&mut self) {
+        self.show_raw_source = !self.show_raw_source;
+        let msg = if self.show_raw_source {
+            "Raw source view enabled"
+        } else {
+            "Rendered view enabled"
+        };
+        self.set_status_message(msg);
+    }
+
+    pub fn toggle_images(&mut self) {
+        self.images.enabled = !self.images.enabled;
+        let msg = if self.images.enabled {
+            format!("✓ Image previews on ({})", self.images.protocol.name())
+        } else {
+            "✓ Image previews off".to_string()
+        };
+        self.set_status_message(&msg);
+    }
+
+    /// Set a status message with automatic timeout tracking
+    pub fn set_status_message(&mut self, msg: &str) {
+        self.status_message = Some(msg.to_string());
+        self.status_message_time = Some(Instant::now());
+    }
+
+    /// Clear status message if it has expired (default 1 second timeout)
+    pub fn clear_expired_status_message(&mut self) {
+        const STATUS_MESSAGE_TIMEOUT: Duration = Duration::from_secs(1);
+
+        if let Some(time) = self.status_message_time {
+            if time.elapsed() >= STATUS_MESSAGE_TIMEOUT {
+                self.status_message = None;
+                self.status_message_time = None;
+            }
+        }
+    }
+
+    /// Check if the document has non-whitespace content before the first heading
     fn has_preamble_content(document: &Document) -> bool {
         if document.headings.is_empty() {
             // No headings at all - entire document is preamble
@@ -952,6 +2004,7 @@ impl App {
                 OutlineItem {
                     level: 0,
                     text: DOCUMENT_OVERVIEW.to_string(),
+                    offset: 0,
                     expanded: true,
                     has_children: !self.outline_items.is_empty(), // Has children if there are other items
                 },
@@ -977,6 +2030,7 @@ impl App {
             items.push(OutlineItem {
                 level: node.heading.level,
                 text: node.heading.text.clone(),
+                offset: node.heading.offset,
                 expanded,
                 has_children,
             });
@@ -1013,6 +2067,18 @@ impl App {
         false
     }
 
+    /// Select the heading at byte `offset`. Returns true if found and selected.
+    fn select_by_offset(&mut self, offset: usize) -> bool {
+        let found = self
+            .outline_items
+            .iter()
+            .position(|item| item.text != DOCUMENT_OVERVIEW && item.offset == offset);
+        if let Some(idx) = found {
+            self.select_outline_index(idx);
+        }
+        found.is_some()
+    }
+
     /// Update content height based on current selection and reset scroll if selection changed
     pub fn update_content_metrics(&mut self) {
         let current_selection = self.selected_heading_text().map(|s| s.to_string());
@@ -1021,6 +2087,7 @@ impl App {
         if current_selection != self.previous_selection {
             // Reset content scroll when selection changes
             self.content_scroll = 0;
+            self.footnote_return = None;
             self.previous_selection = current_selection.clone();
         }
 
@@ -1038,7 +2105,22 @@ impl App {
             self.document.content.clone()
         };
 
-        let content_lines = content_text.lines().count();
+        // Output panels of code blocks run in interactive mode add lines too
+        let output_lines: usize = self
+            .interactive_state
+            .code_outputs
+            .values()
+            .map(CodeOutput::height)
+            .sum();
+        // The collected footnotes section: a header plus one line per footnote
+        let footnote_lines = match referenced_labels(&content_text).len() {
+            0 => 0,
+            n => n + 1,
+        };
+        // Image previews and embedded notes sit below their first line
+        let image_lines: usize = self.interactive_state.preview_rows.values().sum();
+        let content_lines =
+            content_text.lines().count() + output_lines + footnote_lines + image_lines;
         self.content_height = content_lines as u16;
         self.content_scroll_state =
             ScrollbarState::new(content_lines).position(self.content_scroll as usize);
@@ -1263,6 +2345,7 @@ impl App {
                     OutlineItem {
                         level: 0,
                         text: DOCUMENT_OVERVIEW.to_string(),
+                        offset: 0,
                         expanded: true,
                         has_children: !self.tree.is_empty(),
                     },
@@ -1884,40 +2967,53 @@ impl App {
 
     /// Open command palette (triggered by `:`)
     pub fn open_command_palette(&mut self) {
+        self.command_from_table =
+            self.mode == AppMode::Interactive && self.interactive_state.is_in_table_mode();
         self.mode = AppMode::CommandPalette;
         self.command_query.clear();
-        self.command_filtered = (0..PALETTE_COMMANDS.len()).collect();
+        self.command_completions.clear();
+        self.command_completion_idx = None;
+        self.command_history.reset_cursor();
         self.command_selected = 0;
+        self.filter_commands();
     }
 
-    /// Add a character to command palette search
+    /// Add a character to command palette input
     pub fn command_palette_input(&mut self, c: char) {
-        if self.command_query.len() < 32 {
+        if self.command_query.len() < 256 {
             self.command_query.push(c);
+            self.command_completions.clear();
             self.filter_commands();
         }
     }
 
-    /// Remove last character from command palette search
+    /// Remove last character from command palette input
     pub fn command_palette_backspace(&mut self) {
         self.command_query.pop();
+        self.command_completions.clear();
         self.filter_commands();
     }
 
-    /// Filter commands based on current query
+    /// Filter commands based on the command word of the current input
     fn filter_commands(&mut self) {
-        // Filter matching commands
-        let mut matches: Vec<(usize, usize)> = PALETTE_COMMANDS
+        let query = self.command_query.trim_start().trim_start_matches(':');
+        let head = query.split_whitespace().next().unwrap_or("");
+
+        // Palette commands first, then actions by name
+        let mut matches: Vec<(PaletteEntry, usize)> = PALETTE_COMMANDS
             .iter()
             .enumerate()
-            .filter(|(_, cmd)| cmd.matches(&self.command_query))
-            .map(|(idx, cmd)| (idx, cmd.match_score(&self.command_query)))
+            .filter(|(_, cmd)| cmd.matches(head))
+            .map(|(idx, cmd)| (PaletteEntry::Command(idx), cmd.match_score(head)))
             .collect();
+        matches.extend(Action::iter().filter_map(|action| {
+            action_match_score(action, head).map(|score| (PaletteEntry::Action(action), score))
+        }));
 
-        // Sort by score (highest first)
+        // Sort by score (highest first); stable so commands stay ahead of actions
         matches.sort_by(|a, b| b.1.cmp(&a.1));
 
-        self.command_filtered = matches.into_iter().map(|(idx, _)| idx).collect();
+        self.command_filtered = matches.into_iter().map(|(entry, _)| entry).collect();
 
         // Reset selection if it's out of bounds
         if self.command_selected >= self.command_filtered.len() {
@@ -1943,37 +3039,212 @@ impl App {
         }
     }
 
+    /// Complete the command name or argument under the cursor.
+    ///
+    /// A single candidate is inserted directly. With several candidates the
+    /// common prefix is inserted first; further Tab presses cycle through them.
+    pub fn command_palette_complete(&mut self) {
+        // Cycle through candidates from the previous Tab press
+        if let Some(idx) = self.command_completion_idx
+            && self.command_completions.get(idx) == Some(&self.command_query)
+        {
+            let next = (idx + 1) % self.command_completions.len();
+            self.command_query = self.command_completions[next].clone();
+            self.command_completion_idx = Some(next);
+            self.filter_commands();
+            return;
+        }
+
+        let headings: Vec<&str> = self
+            .document
+            .headings
+            .iter()
+            .map(|h| h.text.as_str())
+            .collect();
+        let candidates = command_line::complete(
+            &self.command_query,
+            &headings,
+            self.current_file_path.parent(),
+        );
+
+        match candidates.len() {
+            0 => {
+                self.command_completions.clear();
+                self.set_status_message("No completions");
+            }
+            1 => {
+                self.command_query = candidates[0].clone();
+                self.command_completions.clear();
+            }
+            _ => {
+                let prefix = command_line::common_prefix(&candidates);
+                if prefix.len() > self.command_query.len() {
+                    self.command_query = prefix;
+                    self.command_completion_idx = None;
+                } else {
+                    self.command_query = candidates[0].clone();
+                    self.command_completion_idx = Some(0);
+                }
+                self.command_completions = candidates;
+            }
+        }
+        self.filter_commands();
+    }
+
+    /// Recall the previous command line from history
+    pub fn command_history_prev(&mut self) {
+        if let Some(line) = self.command_history.previous(&self.command_query) {
+            self.command_query = line;
+            self.command_completions.clear();
+            self.filter_commands();
+        }
+    }
+
+    /// Recall the next command line from history
+    pub fn command_history_next(&mut self) {
+        if let Some(line) = self.command_history.next() {
+            self.command_query = line;
+            self.command_completions.clear();
+            self.filter_commands();
+        }
+    }
+
     /// Close command palette without executing
     pub fn close_command_palette(&mut self) {
-        self.mode = AppMode::Normal;
+        self.mode = if self.command_from_table {
+            AppMode::Interactive
+        } else {
+            AppMode::Normal
+        };
         self.command_query.clear();
+        self.command_completions.clear();
+        self.command_completion_idx = None;
+        self.command_history.reset_cursor();
     }
 
-    /// Execute selected command and return whether to quit
-    pub fn execute_selected_command(&mut self) -> bool {
-        let should_quit = if let Some(&cmd_idx) = self.command_filtered.get(self.command_selected) {
-            let action = PALETTE_COMMANDS[cmd_idx].action;
-            self.mode = AppMode::Normal;
-            self.command_query.clear();
-            self.execute_command_action(action)
-        } else {
-            self.mode = AppMode::Normal;
-            false
+    /// Execute the command line, falling back to the selected palette entry
+    pub fn execute_selected_command(&mut self) -> ActionResult {
+        let line = self.command_query.trim().to_string();
+        let selected = self.command_filtered.get(self.command_selected).copied();
+        self.close_command_palette();
+
+        let command = match command_line::parse(&line) {
+            Some(Ok(command)) => {
+                self.record_command(&line);
+                command
+            }
+            Some(Err(e)) => {
+                self.set_status_message(&format!("✗ {}", e));
+                return ActionResult::Continue;
+            }
+            None => match selected {
+                Some(PaletteEntry::Command(idx)) => {
+                    let cmd = &PALETTE_COMMANDS[idx];
+                    if cmd.action.usage().is_some() {
+                        // Needs an argument: reopen with the command name filled in
+                        self.open_command_palette();
+                        self.command_query = format!("{} ", cmd.aliases[0]);
+                        self.filter_commands();
+                        return ActionResult::Continue;
+                    }
+                    self.record_command(cmd.aliases[0]);
+                    ExCommand::Palette(cmd.action)
+                }
+                Some(PaletteEntry::Action(action)) => {
+                    self.record_command(&action.to_string());
+                    ExCommand::Action(action)
+                }
+                None => return ActionResult::Continue,
+            },
         };
-        should_quit
+
+        // Only `import` acts on the selected table; anything else may change
+        // the selection or document, so leave interactive mode first
+        if self.mode == AppMode::Interactive && !matches!(command, ExCommand::Import(_)) {
+            self.exit_interactive_mode();
+        }
+
+        self.execute_ex_command(command)
+    }
+
+    /// Add a command line to history and persist it (errors are ignored)
+    fn record_command(&mut self, line: &str) {
+        self.command_history.push(line);
+        let _ = self.command_history.save();
+    }
+
+    /// Execute a parsed command line
+    fn execute_ex_command(&mut self, command: ExCommand) -> ActionResult {
+        match command {
+            ExCommand::Palette(action) => {
+                if self.execute_command_action(action) {
+                    return ActionResult::Quit;
+                }
+            }
+            ExCommand::Action(action) => return self.execute_action(action),
+            ExCommand::Theme(theme_name) => {
+                self.apply_theme_preview(theme_name);
+                match self.config.set_theme(theme_name) {
+                    Ok(_) => self.set_status_message(&format!("✓ Theme: {}", self.theme.name)),
+                    Err(e) => self.set_status_message(&format!("✗ Failed to save theme: {}", e)),
+                }
+            }
+            ExCommand::Goto(line) => self.goto_source_line(line),
+            ExCommand::Heading(text) => match self.goto_heading(&text) {
+                Ok(_) => self.set_status_message(&format!("✓ Jumped to {}", text)),
+                Err(e) => self.set_status_message(&format!("✗ {}", e)),
+            },
+            ExCommand::Level(level) => self.set_outline_level(level),
+            ExCommand::Open(path) => {
+                if let Err(e) = self.open_relative_path(&path) {
+                    self.set_status_message(&format!("✗ {}", e));
+                }
+            }
+            ExCommand::Query(query) => self.run_palette_query(&query),
+            ExCommand::Set { key, value } => {
+                if let Err(e) = self.set_option(&key, value.as_deref()) {
+                    self.set_status_message(&format!("✗ {}", e));
+                }
+            }
+            ExCommand::Import(path) => {
+                if let Err(e) = self.import_table(path.as_deref()) {
+                    self.set_status_message(&format!("✗ {}", e));
+                }
+            }
+            ExCommand::Rename(text) => {
+                if let Err(e) = self.plan_heading_rename(&text) {
+                    self.set_status_message(&format!("✗ {}", e));
+                }
+            }
+            ExCommand::Move(path) => {
+                if let Err(e) = self.plan_file_rename(&path) {
+                    self.set_status_message(&format!("✗ {}", e));
+                }
+            }
+            ExCommand::Extract(path) => {
+                if let Err(e) = self.extract_section_to(&path) {
+                    self.set_status_message(&format!("✗ {}", e));
+                }
+            }
+        }
+        ActionResult::Continue
     }
 
     /// Execute a command action, returns true if should quit
     fn execute_command_action(&mut self, action: CommandAction) -> bool {
         match action {
             CommandAction::SaveWidth => {
-                match self.config.set_outline_width(self.outline_width) {
+                let result = self
+                    .config
+                    .set_theme(self.current_theme)
+                    .and_then(|_| self.config.set_outline_width(self.outline_width));
+                match result {
                     Ok(_) => {
                         self.config_has_custom_outline_width = self.outline_width != 20
                             && self.outline_width != 30
                             && self.outline_width != 40;
                         self.set_status_message(&format!(
-                            "✓ Width {}% saved to config",
+                            "✓ Width {}% and theme saved to config",
                             self.outline_width
                         ));
                     }
@@ -1995,6 +3266,18 @@ impl App {
                 self.toggle_raw_source();
                 false
             }
+            CommandAction::TaskList => {
+                self.toggle_task_list();
+                false
+            }
+            CommandAction::TagBrowser => {
+                self.toggle_tag_browser();
+                false
+            }
+            CommandAction::GraphView => {
+                self.toggle_graph_view();
+                false
+            }
             CommandAction::JumpToTop => {
                 self.first();
                 false
@@ -2004,64 +3287,335 @@ impl App {
                 false
             }
             CommandAction::Quit => true,
+            // Argument commands are dispatched through execute_ex_command
+            CommandAction::Theme
+            | CommandAction::Goto
+            | CommandAction::Heading
+            | CommandAction::Level
+            | CommandAction::Open
+            | CommandAction::Query
+            | CommandAction::Set
+            | CommandAction::Import
+            | CommandAction::Rename
+            | CommandAction::Move
+            | CommandAction::Extract => false,
         }
     }
 
     /// Get selected command for display
     pub fn selected_command(&self) -> Option<&'static PaletteCommand> {
-        self.command_filtered
-            .get(self.command_selected)
-            .map(|&idx| &PALETTE_COMMANDS[idx])
-    }
-
-    pub fn jump_to_heading(&mut self, index: usize) {
-        if index < self.outline_items.len() {
-            self.select_outline_index(index);
+        match self.command_filtered.get(self.command_selected) {
+            Some(PaletteEntry::Command(idx)) => Some(&PALETTE_COMMANDS[*idx]),
+            _ => None,
         }
     }
 
-    pub fn set_bookmark(&mut self) {
-        // Store bookmark as heading text instead of index
-        self.bookmark_position = self.selected_heading_text().map(|s| s.to_string());
+    /// Jump to the section containing a 1-indexed source line
+    fn goto_source_line(&mut self, line: usize) {
+        let content = &self.document.content;
+
+        // Last heading at or before the target line, with the scroll offset
+        // into its section (content starts after the heading, minus leading blanks)
+        let target = self
+            .document
+            .headings
+            .iter()
+            .map(|h| (h.text.clone(), Self::offset_to_line(content, h.offset)))
+            .take_while(|(_, heading_line)| *heading_line <= line)
+            .last()
+            .map(|(text, heading_line)| {
+                let blank_lines = content
+                    .lines()
+                    .skip(heading_line)
+                    .take_while(|l| l.trim().is_empty())
+                    .count();
+                (text, line.saturating_sub(heading_line + 1 + blank_lines))
+            });
+
+        let scroll = match target {
+            Some((text, scroll)) => {
+                if !self.reveal_heading(&text) {
+                    self.set_status_message(&format!("✗ Heading '{}' is not visible", text));
+                    return;
+                }
+                scroll
+            }
+            None => {
+                // Line is in the preamble (or the document has no headings)
+                if !self.select_by_text(DOCUMENT_OVERVIEW) {
+                    self.first();
+                }
+                line.saturating_sub(1)
+            }
+        };
+
+        self.update_content_metrics();
+        let scroll = (scroll as u16).min(self.content_height.saturating_sub(1));
+        self.content_scroll = scroll;
+        self.content_scroll_state = self.content_scroll_state.position(scroll as usize);
+        self.set_status_message(&format!("✓ Line {}", line));
     }
 
-    pub fn jump_to_bookmark(&mut self) {
-        // Jump to bookmark by finding the heading text
-        if let Some(text) = self.bookmark_position.clone() {
-            self.select_by_text(&text);
+    /// Jump to a heading by name (exact, then prefix, then substring match)
+    fn goto_heading(&mut self, query: &str) -> Result<(), String> {
+        let query_lower = query.to_lowercase();
+        let headings = &self.document.headings;
+
+        let text = headings
+            .iter()
+            .find(|h| h.text.to_lowercase() == query_lower)
+            .or_else(|| {
+                headings
+                    .iter()
+                    .find(|h| h.text.to_lowercase().starts_with(&query_lower))
+            })
+            .or_else(|| {
+                headings
+                    .iter()
+                    .find(|h| h.text.to_lowercase().contains(&query_lower))
+            })
+            .map(|h| h.text.clone())
+            .ok_or_else(|| format!("Heading '{}' not found", query))?;
+
+        if self.reveal_heading(&text) {
+            Ok(())
+        } else {
+            Err(format!("Heading '{}' is not visible", text))
         }
     }
 
-    pub fn selected_heading_text(&self) -> Option<&str> {
-        self.outline_state
-            .selected()
-            .and_then(|i| self.outline_items.get(i))
-            .map(|item| item.text.as_str())
+    /// Select a heading, expanding any collapsed ancestors so it is visible
+    fn reveal_heading(&mut self, text: &str) -> bool {
+        match self.document.headings.iter().find(|h| h.text == text) {
+            Some(heading) => self.reveal_heading_at(heading.offset),
+            None => self.select_by_text(text),
+        }
     }
 
-    /// Get the source line number (1-indexed) for the currently selected heading.
-    ///
-    /// Returns None if no heading is selected or if the selection is the document overview.
-    pub fn selected_heading_source_line(&self) -> Option<u32> {
-        let selected_text = self.selected_heading_text()?;
-
-        // Document overview doesn't have a source line
-        if selected_text == DOCUMENT_OVERVIEW {
-            return Some(1); // Return line 1 for document overview
+    /// Select the heading at byte `offset`, expanding its collapsed parents
+    fn reveal_heading_at(&mut self, offset: usize) -> bool {
+        if self.select_by_offset(offset) {
+            return true;
         }
 
-        // Find the heading in the document by text
-        let heading = self
+        if let Some(pos) = self
             .document
             .headings
             .iter()
-            .find(|h| h.text == selected_text)?;
+            .position(|h| h.offset == offset)
+        {
+            let mut level = self.document.headings[pos].level;
+            for heading in self.document.headings[..pos].iter().rev() {
+                if heading.level < level {
+                    self.collapsed_headings.remove(&heading.text);
+                    level = heading.level;
+                }
+            }
+        }
+        self.rebuild_outline_items();
+
+        self.select_by_offset(offset)
+    }
+
+    /// Collapse the outline so only headings up to `level` are visible
+    fn set_outline_level(&mut self, level: usize) {
+        let selected = self.selected_heading_text().map(|s| s.to_string());
+
+        self.collapsed_headings = self
+            .document
+            .headings
+            .iter()
+            .filter(|h| h.level >= level)
+            .map(|h| h.text.clone())
+            .collect();
+        self.rebuild_outline_items();
+
+        // Keep the selection, or move to its closest visible ancestor
+        let mut restored = false;
+        if let Some(text) = selected {
+            restored = self.select_by_text(&text);
+            if let Some(pos) = self.document.headings.iter().position(|h| h.text == text) {
+                // Walk up the ancestors until one is visible
+                let mut current_level = self.document.headings[pos].level;
+                let mut idx = pos;
+                while !restored && idx > 0 {
+                    idx -= 1;
+                    let heading = &self.document.headings[idx];
+                    if heading.level < current_level {
+                        current_level = heading.level;
+                        let ancestor = heading.text.clone();
+                        restored = self.select_by_text(&ancestor);
+                    }
+                }
+            }
+        }
+        if !restored && !self.outline_items.is_empty() {
+            self.select_outline_index(0);
+        }
+        self.outline_scroll_state = ScrollbarState::new(self.outline_items.len())
+            .position(self.outline_state.selected().unwrap_or(0));
+
+        self.set_status_message(&format!("Outline level {}", level));
+    }
+
+    /// Open a file relative to the current one (`path#anchor` jumps to a heading)
+    fn open_relative_path(&mut self, target: &str) -> Result<(), String> {
+        let (path, anchor) = match target.split_once('#') {
+            Some((path, anchor)) => (path, Some(anchor)),
+            None => (target, None),
+        };
+
+        if path.is_empty() {
+            let anchor = anchor.unwrap_or_default();
+            self.jump_to_anchor(anchor)?;
+            self.set_status_message(&format!("✓ Jumped to #{}", anchor));
+            return Ok(());
+        }
+
+        self.load_file(&PathBuf::from(path), anchor)?;
+        if self.mode != AppMode::ConfirmFileCreate {
+            self.update_content_metrics();
+            self.set_status_message(&format!("✓ Opened {}", path));
+        }
+        Ok(())
+    }
+
+    /// Run a query against the current document and copy the results
+    fn run_palette_query(&mut self, query: &str) {
+        let values = match crate::query::execute(&self.document, query) {
+            Ok(values) => values,
+            Err(e) => {
+                self.set_status_message(&format!("✗ Query error: {}", e.kind));
+                return;
+            }
+        };
+
+        if values.is_empty() {
+            self.set_status_message(&format!("No results for {}", query));
+            return;
+        }
+
+        let output = crate::query::format_output(&values, crate::query::OutputFormat::Plain);
+        match self.copy_to_clipboard(&output) {
+            Ok(_) => {
+                let first_line = output.lines().next().unwrap_or_default();
+                let msg = if values.len() == 1 && first_line.len() <= 60 {
+                    format!("✓ {} (copied)", first_line)
+                } else {
+                    format!("✓ {} results copied to clipboard", values.len())
+                };
+                self.set_status_message(&msg);
+            }
+            Err(e) => self.set_status_message(&format!("✗ {}", e)),
+        }
+    }
+
+    /// Change a session setting via `:set key=value` (`:set key` shows the value)
+    fn set_option(&mut self, key: &str, value: Option<&str>) -> Result<(), String> {
+        let Some(value) = value else {
+            let current = match key {
+                "outline_width" => self.outline_width.to_string(),
+                "outline" => if self.show_outline { "on" } else { "off" }.to_string(),
+                "raw" => if self.show_raw_source { "on" } else { "off" }.to_string(),
+                "theme" => self.current_theme.slug().to_string(),
+                _ => return Err(format!("Unknown option '{}'", key)),
+            };
+            self.set_status_message(&format!("{}={}", key, current));
+            return Ok(());
+        };
+
+        let invalid = || format!("Invalid value for {}: {}", key, value);
+        match key {
+            "outline_width" => {
+                let width = value
+                    .trim_end_matches('%')
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|w| (10..=80).contains(w))
+                    .ok_or_else(|| format!("outline_width must be between 10 and 80: {}", value))?;
+                self.outline_width = width;
+                self.set_status_message(&format!("Width: {}% | :w to save", width));
+            }
+            "outline" => {
+                let show = command_line::parse_bool(value).ok_or_else(invalid)?;
+                if show != self.show_outline {
+                    self.toggle_outline();
+                }
+            }
+            "raw" => {
+                let raw = command_line::parse_bool(value).ok_or_else(invalid)?;
+                if raw != self.show_raw_source {
+                    self.toggle_raw_source();
+                }
+            }
+            "theme" => {
+                let theme_name = ThemeName::parse(value).ok_or_else(invalid)?;
+                self.apply_theme_preview(theme_name);
+                self.set_status_message(&format!("Theme: {} | :w to save", self.theme.name));
+            }
+            _ => return Err(format!("Unknown option '{}'", key)),
+        }
+        Ok(())
+    }
+
+    pub fn jump_to_heading(&mut self, index: usize) {
+        if index < self.outline_items.len() {
+            self.select_outline_index(index);
+        }
+    }
+
+    pub fn set_bookmark(&mut self) {
+        // Store bookmark as heading text instead of index
+        self.bookmark_position = self.selected_heading_text().map(|s| s.to_string());
+    }
+
+    pub fn jump_to_bookmark(&mut self) {
+        // Jump to bookmark by finding the heading text
+        if let Some(text) = self.bookmark_position.clone() {
+            self.select_by_text(&text);
+        }
+    }
+
+    pub fn selected_heading_text(&self) -> Option<&str> {
+        self.outline_state
+            .selected()
+            .and_then(|i| self.outline_items.get(i))
+            .map(|item| item.text.as_str())
+    }
+
+    /// The selected outline row, unless it is the document overview
+    fn selected_heading_item(&self) -> Option<&OutlineItem> {
+        self.outline_state
+            .selected()
+            .and_then(|i| self.outline_items.get(i))
+            .filter(|item| item.text != DOCUMENT_OVERVIEW)
+    }
+
+    /// Get the source line number (1-indexed) for the currently selected heading.
+    ///
+    /// Returns None if no heading is selected or if the selection is the document overview.
+    pub fn selected_heading_source_line(&self) -> Option<u32> {
+        let selected_text = self.selected_heading_text()?;
+
+        // Document overview doesn't have a source line
+        if selected_text == DOCUMENT_OVERVIEW {
+            return Some(1); // Return line 1 for document overview
+        }
+
+        // Find the heading in the document by text
+        let heading = self
+            .document
+            .headings
+            .iter()
+            .find(|h| h.text == selected_text)?;
+
+        Some(Self::offset_to_line(&self.document.content, heading.offset) as u32)
+    }
 
-        // Convert byte offset to line number (1-indexed)
-        let offset = heading.offset.min(self.document.content.len());
-        let before = &self.document.content[..offset];
-        let line = before.chars().filter(|&c| c == '\n').count() + 1;
-        Some(line as u32)
+    /// Convert a byte offset into a 1-indexed line number
+    fn offset_to_line(content: &str, offset: usize) -> usize {
+        let offset = offset.min(content.len());
+        content[..offset].chars().filter(|&c| c == '\n').count() + 1
     }
 
     /// Sync previous_selection to current selection (prevents spurious scroll resets)
@@ -2407,6 +3961,11 @@ impl App {
                 self.exit_link_follow_mode();
                 Ok(())
             }
+            crate::parser::LinkTarget::Footnote(label) => {
+                self.jump_to_footnote(&label)?;
+                self.exit_link_follow_mode();
+                Ok(())
+            }
             crate::parser::LinkTarget::RelativeFile { path, anchor } => {
                 // Check if the file has a markdown extension
                 let has_md_extension = path
@@ -2453,7 +4012,7 @@ impl App {
 
                         if absolute_path.exists() && !absolute_path.is_symlink() {
                             // File exists - open in editor (non-markdown)
-                            self.pending_editor_file = Some(absolute_path);
+                            self.open_in_external_editor(absolute_path)?;
                             self.exit_link_follow_mode();
                         } else {
                             // File doesn't exist - prompt to create markdown file
@@ -2478,13 +4037,20 @@ impl App {
                 // Try to find and load the wikilinked file
                 self.load_wikilink(&target)?;
                 // Only exit link follow mode if we're not prompting for file creation
-                if self.mode != AppMode::ConfirmFileCreate {
+                // or a choice between notes
+                if !matches!(self.mode, AppMode::ConfirmFileCreate | AppMode::NotePicker) {
                     self.status_message = Some(format!("✓ Opened [[{}]]", target));
                     self.exit_link_follow_mode();
                 }
                 Ok(())
             }
             crate::parser::LinkTarget::External(url) => {
+                if self.access_mode == AccessMode::Safe {
+                    self.refuse_external_url(&url);
+                    self.exit_link_follow_mode();
+                    return Ok(());
+                }
+
                 // Try to open in default browser
                 let open_result = open::that(&url);
 
@@ -2514,6 +4080,15 @@ impl App {
         }
     }
 
+    /// Safe mode: don't open external URLs, but offer them on the clipboard
+    fn refuse_external_url(&mut self, url: &str) {
+        self.status_message = Some(if self.copy_to_clipboard(url).is_ok() {
+            format!("⚠ Safe mode: URL not opened, copied to clipboard: {}", url)
+        } else {
+            format!("⚠ Safe mode: URL not opened: {}", url)
+        });
+    }
+
     /// Jump to a heading by anchor name or heading text.
     ///
     /// Supports two matching strategies (checked per-item, Strategy 1 takes priority):
@@ -2521,7 +4096,17 @@ impl App {
     ///    Handles markdown links (`#features`, `#mixed-links-test`) and simple wikilinks (`[[#Features]]`).
     /// 2. **Heading text match** - case-insensitive comparison of raw heading text.
     ///    Handles wikilinks preserving spaces (`[[#Mixed Links Test]]`).
+    ///
+    /// Block references (`^block-id`) jump to the block's source line instead.
     fn jump_to_anchor(&mut self, anchor: &str) -> Result<(), String> {
+        // Block references (`^block-id`) go to the block's line
+        if let Some(id) = anchor.strip_prefix('^') {
+            let block = find_block(&self.document.content, id)
+                .ok_or_else(|| format!("Block '^{}' not found", id))?;
+            self.goto_source_line(block.start + 1);
+            return Ok(());
+        }
+
         let anchor_lower = anchor.to_lowercase();
 
         for (idx, item) in self.outline_items.iter().enumerate() {
@@ -2544,6 +4129,59 @@ impl App {
         Err(format!("Heading '{}' not found", anchor))
     }
 
+    /// Definition of a footnote anywhere in the current document
+    pub fn footnote_definition(&self, label: &str) -> Option<FootnoteDef> {
+        find_definitions(&self.document.content)
+            .into_iter()
+            .find(|def| def.label == label)
+    }
+
+    /// First line of a footnote's definition, truncated to `max_chars`
+    pub fn footnote_preview(&self, label: &str, max_chars: usize) -> String {
+        let Some(def) = self.footnote_definition(label) else {
+            return "(no definition)".to_string();
+        };
+        let first_line = def.content.lines().next().unwrap_or("");
+        if first_line.chars().count() > max_chars {
+            let truncated: String = first_line
+                .chars()
+                .take(max_chars.saturating_sub(3))
+                .collect();
+            format!("{}...", truncated)
+        } else {
+            first_line.to_string()
+        }
+    }
+
+    /// Scroll to a footnote in the section's footnotes list, remembering
+    /// where to come back to
+    fn jump_to_footnote(&mut self, label: &str) -> Result<(), String> {
+        let line = *self
+            .footnote_lines
+            .get(label)
+            .ok_or_else(|| format!("Footnote [^{}] not found", label))?;
+
+        self.footnote_return = Some(self.content_scroll);
+        self.content_scroll = line.min(self.content_height.saturating_sub(1));
+        self.content_scroll_state = self
+            .content_scroll_state
+            .position(self.content_scroll as usize);
+        self.status_message = Some(format!("✓ Footnote [^{}] (Backspace to go back)", label));
+        Ok(())
+    }
+
+    /// Scroll back to where the last footnote jump started
+    fn footnote_back(&mut self) -> Result<(), String> {
+        let scroll = self
+            .footnote_return
+            .take()
+            .ok_or("No footnote to return from")?;
+        self.content_scroll = scroll;
+        self.content_scroll_state = self.content_scroll_state.position(scroll as usize);
+        self.status_message = Some("✓ Back from footnote".to_string());
+        Ok(())
+    }
+
     /// Load a file by relative path
     ///
     /// Security: Validates path to prevent directory traversal attacks.
@@ -2584,8 +4222,11 @@ impl App {
             return Err("Symlinks are not allowed for security reasons".to_string());
         }
 
-        // Check if file exists - if not, prompt to create it
+        // Check if file exists - if not, prompt to create it (unless read-only)
         if !absolute_path.exists() {
+            if self.access_mode.is_read_only() {
+                return Err(format!("File '{}' does not exist", relative_path.display()));
+            }
             self.pending_file_create = Some(absolute_path.clone());
             self.pending_file_create_message = Some(format!(
                 "File '{}' does not exist. Create it?",
@@ -2595,6 +4236,13 @@ impl App {
             return Ok(()); // Not an error - we're asking user to confirm
         }
 
+        self.open_note(absolute_path, anchor)
+    }
+
+    /// Load an existing markdown file by absolute path and jump to `anchor`
+    fn open_note(&mut self, absolute_path: PathBuf, anchor: Option<&str>) -> Result<(), String> {
+        self.ensure_within_safe_root(&absolute_path)?;
+
         // Parse the new file
         let new_document = crate::parser::parse_file(&absolute_path)
             .map_err(|e| format!("Failed to load file: {}", e))?;
@@ -2626,6 +4274,10 @@ impl App {
     /// - `[[filename#anchor]]` - load file and jump to anchor
     /// - `[[#anchor]]` - jump to anchor in current document
     /// - `[[path/to/file]]` - load file with path (e.g., `[[diary/notes.md]]`)
+    /// - `[[filename#^block-id]]` - load file and jump to a block
+    ///
+    /// Inside a vault the file is looked up across every note, by path
+    /// suffix or alias; several matches open the note picker.
     ///
     /// Security: Path traversal (..) and absolute paths are blocked.
     /// The `load_file()` function provides additional security validation.
@@ -2645,67 +4297,37 @@ impl App {
             (target, None)
         };
 
-        // Security: Reject path traversal attempts
-        if file_target.contains("..") {
-            return Err("WikiLinks cannot contain path traversal (..)".to_string());
-        }
-
-        // Security: Reject absolute paths
-        if file_target.starts_with('/') {
-            return Err("WikiLinks cannot be absolute paths".to_string());
-        }
-
-        // Security: Reject Windows absolute paths (drive letters)
-        #[cfg(windows)]
-        if file_target.len() >= 2 && file_target.chars().nth(1) == Some(':') {
-            return Err("WikiLinks cannot be absolute paths".to_string());
+        // Inside a vault, look across all of its notes
+        let mut matches = self.vault_matches(file_target)?;
+        if matches.len() == 1 {
+            return self.open_note(matches.remove(0), anchor);
+        }
+        if matches.len() > 1 {
+            self.note_picker = Some(NotePicker {
+                target: file_target.to_string(),
+                anchor: anchor.map(String::from),
+                notes: matches,
+                selected: 0,
+            });
+            self.mode = AppMode::NotePicker;
+            return Ok(());
         }
 
-        // Normalize backslashes to forward slashes for cross-platform compatibility
-        let file_target = file_target.replace('\\', "/");
-
         // Try to find the file relative to current directory
         let current_dir = self
             .current_file_path
             .parent()
             .ok_or("Cannot determine current directory")?;
+        if let Some(candidate) = resolve_wikilink(current_dir, file_target)? {
+            return self.load_file(&candidate, anchor);
+        }
 
-        // Check if target already has a markdown extension
-        let file_target_lower = file_target.to_lowercase();
-        let has_md_extension = file_target_lower.ends_with(".md")
-            || file_target_lower.ends_with(".markdown")
-            || file_target_lower.ends_with(".mdown");
-
-        // Try various extensions (only add extensions if target doesn't already have one)
-        let candidates: Vec<String> = if has_md_extension {
-            // Already has markdown extension - just try as-is
-            vec![file_target.to_string()]
-        } else {
-            // Try with various extensions
-            vec![
-                format!("{}.md", file_target),
-                format!("{}.markdown", file_target),
-                file_target.to_string(),
-            ]
-        };
-
-        for candidate in &candidates {
-            let path = current_dir.join(candidate);
-            // Check for symlinks
-            if path.is_symlink() {
-                continue; // Skip symlinks for security
-            }
-            if path.exists() {
-                return self.load_file(&PathBuf::from(candidate), anchor);
-            }
+        if self.access_mode.is_read_only() {
+            return Err(format!("Wikilink '[[{}]]' not found", target));
         }
 
         // File not found - prompt to create it (default to .md extension if not already present)
-        let default_filename = if has_md_extension {
-            file_target.to_string()
-        } else {
-            format!("{}.md", file_target)
-        };
+        let default_filename = wikilink_candidates(file_target)?.swap_remove(0);
         let new_path = current_dir.join(&default_filename);
         self.pending_file_create = Some(new_path);
         self.pending_file_create_message = Some(format!(
@@ -2716,6 +4338,47 @@ impl App {
         Ok(()) // Not an error - we're asking user to confirm
     }
 
+    /// Vault notes a wikilink's file part matches, best first. Rescans once
+    /// when nothing matches, in case the note was created since.
+    fn vault_matches(&mut self, file_target: &str) -> Result<Vec<PathBuf>, String> {
+        // Same path checks as links relative to the current file
+        wikilink_candidates(file_target)?;
+        let from = self.current_file_path.clone();
+        let Some(vault) = self.vault.as_mut() else {
+            return Ok(Vec::new());
+        };
+        let mut matches = vault.resolve(file_target, &from);
+        if matches.is_empty() {
+            vault.rescan();
+            matches = vault.resolve(file_target, &from);
+        }
+        Ok(matches)
+    }
+
+    fn note_picker_next(&mut self) {
+        if let Some(picker) = &mut self.note_picker {
+            picker.selected = (picker.selected + 1).min(picker.notes.len().saturating_sub(1));
+        }
+    }
+
+    fn note_picker_previous(&mut self) {
+        if let Some(picker) = &mut self.note_picker {
+            picker.selected = picker.selected.saturating_sub(1);
+        }
+    }
+
+    /// Open the note selected in the note picker
+    fn open_picked_note(&mut self) -> Result<(), String> {
+        let picker = self.note_picker.take().ok_or("No note picker open")?;
+        self.mode = AppMode::Normal;
+        let path = picker
+            .notes
+            .get(picker.selected)
+            .cloned()
+            .ok_or("No note selected")?;
+        self.open_note(path, picker.anchor.as_deref())
+    }
+
     /// Save current state to history before navigating away
     fn save_to_history(&mut self) {
         let state = FileState {
@@ -2737,11 +4400,18 @@ impl App {
         // Signal file watcher if path changed
         if self.current_file_path != path {
             self.file_path_changed = true;
+
+            // Follow the new file into its vault, if it has one
+            let root = Vault::detect(&path, self.config.vault.root.as_deref());
+            if root.as_deref() != self.vault.as_ref().map(Vault::root) {
+                self.vault = root.map(Vault::open);
+            }
         }
 
         self.document = document;
         self.filename = filename;
         self.current_file_path = path;
+        self.disk_snapshot = DiskSnapshot::capture(&self.current_file_path);
 
         // Rebuild tree and outline (with overview entry if applicable)
         self.tree = self.document.build_tree();
@@ -2906,12 +4576,16 @@ impl App {
     /// Exit interactive mode and return to normal
     pub fn exit_interactive_mode(&mut self) {
         self.interactive_state.exit();
+        // Output panels go with interactive mode, so stop anything still running
+        self.code_runs.clear();
         self.mode = AppMode::Normal;
         self.status_message = None;
     }
 
     /// Confirm file creation and open the new file
     pub fn confirm_file_create(&mut self) -> Result<(), String> {
+        self.ensure_writable()?;
+
         if let Some(path) = self.pending_file_create.take() {
             // Create parent directories if needed
             if let Some(parent) = path.parent() {
@@ -2989,8 +4663,13 @@ impl App {
                 item_idx,
                 ..
             } => {
-                // Toggle checkbox and save to file
-                self.toggle_checkbox_and_save(*block_idx, *item_idx, *checked)?;
+                // Toggle checkbox and save to file, preferring the first match
+                // in the current section
+                let text = self
+                    .checkbox_text(*block_idx, *item_idx)
+                    .ok_or_else(|| "Could not find checkbox content".to_string())?;
+                let line_hint = self.selected_heading_source_line().map(|l| l as usize);
+                self.toggle_checkbox_and_save(&text, *checked, line_hint)?;
                 Ok(())
             }
             ElementType::Link { link, .. } => {
@@ -3010,9 +4689,23 @@ impl App {
                 self.status_message = Some(format!("✓ Image path copied: {}", alt));
                 Ok(())
             }
+            ElementType::Embed { target, .. } => {
+                // Open the embedded note at the embedded heading or block
+                let link = Link::new(
+                    target.clone(),
+                    LinkTarget::WikiLink {
+                        target: target.clone(),
+                        alias: None,
+                    },
+                    0,
+                );
+                self.follow_link_from_interactive(&link)
+            }
             ElementType::Table { rows, cols, .. } => {
                 // Enter table navigation mode
                 self.interactive_state.enter_table_mode()?;
+                self.scroll_to_interactive_element(self.content_viewport.1);
+                self.keep_table_selection_visible();
                 self.status_message =
                     Some(self.interactive_state.table_status_text(rows + 1, *cols));
                 Ok(())
@@ -3020,93 +4713,987 @@ impl App {
         }
     }
 
-    /// Re-index interactive elements after state changes
-    pub fn reindex_interactive_elements(&mut self) {
-        let content = if let Some(selected) = self.selected_heading_text() {
-            self.document
-                .extract_section(selected)
-                .unwrap_or_else(|| self.document.content.clone())
-        } else {
-            self.document.content.clone()
+    /// Ask to run the selected code block, or stop it if it is running
+    pub fn run_selected_code_block(&mut self) -> Result<(), String> {
+        use crate::tui::interactive::ElementType;
+
+        if self.access_mode.is_read_only() {
+            return Err("Read-only mode: running code is disabled".to_string());
+        }
+        let element = self
+            .interactive_state
+            .current_element()
+            .cloned()
+            .ok_or_else(|| "No element selected".to_string())?;
+        let ElementType::CodeBlock {
+            language, content, ..
+        } = element.element_type
+        else {
+            return Err("Select a code block to run it".to_string());
         };
+        let id = element.id;
 
-        use crate::parser::content::parse_content;
-        let blocks = parse_content(&content, 0);
-        self.interactive_state.index_elements(&blocks);
-    }
+        if let Some(run) = self.code_runs.get_mut(&id)
+            && let Some(output) = self.interactive_state.code_outputs.get_mut(&id)
+            && output.status == RunStatus::Running
+        {
+            run.stop(output);
+            self.status_message = Some("✓ Stopped".to_string());
+            return Ok(());
+        }
 
-    /// Toggle a checkbox and save changes to the file
-    fn toggle_checkbox_and_save(
-        &mut self,
-        block_idx: usize,
-        item_idx: usize,
-        checked: bool,
-    ) -> Result<(), String> {
-        // Get the checkbox content text to use as identifier
-        let checkbox_content = {
-            let content = if let Some(selected) = self.selected_heading_text() {
-                self.document
-                    .extract_section(selected)
-                    .unwrap_or_else(|| self.document.content.clone())
-            } else {
-                self.document.content.clone()
-            };
+        if id.sub_idx.is_some() {
+            return Err("Only top-level code blocks can be run".to_string());
+        }
+        let language = language
+            .filter(|l| !l.is_empty())
+            .ok_or_else(|| "Code block has no language to pick an interpreter".to_string())?;
+        let command = self.config.exec.interpreter(&language).ok_or_else(|| {
+            format!(
+                "No interpreter for '{}' (add one under [exec.interpreters] in the config)",
+                language
+            )
+        })?;
+        let dir = self
+            .current_file_path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty() && p.is_dir())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        self.pending_code_run = Some(CodeRunRequest {
+            id,
+            language,
+            command,
+            code: content,
+            dir,
+        });
+        self.mode = AppMode::ConfirmCodeRun;
+        Ok(())
+    }
 
-            use crate::parser::content::parse_content;
-            let blocks = parse_content(&content, 0);
+    /// Write the selected code block to a temp file for editing in `$EDITOR`.
+    ///
+    /// Returns the temp file's path; `finish_code_block_edit` writes it back.
+    pub fn start_code_block_edit(&mut self) -> Result<PathBuf, String> {
+        use crate::parser::code_blocks::{find_block_by_content, find_fenced_blocks};
+        use std::io::Write;
 
-            if let Some(crate::parser::output::Block::List { items, .. }) = blocks.get(block_idx) {
-                items.get(item_idx).map(|item| item.content.clone())
-            } else {
-                None
-            }
+        if self.access_mode.is_read_only() {
+            return Err("Read-only mode: editor is disabled".to_string());
+        }
+        let Some(ElementType::CodeBlock {
+            language, content, ..
+        }) = self
+            .interactive_state
+            .current_element()
+            .map(|element| element.element_type.clone())
+        else {
+            return Err("Select a code block to edit it".to_string());
         };
 
-        let checkbox_content =
-            checkbox_content.ok_or_else(|| "Could not find checkbox content".to_string())?;
-
-        // Read the current file
-        let file_content = std::fs::read_to_string(&self.current_file_path)
-            .map_err(|e| format!("Failed to read file: {}", e))?;
-
-        // Find and toggle the checkbox in the file content
-        let new_content =
-            self.toggle_checkbox_by_content(&file_content, &checkbox_content, checked)?;
+        let source = self.loaded_file_content()?;
+        let blocks = find_fenced_blocks(&source);
+        let line_hint = self.selected_heading_source_line().map(|l| l as usize);
+        let block = find_block_by_content(&blocks, &content, line_hint)
+            .ok_or_else(|| "Could not find this code block in the file".to_string())?;
+
+        let extension = language.as_deref().map_or("txt", code_file_extension);
+        let mut file = tempfile::Builder::new()
+            .prefix("treemd-block-")
+            .suffix(&format!(".{}", extension))
+            .tempfile()
+            .map_err(|e| format!("Failed to create temp file: {}", e))?;
+        file.write_all(block.content.as_bytes())
+            .and_then(|_| file.flush())
+            .map_err(|e| format!("Failed to write temp file: {}", e))?;
 
-        // Atomic write: write to temp file, then rename (prevents data corruption)
-        use std::io::Write;
-        let parent_dir = self
-            .current_file_path
-            .parent()
-            .ok_or("Cannot determine parent directory")?;
+        let path = file.path().to_path_buf();
+        self.pending_code_edit = Some(CodeBlockEdit {
+            file,
+            line: block.line,
+            original: block.content.clone(),
+        });
+        Ok(path)
+    }
 
-        let mut temp_file = tempfile::NamedTempFile::new_in(parent_dir)
-            .map_err(|e| format!("Failed to create temp file: {}", e))?;
+    /// Splice the edited temp file back into the code block
+    pub fn finish_code_block_edit(&mut self) -> Result<(), String> {
+        use crate::parser::code_blocks::{find_fenced_blocks, replace_block_content};
 
-        temp_file
-            .write_all(new_content.as_bytes())
-            .map_err(|e| format!("Failed to write temp file: {}", e))?;
+        let edit = self
+            .pending_code_edit
+            .take()
+            .ok_or_else(|| "No code block is being edited".to_string())?;
+        let edited = std::fs::read_to_string(edit.file.path())
+            .map_err(|e| format!("Failed to read edited block: {}", e))?;
+        if edited == edit.original {
+            self.status_message = Some("Code block unchanged".to_string());
+            return Ok(());
+        }
 
-        temp_file
-            .flush()
-            .map_err(|e| format!("Failed to flush temp file: {}", e))?;
+        let source = self.loaded_file_content()?;
+        let block = find_fenced_blocks(&source)
+            .into_iter()
+            .find(|block| block.line == edit.line && block.content == edit.original)
+            .ok_or_else(|| "The code block moved while editing; nothing was written".to_string())?;
+        let new_content = replace_block_content(&source, &block, &edited);
 
-        // Atomic rename (same filesystem guarantees atomicity)
-        temp_file
-            .persist(&self.current_file_path)
-            .map_err(|e| format!("Failed to save file: {}", e))?;
+        if !self.write_and_record_edit(&source, &new_content, "Edit code block")? {
+            return Ok(());
+        }
+        self.reload_after_save()?;
+        self.status_message = Some("✓ Code block saved (u to undo)".to_string());
+        Ok(())
+    }
 
-        // Save scroll position and interactive element index before reload
-        let saved_scroll = self.content_scroll;
-        let saved_element_idx = self.interactive_state.current_index;
+    /// Discard a code block edit (the temp file is removed)
+    pub fn cancel_code_block_edit(&mut self) {
+        self.pending_code_edit = None;
+    }
 
-        // Reload the document
-        self.reload_current_file()?;
+    /// Run the code block the user confirmed
+    pub fn confirm_code_run(&mut self) -> Result<(), String> {
+        self.mode = AppMode::Interactive;
+        let request = self
+            .pending_code_run
+            .take()
+            .ok_or_else(|| "No code block to run".to_string())?;
+        if self.access_mode.is_read_only() {
+            return Err("Read-only mode: running code is disabled".to_string());
+        }
+
+        // Drop any previous run of this block before starting again
+        self.code_runs.remove(&request.id);
+        let run = CodeRun::start(&request.command, &request.code, &request.dir)?;
+        let command = request.command.join(" ");
+        self.code_runs.insert(request.id, run);
+        self.interactive_state
+            .code_outputs
+            .insert(request.id, CodeOutput::new(command.clone()));
+        self.reindex_interactive_elements();
+        self.update_content_metrics();
+        self.status_message = Some(format!("✓ Running {} (x to stop)", command));
+        Ok(())
+    }
 
-        // Re-index interactive elements
+    pub fn cancel_code_run(&mut self) {
+        self.pending_code_run = None;
+        self.mode = AppMode::Interactive;
+        self.status_message = Some("Run cancelled".to_string());
+    }
+
+    /// Collapse or expand the selected code block's output panel
+    pub fn toggle_code_output(&mut self) -> Result<(), String> {
+        let id = self
+            .interactive_state
+            .current_element()
+            .map(|element| element.id)
+            .ok_or_else(|| "No element selected".to_string())?;
+        let output = self
+            .interactive_state
+            .code_outputs
+            .get_mut(&id)
+            .ok_or_else(|| "No output for this element (x runs a code block)".to_string())?;
+        output.collapsed = !output.collapsed;
         self.reindex_interactive_elements();
+        self.update_content_metrics();
+        Ok(())
+    }
 
-        // Restore scroll position (clamped to valid range)
+    /// Switch the selected Mermaid block between its diagram and its source
+    pub fn toggle_diagram_source(&mut self) -> Result<(), String> {
+        let element = self
+            .interactive_state
+            .current_element()
+            .ok_or_else(|| "No element selected".to_string())?;
+        let (id, is_mermaid) = match &element.element_type {
+            ElementType::CodeBlock { language, .. } => {
+                (element.id, language.as_deref() == Some("mermaid"))
+            }
+            _ => (element.id, false),
+        };
+        if !is_mermaid {
+            return Err("Not a Mermaid diagram".to_string());
+        }
+
+        let sources = &mut self.interactive_state.diagram_sources;
+        let showing_source = sources.insert(id) || !sources.remove(&id);
+        self.reindex_interactive_elements();
+        self.update_content_metrics();
+        self.status_message = Some(if showing_source {
+            "✓ Showing diagram source".to_string()
+        } else {
+            "✓ Showing diagram".to_string()
+        });
+        Ok(())
+    }
+
+    /// Collect output from running code blocks. Called from the event loop.
+    pub fn poll_code_runs(&mut self) {
+        if self.code_runs.is_empty() {
+            return;
+        }
+
+        let mut changed = false;
+        let mut exit_status = None;
+        let outputs = &mut self.interactive_state.code_outputs;
+        // Runs whose panel is gone are dropped (which kills them)
+        self.code_runs.retain(|id, run| {
+            let Some(output) = outputs.get_mut(id) else {
+                return false;
+            };
+            let was_running = output.status == RunStatus::Running;
+            changed |= run.poll(output);
+            if was_running && output.status != RunStatus::Running {
+                exit_status = Some(output.status);
+            }
+            !run.is_finished()
+        });
+
+        match exit_status {
+            Some(RunStatus::Exited(0)) => {
+                self.status_message = Some("✓ Code block exited with status 0".to_string());
+            }
+            Some(RunStatus::Exited(code)) => {
+                self.status_message = Some(format!("✗ Code block exited with status {}", code));
+            }
+            Some(RunStatus::Killed) => {
+                self.status_message = Some("✗ Code block was killed".to_string());
+            }
+            _ => {}
+        }
+
+        if changed {
+            self.reindex_interactive_elements();
+            self.update_content_metrics();
+        }
+    }
+
+    /// Re-index interactive elements after state changes
+    pub fn reindex_interactive_elements(&mut self) {
+        let content = if let Some(selected) = self.selected_heading_text() {
+            self.document
+                .extract_section(selected)
+                .unwrap_or_else(|| self.document.content.clone())
+        } else {
+            self.document.content.clone()
+        };
+
+        use crate::parser::content::parse_content;
+        let blocks = parse_content(&content, 0);
+        self.interactive_state.index_elements(&blocks);
+    }
+
+    /// Open or close the task list popup
+    pub fn toggle_task_list(&mut self) {
+        if self.mode == AppMode::TaskList {
+            self.mode = AppMode::Normal;
+            return;
+        }
+
+        if crate::parser::tasks::extract_tasks(&self.document).is_empty() {
+            self.set_status_message("No tasks in this document");
+            return;
+        }
+
+        // Start at the selected section's first task
+        let section_line = self.selected_heading_source_line().unwrap_or(1) as usize;
+        self.task_list_selected = self
+            .visible_tasks()
+            .iter()
+            .position(|t| t.line >= section_line)
+            .unwrap_or(0);
+        self.mode = AppMode::TaskList;
+    }
+
+    /// Open or close the tag browser, starting with the document's tags
+    pub fn toggle_tag_browser(&mut self) {
+        if self.mode == AppMode::TagBrowser {
+            self.tag_browser = None;
+            self.mode = AppMode::Normal;
+            return;
+        }
+
+        let document = (
+            self.current_file_path.clone(),
+            self.document.content.clone(),
+        );
+        self.tag_browser = Some(TagBrowser::new(false, &[document]));
+        self.mode = AppMode::TagBrowser;
+    }
+
+    /// Switch the tag browser between the document's tags and the workspace's
+    fn toggle_tag_scope(&mut self) {
+        let Some(browser) = &self.tag_browser else {
+            return;
+        };
+        let browser = if browser.workspace {
+            let document = (
+                self.current_file_path.clone(),
+                self.document.content.clone(),
+            );
+            self.set_status_message("Showing tags in this document");
+            TagBrowser::new(false, &[document])
+        } else {
+            let notes = self.workspace_notes();
+            self.set_status_message(&format!(
+                "Showing tags in {} ({} notes)",
+                if self.vault.is_some() {
+                    "the vault"
+                } else {
+                    "this folder"
+                },
+                notes.len()
+            ));
+            TagBrowser::new(true, &notes)
+        };
+        self.tag_browser = Some(browser);
+    }
+
+    /// The current file's vault, or its folder outside a vault. `None` for
+    /// documents not on disk.
+    fn workspace_vault(&self) -> Option<Cow<'_, Vault>> {
+        if let Some(vault) = &self.vault {
+            return Some(Cow::Borrowed(vault));
+        }
+        let dir = self
+            .current_file_path
+            .canonicalize()
+            .ok()?
+            .parent()?
+            .to_path_buf();
+        Some(Cow::Owned(Vault::open_folder(dir)))
+    }
+
+    /// Path and content of every note in the current file's vault, or in its
+    /// folder outside a vault
+    fn workspace_notes(&self) -> Vec<(PathBuf, String)> {
+        let Some(notes) = self.workspace_vault() else {
+            return vec![(
+                self.current_file_path.clone(),
+                self.document.content.clone(),
+            )];
+        };
+        notes
+            .notes()
+            .iter()
+            .filter_map(|note| {
+                let path = notes.root().join(&note.path);
+                let content = std::fs::read_to_string(&path).ok()?;
+                Some((path, content))
+            })
+            .collect()
+    }
+
+    /// List the selected tag's locations, or jump to the selected location
+    fn tag_browser_open(&mut self) -> Result<(), String> {
+        let Some(browser) = &mut self.tag_browser else {
+            return Ok(());
+        };
+        if browser.location.is_none() {
+            browser.open();
+            return Ok(());
+        }
+        let Some(location) = browser.selected_location().cloned() else {
+            return Ok(());
+        };
+
+        self.tag_browser = None;
+        self.mode = AppMode::Normal;
+        if !self.is_current_file(&location.path) {
+            self.open_note(location.path, None)?;
+        }
+        self.goto_source_line(location.line);
+        Ok(())
+    }
+
+    /// Whether `path` is the file being viewed
+    fn is_current_file(&self, path: &Path) -> bool {
+        path == self.current_file_path
+            || path.canonicalize().is_ok_and(|path| {
+                self.current_file_path
+                    .canonicalize()
+                    .is_ok_and(|current| path == current)
+            })
+    }
+
+    /// Open the command line to rename the selected heading
+    fn start_heading_rename(&mut self) {
+        let Some(text) = self
+            .selected_heading_text()
+            .filter(|text| *text != DOCUMENT_OVERVIEW)
+            .map(str::to_string)
+        else {
+            self.set_status_message("✗ No heading selected");
+            return;
+        };
+        self.open_command_palette();
+        self.command_query = format!("rename {}", text);
+        self.filter_commands();
+    }
+
+    /// Plan renaming the selected heading and show the preview
+    fn plan_heading_rename(&mut self, new_text: &str) -> Result<(), String> {
+        self.ensure_writable()?;
+        let offset = self
+            .selected_heading_item()
+            .ok_or("No heading selected")?
+            .offset;
+        let workspace = self
+            .workspace_vault()
+            .ok_or("Renaming needs a document saved to disk")?;
+        let plan = rename::rename_heading(&workspace, &self.current_file_path, offset, new_text)?;
+        self.pending_rename = Some(plan);
+        self.mode = AppMode::ConfirmRename;
+        Ok(())
+    }
+
+    /// Plan renaming the current note (relative to its folder) and show the
+    /// preview. The new path must stay inside the workspace.
+    fn plan_file_rename(&mut self, new_path: &str) -> Result<(), String> {
+        self.ensure_writable()?;
+        let workspace = self
+            .workspace_vault()
+            .ok_or("Renaming needs a document saved to disk")?;
+        let dir = self
+            .current_file_path
+            .parent()
+            .ok_or("Cannot determine current directory")?;
+        let plan = rename::rename_file(&workspace, &self.current_file_path, &dir.join(new_path))?;
+        if let Some((_, to)) = &plan.move_file
+            && !to.starts_with(workspace.root())
+        {
+            return Err(format!(
+                "{} is outside the {}",
+                new_path,
+                if self.vault.is_some() {
+                    "vault"
+                } else {
+                    "document's folder"
+                }
+            ));
+        }
+        self.pending_rename = Some(plan);
+        self.mode = AppMode::ConfirmRename;
+        Ok(())
+    }
+
+    /// Apply the previewed rename and reload the document
+    fn confirm_rename(&mut self) -> Result<(), String> {
+        let Some(plan) = self.pending_rename.take() else {
+            return Ok(());
+        };
+        self.mode = AppMode::Normal;
+
+        // The open note goes through the conflict check; the others are
+        // checked against the plan as they are written
+        let mut others_plan = plan.clone();
+        others_plan
+            .rewrites
+            .retain(|r| r.path != self.current_file_path);
+        let current = plan
+            .rewrites
+            .iter()
+            .find(|r| r.path == self.current_file_path);
+        if let Some(current) = current {
+            let label = match (&plan.move_file, &plan.heading) {
+                (_, Some((_, new_text))) => format!("Renamed heading to {}", new_text),
+                _ => "Renamed note".to_string(),
+            };
+            if !self.write_and_record_edit(&current.original, &current.content, &label)? {
+                return Ok(());
+            }
+        }
+        if let Err(e) = others_plan.apply() {
+            if let Some(current) = current {
+                self.revert_recorded_edit(&current.original)?;
+            }
+            return Err(e);
+        }
+        self.suppress_file_watch = true;
+        let others = plan
+            .rewrites
+            .iter()
+            .filter(|r| r.path != self.current_file_path)
+            .count();
+
+        let renamed = match (&plan.move_file, &plan.heading) {
+            (Some((from, to)), _) => {
+                if let Some(vault) = &mut self.vault {
+                    vault.rescan();
+                }
+                for state in &mut self.file_history {
+                    if state.path == *from {
+                        state.path = to.clone();
+                    }
+                }
+                let document = crate::parser::parse_file(to)
+                    .map_err(|e| format!("Failed to load file: {}", e))?;
+                let filename = to
+                    .file_name()
+                    .map_or_else(|| "file".to_string(), |n| n.to_string_lossy().to_string());
+                let selected = self.selected_heading_text().map(str::to_string);
+                self.load_document(document, filename.clone(), to.clone());
+                if let Some(heading) = selected {
+                    self.select_by_text(&heading);
+                }
+                filename
+            }
+            (None, Some((_, new_text))) => {
+                self.reload_current_file()?;
+                self.reveal_heading(new_text);
+                new_text.clone()
+            }
+            (None, None) => return Ok(()),
+        };
+        self.update_content_metrics();
+        self.set_status_message(&format!(
+            "✓ Renamed to {} ({} line{} changed, {} other note{})",
+            renamed,
+            plan.change_count(),
+            if plan.change_count() == 1 { "" } else { "s" },
+            others,
+            if others == 1 { "" } else { "s" }
+        ));
+        Ok(())
+    }
+
+    /// The selected heading and the file contents its section is edited in.
+    ///
+    /// The document is parsed from the file as loaded, so offsets match what
+    /// the write checks against.
+    fn selected_section(&self) -> Result<(String, Document, usize), String> {
+        let item = self.selected_heading_item().ok_or("No section selected")?;
+        let document = crate::parser::parse_markdown(&self.loaded_file_content()?);
+        let idx = document
+            .headings
+            .iter()
+            .position(|h| h.offset == item.offset && h.text == item.text)
+            .ok_or_else(|| format!("Heading '{}' not found", item.text))?;
+        Ok((item.text.clone(), document, idx))
+    }
+
+    /// Rewrite the selected section with `edit`, save, and select it again
+    fn edit_section(
+        &mut self,
+        label: &str,
+        edit: impl FnOnce(&Document, usize) -> Result<SectionEdit, String>,
+    ) -> Result<(), String> {
+        self.ensure_writable()?;
+        let (_, document, idx) = self.selected_section()?;
+        let edited = edit(&document, idx)?;
+        if !self.write_and_record_edit(&document.content, &edited.content, label)? {
+            return Ok(());
+        }
+        self.reload_after_save()?;
+        self.reveal_heading_at(edited.offset);
+        self.update_content_metrics();
+        self.set_status_message(&format!("✓ {} (U to undo)", label));
+        Ok(())
+    }
+
+    /// Ask before deleting the selected section
+    fn start_section_delete(&mut self) -> Result<(), String> {
+        self.ensure_writable()?;
+        let (text, _, _) = self.selected_section()?;
+        self.pending_section_delete = Some(text);
+        self.mode = AppMode::ConfirmDeleteSection;
+        Ok(())
+    }
+
+    /// Delete the section confirmed in the dialog
+    fn confirm_section_delete(&mut self) -> Result<(), String> {
+        self.mode = AppMode::Normal;
+        let Some(text) = self.pending_section_delete.take() else {
+            return Ok(());
+        };
+        let (_, document, idx) = self.selected_section()?;
+        let content = section::delete_section(&document, idx);
+        if !self.write_and_record_edit(&document.content, &content, "Deleted section")? {
+            return Ok(());
+        }
+        self.reload_after_save()?;
+        self.update_content_metrics();
+        self.set_status_message(&format!("✓ Deleted '{}' (U to undo)", text));
+        Ok(())
+    }
+
+    /// Open the command line to extract the selected section
+    fn start_section_extract(&mut self) {
+        let Some(text) = self
+            .selected_heading_text()
+            .filter(|text| *text != DOCUMENT_OVERVIEW)
+            .map(str::to_string)
+        else {
+            self.set_status_message("✗ No section selected");
+            return;
+        };
+        self.open_command_palette();
+        self.command_query = format!("extract {}.md", crate::parser::content::slugify(&text));
+        self.filter_commands();
+    }
+
+    /// Move the selected section's body to `path` (relative to the current
+    /// file), leaving its heading and a link to the new file
+    fn extract_section_to(&mut self, path: &str) -> Result<(), String> {
+        self.ensure_writable()?;
+        let (text, document, idx) = self.selected_section()?;
+        let mut link = path.replace('\\', "/");
+        if Path::new(path).extension().is_none() {
+            link.push_str(".md");
+        }
+        let target = self
+            .current_file_path
+            .parent()
+            .ok_or("Cannot determine current directory")?
+            .join(&link);
+        if target.exists() {
+            return Err(format!("{} already exists", link));
+        }
+        let folder = target.parent().ok_or("Cannot determine target folder")?;
+        if !folder.is_dir() {
+            return Err(format!("Folder {} doesn't exist", folder.display()));
+        }
+        self.ensure_within_safe_root(folder)?;
+
+        if link.contains(' ') {
+            link = format!("<{}>", link);
+        }
+        let (content, extracted) =
+            section::extract_section(&document, idx, &format!("[{}]({})", text, link))?;
+        // The new file is only created once the section is out of this one
+        if !self.write_and_record_edit(&document.content, &content, "Extracted section")? {
+            return Ok(());
+        }
+        if let Err(e) = crate::edit::write_file_atomic(&target, &extracted) {
+            self.revert_recorded_edit(&document.content)?;
+            return Err(e);
+        }
+        if let Some(mut edit) = self.edit_journal.take_undo(&self.current_file_path) {
+            edit.created = Some((target.clone(), extracted));
+            self.edit_journal.push_undo(edit);
+        }
+        self.reload_after_save()?;
+        self.reveal_heading_at(document.headings[idx].offset);
+        self.update_content_metrics();
+        self.set_status_message(&format!("✓ Extracted '{}' to {}", text, target.display()));
+        Ok(())
+    }
+
+    /// Open or close the link graph around the current note
+    pub fn toggle_graph_view(&mut self) {
+        if self.mode == AppMode::GraphView {
+            self.graph_view = None;
+            self.mode = AppMode::Normal;
+            return;
+        }
+
+        let Some(vault) = self.workspace_vault() else {
+            self.set_status_message("✗ The link graph needs a document saved to disk");
+            return;
+        };
+        let graph = LinkGraph::build(&vault);
+        let Some(center) = graph.index_of(&self.current_file_path) else {
+            self.set_status_message("✗ This document isn't a note in the link graph");
+            return;
+        };
+        self.graph_view = Some(GraphView::new(graph, center, DEFAULT_GRAPH_DEPTH));
+        self.mode = AppMode::GraphView;
+    }
+
+    /// Open the note selected in the link graph
+    fn graph_open(&mut self) -> Result<(), String> {
+        let Some(path) = self.graph_view.as_ref().and_then(GraphView::selected_path) else {
+            return Err("Link target doesn't exist".to_string());
+        };
+        self.graph_view = None;
+        self.mode = AppMode::Normal;
+        if !self.is_current_file(&path) {
+            self.open_note(path, None)?;
+        }
+        Ok(())
+    }
+
+    /// Tasks shown in the task list popup: open ones, plus completed ones
+    /// when toggled on
+    pub fn visible_tasks(&self) -> Vec<crate::parser::tasks::Task> {
+        crate::parser::tasks::extract_tasks(&self.document)
+            .into_iter()
+            .filter(|t| self.task_list_show_done || !t.checked)
+            .collect()
+    }
+
+    /// Selected task list row, clamped to the tasks currently shown
+    pub fn task_list_selection(&self, visible: usize) -> usize {
+        self.task_list_selected.min(visible.saturating_sub(1))
+    }
+
+    fn task_list_next(&mut self) {
+        let count = self.visible_tasks().len();
+        self.task_list_selected =
+            (self.task_list_selection(count) + 1).min(count.saturating_sub(1));
+    }
+
+    fn task_list_previous(&mut self) {
+        let count = self.visible_tasks().len();
+        self.task_list_selected = self.task_list_selection(count).saturating_sub(1);
+    }
+
+    fn toggle_task_list_done(&mut self) {
+        // Keep the selected task (or the next one shown) selected
+        let visible = self.visible_tasks();
+        let line = visible
+            .get(self.task_list_selection(visible.len()))
+            .map_or(0, |t| t.line);
+
+        self.task_list_show_done = !self.task_list_show_done;
+        let visible = self.visible_tasks();
+        self.task_list_selected = visible
+            .iter()
+            .position(|t| t.line >= line)
+            .unwrap_or(visible.len().saturating_sub(1));
+        self.set_status_message(if self.task_list_show_done {
+            "Showing all tasks"
+        } else {
+            "Showing open tasks"
+        });
+    }
+
+    /// Check or uncheck the selected task in the task list and save
+    fn toggle_selected_task(&mut self) -> Result<(), String> {
+        let visible = self.visible_tasks();
+        let task = visible
+            .get(self.task_list_selection(visible.len()))
+            .ok_or_else(|| "No task selected".to_string())?;
+
+        self.toggle_checkbox_and_save(&task.text, task.checked, Some(task.line))
+    }
+
+    /// Close the task list and show the selected task's section
+    fn jump_to_selected_task(&mut self) {
+        let visible = self.visible_tasks();
+        let Some(task) = visible.get(self.task_list_selection(visible.len())) else {
+            return;
+        };
+
+        self.mode = AppMode::Normal;
+        self.goto_source_line(task.line);
+    }
+
+    /// Text of a checkbox list item in the current section
+    fn checkbox_text(&self, block_idx: usize, item_idx: usize) -> Option<String> {
+        let content = if let Some(selected) = self.selected_heading_text() {
+            self.document
+                .extract_section(selected)
+                .unwrap_or_else(|| self.document.content.clone())
+        } else {
+            self.document.content.clone()
+        };
+
+        use crate::parser::content::parse_content;
+        let blocks = parse_content(&content, 0);
+
+        if let Some(crate::parser::output::Block::List { items, .. }) = blocks.get(block_idx) {
+            items.get(item_idx).map(|item| item.content.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Toggle a checkbox and save changes to the file.
+    ///
+    /// The checkbox is found by its text; `line_hint` (1-indexed) picks
+    /// between checkboxes with the same text, preferring the first one at or
+    /// after that line.
+    fn toggle_checkbox_and_save(
+        &mut self,
+        checkbox_text: &str,
+        checked: bool,
+        line_hint: Option<usize>,
+    ) -> Result<(), String> {
+        self.ensure_writable()?;
+
+        // Edit the file as it was loaded (the write checks it hasn't changed since)
+        let file_content = self.loaded_file_content()?;
+
+        // Find and toggle the checkbox in the file content
+        let new_content =
+            self.toggle_checkbox_by_content(&file_content, checkbox_text, checked, line_hint)?;
+
+        let new_state = if checked { "unchecked" } else { "checked" };
+        if !self.write_and_record_edit(
+            &file_content,
+            &new_content,
+            &format!("Checkbox {}", new_state),
+        )? {
+            return Ok(());
+        }
+        self.reload_after_save()?;
+
+        self.status_message = Some(format!("✓ Checkbox {} and saved (u to undo)", new_state));
+
+        Ok(())
+    }
+
+    /// Contents of the current file as of the last load
+    fn loaded_file_content(&self) -> Result<String, String> {
+        match &self.disk_snapshot {
+            Some(snapshot) => Ok(snapshot.content.clone()),
+            None => std::fs::read_to_string(&self.current_file_path)
+                .map_err(|e| format!("Failed to read file: {}", e)),
+        }
+    }
+
+    /// Write new file contents atomically and record the change in the edit journal.
+    ///
+    /// `old_content` must be the contents the edit was computed against. If the
+    /// file on disk no longer matches them, nothing is written: the conflict
+    /// dialog is opened instead and `Ok(false)` is returned.
+    fn write_and_record_edit(
+        &mut self,
+        old_content: &str,
+        new_content: &str,
+        label: &str,
+    ) -> Result<bool, String> {
+        self.ensure_writable()?;
+        let disk_content = std::fs::read_to_string(&self.current_file_path)
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+
+        let unchanged = match &self.disk_snapshot {
+            Some(snapshot) => snapshot.matches(&disk_content),
+            None => disk_content == old_content,
+        };
+
+        if !unchanged {
+            self.pending_conflict = Some(WriteConflict {
+                path: self.current_file_path.clone(),
+                base: old_content.to_string(),
+                ours: new_content.to_string(),
+                theirs: disk_content,
+                label: label.to_string(),
+            });
+            self.mode = AppMode::ConfirmConflict;
+            self.status_message = Some(format!("⚠ {} changed on disk", self.filename));
+            return Ok(false);
+        }
+
+        self.write_edit_unchecked(old_content, new_content, label)?;
+        Ok(true)
+    }
+
+    /// Put back the contents the last recorded edit replaced, when the rest
+    /// of an operation fails after it was written
+    fn revert_recorded_edit(&mut self, old_content: &str) -> Result<(), String> {
+        self.edit_journal.take_undo(&self.current_file_path);
+        crate::edit::write_file_atomic(&self.current_file_path, old_content)?;
+        self.disk_snapshot = Some(DiskSnapshot::new(old_content.to_string()));
+        Ok(())
+    }
+
+    /// Write new file contents without checking for external changes
+    fn write_edit_unchecked(
+        &mut self,
+        old_content: &str,
+        new_content: &str,
+        label: &str,
+    ) -> Result<(), String> {
+        crate::edit::write_file_atomic(&self.current_file_path, new_content)?;
+        self.disk_snapshot = Some(DiskSnapshot::new(new_content.to_string()));
+        if let Some(edit) =
+            FileEdit::from_contents(&self.current_file_path, old_content, new_content, label)
+        {
+            self.edit_journal.record(edit);
+        }
+        Ok(())
+    }
+
+    /// Resolve a write conflict by discarding our edit and reloading from disk
+    pub fn resolve_conflict_reload(&mut self) -> Result<(), String> {
+        let Some(conflict) = self.pending_conflict.take() else {
+            return Ok(());
+        };
+        self.finish_conflict();
+        self.reload_after_save()?;
+        self.status_message = Some(format!("↻ Reloaded, discarded: {}", conflict.label));
+        Ok(())
+    }
+
+    /// Resolve a write conflict by writing our version over the external changes
+    pub fn resolve_conflict_overwrite(&mut self) -> Result<(), String> {
+        self.refresh_conflict();
+        let Some(conflict) = self.pending_conflict.take() else {
+            return Ok(());
+        };
+        self.finish_conflict();
+        self.write_edit_unchecked(&conflict.theirs, &conflict.ours, &conflict.label)?;
+        self.reload_after_save()?;
+        self.status_message = Some(format!("✓ Overwrote external changes: {}", conflict.label));
+        Ok(())
+    }
+
+    /// Resolve a write conflict by applying our changed line to the disk version
+    pub fn resolve_conflict_merge(&mut self) -> Result<(), String> {
+        self.refresh_conflict();
+        // Stay in the dialog on failure so reload/overwrite remain available
+        let merged = match &self.pending_conflict {
+            Some(conflict) => conflict
+                .merge()
+                .map_err(|e| format!("Merge failed: {}", e))?,
+            None => return Ok(()),
+        };
+        let Some(conflict) = self.pending_conflict.take() else {
+            return Ok(());
+        };
+        self.finish_conflict();
+        self.write_edit_unchecked(&conflict.theirs, &merged, &conflict.label)?;
+        self.reload_after_save()?;
+        self.status_message = Some(format!(
+            "✓ Merged with external changes: {}",
+            conflict.label
+        ));
+        Ok(())
+    }
+
+    /// Pick up any further external changes made while the dialog was open
+    fn refresh_conflict(&mut self) {
+        if let Some(conflict) = &mut self.pending_conflict
+            && let Ok(content) = std::fs::read_to_string(&conflict.path)
+        {
+            conflict.theirs = content;
+        }
+    }
+
+    /// Cancel the conflict dialog, dropping our edit without touching the file
+    pub fn cancel_conflict(&mut self) {
+        if let Some(conflict) = self.pending_conflict.take() {
+            self.status_message = Some(format!("Edit cancelled: {}", conflict.label));
+        }
+        self.finish_conflict();
+    }
+
+    /// Return to the mode the conflicting edit was made from
+    fn finish_conflict(&mut self) {
+        self.mode = if self.interactive_state.is_active() {
+            AppMode::Interactive
+        } else {
+            AppMode::Normal
+        };
+    }
+
+    /// Reload the document after an internal save, keeping scroll and element selection
+    fn reload_after_save(&mut self) -> Result<(), String> {
+        // Save scroll position and interactive element index before reload
+        let saved_scroll = self.content_scroll;
+        let saved_element_idx = self.interactive_state.current_index;
+
+        // Reload the document
+        self.reload_current_file()?;
+
+        // Re-index interactive elements
+        self.reindex_interactive_elements();
+
+        // Restore scroll position (clamped to valid range)
         self.content_scroll = saved_scroll.min(self.content_height.saturating_sub(1));
         self.content_scroll_state = self
             .content_scroll_state
@@ -3128,9 +5715,85 @@ impl App {
         // Without this, file watcher detects our save and triggers a second reload
         self.suppress_file_watch = true;
 
-        let new_state = if checked { "unchecked" } else { "checked" };
-        self.status_message = Some(format!("✓ Checkbox {} and saved", new_state));
+        Ok(())
+    }
 
+    /// Undo the most recent checkbox toggle or cell edit in the current file
+    pub fn undo_edit(&mut self) -> Result<(), String> {
+        self.apply_journal_edit(true)
+    }
+
+    /// Redo the most recently undone edit in the current file
+    pub fn redo_edit(&mut self) -> Result<(), String> {
+        self.apply_journal_edit(false)
+    }
+
+    /// Revert (undo) or re-apply (redo) a journaled edit against the file on disk
+    fn apply_journal_edit(&mut self, undo: bool) -> Result<(), String> {
+        self.ensure_writable()?;
+
+        let path = self.current_file_path.clone();
+        let edit = if undo {
+            self.edit_journal.take_undo(&path)
+        } else {
+            self.edit_journal.take_redo(&path)
+        };
+        let Some(edit) = edit else {
+            return Err(if undo {
+                "Nothing to undo".to_string()
+            } else {
+                "Nothing to redo".to_string()
+            });
+        };
+
+        let file_content =
+            std::fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+        let result = if undo {
+            edit.revert(&file_content)
+        } else {
+            edit.reapply(&file_content)
+        };
+
+        let new_content = match result {
+            Ok(content) => content,
+            Err(e) => {
+                // Keep the edit so it can be retried if the external change is reverted
+                if undo {
+                    self.edit_journal.push_undo(edit);
+                } else {
+                    self.edit_journal.push_redo(edit);
+                }
+                return Err(e);
+            }
+        };
+
+        crate::edit::write_file_atomic(&path, &new_content)?;
+        let label = edit.label.clone();
+        let created = edit.created.clone();
+        if undo {
+            self.edit_journal.push_redo(edit);
+        } else {
+            self.edit_journal.push_undo(edit);
+        }
+        if let Some((created, contents)) = created {
+            if undo {
+                // A file edited since it was created is kept
+                if std::fs::read_to_string(&created).is_ok_and(|c| c == contents) {
+                    std::fs::remove_file(&created)
+                        .map_err(|e| format!("Failed to remove {}: {}", created.display(), e))?;
+                }
+            } else if !created.exists() {
+                crate::edit::write_file_atomic(&created, &contents)?;
+            }
+        }
+
+        self.reload_after_save()?;
+
+        self.status_message = Some(if undo {
+            format!("✓ Undid: {}", label)
+        } else {
+            format!("✓ Redid: {}", label)
+        });
         Ok(())
     }
 
@@ -3140,61 +5803,19 @@ impl App {
         file_content: &str,
         checkbox_text: &str,
         current_checked: bool,
+        line_hint: Option<usize>,
     ) -> Result<String, String> {
-        let lines: Vec<&str> = file_content.lines().collect();
-        let mut result = Vec::new();
-        let mut found = false;
+        use crate::parser::tasks::{find_task_by_text, scan_tasks, set_task_checked};
 
-        // Clean the checkbox text to match (remove any checkbox markers if present)
-        let clean_text = checkbox_text
-            .trim_start()
-            .trim_start_matches("[x]")
-            .trim_start_matches("[X]")
-            .trim_start_matches("[ ]")
-            .trim();
+        // Checkboxes inside code blocks are skipped by the scanner
+        let tasks = scan_tasks(file_content);
+        let task = find_task_by_text(&tasks, checkbox_text, Some(current_checked), line_hint)
+            .ok_or_else(|| {
+                let text = checkbox_text.lines().next().unwrap_or_default().trim();
+                format!("Checkbox not found in file: '{}'", text)
+            })?;
 
-        for line in lines {
-            let trimmed = line.trim_start();
-
-            // Check if this is a checkbox line
-            if (trimmed.starts_with("- [ ]")
-                || trimmed.starts_with("- [x]")
-                || trimmed.starts_with("- [X]"))
-                && !found
-            {
-                // Extract the text after the checkbox marker
-                let line_text = trimmed
-                    .trim_start_matches("- [ ]")
-                    .trim_start_matches("- [x]")
-                    .trim_start_matches("- [X]")
-                    .trim();
-
-                // Check if this matches our target checkbox
-                let stripped_line_text = crate::parser::utils::strip_markdown_inline(line_text);
-                if stripped_line_text == clean_text {
-                    // Toggle the checkbox
-                    let new_line = if current_checked {
-                        // Change [x] or [X] to [ ]
-                        line.replacen("[x]", "[ ]", 1).replacen("[X]", "[ ]", 1)
-                    } else {
-                        // Change [ ] to [x]
-                        line.replacen("[ ]", "[x]", 1)
-                    };
-                    result.push(new_line);
-                    found = true;
-                } else {
-                    result.push(line.to_string());
-                }
-            } else {
-                result.push(line.to_string());
-            }
-        }
-
-        if !found {
-            return Err(format!("Checkbox not found in file: '{}'", clean_text));
-        }
-
-        Ok(result.join("\n") + "\n")
+        Ok(set_task_checked(file_content, task, !current_checked))
     }
 
     /// Follow a link from interactive mode
@@ -3209,6 +5830,10 @@ impl App {
                 self.status_message = Some(format!("✓ Jumped to #{}", anchor));
                 Ok(())
             }
+            LinkTarget::Footnote(label) => {
+                self.exit_interactive_mode();
+                self.jump_to_footnote(label)
+            }
             LinkTarget::RelativeFile { path, anchor } => {
                 // Check if the file has a markdown extension
                 let has_md_extension = path
@@ -3251,7 +5876,7 @@ impl App {
 
                         if absolute_path.exists() && !absolute_path.is_symlink() {
                             // File exists - open in editor (non-markdown)
-                            self.pending_editor_file = Some(absolute_path);
+                            self.open_in_external_editor(absolute_path)?;
                             self.exit_interactive_mode();
                         } else {
                             // File doesn't exist - prompt to create markdown file
@@ -3274,12 +5899,18 @@ impl App {
                 // Try to find and load the wikilinked file
                 self.load_wikilink(target)?;
                 // Only exit interactive mode if we're not prompting for file creation
-                if self.mode != AppMode::ConfirmFileCreate {
+                // or a choice between notes
+                if !matches!(self.mode, AppMode::ConfirmFileCreate | AppMode::NotePicker) {
                     self.exit_interactive_mode();
                 }
                 Ok(())
             }
             LinkTarget::External(url) => {
+                if self.access_mode == AccessMode::Safe {
+                    self.refuse_external_url(url);
+                    return Ok(());
+                }
+
                 // Security: Validate URL scheme (only http/https allowed)
                 if !url.starts_with("http://") && !url.starts_with("https://") {
                     return Err(
@@ -3392,10 +6023,158 @@ impl App {
         }
     }
 
+    /// Copy the table as CSV (rows as currently sorted and filtered)
+    fn copy_table_csv(&mut self) -> Result<(), String> {
+        self.copy_table_delimited(Delimiter::Comma)
+    }
+
+    /// Copy the table as TSV (rows as currently sorted and filtered)
+    fn copy_table_tsv(&mut self) -> Result<(), String> {
+        self.copy_table_delimited(Delimiter::Tab)
+    }
+
+    fn copy_table_delimited(&mut self, delimiter: Delimiter) -> Result<(), String> {
+        let (headers, rows) = self
+            .get_current_table_data()
+            .ok_or_else(|| "No table data available".to_string())?;
+        let visible = self
+            .visible_table_rows()
+            .unwrap_or_else(|| (0..rows.len()).collect());
+        let shown: Vec<Vec<String>> = visible.iter().map(|&i| rows[i].clone()).collect();
+
+        self.copy_to_clipboard(&delimited::write_table(&headers, &shown, delimiter))?;
+        self.status_message = Some(if shown.len() < rows.len() {
+            format!(
+                "✓ Table copied as {} ({} of {} rows)",
+                delimiter.name(),
+                shown.len(),
+                rows.len()
+            )
+        } else {
+            format!("✓ Table copied as {}", delimiter.name())
+        });
+        Ok(())
+    }
+
+    /// Import CSV/TSV from a file (relative to the current file) or, without
+    /// a path, from the clipboard.
+    ///
+    /// In table mode the selected table is replaced, keeping its column
+    /// alignments; otherwise a new table is added at the end of the selected
+    /// section's own text.
+    fn import_table(&mut self, path: Option<&str>) -> Result<(), String> {
+        self.ensure_writable()?;
+
+        let (text, delimiter, source) = match path {
+            Some(path) => {
+                let full_path = match self.current_file_path.parent() {
+                    Some(dir) => dir.join(path),
+                    None => PathBuf::from(path),
+                };
+                self.ensure_within_safe_root(&full_path)?;
+                let text = std::fs::read_to_string(&full_path)
+                    .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+                let delimiter =
+                    Delimiter::from_path(&full_path).unwrap_or_else(|| Delimiter::detect(&text));
+                (text, delimiter, path.to_string())
+            }
+            None => {
+                let text = match &mut self.clipboard {
+                    Some(clipboard) => clipboard
+                        .get_text()
+                        .map_err(|e| format!("Clipboard error: {}", e))?,
+                    None => return Err("Clipboard not available".to_string()),
+                };
+                let delimiter = Delimiter::detect(&text);
+                (text, delimiter, "clipboard".to_string())
+            }
+        };
+
+        let records = delimited::parse(&text, delimiter)
+            .into_iter()
+            .map(|record| {
+                record
+                    .iter()
+                    .map(|cell| Self::sanitize_table_cell(cell.trim()))
+                    .collect()
+            })
+            .collect();
+        let table = MarkdownTable::from_records(records)
+            .ok_or_else(|| format!("No {} data in {}", delimiter.name(), source))?;
+        let row_count = table.rows.len();
+
+        let file_content = self.loaded_file_content()?;
+        let replacing = self.interactive_state.is_in_table_mode();
+        let (range, replacement) = if replacing {
+            let range = self.current_table_range(&file_content)?;
+            let table = match MarkdownTable::parse(&file_content[range.clone()]) {
+                Some(existing) => table.with_layout_of(&existing),
+                None => table,
+            };
+            (range, table.to_markdown())
+        } else {
+            let end = self.section_body_end(&file_content);
+            (end..end, format!("\n\n{}", table.to_markdown()))
+        };
+
+        let new_content = format!(
+            "{}{}{}",
+            &file_content[..range.start],
+            replacement,
+            &file_content[range.end..]
+        );
+        let label = format!("Import table from {}", source);
+        if !self.write_and_record_edit(&file_content, &new_content, &label)? {
+            return Ok(());
+        }
+        self.reload_after_save()?;
+
+        if replacing {
+            // The old sort/filter may not apply to the new columns
+            if let Some(view) = self.interactive_state.current_table_view_mut() {
+                *view = Default::default();
+            }
+            self.interactive_state.set_table_position(0, 0);
+        }
+        // `u` only undoes in interactive mode (it pages up in normal mode)
+        let undo_hint = if replacing { ", u to undo" } else { "" };
+        self.status_message = Some(format!(
+            "✓ Imported {} rows from {} ({}{})",
+            row_count,
+            source,
+            delimiter.name(),
+            undo_hint
+        ));
+        Ok(())
+    }
+
+    /// Byte offset just after the selected section's own text (before any
+    /// subheading), or the end of the document without a selection
+    fn section_body_end(&self, content: &str) -> usize {
+        let next_heading = self.selected_heading_text().and_then(|text| {
+            let text = text.to_lowercase();
+            let idx = self
+                .document
+                .headings
+                .iter()
+                .position(|h| h.text.to_lowercase() == text)?;
+            self.document.headings.get(idx + 1).map(|h| h.offset)
+        });
+        let end = next_heading.unwrap_or(content.len()).min(content.len());
+        content[..end].trim_end().len()
+    }
+
     /// Enter cell edit mode for the currently selected table cell
     pub fn enter_cell_edit_mode(&mut self) -> Result<(), String> {
+        self.ensure_writable()?;
         if let Some((headers, rows)) = self.get_current_table_data() {
-            if let Some((row, col)) = self.interactive_state.get_table_position() {
+            // Edit the row as it is in the file, whatever the sort/filter
+            let row = self.interactive_state.table_source_row(&rows);
+            let col = self
+                .interactive_state
+                .get_table_position()
+                .map(|(_, col)| col);
+            if let (Some(row), Some(col)) = (row, col) {
                 // Get current cell value
                 let cell_value = if row == 0 {
                     // Header row
@@ -3427,15 +6206,13 @@ impl App {
 
     /// Save the edited cell value back to the file
     pub fn save_edited_cell(&mut self) -> Result<(), String> {
-        use std::fs;
-        use std::io::Write;
+        self.ensure_writable()?;
 
         // Sanitize the cell value to prevent table structure corruption
         let sanitized_value = Self::sanitize_table_cell(&self.cell_edit_value);
 
-        // Read the current file
-        let file_content = fs::read_to_string(&self.current_file_path)
-            .map_err(|e| format!("Failed to read file: {}", e))?;
+        // Edit the file as it was loaded (the write checks it hasn't changed since)
+        let file_content = self.loaded_file_content()?;
 
         // Find and replace the table cell in the markdown
         let new_content = self.replace_table_cell_in_markdown(
@@ -3445,34 +6222,20 @@ impl App {
             &sanitized_value,
         )?;
 
-        // Atomic write: write to temp file, then rename (prevents data corruption)
-        let parent_dir = self
-            .current_file_path
-            .parent()
-            .ok_or("Cannot determine parent directory")?;
-
-        let mut temp_file = tempfile::NamedTempFile::new_in(parent_dir)
-            .map_err(|e| format!("Failed to create temp file: {}", e))?;
-
-        temp_file
-            .write_all(new_content.as_bytes())
-            .map_err(|e| format!("Failed to write temp file: {}", e))?;
-
-        temp_file
-            .flush()
-            .map_err(|e| format!("Failed to flush temp file: {}", e))?;
-
-        // Atomic rename
-        temp_file
-            .persist(&self.current_file_path)
-            .map_err(|e| format!("Failed to save file: {}", e))?;
+        if !self.write_and_record_edit(
+            &file_content,
+            &new_content,
+            &format!("Edit cell ({}, {})", self.cell_edit_row, self.cell_edit_col),
+        )? {
+            return Ok(());
+        }
 
         // Reload the document
         let updated_document = crate::parser::parse_file(&self.current_file_path)
             .map_err(|e| format!("Failed to reload document: {}", e))?;
 
         self.document = updated_document;
-        self.status_message = Some("✓ Cell updated".to_string());
+        self.status_message = Some("✓ Cell updated (u to undo)".to_string());
         Ok(())
     }
 
@@ -3484,34 +6247,19 @@ impl App {
         col: usize,
         new_value: &str,
     ) -> Result<String, String> {
-        use crate::parser::content::parse_content;
-
-        // Get the current section content to find the right table
-        let section_content = if let Some(heading_text) = self.selected_heading_text() {
-            self.document
-                .extract_section(heading_text)
-                .unwrap_or_else(|| self.document.content.clone())
-        } else {
-            self.document.content.clone()
-        };
-
-        // Parse to find the table block
-        let blocks = parse_content(&section_content, 0);
-
-        // Find the block index of the current table element
-        if let Some(element) = self.interactive_state.current_element() {
-            let block_idx = element.id.block_idx;
-
-            if let Some(crate::parser::output::Block::Table { .. }) = blocks.get(block_idx) {
-                // Find this table in the full file content
-                return self.replace_table_cell_in_file(content, row, col, new_value);
-            }
-        }
-
-        Err("Could not locate table in file".to_string())
-    }
-
-    /// Find and replace a cell in the first table found in the current section
+        // Only look at the selected table, not the first one in the file
+        let range = self.current_table_range(content)?;
+        let table =
+            self.replace_table_cell_in_file(&content[range.clone()], row, col, new_value)?;
+        Ok(format!(
+            "{}{}{}",
+            &content[..range.start],
+            table,
+            &content[range.end..]
+        ))
+    }
+
+    /// Find and replace a cell in the first table found in `content`
     fn replace_table_cell_in_file(
         &self,
         content: &str,
@@ -3562,7 +6310,12 @@ impl App {
         }
 
         if found_table {
-            Ok(result.join("\n"))
+            let mut new_content = result.join("\n");
+            // Keep the trailing newline so the write only touches the edited row
+            if content.ends_with('\n') {
+                new_content.push('\n');
+            }
+            Ok(new_content)
         } else {
             Err("Table not found in file".to_string())
         }
@@ -3592,3 +6345,268 @@ impl App {
         new_parts.join("|")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_markdown;
+
+    /// An app viewing `content`, saved as `doc.md` in a new directory
+    fn app_with(content: &str) -> (tempfile::TempDir, App) {
+        app_with_config(content, Config::default())
+    }
+
+    fn app_with_config(content: &str, config: Config) -> (tempfile::TempDir, App) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().canonicalize().unwrap().join("doc.md");
+        std::fs::write(&path, content).unwrap();
+        let app = App::new(
+            parse_markdown(content),
+            "doc.md".to_string(),
+            path,
+            config,
+            ColorMode::Rgb,
+        );
+        (dir, app)
+    }
+
+    /// Select the first interactive element of `kind`
+    fn select_element(app: &mut App, kind: fn(&ElementType) -> bool) {
+        app.enter_interactive_mode();
+        let idx = app
+            .interactive_state
+            .elements
+            .iter()
+            .position(|element| kind(&element.element_type))
+            .unwrap();
+        while app.interactive_state.current_index != Some(idx) {
+            app.interactive_state.next();
+        }
+    }
+
+    #[test]
+    fn test_cli_flags_only_tighten_access() {
+        let config = |read_only, safe| {
+            let mut config = Config::default();
+            config.security.read_only = read_only;
+            config.security.safe = safe;
+            config
+        };
+        for ((read_only, safe), flags, expected) in [
+            ((false, false), AccessMode::Normal, AccessMode::Normal),
+            ((false, false), AccessMode::ReadOnly, AccessMode::ReadOnly),
+            ((true, false), AccessMode::Normal, AccessMode::ReadOnly),
+            ((true, false), AccessMode::Safe, AccessMode::Safe),
+            ((false, true), AccessMode::ReadOnly, AccessMode::Safe),
+            ((true, true), AccessMode::Normal, AccessMode::Safe),
+        ] {
+            let (_dir, mut app) = app_with_config("# T\n", config(read_only, safe));
+            app.restrict_access(flags);
+            assert_eq!(
+                app.access_mode,
+                expected,
+                "{:?}",
+                ((read_only, safe), flags)
+            );
+            assert_eq!(app.safe_root.is_some(), expected == AccessMode::Safe);
+        }
+        assert_eq!(AccessMode::from_flags(true, true), AccessMode::Safe);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_safe_root_containment() {
+        let (dir, mut app) = app_with("# T\n");
+        let root = dir.path().canonicalize().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("secret.md"), "# Secret\n").unwrap();
+        std::fs::create_dir(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub/note.md"), "# Note\n").unwrap();
+        std::os::unix::fs::symlink(outside.path().join("secret.md"), root.join("link.md")).unwrap();
+        std::os::unix::fs::symlink(outside.path(), root.join("elsewhere")).unwrap();
+
+        // Nothing is confined outside safe mode
+        app.restrict_access(AccessMode::ReadOnly);
+        assert!(app.ensure_within_safe_root(&root.join("link.md")).is_ok());
+
+        app.restrict_access(AccessMode::Safe);
+        assert_eq!(app.safe_root.as_deref(), Some(root.as_path()));
+        for inside in ["doc.md", "sub/note.md", "sub/../doc.md", "./sub/note.md"] {
+            assert!(
+                app.ensure_within_safe_root(&root.join(inside)).is_ok(),
+                "{}",
+                inside
+            );
+        }
+        let escape = format!(
+            "../{}/secret.md",
+            outside.path().file_name().unwrap().to_str().unwrap()
+        );
+        for outside in [&escape, "link.md", "elsewhere/secret.md", "sub/../../x.md"] {
+            assert!(
+                app.ensure_within_safe_root(&root.join(outside)).is_err(),
+                "{}",
+                outside
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_only_refuses_every_write() {
+        let content = "# T\n\n- [ ] task\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\n```sh\necho hi\n```\n\n## S\n\ntext\n\n## U\n";
+        let (dir, mut app) = app_with(content);
+
+        // A run confirmed after switching to read-only is refused too
+        select_element(&mut app, |kind| {
+            matches!(kind, ElementType::CodeBlock { .. })
+        });
+        app.run_selected_code_block().unwrap();
+        assert!(app.pending_code_run.is_some());
+        app.restrict_access(AccessMode::ReadOnly);
+        assert!(
+            app.confirm_code_run()
+                .is_err_and(|e| e.starts_with("Read-only mode"))
+        );
+        assert!(
+            app.run_selected_code_block()
+                .is_err_and(|e| e.starts_with("Read-only mode"))
+        );
+
+        let refused =
+            |result: Result<(), String>| result.is_err_and(|e| e.starts_with("Read-only mode"));
+
+        assert!(refused(
+            app.write_and_record_edit(content, "changed", "Edit")
+                .map(|_| ())
+        ));
+        assert!(refused(app.toggle_checkbox_and_save("task", false, None)));
+        assert!(refused(
+            app.edit_section("Moved section down", |doc, idx| {
+                section::move_section(doc, idx, false)
+            })
+        ));
+        assert!(refused(app.plan_heading_rename("Renamed")));
+        assert!(refused(app.plan_file_rename("renamed.md")));
+        assert!(refused(app.confirm_file_create()));
+
+        select_element(&mut app, |kind| matches!(kind, ElementType::Table { .. }));
+        assert!(refused(app.enter_cell_edit_mode()));
+        assert!(refused(app.save_edited_cell()));
+        assert!(refused(
+            app.edit_table_structure(TableOp::InsertRow { below: true })
+        ));
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("doc.md")).unwrap(),
+            content
+        );
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_table_range_matches_contents() {
+        // A same-shaped table in an indented code block isn't the selected one
+        let content = "# T\n\n    | a | b |\n    |---|---|\n    | 1 | 2 |\n\n| a | b |\n|---|---|\n| 3 | 4 |\n";
+        let (_dir, mut app) = app_with(content);
+        app.enter_interactive_mode();
+        while !app
+            .interactive_state
+            .current_element()
+            .is_some_and(|element| matches!(element.element_type, ElementType::Table { .. }))
+        {
+            app.interactive_state.next();
+        }
+        let range = app.current_table_range(content).unwrap();
+        assert_eq!(&content[range], "| a | b |\n|---|---|\n| 3 | 4 |");
+    }
+
+    #[test]
+    fn test_confirm_rename_checks_for_changes() {
+        let content = "# Doc\n\n## Setup\n\nSee [setup](#setup).\n";
+        let (dir, mut app) = app_with(content);
+        let doc = dir.path().join("doc.md");
+        let other = dir.path().join("other.md");
+        std::fs::write(&other, "[Setup](doc.md#setup)\n").unwrap();
+        let read = |path: &std::path::Path| std::fs::read_to_string(path).unwrap();
+
+        // The open note changed after the preview: the conflict dialog opens
+        assert!(app.select_by_text("Setup"));
+        app.plan_heading_rename("Install").unwrap();
+        let edited = format!("{}More.\n", content);
+        std::fs::write(&doc, &edited).unwrap();
+        app.confirm_rename().unwrap();
+        assert_eq!(app.mode, AppMode::ConfirmConflict);
+        assert_eq!(read(&doc), edited);
+        assert_eq!(read(&other), "[Setup](doc.md#setup)\n");
+
+        // Another note changed: nothing is renamed
+        app.resolve_conflict_reload().unwrap();
+        assert!(app.select_by_text("Setup"));
+        app.plan_heading_rename("Install").unwrap();
+        std::fs::write(&other, "[Setup](doc.md#setup) and more\n").unwrap();
+        assert_eq!(
+            app.confirm_rename().unwrap_err(),
+            "other.md changed since the rename was planned"
+        );
+        assert_eq!(read(&doc), edited);
+        assert_eq!(read(&other), "[Setup](doc.md#setup) and more\n");
+    }
+
+    #[test]
+    fn test_extract_section_creates_file_last() {
+        let content = "# Doc\n\n## Notes\n\nSome notes.\n";
+        let (dir, mut app) = app_with(content);
+        let doc = dir.path().join("doc.md");
+        let notes = dir.path().join("notes.md");
+
+        // A conflict on the open note leaves no new file behind
+        std::fs::write(&doc, format!("{}More.\n", content)).unwrap();
+        assert!(app.select_by_text("Notes"));
+        app.extract_section_to("notes").unwrap();
+        assert_eq!(app.mode, AppMode::ConfirmConflict);
+        assert!(!notes.exists());
+
+        app.resolve_conflict_reload().unwrap();
+        assert!(app.select_by_text("Notes"));
+        app.extract_section_to("notes").unwrap();
+        let extracted = std::fs::read_to_string(&notes).unwrap();
+        assert!(extracted.starts_with("# Notes\n\nSome notes."));
+
+        // Undo removes the new file, redo writes it again
+        app.undo_edit().unwrap();
+        assert!(!notes.exists());
+        assert!(
+            std::fs::read_to_string(&doc)
+                .unwrap()
+                .contains("Some notes.")
+        );
+        app.redo_edit().unwrap();
+        assert_eq!(std::fs::read_to_string(&notes).unwrap(), extracted);
+    }
+
+    #[test]
+    fn test_section_edits_use_the_selected_repeated_heading() {
+        let content =
+            "# A\n\n## Notes\n\nfirst\n\n# B\n\n## Intro\n\nintro\n\n## Notes\n\nsecond\n";
+        let (dir, mut app) = app_with(content);
+        let second = app
+            .outline_items
+            .iter()
+            .rposition(|item| item.text == "Notes")
+            .unwrap();
+        app.select_outline_index(second);
+
+        app.edit_section("Moved section up", |doc, idx| {
+            section::move_section(doc, idx, true)
+        })
+        .unwrap();
+        let moved = "# A\n\n## Notes\n\nfirst\n\n# B\n\n## Notes\n\nsecond\n\n## Intro\n\nintro\n";
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("doc.md")).unwrap(),
+            moved
+        );
+        // The moved section stays selected, not the first "Notes"
+        let selected = app.selected_heading_item().unwrap();
+        assert_eq!(selected.offset, moved.find("## Notes\n\nsecond").unwrap());
+    }
+}
@@ -0,0 +1,284 @@
+//! Running code blocks from interactive mode.
+//!
+//! A block is written to a temporary script and run with the interpreter
+//! configured for its language. Output is read on background threads and
+//! collected by polling from the event loop, so the UI never blocks.
+
+use crate::tui::interactive::ElementId;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use tempfile::NamedTempFile;
+
+/// Output lines kept per block; older lines are dropped
+const MAX_OUTPUT_LINES: usize = 500;
+
+/// A code block waiting for the user to confirm running it
+#[derive(Debug, Clone)]
+pub struct CodeRunRequest {
+    pub id: ElementId,
+    pub language: String,
+    /// Interpreter and its arguments (the script path is appended)
+    pub command: Vec<String>,
+    pub code: String,
+    /// Working directory for the run
+    pub dir: PathBuf,
+}
+
+/// One line of output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputLine {
+    pub text: String,
+    pub stderr: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunStatus {
+    Running,
+    Exited(i32),
+    /// Stopped by the user or terminated by a signal
+    Killed,
+}
+
+/// Output panel shown beneath a code block
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeOutput {
+    pub command: String,
+    pub lines: Vec<OutputLine>,
+    /// Lines dropped from the start once the panel was full
+    pub dropped: usize,
+    pub status: RunStatus,
+    pub collapsed: bool,
+}
+
+impl CodeOutput {
+    pub fn new(command: String) -> Self {
+        Self {
+            command,
+            lines: Vec::new(),
+            dropped: 0,
+            status: RunStatus::Running,
+            collapsed: false,
+        }
+    }
+
+    fn push(&mut self, line: OutputLine) {
+        if self.lines.len() == MAX_OUTPUT_LINES {
+            self.lines.remove(0);
+            self.dropped += 1;
+        }
+        self.lines.push(line);
+    }
+
+    /// Rendered height in lines: a header, then the output unless collapsed
+    pub fn height(&self) -> usize {
+        if self.collapsed {
+            1
+        } else {
+            1 + usize::from(self.dropped > 0) + self.lines.len()
+        }
+    }
+
+    /// Header text, e.g. `▾ $ bash · exit 0 · 3 lines`
+    pub fn header(&self) -> String {
+        let arrow = if self.collapsed { "▸" } else { "▾" };
+        let status = match self.status {
+            RunStatus::Running => "running…".to_string(),
+            RunStatus::Exited(code) => format!("exit {}", code),
+            RunStatus::Killed => "killed".to_string(),
+        };
+        let count = self.lines.len() + self.dropped;
+        let lines = if count == 1 { "line" } else { "lines" };
+        format!(
+            "{} $ {} · {} · {} {}",
+            arrow, self.command, status, count, lines
+        )
+    }
+}
+
+/// A running code block
+pub struct CodeRun {
+    child: Child,
+    receiver: Receiver<OutputLine>,
+    exited: bool,
+    disconnected: bool,
+    // Keeps the script on disk until the run is dropped
+    _script: NamedTempFile,
+}
+
+impl CodeRun {
+    /// Write `code` to a temporary script and start the interpreter on it
+    pub fn start(command: &[String], code: &str, dir: &Path) -> Result<Self, String> {
+        let (program, args) = command
+            .split_first()
+            .ok_or_else(|| "Empty interpreter command".to_string())?;
+
+        let mut script = tempfile::Builder::new()
+            .prefix("treemd-run-")
+            .tempfile()
+            .map_err(|e| format!("Failed to create script: {}", e))?;
+        script
+            .write_all(code.as_bytes())
+            .and_then(|_| script.flush())
+            .map_err(|e| format!("Failed to write script: {}", e))?;
+
+        let mut child = Command::new(program)
+            .args(args)
+            .arg(script.path())
+            .current_dir(dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+        let (tx, rx) = mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            forward_lines(stdout, false, tx.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(stderr, true, tx);
+        }
+
+        Ok(Self {
+            child,
+            receiver: rx,
+            exited: false,
+            disconnected: false,
+            _script: script,
+        })
+    }
+
+    /// Move new output and the exit status into `output`.
+    ///
+    /// Returns whether anything changed.
+    pub fn poll(&mut self, output: &mut CodeOutput) -> bool {
+        let mut changed = false;
+
+        loop {
+            match self.receiver.try_recv() {
+                Ok(line) => {
+                    output.push(line);
+                    changed = true;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.disconnected = true;
+                    break;
+                }
+            }
+        }
+
+        if !self.exited
+            && let Ok(Some(status)) = self.child.try_wait()
+        {
+            self.exited = true;
+            // A stopped run has already been marked as killed
+            if output.status == RunStatus::Running {
+                output.status = status.code().map_or(RunStatus::Killed, RunStatus::Exited);
+            }
+            changed = true;
+        }
+
+        changed
+    }
+
+    /// Whether the process has exited and all its output has been collected
+    pub fn is_finished(&self) -> bool {
+        self.exited && self.disconnected
+    }
+
+    /// Kill the process
+    pub fn stop(&mut self, output: &mut CodeOutput) {
+        if !self.exited {
+            let _ = self.child.kill();
+            output.status = RunStatus::Killed;
+        }
+    }
+}
+
+impl Drop for CodeRun {
+    fn drop(&mut self) {
+        if !self.exited {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+/// Send each line read from `stream` until it closes
+fn forward_lines(stream: impl Read + Send + 'static, stderr: bool, tx: Sender<OutputLine>) {
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        let mut buf = Vec::new();
+        while let Ok(n) = reader.read_until(b'\n', &mut buf) {
+            if n == 0 {
+                break;
+            }
+            let text = String::from_utf8_lossy(&buf)
+                .trim_end_matches(['\n', '\r'])
+                .replace('\t', "    ");
+            if tx.send(OutputLine { text, stderr }).is_err() {
+                break;
+            }
+            buf.clear();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_output_panel_height_and_limit() {
+        let mut output = CodeOutput::new("bash".to_string());
+        for i in 0..MAX_OUTPUT_LINES + 2 {
+            output.push(OutputLine {
+                text: i.to_string(),
+                stderr: false,
+            });
+        }
+
+        assert_eq!(output.dropped, 2);
+        assert_eq!(output.lines[0].text, "2");
+        assert_eq!(output.height(), MAX_OUTPUT_LINES + 2);
+        output.collapsed = true;
+        assert_eq!(output.height(), 1);
+        assert_eq!(output.header(), "▸ $ bash · running… · 502 lines");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_collects_output_and_status() {
+        let command = vec!["sh".to_string()];
+        let mut run =
+            CodeRun::start(&command, "echo out\necho err >&2\nexit 3\n", Path::new(".")).unwrap();
+        let mut output = CodeOutput::new("sh".to_string());
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !run.is_finished() && Instant::now() < deadline {
+            run.poll(&mut output);
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(output.status, RunStatus::Exited(3));
+        let mut lines = output.lines.clone();
+        lines.sort_by_key(|l| l.stderr);
+        assert_eq!(
+            lines,
+            vec![
+                OutputLine {
+                    text: "out".to_string(),
+                    stderr: false
+                },
+                OutputLine {
+                    text: "err".to_string(),
+                    stderr: true
+                },
+            ]
+        );
+    }
+}
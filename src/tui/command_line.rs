@@ -0,0 +1,674 @@
+//! Ex-style command line for the command palette
+//!
+//! The palette accepts vim-like commands with arguments (`theme nord`,
+//! `goto 120`, `set outline_width=35`) as well as any keybinding [`Action`]
+//! by name. Input that doesn't start with a known command falls back to the
+//! fuzzy-selected palette entry.
+
+use crate::keybindings::Action;
+use crate::tui::app::{CommandAction, PALETTE_COMMANDS, PaletteCommand};
+use crate::tui::theme::ThemeName;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use strum::IntoEnumIterator;
+
+/// Maximum number of entries kept in the history ring
+const HISTORY_LIMIT: usize = 100;
+
+/// Options accepted by `:set`
+pub const SET_OPTIONS: &[&str] = &["outline_width", "outline", "raw", "theme"];
+
+/// A parsed command line
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExCommand {
+    /// Palette command without arguments (e.g. `w`, `q`)
+    Palette(CommandAction),
+    /// Any keybinding action by name (e.g. `ToggleOutline`)
+    Action(Action),
+    /// `theme <name>`
+    Theme(ThemeName),
+    /// `goto <line>` (1-indexed source line)
+    Goto(usize),
+    /// `heading <text>`
+    Heading(String),
+    /// `level <1-6>`
+    Level(usize),
+    /// `open <path>[#anchor]`
+    Open(String),
+    /// `query <expr>`
+    Query(String),
+    /// `set <key>[=<value>]`
+    Set { key: String, value: Option<String> },
+    /// `import [path]` (clipboard when no path is given)
+    Import(Option<String>),
+    /// `rename <new heading>` (the selected heading)
+    Rename(String),
+    /// `move <path>` (the current note, relative to its folder)
+    Move(String),
+    /// `extract <path>` (the selected section, relative to the current folder)
+    Extract(String),
+}
+
+/// Parse a command line.
+///
+/// Returns `None` when the first word is neither a palette command nor an
+/// action name, so the caller can fall back to the selected palette entry.
+pub fn parse(line: &str) -> Option<Result<ExCommand, String>> {
+    let line = line.trim().trim_start_matches(':').trim();
+    if line.is_empty() {
+        return None;
+    }
+
+    // `:120` is shorthand for `:goto 120`
+    if line.chars().all(|c| c.is_ascii_digit()) {
+        return Some(parse_argument(CommandAction::Goto, line, line));
+    }
+
+    let (head, arg) = split_command(line);
+
+    if let Some(command) = lookup_command(head) {
+        return Some(parse_argument(command.action, head, arg));
+    }
+
+    let action = lookup_action(head)?;
+    if arg.is_empty() {
+        Some(Ok(ExCommand::Action(action)))
+    } else {
+        Some(Err(format!("{} takes no arguments", action)))
+    }
+}
+
+/// Parse a boolean option value (`on`/`off`, `true`/`false`, `yes`/`no`, `1`/`0`)
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "on" | "true" | "yes" | "1" => Some(true),
+        "off" | "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Split a command line into its command word and the (trimmed) remainder
+fn split_command(line: &str) -> (&str, &str) {
+    match line.find(char::is_whitespace) {
+        Some(idx) => (&line[..idx], line[idx..].trim()),
+        None => (line, ""),
+    }
+}
+
+/// Find a palette command by one of its aliases (case-insensitive)
+fn lookup_command(name: &str) -> Option<&'static PaletteCommand> {
+    PALETTE_COMMANDS.iter().find(|cmd| {
+        cmd.aliases
+            .iter()
+            .any(|alias| alias.eq_ignore_ascii_case(name))
+    })
+}
+
+/// Find a keybinding action by its name (case-insensitive)
+fn lookup_action(name: &str) -> Option<Action> {
+    Action::iter().find(|action| action.to_string().eq_ignore_ascii_case(name))
+}
+
+fn parse_argument(action: CommandAction, head: &str, arg: &str) -> Result<ExCommand, String> {
+    let usage = || match action.usage() {
+        Some(usage) => format!("Usage: {}", usage),
+        None => format!("'{}' takes no arguments", head),
+    };
+
+    match action {
+        CommandAction::Theme => ThemeName::parse(arg).map(ExCommand::Theme).ok_or_else(|| {
+            if arg.is_empty() {
+                usage()
+            } else {
+                format!("Unknown theme: {}", arg)
+            }
+        }),
+        CommandAction::Goto => arg
+            .parse::<usize>()
+            .ok()
+            .filter(|&line| line > 0)
+            .map(ExCommand::Goto)
+            .ok_or_else(usage),
+        CommandAction::Level => arg
+            .parse::<usize>()
+            .ok()
+            .filter(|level| (1..=6).contains(level))
+            .map(ExCommand::Level)
+            .ok_or_else(usage),
+        CommandAction::Heading
+        | CommandAction::Open
+        | CommandAction::Query
+        | CommandAction::Rename
+        | CommandAction::Move
+        | CommandAction::Extract
+            if arg.is_empty() =>
+        {
+            Err(usage())
+        }
+        CommandAction::Heading => Ok(ExCommand::Heading(arg.to_string())),
+        CommandAction::Open => Ok(ExCommand::Open(arg.to_string())),
+        CommandAction::Query => Ok(ExCommand::Query(arg.to_string())),
+        CommandAction::Rename => Ok(ExCommand::Rename(arg.to_string())),
+        CommandAction::Move => Ok(ExCommand::Move(arg.to_string())),
+        CommandAction::Extract => Ok(ExCommand::Extract(arg.to_string())),
+        CommandAction::Set => {
+            let (key, value) = match arg.split_once('=') {
+                Some((key, value)) => (key.trim(), Some(value.trim().to_string())),
+                None => (arg, None),
+            };
+            if SET_OPTIONS.contains(&key) {
+                Ok(ExCommand::Set {
+                    key: key.to_string(),
+                    value,
+                })
+            } else if key.is_empty() {
+                Err(usage())
+            } else {
+                Err(format!(
+                    "Unknown option '{}' (available: {})",
+                    key,
+                    SET_OPTIONS.join(", ")
+                ))
+            }
+        }
+        CommandAction::Import => Ok(ExCommand::Import(
+            Some(arg.to_string()).filter(|path| !path.is_empty()),
+        )),
+        _ if arg.is_empty() => Ok(ExCommand::Palette(action)),
+        _ => Err(usage()),
+    }
+}
+
+/// Compute completions for a command line.
+///
+/// Each candidate is a complete replacement for the input line. Command
+/// names are completed when there's no argument yet; afterwards the
+/// argument is completed based on the command (themes, headings, files, ...).
+pub fn complete(line: &str, headings: &[&str], base_dir: Option<&Path>) -> Vec<String> {
+    let input = line.trim_start().trim_start_matches(':');
+
+    let Some(idx) = input.find(char::is_whitespace) else {
+        return complete_command_name(input);
+    };
+
+    let head = &input[..idx];
+    let arg = input[idx..].trim_start();
+    let Some(command) = lookup_command(head) else {
+        return Vec::new();
+    };
+
+    let arg_lower = arg.to_lowercase();
+    let candidates: Vec<String> = match command.action {
+        CommandAction::Theme => ThemeName::ALL
+            .iter()
+            .map(|theme| theme.slug().to_string())
+            .filter(|slug| slug.starts_with(&arg_lower))
+            .collect(),
+        CommandAction::Level => (1..=6)
+            .map(|level| level.to_string())
+            .filter(|level| level.starts_with(arg))
+            .collect(),
+        CommandAction::Heading => complete_heading(arg, headings),
+        CommandAction::Open | CommandAction::Move | CommandAction::Extract => base_dir
+            .map(|dir| complete_path(arg, dir, MARKDOWN_EXTENSIONS))
+            .unwrap_or_default(),
+        CommandAction::Import => base_dir
+            .map(|dir| complete_path(arg, dir, DELIMITED_EXTENSIONS))
+            .unwrap_or_default(),
+        CommandAction::Set => complete_set_option(arg),
+        _ => Vec::new(),
+    };
+
+    candidates
+        .into_iter()
+        .map(|candidate| format!("{} {}", head, candidate))
+        .collect()
+}
+
+/// Complete a command name from palette aliases and action names
+fn complete_command_name(prefix: &str) -> Vec<String> {
+    let prefix_lower = prefix.to_lowercase();
+
+    let mut candidates: Vec<String> = PALETTE_COMMANDS
+        .iter()
+        .flat_map(|cmd| {
+            cmd.aliases.iter().map(move |alias| {
+                // Commands that need an argument get a trailing space
+                if cmd.action.usage().is_some() {
+                    format!("{} ", alias)
+                } else {
+                    alias.to_string()
+                }
+            })
+        })
+        .chain(Action::iter().map(|action| action.to_string()))
+        .filter(|name| name.to_lowercase().starts_with(&prefix_lower))
+        .collect();
+
+    // Keep first occurrence of each name (e.g. `quit` alias vs `Quit` action)
+    let mut seen = std::collections::HashSet::new();
+    candidates.retain(|name| seen.insert(name.trim_end().to_lowercase()));
+    candidates
+}
+
+/// Complete a heading name (prefix matches first, then substring matches)
+fn complete_heading(arg: &str, headings: &[&str]) -> Vec<String> {
+    let arg_lower = arg.to_lowercase();
+    let mut prefix_matches = Vec::new();
+    let mut other_matches = Vec::new();
+
+    for heading in headings {
+        let heading_lower = heading.to_lowercase();
+        let candidate = heading.to_string();
+        if prefix_matches.contains(&candidate) || other_matches.contains(&candidate) {
+            continue;
+        }
+        if heading_lower.starts_with(&arg_lower) {
+            prefix_matches.push(candidate);
+        } else if heading_lower.contains(&arg_lower) {
+            other_matches.push(candidate);
+        }
+    }
+
+    prefix_matches.extend(other_matches);
+    prefix_matches
+}
+
+/// File extensions completed by `open`
+const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown"];
+
+/// File extensions completed by `import`
+const DELIMITED_EXTENSIONS: &[&str] = &["csv", "tsv", "tab"];
+
+/// Complete a relative path to a directory or a file with one of `extensions`
+/// under `base_dir`.
+///
+/// Absolute paths and `..` components are never completed, matching the
+/// restrictions enforced when the file is opened.
+fn complete_path(arg: &str, base_dir: &Path, extensions: &[&str]) -> Vec<String> {
+    let arg_path = Path::new(arg);
+    if arg_path.is_absolute()
+        || arg_path
+            .components()
+            .any(|c| matches!(c, Component::ParentDir))
+    {
+        return Vec::new();
+    }
+
+    let (dir_part, file_prefix) = match arg.rfind('/') {
+        Some(idx) => (&arg[..=idx], &arg[idx + 1..]),
+        None => ("", arg),
+    };
+
+    let Ok(entries) = fs::read_dir(base_dir.join(dir_part)) else {
+        return Vec::new();
+    };
+
+    let mut candidates: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            if !name.starts_with(file_prefix) {
+                return None;
+            }
+            // Hidden entries only when explicitly requested
+            if name.starts_with('.') && !file_prefix.starts_with('.') {
+                return None;
+            }
+
+            let path = entry.path();
+            if path.is_dir() {
+                Some(format!("{}{}/", dir_part, name))
+            } else if path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|ext| extensions.contains(&ext))
+            {
+                Some(format!("{}{}", dir_part, name))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    candidates.sort();
+    candidates
+}
+
+/// Complete `set` option names and their values
+fn complete_set_option(arg: &str) -> Vec<String> {
+    let Some((key, value)) = arg.split_once('=') else {
+        return SET_OPTIONS
+            .iter()
+            .filter(|option| option.starts_with(arg))
+            .map(|option| format!("{}=", option))
+            .collect();
+    };
+
+    let values: Vec<&str> = match key {
+        "theme" => ThemeName::ALL.iter().map(|theme| theme.slug()).collect(),
+        "outline" | "raw" => vec!["on", "off"],
+        "outline_width" => vec!["20", "30", "40"],
+        _ => Vec::new(),
+    };
+
+    values
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(value))
+        .map(|candidate| format!("{}={}", key, candidate))
+        .collect()
+}
+
+/// Longest common prefix shared by all candidates
+pub fn common_prefix(candidates: &[String]) -> String {
+    let Some(first) = candidates.first() else {
+        return String::new();
+    };
+
+    let mut prefix_len = first.len();
+    for candidate in &candidates[1..] {
+        prefix_len = first
+            .char_indices()
+            .zip(candidate.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map(|((idx, c), _)| idx + c.len_utf8())
+            .unwrap_or(0)
+            .min(prefix_len);
+    }
+
+    first[..prefix_len].to_string()
+}
+
+/// History of executed command lines, persisted between sessions
+#[derive(Debug, Default)]
+pub struct CommandHistory {
+    entries: VecDeque<String>,
+    /// Position while browsing with Ctrl-p/Ctrl-n (None = editing a new line)
+    cursor: Option<usize>,
+    /// The line being edited before browsing started
+    draft: String,
+    path: Option<PathBuf>,
+}
+
+impl CommandHistory {
+    /// Load history from a file (missing or unreadable files give an empty history)
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let mut entries: VecDeque<String> = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .map(|contents| {
+                contents
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| line.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        while entries.len() > HISTORY_LIMIT {
+            entries.pop_front();
+        }
+
+        Self {
+            entries,
+            cursor: None,
+            draft: String::new(),
+            path,
+        }
+    }
+
+    /// Record an executed command line (moves duplicates to the end)
+    pub fn push(&mut self, line: &str) {
+        let line = line.trim();
+        self.cursor = None;
+        if line.is_empty() {
+            return;
+        }
+
+        self.entries.retain(|entry| entry != line);
+        self.entries.push_back(line.to_string());
+        while self.entries.len() > HISTORY_LIMIT {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Write history to disk
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut contents = self.entries.iter().cloned().collect::<Vec<_>>().join("\n");
+        contents.push('\n');
+        fs::write(path, contents)
+    }
+
+    /// Step back in history, remembering `current` so it can be restored
+    pub fn previous(&mut self, current: &str) -> Option<String> {
+        let idx = match self.cursor {
+            None if self.entries.is_empty() => return None,
+            None => {
+                self.draft = current.to_string();
+                self.entries.len() - 1
+            }
+            Some(idx) => idx.saturating_sub(1),
+        };
+
+        self.cursor = Some(idx);
+        self.entries.get(idx).cloned()
+    }
+
+    /// Step forward in history, returning the draft line after the newest entry
+    pub fn next(&mut self) -> Option<String> {
+        let idx = self.cursor?;
+        if idx + 1 < self.entries.len() {
+            self.cursor = Some(idx + 1);
+            self.entries.get(idx + 1).cloned()
+        } else {
+            self.cursor = None;
+            Some(std::mem::take(&mut self.draft))
+        }
+    }
+
+    /// Stop browsing history (called when the palette closes or input changes)
+    pub fn reset_cursor(&mut self) {
+        self.cursor = None;
+        self.draft.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_argument_commands() {
+        assert_eq!(
+            parse("theme nord"),
+            Some(Ok(ExCommand::Theme(ThemeName::Nord)))
+        );
+        assert_eq!(
+            parse(":theme Tokyo-Night"),
+            Some(Ok(ExCommand::Theme(ThemeName::TokyoNight)))
+        );
+        assert_eq!(parse("goto 120"), Some(Ok(ExCommand::Goto(120))));
+        assert_eq!(parse("120"), Some(Ok(ExCommand::Goto(120))));
+        assert_eq!(parse("level 2"), Some(Ok(ExCommand::Level(2))));
+        assert_eq!(
+            parse("heading Getting Started"),
+            Some(Ok(ExCommand::Heading("Getting Started".to_string())))
+        );
+        assert_eq!(
+            parse("open docs/guide.md#setup"),
+            Some(Ok(ExCommand::Open("docs/guide.md#setup".to_string())))
+        );
+        assert_eq!(
+            parse("rename Quick Start"),
+            Some(Ok(ExCommand::Rename("Quick Start".to_string())))
+        );
+        assert_eq!(
+            parse("mv archive/old.md"),
+            Some(Ok(ExCommand::Move("archive/old.md".to_string())))
+        );
+        assert_eq!(
+            parse("extract setup.md"),
+            Some(Ok(ExCommand::Extract("setup.md".to_string())))
+        );
+        assert_eq!(
+            parse("query .code[rust]"),
+            Some(Ok(ExCommand::Query(".code[rust]".to_string())))
+        );
+        assert_eq!(
+            parse("set outline_width=35"),
+            Some(Ok(ExCommand::Set {
+                key: "outline_width".to_string(),
+                value: Some("35".to_string())
+            }))
+        );
+        assert_eq!(
+            parse("set raw"),
+            Some(Ok(ExCommand::Set {
+                key: "raw".to_string(),
+                value: None
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_palette_and_actions() {
+        assert_eq!(
+            parse("w"),
+            Some(Ok(ExCommand::Palette(CommandAction::SaveWidth)))
+        );
+        assert_eq!(
+            parse("q"),
+            Some(Ok(ExCommand::Palette(CommandAction::Quit)))
+        );
+        assert_eq!(
+            parse("ToggleOutline"),
+            Some(Ok(ExCommand::Action(Action::ToggleOutline)))
+        );
+        assert_eq!(
+            parse("enterinteractivemode"),
+            Some(Ok(ExCommand::Action(Action::EnterInteractiveMode)))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_and_fallback() {
+        assert!(matches!(parse("theme"), Some(Err(_))));
+        assert!(matches!(parse("theme nope"), Some(Err(_))));
+        assert!(matches!(parse("goto abc"), Some(Err(_))));
+        assert!(matches!(parse("goto 0"), Some(Err(_))));
+        assert!(matches!(parse("level 7"), Some(Err(_))));
+        assert!(matches!(parse("set color=red"), Some(Err(_))));
+        assert!(matches!(parse("w extra"), Some(Err(_))));
+        assert!(matches!(parse("ToggleOutline now"), Some(Err(_))));
+
+        // Unknown words fall back to fuzzy selection
+        assert_eq!(parse("tog out"), None);
+        assert_eq!(parse(""), None);
+        assert_eq!(parse(":"), None);
+    }
+
+    #[test]
+    fn test_complete_command_names() {
+        let candidates = complete("the", &[], None);
+        assert!(candidates.contains(&"theme ".to_string()));
+
+        let candidates = complete("toggleo", &[], None);
+        assert_eq!(candidates, vec!["ToggleOutline".to_string()]);
+
+        // Alias and action with the same name appear once
+        let candidates = complete("quit", &[], None);
+        assert_eq!(candidates, vec!["quit".to_string()]);
+    }
+
+    #[test]
+    fn test_complete_arguments() {
+        assert_eq!(complete("theme no", &[], None), vec!["theme nord"]);
+        assert_eq!(complete("level 3", &[], None), vec!["level 3"]);
+        assert_eq!(complete("set out", &[], None).len(), 2);
+        assert_eq!(
+            complete("set raw=o", &[], None),
+            vec!["set raw=on", "set raw=off"]
+        );
+
+        let headings = ["Installation", "Usage", "Advanced Installation"];
+        assert_eq!(
+            complete("heading inst", &headings, None),
+            vec!["heading Installation", "heading Advanced Installation"]
+        );
+
+        assert!(complete("goto 1", &headings, None).is_empty());
+        assert!(complete("unknown arg", &headings, None).is_empty());
+    }
+
+    #[test]
+    fn test_complete_path() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("guide.md"), "# Guide").unwrap();
+        fs::write(dir.path().join("notes.txt"), "notes").unwrap();
+        fs::create_dir(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("docs").join("api.md"), "# API").unwrap();
+
+        assert_eq!(
+            complete("open ", &[], Some(dir.path())),
+            vec!["open docs/", "open guide.md"]
+        );
+        assert_eq!(
+            complete("open docs/", &[], Some(dir.path())),
+            vec!["open docs/api.md"]
+        );
+        assert!(complete("open ../", &[], Some(dir.path())).is_empty());
+    }
+
+    #[test]
+    fn test_common_prefix() {
+        let candidates = vec!["set outline=".to_string(), "set outline_width=".to_string()];
+        assert_eq!(common_prefix(&candidates), "set outline");
+        assert_eq!(common_prefix(&[]), "");
+    }
+
+    #[test]
+    fn test_history_ring() {
+        let mut history = CommandHistory::default();
+        history.push("theme nord");
+        history.push("goto 10");
+        history.push("theme nord");
+        assert_eq!(history.entries.len(), 2);
+
+        assert_eq!(history.previous("dra"), Some("theme nord".to_string()));
+        assert_eq!(history.previous("dra"), Some("goto 10".to_string()));
+        assert_eq!(history.previous("dra"), Some("goto 10".to_string()));
+        assert_eq!(history.next(), Some("theme nord".to_string()));
+        assert_eq!(history.next(), Some("dra".to_string()));
+        assert_eq!(history.next(), None);
+    }
+
+    #[test]
+    fn test_history_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("treemd").join("history");
+
+        let mut history = CommandHistory::load(Some(path.clone()));
+        assert!(history.entries.is_empty());
+        for i in 0..(HISTORY_LIMIT + 5) {
+            history.push(&format!("goto {}", i + 1));
+        }
+        history.save().unwrap();
+
+        let loaded = CommandHistory::load(Some(path));
+        assert_eq!(loaded.entries.len(), HISTORY_LIMIT);
+        assert_eq!(
+            loaded.entries.back(),
+            Some(&format!("goto {}", HISTORY_LIMIT + 5))
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! Detection and resolution of external modifications before writing.
+//!
+//! A [`DiskSnapshot`] is taken whenever a file is loaded. In-TUI edits are
+//! computed against that snapshot, and before writing the file on disk is
+//! compared to it. If someone else changed the file in the meantime, the edit
+//! becomes a [`WriteConflict`] that the user resolves by reloading,
+//! overwriting, or merging the single changed line into their version.
+
+use crate::parser::utils::line_of;
+use crate::tui::edit_journal::FileEdit;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// File contents as they were on disk when the document was loaded
+#[derive(Debug, Clone)]
+pub struct DiskSnapshot {
+    pub content: String,
+    hash: u64,
+}
+
+impl DiskSnapshot {
+    pub fn new(content: String) -> Self {
+        let hash = content_hash(&content);
+        Self { content, hash }
+    }
+
+    /// Read the file at `path`, or `None` if it can't be read
+    pub fn capture(path: &Path) -> Option<Self> {
+        std::fs::read_to_string(path).ok().map(Self::new)
+    }
+
+    /// Whether `content` (freshly read from disk) is what we loaded
+    pub fn matches(&self, content: &str) -> bool {
+        self.content.len() == content.len() && self.hash == content_hash(content)
+    }
+}
+
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// An edit that could not be written because the file changed on disk
+#[derive(Debug, Clone)]
+pub struct WriteConflict {
+    pub path: PathBuf,
+    /// Contents the edit was computed against (as loaded)
+    pub base: String,
+    /// Base contents with our edit applied
+    pub ours: String,
+    /// Contents currently on disk
+    pub theirs: String,
+    /// Description of our edit
+    pub label: String,
+}
+
+impl WriteConflict {
+    /// Our edit, as a line range relative to the base contents
+    fn our_edit(&self) -> Option<FileEdit> {
+        FileEdit::from_contents(&self.path, &self.base, &self.ours, &self.label)
+    }
+
+    /// 1-based line number and before/after text of our changed line(s)
+    pub fn summary(&self) -> Option<(usize, String, String)> {
+        let edit = self.our_edit()?;
+        let line = line_of(&self.base, edit.offset);
+        Some((
+            line,
+            edit.old_text.trim_end().to_string(),
+            edit.new_text.trim_end().to_string(),
+        ))
+    }
+
+    /// Three-way merge: apply our changed lines on top of the disk contents.
+    ///
+    /// Fails if the other side also changed those lines, or if they can no
+    /// longer be located unambiguously.
+    pub fn merge(&self) -> Result<String, String> {
+        let edit = self
+            .our_edit()
+            .ok_or_else(|| "Nothing to merge".to_string())?;
+        edit.reapply(&self.theirs)
+            .map_err(|_| "The same line was changed on disk".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conflict(base: &str, ours: &str, theirs: &str) -> WriteConflict {
+        WriteConflict {
+            path: PathBuf::from("test.md"),
+            base: base.to_string(),
+            ours: ours.to_string(),
+            theirs: theirs.to_string(),
+            label: "Checkbox checked".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_matches() {
+        let snapshot = DiskSnapshot::new("# Title\n".to_string());
+        assert!(snapshot.matches("# Title\n"));
+        assert!(!snapshot.matches("# Title\n\nMore\n"));
+        assert!(!snapshot.matches("# Other\n"));
+    }
+
+    #[test]
+    fn test_merge_applies_our_line_to_their_version() {
+        let c = conflict(
+            "# Tasks\n- [ ] one\n- [ ] two\n",
+            "# Tasks\n- [ ] one\n- [x] two\n",
+            "# My Tasks\n- [ ] one\n- [ ] two\n- [ ] three\n",
+        );
+
+        assert_eq!(
+            c.merge().unwrap(),
+            "# My Tasks\n- [ ] one\n- [x] two\n- [ ] three\n"
+        );
+        assert_eq!(
+            c.summary(),
+            Some((3, "- [ ] two".to_string(), "- [x] two".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_merge_fails_when_same_line_changed() {
+        let c = conflict(
+            "- [ ] one\n- [ ] two\n",
+            "- [ ] one\n- [x] two\n",
+            "- [ ] one\n- [ ] two, renamed\n",
+        );
+
+        assert!(c.merge().is_err());
+    }
+}
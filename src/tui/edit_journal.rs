@@ -0,0 +1,257 @@
+//! Undo/redo journal for in-place file edits.
+//!
+//! Checkbox toggles and table cell edits write straight to disk. Each write is
+//! recorded as a [`FileEdit`] covering only the lines it changed, so it can be
+//! reverted (or re-applied) later without touching the rest of the file, even
+//! after the document has been reloaded from disk.
+
+use std::path::{Path, PathBuf};
+
+/// Maximum number of edits kept on the undo stack
+const JOURNAL_LIMIT: usize = 100;
+
+/// A single change to a file on disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEdit {
+    /// File the edit was made to
+    pub path: PathBuf,
+    /// Byte offset of the changed range (always the start of a line)
+    pub offset: usize,
+    /// Text of the range before the edit
+    pub old_text: String,
+    /// Text of the range after the edit
+    pub new_text: String,
+    /// Short description shown in status messages
+    pub label: String,
+    /// A file the edit created, with its contents: removed on undo (if
+    /// untouched since) and written again on redo
+    pub created: Option<(PathBuf, String)>,
+}
+
+impl FileEdit {
+    /// Build an edit from the file contents before and after a write.
+    ///
+    /// The range is widened to whole lines so it can still be found if the
+    /// file shifts around it. Returns `None` if the contents are identical.
+    pub fn from_contents(path: &Path, before: &str, after: &str, label: &str) -> Option<Self> {
+        if before == after {
+            return None;
+        }
+
+        let (b, a) = (before.as_bytes(), after.as_bytes());
+        let prefix = b.iter().zip(a).take_while(|(x, y)| x == y).count();
+        let max_suffix = b.len().min(a.len()) - prefix;
+        let suffix = b
+            .iter()
+            .rev()
+            .zip(a.iter().rev())
+            .take(max_suffix)
+            .take_while(|(x, y)| x == y)
+            .count();
+
+        // Widen to line boundaries ('\n' is ASCII, so these are char boundaries)
+        let start = b[..prefix]
+            .iter()
+            .rposition(|&c| c == b'\n')
+            .map(|p| p + 1)
+            .unwrap_or(0);
+        let changed_end = b.len() - suffix;
+        let end = if changed_end > start && b[changed_end - 1] == b'\n' {
+            changed_end
+        } else {
+            b[changed_end..]
+                .iter()
+                .position(|&c| c == b'\n')
+                .map(|p| changed_end + p + 1)
+                .unwrap_or(b.len())
+        };
+        let after_end = a.len() - (b.len() - end);
+
+        Some(Self {
+            path: path.to_path_buf(),
+            offset: start,
+            old_text: before[start..end].to_string(),
+            new_text: after[start..after_end].to_string(),
+            label: label.to_string(),
+            created: None,
+        })
+    }
+
+    /// Undo this edit in `content`, returning the reverted file contents
+    pub fn revert(&self, content: &str) -> Result<String, String> {
+        self.splice(content, &self.new_text, &self.old_text)
+    }
+
+    /// Redo this edit in `content`, returning the updated file contents
+    pub fn reapply(&self, content: &str) -> Result<String, String> {
+        self.splice(content, &self.old_text, &self.new_text)
+    }
+
+    /// Replace `expected` with `replacement` at the recorded offset.
+    ///
+    /// If lines were added or removed elsewhere in the file, the range is
+    /// relocated as long as `expected` occurs exactly once at a line start.
+    fn splice(&self, content: &str, expected: &str, replacement: &str) -> Result<String, String> {
+        let fits = |i: usize| {
+            is_line_start(content, i) && is_line_start_or_end(content, i + expected.len())
+        };
+        let at_offset = content.get(self.offset..self.offset + expected.len()) == Some(expected)
+            && fits(self.offset);
+
+        let offset = if at_offset {
+            self.offset
+        } else {
+            let mut candidates = if expected.is_empty() {
+                Vec::new()
+            } else {
+                content
+                    .match_indices(expected)
+                    .map(|(i, _)| i)
+                    .filter(|&i| fits(i))
+                    .collect()
+            };
+            if candidates.len() != 1 {
+                return Err(format!(
+                    "File changed on disk, cannot apply '{}' safely",
+                    self.label
+                ));
+            }
+            candidates.remove(0)
+        };
+
+        let mut result = String::with_capacity(content.len() + replacement.len());
+        result.push_str(&content[..offset]);
+        result.push_str(replacement);
+        result.push_str(&content[offset + expected.len()..]);
+        Ok(result)
+    }
+}
+
+fn is_line_start(content: &str, offset: usize) -> bool {
+    offset == 0 || content.as_bytes().get(offset - 1) == Some(&b'\n')
+}
+
+fn is_line_start_or_end(content: &str, offset: usize) -> bool {
+    offset == content.len() || is_line_start(content, offset)
+}
+
+/// Undo and redo stacks of file edits, kept across document reloads
+#[derive(Debug, Default)]
+pub struct EditJournal {
+    undo: Vec<FileEdit>,
+    redo: Vec<FileEdit>,
+}
+
+impl EditJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a new edit, discarding redo history for the same file
+    pub fn record(&mut self, edit: FileEdit) {
+        self.redo.retain(|e| e.path != edit.path);
+        self.push_undo(edit);
+    }
+
+    /// Push an edit onto the undo stack without touching redo history
+    pub fn push_undo(&mut self, edit: FileEdit) {
+        self.undo.push(edit);
+        if self.undo.len() > JOURNAL_LIMIT {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Push an edit onto the redo stack
+    pub fn push_redo(&mut self, edit: FileEdit) {
+        self.redo.push(edit);
+    }
+
+    /// Take the most recent undoable edit for `path`
+    pub fn take_undo(&mut self, path: &Path) -> Option<FileEdit> {
+        let idx = self.undo.iter().rposition(|e| e.path == path)?;
+        Some(self.undo.remove(idx))
+    }
+
+    /// Take the most recent redoable edit for `path`
+    pub fn take_redo(&mut self, path: &Path) -> Option<FileEdit> {
+        let idx = self.redo.iter().rposition(|e| e.path == path)?;
+        Some(self.redo.remove(idx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(before: &str, after: &str) -> FileEdit {
+        FileEdit::from_contents(Path::new("test.md"), before, after, "Toggle checkbox").unwrap()
+    }
+
+    #[test]
+    fn test_from_contents_covers_changed_lines() {
+        let before = "# Tasks\n\n- [ ] one\n- [ ] two\n";
+        let after = "# Tasks\n\n- [ ] one\n- [x] two\n";
+        let e = edit(before, after);
+
+        assert_eq!(e.offset, before.find("- [ ] two").unwrap());
+        assert_eq!(e.old_text, "- [ ] two\n");
+        assert_eq!(e.new_text, "- [x] two\n");
+        assert!(FileEdit::from_contents(Path::new("test.md"), before, before, "x").is_none());
+    }
+
+    #[test]
+    fn test_revert_and_reapply_roundtrip() {
+        let before = "| a | b |\n|---|---|\n| 1 | 2 |";
+        let after = "| a | b |\n|---|---|\n| 1 | 42 |";
+        let e = edit(before, after);
+
+        assert_eq!(e.revert(after).unwrap(), before);
+        assert_eq!(e.reapply(before).unwrap(), after);
+    }
+
+    #[test]
+    fn test_revert_relocates_after_unrelated_change() {
+        let before = "- [ ] one\n- [ ] two\n";
+        let after = "- [ ] one\n- [x] two\n";
+        let e = edit(before, after);
+
+        // Lines inserted above the edit shift its offset
+        let changed = format!("# Intro\n\n{}", after);
+        assert_eq!(
+            e.revert(&changed).unwrap(),
+            format!("# Intro\n\n{}", before)
+        );
+    }
+
+    #[test]
+    fn test_revert_refuses_incompatible_change() {
+        let before = "- [ ] one\n- [ ] two\n";
+        let after = "- [ ] one\n- [x] two\n";
+        let e = edit(before, after);
+
+        // The edited line itself was changed externally
+        assert!(e.revert("- [ ] one\n- [x] two (renamed)\n").is_err());
+        // Text was appended to the edited line
+        let table = edit("| 1 | 2 |", "| 1 | 42 |");
+        assert!(table.revert("| 1 | 42 | 7 |").is_err());
+        // The edited line now appears twice, so the target is ambiguous
+        assert!(e.revert("- [x] two\n- [ ] one\n- [x] two\n").is_err());
+    }
+
+    #[test]
+    fn test_journal_stacks_per_file() {
+        let mut journal = EditJournal::new();
+        let a = FileEdit::from_contents(Path::new("a.md"), "x\n", "y\n", "a").unwrap();
+        let b = FileEdit::from_contents(Path::new("b.md"), "x\n", "y\n", "b").unwrap();
+        journal.record(a.clone());
+        journal.record(b.clone());
+
+        assert_eq!(journal.take_undo(Path::new("a.md")), Some(a.clone()));
+        assert_eq!(journal.take_undo(Path::new("a.md")), None);
+
+        journal.push_redo(a.clone());
+        journal.record(FileEdit::from_contents(Path::new("a.md"), "y\n", "z\n", "c").unwrap());
+        assert_eq!(journal.take_redo(Path::new("a.md")), None);
+        assert_eq!(journal.take_undo(Path::new("b.md")), Some(b));
+    }
+}
@@ -0,0 +1,243 @@
+//! Link graph popup: the current note's neighbourhood as a tree.
+//!
+//! The note sits at the top, with the notes it links to (→) and the notes
+//! linking to it (←) branching below, each followed outwards up to the
+//! chosen depth. A note already drawn in a branch is marked ↺ instead of
+//! being expanded again, so cycles stay finite.
+
+use crate::graph::LinkGraph;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Levels of links drawn when the popup opens
+pub const DEFAULT_GRAPH_DEPTH: usize = 2;
+/// Deepest neighbourhood the popup draws
+pub const MAX_GRAPH_DEPTH: usize = 4;
+
+/// A line of the neighbourhood tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphRow {
+    /// Box-drawing prefix and arrow, e.g. `│   └─→ `
+    pub prefix: String,
+    pub label: String,
+    /// Note this row stands for (`None` for an unresolved link)
+    pub note: Option<usize>,
+    /// Drawn before in this branch, so not expanded
+    pub repeated: bool,
+}
+
+/// State of the graph popup
+#[derive(Debug, Clone)]
+pub struct GraphView {
+    pub graph: LinkGraph,
+    pub center: usize,
+    pub depth: usize,
+    pub rows: Vec<GraphRow>,
+    pub selected: usize,
+}
+
+impl GraphView {
+    pub fn new(graph: LinkGraph, center: usize, depth: usize) -> Self {
+        let mut view = Self {
+            graph,
+            center,
+            depth: depth.clamp(1, MAX_GRAPH_DEPTH),
+            rows: Vec::new(),
+            selected: 0,
+        };
+        view.rebuild();
+        view
+    }
+
+    fn rebuild(&mut self) {
+        let graph = &self.graph;
+        let center = self.center;
+        let mut rows = vec![GraphRow {
+            prefix: String::new(),
+            label: graph.notes()[center].display().to_string(),
+            note: Some(center),
+            repeated: false,
+        }];
+
+        // Top-level branches: outgoing, unresolved, then incoming
+        let outgoing = graph.outgoing(center);
+        let unresolved = graph.unresolved_from(center);
+        let incoming = graph.incoming(center);
+        let total = outgoing.len() + unresolved.len() + incoming.len();
+        let mut drawn = 0;
+        let mut next_last = || {
+            drawn += 1;
+            drawn == total
+        };
+
+        let mut branches = Branches::new(self, true);
+        for note in outgoing {
+            let last = next_last();
+            branches.add(&mut rows, note, "", last, 1);
+        }
+        for target in unresolved {
+            let last = next_last();
+            rows.push(GraphRow {
+                prefix: format!("{}→ ", if last { "└─" } else { "├─" }),
+                label: format!("{} (missing)", target),
+                note: None,
+                repeated: false,
+            });
+        }
+        let mut branches = Branches::new(self, false);
+        for note in incoming {
+            let last = next_last();
+            branches.add(&mut rows, note, "", last, 1);
+        }
+
+        self.selected = self.selected.min(rows.len() - 1);
+        self.rows = rows;
+    }
+
+    pub fn next(&mut self) {
+        self.selected = (self.selected + 1).min(self.rows.len().saturating_sub(1));
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Change the depth by `delta`; false if already at the limit
+    pub fn change_depth(&mut self, delta: isize) -> bool {
+        let depth = self
+            .depth
+            .saturating_add_signed(delta)
+            .clamp(1, MAX_GRAPH_DEPTH);
+        if depth == self.depth {
+            return false;
+        }
+        self.depth = depth;
+        self.rebuild();
+        true
+    }
+
+    /// Redraw the tree around the selected note
+    pub fn recenter(&mut self) -> bool {
+        match self.rows.get(self.selected).and_then(|row| row.note) {
+            Some(note) if note != self.center => {
+                self.center = note;
+                self.selected = 0;
+                self.rebuild();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Absolute path of the selected note
+    pub fn selected_path(&self) -> Option<PathBuf> {
+        let note = self.rows.get(self.selected)?.note?;
+        Some(self.graph.root().join(&self.graph.notes()[note]))
+    }
+}
+
+/// The links followed in one direction from the centre note
+struct Branches<'a> {
+    view: &'a GraphView,
+    outgoing: bool,
+    /// Notes drawn so far in this direction
+    seen: HashSet<usize>,
+}
+
+impl<'a> Branches<'a> {
+    fn new(view: &'a GraphView, outgoing: bool) -> Self {
+        Self {
+            view,
+            outgoing,
+            seen: HashSet::from([view.center]),
+        }
+    }
+
+    /// Add `note` and, below the depth limit, the notes it leads to
+    fn add(
+        &mut self,
+        rows: &mut Vec<GraphRow>,
+        note: usize,
+        indent: &str,
+        last: bool,
+        level: usize,
+    ) {
+        let graph = &self.view.graph;
+        let repeated = !self.seen.insert(note);
+        rows.push(GraphRow {
+            prefix: format!(
+                "{}{}{} ",
+                indent,
+                if last { "└─" } else { "├─" },
+                if self.outgoing { '→' } else { '←' }
+            ),
+            label: graph.notes()[note].display().to_string(),
+            note: Some(note),
+            repeated,
+        });
+        if repeated || level >= self.view.depth {
+            return;
+        }
+
+        // The centre note is the top row already
+        let next: Vec<usize> = if self.outgoing {
+            graph.outgoing(note)
+        } else {
+            graph.incoming(note)
+        }
+        .into_iter()
+        .filter(|&child| child != self.view.center)
+        .collect();
+        let indent = format!("{}{}", indent, if last { "    " } else { "│   " });
+        let count = next.len();
+        for (idx, child) in next.into_iter().enumerate() {
+            self.add(rows, child, &indent, idx + 1 == count, level + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::Vault;
+    use std::fs;
+
+    #[test]
+    fn test_neighbourhood_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::write(root.join("a.md"), "[[b]] [[gone]]").unwrap();
+        fs::write(root.join("b.md"), "[[c]] [[a]]").unwrap();
+        fs::write(root.join("c.md"), "[[a]]").unwrap();
+
+        let graph = LinkGraph::build(&Vault::open(root.clone()));
+        let center = graph.index_of(&root.join("a.md")).unwrap();
+        let mut view = GraphView::new(graph, center, 2);
+        let lines: Vec<String> = view
+            .rows
+            .iter()
+            .map(|row| format!("{}{}", row.prefix, row.label))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "a.md",
+                "├─→ b.md",
+                "│   └─→ c.md",
+                "├─→ [[gone]] (missing)",
+                "├─← b.md",
+                "└─← c.md",
+                "    └─← b.md",
+            ]
+        );
+        assert!(view.rows[6].repeated);
+
+        assert!(view.change_depth(5));
+        assert_eq!(view.depth, MAX_GRAPH_DEPTH);
+        assert!(!view.change_depth(1));
+        view.selected = 2;
+        assert!(view.recenter());
+        assert_eq!(view.rows[0].label, "c.md");
+        assert_eq!(view.selected_path(), Some(root.join("c.md")));
+    }
+}
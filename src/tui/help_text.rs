@@ -107,6 +107,14 @@ pub const HELP_LINES: &[HelpLine] = &[
     keybinding("l/→", "Expand heading"),
     keybinding("h/←", "Collapse (or parent if no children)"),
     blank(),
+    // Outline Editing
+    section("Outline Editing"),
+    keybinding("K/J", "Move section (with subsections) up/down"),
+    keybinding("< >", "Promote/demote section and its subsections"),
+    keybinding("D", "Delete section (asks first)"),
+    keybinding("X", "Extract section to a new file, leaving a link"),
+    keybinding("U/Ctrl+r", "Undo/redo the last edit"),
+    blank(),
     // General
     section("General"),
     keybinding("Tab", "Switch between Outline and Content"),
@@ -114,6 +122,7 @@ pub const HELP_LINES: &[HelpLine] = &[
     keybinding("s", "Filter outline headings (Esc: clear, Enter: keep)"),
     keybinding("n/N", "Next/previous search match"),
     keybinding("r", "Toggle raw source view"),
+    keybinding("I", "Toggle image previews"),
     keybinding("?", "Toggle this help"),
     keybinding("q/Esc", "Quit"),
     blank(),
@@ -122,10 +131,33 @@ pub const HELP_LINES: &[HelpLine] = &[
     keybinding("w", "Toggle outline visibility (full-width content)"),
     keybinding("[ ]", "Decrease/increase outline width (20%, 30%, 40%)"),
     keybinding("S", "Save outline width to config (with confirmation)"),
-    keybinding(":", "Open command palette (fuzzy search commands)"),
+    keybinding(":", "Open command palette (fuzzy search or :command args)"),
     keybinding("1-9", "Jump to heading 1-9"),
     keybinding("m", "Set bookmark (shows ⚑ indicator)"),
     keybinding("'", "Jump to bookmarked position"),
+    keybinding("T", "Task list: open tasks by heading (Space: check)"),
+    keybinding("#", "Tag browser: tags by count and where they are used"),
+    keybinding("L", "Link graph: notes linking to and from this one"),
+    blank(),
+    // Command Palette
+    section("Command Palette"),
+    keybinding(":theme", "Switch theme (e.g. :theme nord)"),
+    keybinding(":goto N", "Jump to source line N (also :N)"),
+    keybinding(":heading", "Jump to heading by name"),
+    keybinding(":level N", "Collapse outline below level N"),
+    keybinding(":open", "Open file relative to current one"),
+    keybinding(":query", "Run query, copy results to clipboard"),
+    keybinding(":set", "Set option (outline_width, outline, raw, theme)"),
+    keybinding(":import", "Import table from CSV/TSV file or clipboard"),
+    keybinding(":tasks", "Open the task list"),
+    keybinding(":tags", "Open the tag browser"),
+    keybinding(":graph", "Open the link graph"),
+    keybinding(":rename", "Rename selected heading, fix links"),
+    keybinding(":mv", "Move this note, fix links to it"),
+    keybinding(":extract", "Extract selected section to a file"),
+    keybinding(":w", "Save outline width and theme to config"),
+    keybinding("Tab", "Complete command or argument"),
+    keybinding("Ctrl+p/n", "Previous/next command from history"),
     blank(),
     // Link Following
     section("Link Following"),
@@ -134,19 +166,40 @@ pub const HELP_LINES: &[HelpLine] = &[
     keybinding("1-9", "Jump to link by number (in link mode)"),
     keybinding("Enter", "Follow selected link (in link mode)"),
     keybinding("p", "Jump to parent's links (stay in link mode)"),
-    keybinding("b/Bksp", "Go back to previous file"),
+    keybinding("b/Bksp", "Go back (from a footnote, then previous file)"),
     keybinding("F", "Go forward in navigation history"),
+    keybinding("j/k Enter", "Pick a note when a wikilink matches several"),
     blank(),
     // Interactive Mode
     section("Interactive Mode"),
     keybinding("i", "Enter interactive mode (navigate elements)"),
     keybinding("Tab/j/k", "Next element | Shift+Tab/k to go back"),
-    keybinding("u/d", "Page up/down (PgUp/PgDn also work)"),
-    keybinding("Enter", "Activate element (toggle/follow/edit)"),
+    keybinding("Ctrl+u/d", "Page up/down (PgUp/PgDn also work)"),
+    keybinding("Enter", "Activate element (toggle/follow/edit/open embed)"),
     keybinding("Space", "Toggle checkboxes/details blocks"),
     keybinding("y", "Copy element (code/cell/link)"),
+    keybinding("x", "Run code block after confirming (x again: stop)"),
+    keybinding("o", "Collapse/expand code block output"),
+    keybinding("e", "Edit code block in $EDITOR (saved in place)"),
+    keybinding("v", "Toggle Mermaid diagram/source"),
     keybinding("hjkl", "Navigate table cells (in table mode)"),
     keybinding("Enter", "Edit table cell (in table mode)"),
+    keybinding("H/L", "Scroll table columns (in table mode)"),
+    keybinding("s", "Sort table by column: asc/desc/off (in table mode)"),
+    keybinding("/", "Filter table rows (in table mode)"),
+    keybinding("w", "Toggle table cell wrapping (in table mode)"),
+    keybinding(
+        "o/O D J/K",
+        "Add below/above, delete, move rows (table mode)",
+    ),
+    keybinding(
+        "a/A X </>",
+        "Add right/left, delete, move columns (table mode)",
+    ),
+    keybinding("=", "Cycle column alignment (in table mode)"),
+    keybinding("c/t", "Copy table as CSV/TSV (in table mode)"),
+    keybinding("I", "Replace table from clipboard CSV/TSV (table mode)"),
+    keybinding("u/Ctrl+r", "Undo/redo checkbox toggle or table edit"),
     keybinding("Esc", "Exit interactive mode"),
     blank(),
     // Themes & Clipboard
@@ -155,6 +208,7 @@ pub const HELP_LINES: &[HelpLine] = &[
     keybinding("y", "Copy current section content (works in all modes)"),
     keybinding("Y", "Copy anchor link (works in all modes)"),
     keybinding("e", "Edit file in default editor ($VISUAL or $EDITOR)"),
+    keybinding("R", "Rename heading and fix links to it"),
     blank(),
     // Note
     note("On Linux, install a clipboard manager (clipit, parcellite, xclip) for best results"),
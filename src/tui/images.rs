@@ -0,0 +1,559 @@
+//! Inline previews of local images.
+//!
+//! Images are always drawn into the text as Unicode half blocks (`▀` with the
+//! upper pixel as foreground and the lower one as background), which works in
+//! any color terminal and keeps the content layout plain text. When the
+//! terminal speaks a graphics protocol (kitty, sixel or iTerm2) the real image
+//! is written over those cells after each frame, as long as it is fully in
+//! view. Remote images are never fetched.
+
+use super::app::ensure_within;
+use super::terminal_compat::{ColorMode, TerminalCapabilities};
+use super::theme::rgb_to_256;
+use crate::parser::output::InlineElement;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use image::imageops::FilterType;
+use image::{DynamicImage, RgbaImage};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// How images are drawn on screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageProtocol {
+    /// Kitty graphics protocol (kitty, Ghostty)
+    Kitty,
+    /// DEC sixel graphics (foot, mlterm, Contour, xterm with sixel)
+    Sixel,
+    /// iTerm2 inline images (iTerm2, WezTerm)
+    Iterm2,
+    /// Unicode half blocks only
+    HalfBlock,
+}
+
+impl ImageProtocol {
+    /// Parse a `[terminal] images` config value; `None` for `auto` / `off`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "kitty" => Some(Self::Kitty),
+            "sixel" => Some(Self::Sixel),
+            "iterm2" | "iterm" => Some(Self::Iterm2),
+            "halfblock" | "half-block" | "blocks" => Some(Self::HalfBlock),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Kitty => "kitty",
+            Self::Sixel => "sixel",
+            Self::Iterm2 => "iTerm2",
+            Self::HalfBlock => "half-block",
+        }
+    }
+}
+
+/// Where an image's preview landed in the last rendered frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    key: RenderKey,
+    /// Screen cell of the top-left corner
+    pub x: u16,
+    pub y: u16,
+}
+
+/// Cache key: file plus the box the preview had to fit in
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RenderKey {
+    path: PathBuf,
+    max_cols: u16,
+    max_rows: u16,
+}
+
+/// A preview in rendered content: it starts at `line` and spans `rows` lines
+#[derive(Debug, Clone)]
+pub struct PreviewSpot {
+    pub line: usize,
+    pub rows: usize,
+    pub src: String,
+    pub key: RenderKey,
+}
+
+/// An image sized for the content pane
+#[derive(Debug)]
+struct Rendered {
+    cols: u16,
+    rows: u16,
+    /// Pixels at `cols × rows` cells, for the graphics protocols
+    pixels: RgbaImage,
+    lines: Vec<Line<'static>>,
+    /// Escape sequence drawing the image, built on first use
+    payload: Option<String>,
+}
+
+/// Decoded image and the modification time it was read at
+type Decoded = (Option<SystemTime>, Result<DynamicImage, String>);
+
+/// Image preview state: protocol, on/off toggle and caches
+#[derive(Debug)]
+pub struct ImagePreviews {
+    pub protocol: ImageProtocol,
+    pub enabled: bool,
+    color_mode: ColorMode,
+    decoded: HashMap<PathBuf, Decoded>,
+    rendered: HashMap<RenderKey, Rendered>,
+    /// Previews in the frame being drawn that can get real graphics
+    pending: Vec<Placement>,
+    /// Graphics currently on screen
+    shown: Vec<Placement>,
+}
+
+impl ImagePreviews {
+    /// Previews configured by a `[terminal] images` value: `auto`, `off`, or
+    /// a protocol name
+    pub fn new(setting: &str, color_mode: ColorMode) -> Self {
+        let protocol = ImageProtocol::from_name(setting)
+            .unwrap_or_else(TerminalCapabilities::detect_image_protocol);
+        Self {
+            protocol,
+            enabled: !setting.eq_ignore_ascii_case("off"),
+            color_mode,
+            decoded: HashMap::new(),
+            rendered: HashMap::new(),
+            pending: Vec::new(),
+            shown: Vec::new(),
+        }
+    }
+
+    /// Half-block lines previewing `src` within `max_cols × max_rows` cells.
+    ///
+    /// `Ok(None)` for images that aren't previewed (remote URLs, previews
+    /// off); `Err` when a local file can't be read or decoded, or lies
+    /// outside `safe_root` in safe mode.
+    pub fn preview(
+        &mut self,
+        src: &str,
+        base_dir: Option<&Path>,
+        safe_root: Option<&Path>,
+        max_cols: u16,
+        max_rows: u16,
+    ) -> Result<Option<(RenderKey, &[Line<'static>])>, String> {
+        if !self.enabled || max_cols == 0 || max_rows == 0 {
+            return Ok(None);
+        }
+        let Some(path) = local_path(src, base_dir) else {
+            return Ok(None);
+        };
+        if let Some(root) = safe_root {
+            ensure_within(root, &path)?;
+        }
+
+        let modified = std::fs::metadata(&path)
+            .and_then(|meta| meta.modified())
+            .ok();
+        let stale = self
+            .decoded
+            .get(&path)
+            .is_none_or(|(read_at, _)| *read_at != modified);
+        if stale {
+            let image = image::open(&path).map_err(|e| e.to_string());
+            self.rendered.retain(|key, _| key.path != path);
+            self.decoded.insert(path.clone(), (modified, image));
+        }
+
+        let key = RenderKey {
+            path,
+            max_cols,
+            max_rows,
+        };
+        if !self.rendered.contains_key(&key) {
+            let image = match &self.decoded[&key.path].1 {
+                Ok(image) => image,
+                Err(e) => return Err(e.clone()),
+            };
+            let rendered = render(image, (max_cols, max_rows), cell_size(), self.color_mode);
+            self.rendered.insert(key.clone(), rendered);
+        }
+        let lines = &self.rendered[&key].lines;
+        Ok(Some((key, lines)))
+    }
+
+    /// Start collecting placements for a new frame
+    pub fn begin_frame(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Ask for real graphics over a preview drawn at `(x, y)`
+    pub fn place(&mut self, key: RenderKey, x: u16, y: u16) {
+        if self.protocol != ImageProtocol::HalfBlock {
+            self.pending.push(Placement { key, x, y });
+        }
+    }
+
+    /// Whether graphics from the last frame have to be wiped by repainting
+    /// the whole screen before the new ones are drawn. Kitty images are
+    /// deleted with an escape sequence instead.
+    pub fn needs_repaint(&self) -> bool {
+        self.pending != self.shown
+            && !self.shown.is_empty()
+            && matches!(self.protocol, ImageProtocol::Sixel | ImageProtocol::Iterm2)
+    }
+
+    /// Forget what's on screen, after the terminal was cleared
+    pub fn reset(&mut self) {
+        self.shown.clear();
+    }
+
+    /// Draw this frame's graphics if they changed since the last one
+    pub fn flush(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if self.pending == self.shown {
+            return Ok(());
+        }
+        if self.protocol == ImageProtocol::Kitty && !self.shown.is_empty() {
+            write!(out, "\x1b_Ga=d,d=A,q=2\x1b\\")?;
+        }
+        for placement in &self.pending {
+            let Some(rendered) = self.rendered.get_mut(&placement.key) else {
+                continue;
+            };
+            if rendered.payload.is_none() {
+                rendered.payload = Some(encode(self.protocol, rendered));
+            }
+            let payload = rendered.payload.as_deref().unwrap_or_default();
+            write!(
+                out,
+                "\x1b7\x1b[{};{}H{}\x1b8",
+                placement.y + 1,
+                placement.x + 1,
+                payload
+            )?;
+        }
+        out.flush()?;
+        self.shown = self.pending.clone();
+        Ok(())
+    }
+}
+
+/// Source of the image a paragraph consists of, if it holds nothing else
+pub fn standalone_image(inline: &[InlineElement]) -> Option<&str> {
+    let mut elements = inline.iter().filter(
+        |element| !matches!(element, InlineElement::Text { value } if value.trim().is_empty()),
+    );
+    match (elements.next(), elements.next()) {
+        (Some(InlineElement::Image { src, .. }), None) => Some(src),
+        _ => None,
+    }
+}
+
+/// Resolve an image source to a local file, or `None` for URLs
+fn local_path(src: &str, base_dir: Option<&Path>) -> Option<PathBuf> {
+    let src = src.trim().trim_start_matches('<').trim_end_matches('>');
+    if src.is_empty() || src.contains("://") || src.starts_with("data:") {
+        return None;
+    }
+    let src = src.strip_prefix("file:").unwrap_or(src);
+    let path = Path::new(src);
+    Some(match base_dir {
+        Some(dir) if path.is_relative() => dir.join(path),
+        _ => path.to_path_buf(),
+    })
+}
+
+/// Size of a terminal cell in pixels, guessing 8×16 when unknown
+fn cell_size() -> (u32, u32) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            u32::from(size.width / size.columns).max(1),
+            u32::from(size.height / size.rows).max(1),
+        ),
+        _ => (8, 16),
+    }
+}
+
+/// Preview size in cells: the image's natural size, shrunk to fit
+fn fit(width: u32, height: u32, max_cols: u16, max_rows: u16, cell: (u32, u32)) -> (u16, u16) {
+    let (cell_w, cell_h) = cell;
+    let (width, height) = (u64::from(width.max(1)), u64::from(height.max(1)));
+    let (cell_w, cell_h) = (u64::from(cell_w), u64::from(cell_h));
+
+    let mut cols = width.div_ceil(cell_w).clamp(1, u64::from(max_cols));
+    let mut rows = (height * cols * cell_w).div_ceil(width * cell_h).max(1);
+    if rows > u64::from(max_rows) {
+        rows = u64::from(max_rows);
+        cols = (width * rows * cell_h / (height * cell_w)).clamp(1, u64::from(max_cols));
+    }
+    (cols as u16, rows as u16)
+}
+
+/// Size an image to fit `max_cols × max_rows` and draw it in half blocks
+fn render(
+    image: &DynamicImage,
+    (max_cols, max_rows): (u16, u16),
+    cell: (u32, u32),
+    color_mode: ColorMode,
+) -> Rendered {
+    let (cols, rows) = fit(image.width(), image.height(), max_cols, max_rows, cell);
+
+    // Two pixels per cell, one above the other
+    let blocks = image
+        .resize_exact(u32::from(cols), u32::from(rows) * 2, FilterType::Triangle)
+        .to_rgba8();
+    let color = |pixel: &image::Rgba<u8>| {
+        let [r, g, b, _] = pixel.0;
+        match color_mode {
+            ColorMode::Rgb => Color::Rgb(r, g, b),
+            ColorMode::Indexed256 => rgb_to_256(Color::Rgb(r, g, b)),
+        }
+    };
+    let lines = (0..u32::from(rows))
+        .map(|row| {
+            let spans: Vec<Span<'static>> = (0..u32::from(cols))
+                .map(|col| {
+                    let upper = blocks.get_pixel(col, row * 2);
+                    let lower = blocks.get_pixel(col, row * 2 + 1);
+                    match (upper.0[3] >= 128, lower.0[3] >= 128) {
+                        (true, true) => {
+                            Span::styled("▀", Style::default().fg(color(upper)).bg(color(lower)))
+                        }
+                        (true, false) => Span::styled("▀", Style::default().fg(color(upper))),
+                        (false, true) => Span::styled("▄", Style::default().fg(color(lower))),
+                        (false, false) => Span::raw(" "),
+                    }
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect();
+
+    let pixels = image
+        .resize_exact(
+            u32::from(cols) * cell.0,
+            u32::from(rows) * cell.1,
+            FilterType::Triangle,
+        )
+        .to_rgba8();
+    Rendered {
+        cols,
+        rows,
+        pixels,
+        lines,
+        payload: None,
+    }
+}
+
+/// Escape sequence drawing an image at the cursor
+fn encode(protocol: ImageProtocol, rendered: &Rendered) -> String {
+    match protocol {
+        ImageProtocol::Kitty => {
+            let data = BASE64.encode(png(&rendered.pixels));
+            let chunks: Vec<&[u8]> = data.as_bytes().chunks(4096).collect();
+            let mut out = String::new();
+            for (i, chunk) in chunks.iter().enumerate() {
+                let more = u8::from(i + 1 < chunks.len());
+                let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+                if i == 0 {
+                    out.push_str(&format!(
+                        "\x1b_Ga=T,f=100,t=d,c={},r={},C=1,q=2,m={};{}\x1b\\",
+                        rendered.cols, rendered.rows, more, chunk
+                    ));
+                } else {
+                    out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+                }
+            }
+            out
+        }
+        ImageProtocol::Iterm2 => {
+            let data = png(&rendered.pixels);
+            format!(
+                "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=0:{}\x07",
+                data.len(),
+                rendered.cols,
+                rendered.rows,
+                BASE64.encode(&data)
+            )
+        }
+        ImageProtocol::Sixel => sixel(&rendered.pixels),
+        ImageProtocol::HalfBlock => String::new(),
+    }
+}
+
+fn png(pixels: &RgbaImage) -> Vec<u8> {
+    let mut data = Vec::new();
+    // Encoding an in-memory RGBA buffer can't fail
+    let _ = pixels.write_to(&mut io::Cursor::new(&mut data), image::ImageFormat::Png);
+    data
+}
+
+/// Sixel encoding with a 6×6×6 color cube; transparent pixels are skipped
+fn sixel(pixels: &RgbaImage) -> String {
+    let (width, height) = pixels.dimensions();
+    let index = |pixel: &image::Rgba<u8>| -> Option<usize> {
+        let [r, g, b, a] = pixel.0;
+        (a >= 128).then(|| {
+            let level = |v: u8| (usize::from(v) * 5 + 127) / 255;
+            level(r) * 36 + level(g) * 6 + level(b)
+        })
+    };
+
+    let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", width, height);
+    for color in 0..216 {
+        let percent = |level: usize| level * 100 / 5;
+        out.push_str(&format!(
+            "#{};2;{};{};{}",
+            color,
+            percent(color / 36),
+            percent(color / 6 % 6),
+            percent(color % 6)
+        ));
+    }
+
+    for band in (0..height).step_by(6) {
+        let band_rows = (height - band).min(6);
+        // Sixel bits per column for each color used in the band
+        let mut colors: Vec<(usize, Vec<u8>)> = Vec::new();
+        for x in 0..width {
+            for dy in 0..band_rows {
+                let Some(color) = index(pixels.get_pixel(x, band + dy)) else {
+                    continue;
+                };
+                let column = match colors.iter().position(|(c, _)| *c == color) {
+                    Some(pos) => &mut colors[pos].1,
+                    None => {
+                        colors.push((color, vec![0; width as usize]));
+                        &mut colors.last_mut().unwrap().1
+                    }
+                };
+                column[x as usize] |= 1 << dy;
+            }
+        }
+
+        for (color, bits) in &colors {
+            out.push_str(&format!("#{}", color));
+            let mut run: Option<(u8, usize)> = None;
+            for &value in bits.iter().chain(std::iter::once(&u8::MAX)) {
+                match run {
+                    Some((current, count)) if current == value => run = Some((current, count + 1)),
+                    _ => {
+                        if let Some((current, count)) = run {
+                            push_run(&mut out, current, count);
+                        }
+                        run = Some((value, 1));
+                    }
+                }
+            }
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+fn push_run(out: &mut String, bits: u8, count: usize) {
+    let ch = char::from(63 + bits);
+    if count > 3 {
+        out.push_str(&format!("!{}{}", count, ch));
+    } else {
+        out.extend(std::iter::repeat_n(ch, count));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_path() {
+        let base = Path::new("/docs");
+        assert_eq!(
+            local_path("img/a.png", Some(base)),
+            Some(PathBuf::from("/docs/img/a.png"))
+        );
+        assert_eq!(
+            local_path("</abs/b c.png>", Some(base)),
+            Some(PathBuf::from("/abs/b c.png"))
+        );
+        assert_eq!(local_path("https://example.com/a.png", Some(base)), None);
+        assert_eq!(local_path("data:image/png;base64,AAAA", None), None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_safe_root_confines_previews() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = dir.path().canonicalize().unwrap();
+        let root = outside.join("docs");
+        std::fs::create_dir_all(root.join("img")).unwrap();
+        let image = RgbaImage::new(2, 2);
+        image.save(outside.join("secret.png")).unwrap();
+        image.save(root.join("img/a.png")).unwrap();
+        std::os::unix::fs::symlink(outside.join("secret.png"), root.join("link.png")).unwrap();
+
+        let mut previews = ImagePreviews::new("halfblock", ColorMode::Rgb);
+        let preview = |previews: &mut ImagePreviews, src: &str| {
+            previews
+                .preview(src, Some(&root), Some(&root), 20, 10)
+                .map(|lines| lines.is_some())
+        };
+        assert_eq!(preview(&mut previews, "img/a.png"), Ok(true));
+        assert_eq!(preview(&mut previews, "img/../img/a.png"), Ok(true));
+        let secret = outside.join("secret.png");
+        for src in ["../secret.png", secret.to_str().unwrap(), "link.png"] {
+            let error = preview(&mut previews, src).unwrap_err();
+            assert!(error.starts_with("Safe mode"), "{}: {}", src, error);
+        }
+        assert!(
+            previews
+                .preview("../secret.png", Some(&root), None, 20, 10)
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_fit_keeps_aspect_ratio() {
+        // 160×80 px at 8×16 px cells: 20 columns, 5 rows
+        assert_eq!(fit(160, 80, 60, 20, (8, 16)), (20, 5));
+        // Wider than the pane: shrunk to 40 columns
+        assert_eq!(fit(640, 320, 40, 20, (8, 16)), (40, 10));
+        // Taller than allowed: shrunk to 10 rows
+        assert_eq!(fit(160, 640, 60, 10, (8, 16)), (5, 10));
+    }
+
+    #[test]
+    fn test_half_block_rendering() {
+        let mut image = RgbaImage::new(2, 2);
+        image.put_pixel(0, 0, image::Rgba([255, 0, 0, 255]));
+        image.put_pixel(0, 1, image::Rgba([0, 0, 255, 255]));
+        image.put_pixel(1, 0, image::Rgba([0, 0, 0, 0]));
+        image.put_pixel(1, 1, image::Rgba([0, 255, 0, 255]));
+        let rendered = render(
+            &DynamicImage::ImageRgba8(image),
+            (2, 1),
+            (1, 2),
+            ColorMode::Rgb,
+        );
+
+        assert_eq!((rendered.cols, rendered.rows), (2, 1));
+        let spans = &rendered.lines[0].spans;
+        assert_eq!(spans[0].content, "▀");
+        assert_eq!(spans[0].style.fg, Some(Color::Rgb(255, 0, 0)));
+        assert_eq!(spans[0].style.bg, Some(Color::Rgb(0, 0, 255)));
+        assert_eq!(spans[1].content, "▄");
+        assert_eq!(spans[1].style.fg, Some(Color::Rgb(0, 255, 0)));
+    }
+
+    #[test]
+    fn test_sixel_encoding() {
+        let image = RgbaImage::from_pixel(5, 7, image::Rgba([255, 0, 0, 255]));
+        let encoded = sixel(&image);
+
+        assert!(encoded.starts_with("\x1bP0;1;0q\"1;1;5;7#0;2;0;0;0"));
+        // Red is color 180; all six rows set in the first band, one in the second
+        assert!(encoded.ends_with("#180!5~$-#180!5@$-\x1b\\"));
+    }
+}
@@ -4,13 +4,20 @@
 //! - Details blocks (expand/collapse)
 //! - Links (follow/copy)
 //! - Checkboxes (toggle/save)
-//! - Code blocks (copy)
+//! - Code blocks (copy/run)
 //! - Tables (navigate cells)
 //! - Images (view info)
 
+use crate::parser::callouts::{Callout, CalloutFold};
+use crate::parser::embeds::Embed;
+use crate::parser::math::render_display;
+use crate::parser::mermaid::render_mermaid;
 use crate::parser::output::{Block, InlineElement};
 use crate::parser::{Link, LinkTarget};
-use std::collections::HashMap;
+use crate::tui::code_runner::CodeOutput;
+use crate::tui::images::standalone_image;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 
 // Sub-index encoding constants for nested elements within list items
 // Format: item_idx * ITEM_MULTIPLIER + nested_idx * NESTED_MULTIPLIER + TYPE_OFFSET
@@ -40,6 +47,15 @@ pub struct InteractiveState {
     pub element_states: HashMap<ElementId, ElementState>,
     /// Current detail navigation mode (for tables/lists)
     pub detail_mode: Option<DetailMode>,
+    /// Sort/filter/scroll view of tables (never written to the file)
+    pub table_views: HashMap<ElementId, TableView>,
+    /// Output panels of code blocks that have been run
+    pub code_outputs: HashMap<ElementId, CodeOutput>,
+    /// Mermaid blocks switched from their diagram back to source
+    pub diagram_sources: HashSet<ElementId>,
+    /// Lines drawn below a standalone image's alt text (its preview) or an
+    /// embed's label (the embedded note), by image source
+    pub preview_rows: HashMap<String, usize>,
 }
 
 /// Unique identifier for an element
@@ -100,6 +116,11 @@ pub enum ElementType {
         src: String,
         block_idx: usize,
     },
+    /// An Obsidian embed (`![[note#Heading]]`), drawn as the embedded content
+    Embed {
+        target: String,
+        block_idx: usize,
+    },
 }
 
 /// Per-element state
@@ -117,6 +138,98 @@ pub enum ElementState {
     },
 }
 
+/// Sort order applied to a table view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableSort {
+    pub column: usize,
+    pub ascending: bool,
+}
+
+/// How a table is presented in table mode.
+///
+/// This is view state only: the file keeps its row order, and selections are
+/// mapped back to source rows for copying and editing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableView {
+    pub sort: Option<TableSort>,
+    /// Case-insensitive text that a row must contain (in any cell)
+    pub filter: String,
+    /// First column shown when the table is wider than the pane
+    pub col_offset: usize,
+    /// First data row shown when the table is taller than the pane
+    pub row_offset: usize,
+    /// Wrap long cells onto several lines instead of truncating them
+    pub wrap: bool,
+}
+
+impl TableView {
+    /// Source indices of the data rows to display, in display order
+    pub fn visible_rows(&self, rows: &[Vec<String>]) -> Vec<usize> {
+        let needle = self.filter.to_lowercase();
+        let mut visible: Vec<usize> = (0..rows.len())
+            .filter(|&i| {
+                needle.is_empty()
+                    || rows[i]
+                        .iter()
+                        .any(|cell| cell.to_lowercase().contains(&needle))
+            })
+            .collect();
+
+        if let Some(sort) = self.sort {
+            let cell = |i: usize| rows[i].get(sort.column).map(String::as_str).unwrap_or("");
+            visible.sort_by(|&a, &b| {
+                let ordering = compare_cells(cell(a), cell(b));
+                if sort.ascending {
+                    ordering
+                } else {
+                    ordering.reverse()
+                }
+            });
+        }
+
+        visible
+    }
+
+    /// Short description of the active sort and filter, if any
+    pub fn summary(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(sort) = self.sort {
+            let arrow = if sort.ascending { "▲" } else { "▼" };
+            parts.push(format!("Sort:C{}{}", sort.column + 1, arrow));
+        }
+        if !self.filter.is_empty() {
+            parts.push(format!("Filter:\"{}\"", self.filter));
+        }
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+}
+
+/// Compare two table cells, numerically when both look like numbers.
+///
+/// Numbers sort before text; text compares case-insensitively.
+pub fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (parse_cell_number(a), parse_cell_number(b)) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+/// Parse a cell such as `1,024`, `$3.50` or `75%` as a number
+fn parse_cell_number(cell: &str) -> Option<f64> {
+    let trimmed = cell
+        .trim()
+        .trim_start_matches(['$', '€', '£'])
+        .trim_end_matches('%')
+        .trim();
+    let cleaned: String = trimmed.chars().filter(|&c| c != ',' && c != '_').collect();
+    if cleaned.is_empty() {
+        return None;
+    }
+    cleaned.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
 /// Fine-grained navigation mode for complex elements
 #[derive(Debug, Clone)]
 pub enum DetailMode {
@@ -131,6 +244,10 @@ impl InteractiveState {
             current_index: None,
             element_states: HashMap::new(),
             detail_mode: None,
+            table_views: HashMap::new(),
+            code_outputs: HashMap::new(),
+            diagram_sources: HashSet::new(),
+            preview_rows: HashMap::new(),
         }
     }
 
@@ -146,17 +263,35 @@ impl InteractiveState {
             let start_line = current_line;
 
             match block {
+                Block::Details {
+                    summary,
+                    blocks: nested,
+                    ..
+                } if Callout::from_summary(summary).is_some_and(|c| !c.is_foldable()) => {
+                    // Callouts that can't be folded are always shown
+                    current_line += 1 + count_block_lines(nested);
+                }
                 Block::Details {
                     summary,
                     blocks: nested,
                     ..
                 } => {
+                    let callout = Callout::from_summary(summary);
+
                     // Add details block as interactive element
                     let id = ElementId {
                         block_idx,
                         sub_idx: None,
                     };
 
+                    // Initialize state if not exists (`[!note]+` callouts start expanded)
+                    let expanded = callout
+                        .as_ref()
+                        .is_some_and(|c| c.fold == CalloutFold::Open);
+                    self.element_states
+                        .entry(id)
+                        .or_insert(ElementState::Details { expanded });
+
                     // Count lines for this details block
                     let lines = 1 + if self.is_details_expanded(id) {
                         count_block_lines(nested)
@@ -167,17 +302,12 @@ impl InteractiveState {
                     self.elements.push(InteractiveElement {
                         id,
                         element_type: ElementType::Details {
-                            summary: summary.clone(),
+                            summary: callout.map_or_else(|| summary.clone(), |c| c.title),
                             block_idx,
                         },
                         line_range: (start_line, start_line + lines),
                     });
 
-                    // Initialize state if not exists
-                    self.element_states
-                        .entry(id)
-                        .or_insert(ElementState::Details { expanded: false });
-
                     current_line += lines;
                 }
                 Block::Paragraph { inline, .. } => {
@@ -202,6 +332,8 @@ impl InteractiveState {
                                         None
                                     },
                                 }
+                            } else if let Some(label) = url.strip_prefix("footnote:") {
+                                LinkTarget::Footnote(label.to_string())
                             } else if let Some(anchor) = url.strip_prefix('#') {
                                 LinkTarget::Anchor(anchor.to_string())
                             } else if url.starts_with("http://") || url.starts_with("https://") {
@@ -228,7 +360,30 @@ impl InteractiveState {
                             });
                         }
                     }
-                    current_line += 1;
+                    // A paragraph holding just an image gets its preview
+                    // below, an embed (`![[note]]`) the embedded note
+                    let image = standalone_image(inline);
+                    if let Some(src) = image
+                        && let Some(embed) = Embed::from_src(src)
+                        && !embed.is_image()
+                    {
+                        self.elements.push(InteractiveElement {
+                            id: ElementId {
+                                block_idx,
+                                sub_idx: None,
+                            },
+                            element_type: ElementType::Embed {
+                                target: src.trim_start_matches("wikilink:").to_string(),
+                                block_idx,
+                            },
+                            line_range: (current_line, current_line + 1),
+                        });
+                    }
+                    let preview_rows = image
+                        .and_then(|src| self.preview_rows.get(src))
+                        .copied()
+                        .unwrap_or(0);
+                    current_line += 1 + preview_rows;
                 }
                 Block::List { items, .. } => {
                     // Extract checkboxes and links from list items
@@ -277,6 +432,8 @@ impl InteractiveState {
                                             None
                                         },
                                     }
+                                } else if let Some(label) = url.strip_prefix("footnote:") {
+                                    LinkTarget::Footnote(label.to_string())
                                 } else if let Some(anchor) = url.strip_prefix('#') {
                                     LinkTarget::Anchor(anchor.to_string())
                                 } else if url.starts_with("http://") || url.starts_with("https://")
@@ -322,7 +479,8 @@ impl InteractiveState {
                                         sub_idx: Some(nested_base + CODE_BLOCK_OFFSET),
                                     };
 
-                                    let lines = 2 + content.lines().count();
+                                    let lines =
+                                        rendered_code_lines(language.as_deref(), content, false);
 
                                     self.elements.push(InteractiveElement {
                                         id,
@@ -397,7 +555,8 @@ impl InteractiveState {
                         sub_idx: None,
                     };
 
-                    let lines = 2 + content.lines().count(); // +2 for fences
+                    let shows_source = self.diagram_sources.contains(&id);
+                    let lines = rendered_code_lines(language.as_deref(), content, shows_source);
 
                     self.elements.push(InteractiveElement {
                         id,
@@ -410,6 +569,8 @@ impl InteractiveState {
                     });
 
                     current_line += lines;
+                    // Output panel beneath a block that has been run
+                    current_line += self.code_outputs.get(&id).map_or(0, CodeOutput::height);
                 }
                 Block::Table { headers, rows, .. } => {
                     let id = ElementId {
@@ -417,7 +578,12 @@ impl InteractiveState {
                         sub_idx: None,
                     };
 
-                    let lines = 3 + rows.len(); // Top border + header + separator + rows + bottom
+                    // Filtered-out rows aren't rendered
+                    let shown = self
+                        .table_views
+                        .get(&id)
+                        .map_or(rows.len(), |view| view.visible_rows(rows).len());
+                    let lines = 3 + shown; // Top border + header + separator + rows + bottom
 
                     self.elements.push(InteractiveElement {
                         id,
@@ -455,7 +621,7 @@ impl InteractiveState {
                         line_range: (current_line, current_line + 1),
                     });
 
-                    current_line += 1;
+                    current_line += 1 + self.preview_rows.get(src).copied().unwrap_or(0);
                 }
                 _ => {
                     // Non-interactive blocks (still count lines)
@@ -525,6 +691,14 @@ impl InteractiveState {
         )
     }
 
+    /// Expanded state of a details block, if it has been indexed
+    pub fn details_state(&self, id: ElementId) -> Option<bool> {
+        match self.element_states.get(&id) {
+            Some(ElementState::Details { expanded }) => Some(*expanded),
+            _ => None,
+        }
+    }
+
     /// Toggle details block expansion
     pub fn toggle_details(&mut self, id: ElementId) {
         if let Some(ElementState::Details { expanded }) = self.element_states.get_mut(&id) {
@@ -582,6 +756,12 @@ impl InteractiveState {
                         position
                     )
                 }
+                ElementType::Embed { .. } => {
+                    format!(
+                        "[INTERACTIVE] Embed({}) | Enter:Open source Tab:Next Esc:Exit",
+                        position
+                    )
+                }
             }
         } else if self.elements.is_empty() {
             "[INTERACTIVE] No interactive elements in this section | Esc:Exit".to_string()
@@ -634,6 +814,9 @@ impl InteractiveState {
     pub fn exit(&mut self) {
         self.current_index = None;
         self.detail_mode = None;
+        self.table_views.clear();
+        self.code_outputs.clear();
+        self.diagram_sources.clear();
     }
 
     /// Check if in interactive mode
@@ -674,10 +857,17 @@ impl InteractiveState {
                     selected_col,
                 }) = self.element_states.get(&id)
                 {
+                    let view = self
+                        .table_views
+                        .get(&id)
+                        .and_then(|view| view.summary())
+                        .map(|summary| format!(" {}", summary))
+                        .unwrap_or_default();
                     return format!(
-                        "[TABLE] Cell({},{}) | hjkl:Move y:Copy Y:CopyRow r:CopyTable Esc:Exit",
+                        "[TABLE] Cell({},{}){} | hjkl:Move s:Sort /:Filter w:Wrap y:Copy Esc:Exit",
                         selected_row + 1,
-                        selected_col + 1
+                        selected_col + 1,
+                        view
                     );
                 }
             }
@@ -685,6 +875,58 @@ impl InteractiveState {
         "[TABLE] hjkl:Move y:Copy Esc:Exit".to_string()
     }
 
+    /// Element id of the table being navigated, if in table mode
+    fn current_table_id(&self) -> Option<ElementId> {
+        match &self.detail_mode {
+            Some(DetailMode::Table { element_idx }) => {
+                self.elements.get(*element_idx).map(|element| element.id)
+            }
+            _ => None,
+        }
+    }
+
+    /// View state of a table, if it has been sorted, filtered or scrolled
+    pub fn table_view(&self, id: ElementId) -> Option<&TableView> {
+        self.table_views.get(&id)
+    }
+
+    /// View state of the table being navigated (created on first use)
+    pub fn current_table_view_mut(&mut self) -> Option<&mut TableView> {
+        let id = self.current_table_id()?;
+        Some(self.table_views.entry(id).or_default())
+    }
+
+    /// Set the selected cell of the table being navigated (row 0 is the header)
+    pub fn set_table_position(&mut self, row: usize, col: usize) {
+        if let Some(id) = self.current_table_id()
+            && let Some(ElementState::Table {
+                selected_row,
+                selected_col,
+            }) = self.element_states.get_mut(&id)
+        {
+            *selected_row = row;
+            *selected_col = col;
+        }
+    }
+
+    /// Map the selected display row to its row in the file (0 is the header)
+    pub fn table_source_row(&self, rows: &[Vec<String>]) -> Option<usize> {
+        let (row, _) = self.get_table_position()?;
+        if row == 0 {
+            return Some(0);
+        }
+        match self
+            .current_table_id()
+            .and_then(|id| self.table_views.get(&id))
+        {
+            Some(view) => view
+                .visible_rows(rows)
+                .get(row - 1)
+                .map(|source| source + 1),
+            None => (row <= rows.len()).then_some(row),
+        }
+    }
+
     /// Move to next cell (right)
     pub fn table_move_right(&mut self, cols: usize) {
         if let Some(DetailMode::Table { element_idx }) = &self.detail_mode {
@@ -753,49 +995,18 @@ impl InteractiveState {
 
     /// Get the currently selected table cell content
     pub fn get_table_cell(&self, headers: &[String], rows: &[Vec<String>]) -> Option<String> {
-        if let Some(DetailMode::Table { element_idx }) = &self.detail_mode {
-            if let Some(element) = self.elements.get(*element_idx) {
-                let id = element.id;
-                if let Some(ElementState::Table {
-                    selected_row,
-                    selected_col,
-                }) = self.element_states.get(&id)
-                {
-                    if *selected_row == 0 {
-                        // Header row
-                        return headers.get(*selected_col).cloned();
-                    } else {
-                        // Data row
-                        let data_row = *selected_row - 1;
-                        return rows
-                            .get(data_row)
-                            .and_then(|row| row.get(*selected_col).cloned());
-                    }
-                }
-            }
-        }
-        None
+        let (_, col) = self.get_table_position()?;
+        self.get_table_row(headers, rows)?.get(col).cloned()
     }
 
     /// Get the currently selected table row
     pub fn get_table_row(&self, headers: &[String], rows: &[Vec<String>]) -> Option<Vec<String>> {
-        if let Some(DetailMode::Table { element_idx }) = &self.detail_mode {
-            if let Some(element) = self.elements.get(*element_idx) {
-                let id = element.id;
-                if let Some(ElementState::Table { selected_row, .. }) = self.element_states.get(&id)
-                {
-                    if *selected_row == 0 {
-                        // Header row
-                        return Some(headers.to_vec());
-                    } else {
-                        // Data row
-                        let data_row = *selected_row - 1;
-                        return rows.get(data_row).cloned();
-                    }
-                }
-            }
+        match self.table_source_row(rows)? {
+            // Header row
+            0 => Some(headers.to_vec()),
+            // Data row
+            row => rows.get(row - 1).cloned(),
         }
-        None
     }
 
     /// Get the selected cell position (row, col)
@@ -822,6 +1033,17 @@ impl Default for InteractiveState {
     }
 }
 
+/// Lines a code block takes on screen: math and Mermaid blocks are drawn
+/// (unless `shows_source`), other blocks show their source between fences
+fn rendered_code_lines(language: Option<&str>, content: &str, shows_source: bool) -> usize {
+    let drawn = match language {
+        Some("math") => render_display(content).map(|lines| lines.len()),
+        Some("mermaid") if !shows_source => render_mermaid(content).map(|lines| lines.len()),
+        _ => None,
+    };
+    drawn.unwrap_or(2 + content.lines().count())
+}
+
 /// Count lines for nested blocks
 fn count_block_lines(blocks: &[Block]) -> usize {
     blocks.iter().map(count_single_block_lines).sum()
@@ -832,7 +1054,9 @@ fn count_single_block_lines(block: &Block) -> usize {
     match block {
         Block::Heading { .. } => 1,
         Block::Paragraph { .. } => 1,
-        Block::Code { content, .. } => 2 + content.lines().count(),
+        Block::Code {
+            language, content, ..
+        } => rendered_code_lines(language.as_deref(), content, false),
         Block::List { items, .. } => items.len(),
         Block::Blockquote { blocks, .. } => count_block_lines(blocks),
         Block::Table { rows, .. } => 3 + rows.len(),
@@ -923,4 +1147,76 @@ fn main() {}
             state.elements.len()
         );
     }
+
+    fn table_rows(cells: &[&[&str]]) -> Vec<Vec<String>> {
+        cells
+            .iter()
+            .map(|row| row.iter().map(|c| c.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_table_view_sorts_numerically_and_filters() {
+        let rows = table_rows(&[
+            &["api", "1,200"],
+            &["auth", "90"],
+            &["billing", "n/a"],
+            &["cache", "$300"],
+        ]);
+        let mut view = TableView {
+            sort: Some(TableSort {
+                column: 1,
+                ascending: true,
+            }),
+            ..TableView::default()
+        };
+
+        // Numbers compare by value (not text) and sort before non-numbers
+        assert_eq!(view.visible_rows(&rows), vec![1, 3, 0, 2]);
+
+        view.sort = Some(TableSort {
+            column: 1,
+            ascending: false,
+        });
+        assert_eq!(view.visible_rows(&rows), vec![2, 0, 3, 1]);
+
+        view.filter = "A".to_string();
+        assert_eq!(view.visible_rows(&rows), vec![2, 0, 3, 1]);
+        view.filter = "au".to_string();
+        assert_eq!(view.visible_rows(&rows), vec![1]);
+        assert_eq!(view.summary(), Some("Sort:C2▼ Filter:\"au\"".to_string()));
+    }
+
+    #[test]
+    fn test_table_selection_maps_to_source_row() {
+        let markdown = "| Name | Size |\n|------|------|\n| b | 20 |\n| a | 3 |\n| c | 100 |\n";
+        let blocks = parse_content(markdown, 0);
+        let mut state = InteractiveState::new();
+        state.index_elements(&blocks);
+        state.current_index = Some(0);
+        state.enter_table_mode().unwrap();
+
+        let headers = vec!["Name".to_string(), "Size".to_string()];
+        let rows = table_rows(&[&["b", "20"], &["a", "3"], &["c", "100"]]);
+        state.current_table_view_mut().unwrap().sort = Some(TableSort {
+            column: 1,
+            ascending: false,
+        });
+
+        // Display row 1 is the largest size, which is the file's third row
+        state.set_table_position(1, 0);
+        assert_eq!(state.table_source_row(&rows), Some(3));
+        assert_eq!(state.get_table_cell(&headers, &rows), Some("c".to_string()));
+
+        state.set_table_position(0, 1);
+        assert_eq!(state.table_source_row(&rows), Some(0));
+        assert_eq!(
+            state.get_table_cell(&headers, &rows),
+            Some("Size".to_string())
+        );
+
+        // The view is only kept for the interactive session
+        state.exit();
+        assert!(state.table_views.is_empty());
+    }
 }
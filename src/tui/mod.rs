@@ -1,14 +1,22 @@
 mod app;
+mod code_runner;
+mod command_line;
+mod conflict;
+mod edit_journal;
+mod graph_view;
 mod help_text;
+mod images;
 mod interactive;
 mod syntax;
+mod table_edit;
+mod tag_browser;
 pub mod terminal_compat;
 pub mod theme;
 pub mod tty; // Public module for TTY handling
 mod ui;
 mod watcher;
 
-pub use app::{ActionResult, App};
+pub use app::{AccessMode, ActionResult, App};
 pub use interactive::InteractiveState;
 pub use terminal_compat::{ColorMode, TerminalCapabilities};
 pub use theme::ThemeName;
@@ -71,8 +79,20 @@ pub fn run(terminal: &mut DefaultTerminal, app: App) -> Result<()> {
     }
 
     loop {
+        // Stream output from code blocks run in interactive mode
+        app.poll_code_runs();
+
         terminal.draw(|frame| ui::render(frame, &mut app))?;
 
+        // Image graphics are written past ratatui's buffer; sixel and iTerm2
+        // images can only be taken off screen by repainting everything
+        if app.images.needs_repaint() {
+            terminal.clear()?;
+            app.images.reset();
+            terminal.draw(|frame| ui::render(frame, &mut app))?;
+        }
+        app.images.flush(&mut stdout())?;
+
         // Update file watcher if the current file changed (e.g., via navigation)
         if app.file_path_changed {
             app.file_path_changed = false;
@@ -87,6 +107,7 @@ pub fn run(terminal: &mut DefaultTerminal, app: App) -> Result<()> {
                 .file_name()
                 .and_then(|n| n.to_str())
                 .unwrap_or("file");
+            app.images.reset();
             match run_editor(terminal, &file_path, None) {
                 Ok(_) => {
                     app.status_message = Some(format!("✓ Opened {} in editor", filename));
@@ -107,6 +128,8 @@ pub fn run(terminal: &mut DefaultTerminal, app: App) -> Result<()> {
                 app.suppress_file_watch = false;
                 if let Some(ref mut watcher) = file_watcher {
                     watcher.check_for_changes(); // Drain events, ignore result
+                    // Atomic saves replace the file, so re-arm the watch on the new inode
+                    let _ = watcher.watch(&app.current_file_path);
                 }
             } else if let Some(ref mut watcher) = file_watcher {
                 if watcher.check_for_changes() {
@@ -152,133 +175,53 @@ pub fn run(terminal: &mut DefaultTerminal, app: App) -> Result<()> {
                 if !handled {
                     // Try to get an action from the keybinding system
                     if let Some(action) = app.get_action_for_key(key.code, key.modifiers) {
-                        // Special handling for CommandPalette confirm - it may return Quit
-                        if action == Action::ConfirmAction
+                        // CommandPalette confirm runs the command line, which may quit or open an editor
+                        let result = if action == Action::ConfirmAction
                             && app.mode == app::AppMode::CommandPalette
                         {
-                            if app.execute_selected_command() {
-                                return Ok(()); // Quit command executed
-                            }
+                            app.execute_selected_command()
                         } else {
-                            match app.execute_action(action) {
-                                ActionResult::Quit => return Ok(()),
-                                ActionResult::RunEditor(path, line) => {
-                                    match run_editor(terminal, &path, line) {
-                                        Ok(_) => {
-                                            if let Err(e) = app.reload_current_file() {
-                                                app.status_message =
-                                                    Some(format!("✗ Failed to reload: {}", e));
-                                            } else {
-                                                app.status_message = Some(
-                                                    "✓ File reloaded after editing".to_string(),
-                                                );
-                                            }
-                                            app.update_content_metrics();
-                                        }
-                                        Err(e) => {
+                            app.execute_action(action)
+                        };
+
+                        match result {
+                            ActionResult::Quit => return Ok(()),
+                            ActionResult::RunEditor(path, line) => {
+                                app.images.reset();
+                                match run_editor(terminal, &path, line) {
+                                    Ok(_) => {
+                                        if let Err(e) = app.reload_current_file() {
                                             app.status_message =
-                                                Some(format!("✗ Editor failed: {}", e));
+                                                Some(format!("✗ Failed to reload: {}", e));
+                                        } else {
+                                            app.status_message =
+                                                Some("✓ File reloaded after editing".to_string());
                                         }
+                                        app.update_content_metrics();
+                                    }
+                                    Err(e) => {
+                                        app.status_message =
+                                            Some(format!("✗ Editor failed: {}", e));
                                     }
                                 }
-                                ActionResult::Continue => {}
-                            }
-                            KeyCode::Char('y') => {
-                                // Copy cell
-                                if let Err(e) = app.copy_table_cell() {
-                                    app.status_message = Some(format!("✗ Error: {}", e));
-                                }
-                            }
-                            KeyCode::Char('Y') => {
-                                // Copy row
-                                if let Err(e) = app.copy_table_row() {
-                                    app.status_message = Some(format!("✗ Error: {}", e));
-                                }
-                            }
-                            KeyCode::Char('r') => {
-                                // Copy table as markdown
-                                if let Err(e) = app.copy_table_markdown() {
-                                    app.status_message = Some(format!("✗ Error: {}", e));
-                                }
-                            }
-                            KeyCode::Enter => {
-                                // Enter cell edit mode
-                                if let Err(e) = app.enter_cell_edit_mode() {
-                                    app.status_message = Some(format!("✗ Error: {}", e));
-                                }
-                            }
-                            KeyCode::Char('q') => return Ok(()),
-                            _ => {}
-                        }
-                    } else {
-                        // Regular interactive mode
-                        // Clear status message on most key presses
-                        if key.code != KeyCode::Tab {
-                            app.status_message = None;
-                        }
-
-                        match key.code {
-                            KeyCode::Esc | KeyCode::Char('i') => app.exit_interactive_mode(),
-                            KeyCode::Tab => {
-                                app.interactive_state.next();
-                                // Auto-scroll to keep element in view
-                                app.scroll_to_interactive_element(20);
-                                // Update status bar
-                                app.status_message = Some(app.interactive_state.status_text());
-                            }
-                            KeyCode::BackTab => {
-                                app.interactive_state.previous();
-                                // Auto-scroll to keep element in view
-                                app.scroll_to_interactive_element(20);
-                                // Update status bar
-                                app.status_message = Some(app.interactive_state.status_text());
-                            }
-                            KeyCode::Char('l') | KeyCode::Down => {
-                                app.interactive_state.next();
-                                // Auto-scroll to keep element in view
-                                app.scroll_to_interactive_element(20);
-                                app.status_message = Some(app.interactive_state.status_text());
-                            }
-                            KeyCode::Char('k') | KeyCode::Up => {
-                                app.interactive_state.previous();
-                                // Auto-scroll to keep element in view
-                                app.scroll_to_interactive_element(20);
-                                app.status_message = Some(app.interactive_state.status_text());
-                            }
-                            KeyCode::Enter | KeyCode::Char(' ') => {
-                                // Activate the selected element
-                                if let Err(e) = app.activate_interactive_element() {
-                                    app.status_message = Some(format!("✗ Error: {}", e));
-                                }
-                                // Update content metrics after actions that might change content
-                                app.update_content_metrics();
                             }
-                            KeyCode::Char('y') => {
-                                // Copy action - delegate to activate for code/image elements
-                                if let Err(e) = app.activate_interactive_element() {
-                                    app.status_message = Some(format!("✗ Error: {}", e));
+                            ActionResult::EditCodeBlock(path) => {
+                                app.images.reset();
+                                match run_editor(terminal, &path, None) {
+                                    Ok(_) => {
+                                        if let Err(e) = app.finish_code_block_edit() {
+                                            app.status_message = Some(format!("✗ {}", e));
+                                        }
+                                        app.update_content_metrics();
+                                    }
+                                    Err(e) => {
+                                        app.cancel_code_block_edit();
+                                        app.status_message =
+                                            Some(format!("✗ Editor failed: {}", e));
+                                    }
                                 }
                             }
-                            // Page motions in interactive mode
-                            KeyCode::Char('d') | KeyCode::PageDown => {
-                                app.scroll_page_down_interactive();
-                            }
-                            KeyCode::Char('u') | KeyCode::PageUp => {
-                                app.scroll_page_up_interactive();
-                            }
-                            // Document search from interactive mode
-                            KeyCode::Char('/') => {
-                                app.enter_doc_search();
-                            }
-                            // Navigate search matches while in interactive mode
-                            KeyCode::Char('n') if !app.doc_search_matches.is_empty() => {
-                                app.next_doc_match();
-                            }
-                            KeyCode::Char('N') if !app.doc_search_matches.is_empty() => {
-                                app.prev_doc_match();
-                            }
-                            KeyCode::Char('q') => return Ok(()),
-                            _ => {}
+                            ActionResult::Continue => {}
                         }
                     }
                 }
@@ -294,7 +237,8 @@ fn handle_text_input(
     code: KeyCode,
     modifiers: crossterm::event::KeyModifiers,
 ) -> bool {
-    // Text input modes: outline search, doc search, link search, command palette, cell edit
+    // Text input modes: outline search, doc search, link search, command palette,
+    // table filter, cell edit
 
     // Outline search mode - only handle input when active
     if app.show_search && app.outline_search_active {
@@ -336,11 +280,27 @@ fn handle_text_input(
         }
     }
 
-    // Command palette input mode
-    if app.mode == app::AppMode::CommandPalette {
-        if let KeyCode::Char(c) = code {
-            app.command_palette_input(c);
-            return true;
+    // Command palette input mode (Ctrl combinations are history/navigation keys)
+    if app.mode == app::AppMode::CommandPalette
+        && !modifiers.contains(crossterm::event::KeyModifiers::CONTROL)
+        && let KeyCode::Char(c) = code
+    {
+        app.command_palette_input(c);
+        return true;
+    }
+
+    // Table filter input mode
+    if app.mode == app::AppMode::TableFilter {
+        match code {
+            KeyCode::Char('u') if modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => {
+                app.clear_table_filter_input();
+                return true;
+            }
+            KeyCode::Char(c) => {
+                app.table_filter_input(c);
+                return true;
+            }
+            _ => {}
         }
     }
 
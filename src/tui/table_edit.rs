@@ -0,0 +1,525 @@
+//! Structural editing of markdown tables.
+//!
+//! A table is located in the file source, parsed into a [`MarkdownTable`],
+//! changed (rows and columns inserted, deleted or moved, alignment set) and
+//! then written back as a whole, with the pipes aligned and the alignment
+//! markers of the delimiter row preserved.
+
+use crate::parser::output::Alignment;
+use crate::parser::utils::FenceTracker;
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
+
+/// A markdown table as written in the source (cells keep their escapes)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownTable {
+    pub headers: Vec<String>,
+    pub alignments: Vec<Alignment>,
+    pub rows: Vec<Vec<String>>,
+    /// Indentation of the table's lines
+    indent: String,
+}
+
+impl MarkdownTable {
+    /// Parse the source of a table (header, delimiter row and body rows)
+    pub fn parse(source: &str) -> Option<Self> {
+        let mut lines = source.lines().filter(|line| !line.trim().is_empty());
+        let header_line = lines.next()?;
+        let alignments: Vec<Alignment> = parse_delimiter_row(lines.next()?)?;
+        let indent = header_line[..header_line.len() - header_line.trim_start().len()].to_string();
+
+        let width = alignments.len();
+        let fit = |mut cells: Vec<String>| {
+            cells.resize(width, String::new());
+            cells
+        };
+
+        Some(Self {
+            headers: fit(split_row(header_line)),
+            rows: lines.map(|line| fit(split_row(line))).collect(),
+            alignments,
+            indent,
+        })
+    }
+
+    /// Build a table from records (header first), padding ragged records.
+    ///
+    /// Cells are used as-is, so they must already be escaped for markdown.
+    pub fn from_records(mut records: Vec<Vec<String>>) -> Option<Self> {
+        let width = records.iter().map(Vec::len).max().filter(|&w| w > 0)?;
+        for record in &mut records {
+            record.resize(width, String::new());
+        }
+
+        let mut headers = records.remove(0);
+        for (i, header) in headers.iter_mut().enumerate() {
+            if header.is_empty() {
+                *header = format!("Column {}", i + 1);
+            }
+        }
+
+        Some(Self {
+            headers,
+            alignments: vec![Alignment::None; width],
+            rows: records,
+            indent: String::new(),
+        })
+    }
+
+    /// Take the indentation and column alignments of the table this one replaces
+    pub fn with_layout_of(mut self, existing: &MarkdownTable) -> Self {
+        self.indent = existing.indent.clone();
+        for (alignment, existing) in self.alignments.iter_mut().zip(&existing.alignments) {
+            *alignment = *existing;
+        }
+        self
+    }
+
+    pub fn column_count(&self) -> usize {
+        self.headers.len()
+    }
+
+    /// Insert an empty row at `index` (0 is the first data row)
+    pub fn insert_row(&mut self, index: usize) {
+        let index = index.min(self.rows.len());
+        self.rows
+            .insert(index, vec![String::new(); self.column_count()]);
+    }
+
+    pub fn delete_row(&mut self, index: usize) -> Result<(), String> {
+        if index >= self.rows.len() {
+            return Err("No data row selected".to_string());
+        }
+        self.rows.remove(index);
+        Ok(())
+    }
+
+    /// Swap data row `index` with its neighbour; returns the row's new index
+    pub fn move_row(&mut self, index: usize, down: bool) -> Result<usize, String> {
+        let target = neighbour(index, down, self.rows.len())
+            .ok_or_else(|| "Row can't move further".to_string())?;
+        self.rows.swap(index, target);
+        Ok(target)
+    }
+
+    /// Insert a column named `header` at `index`
+    pub fn insert_column(&mut self, index: usize, header: &str) {
+        let index = index.min(self.column_count());
+        self.headers.insert(index, header.to_string());
+        self.alignments.insert(index, Alignment::None);
+        for row in &mut self.rows {
+            row.insert(index, String::new());
+        }
+    }
+
+    pub fn delete_column(&mut self, index: usize) -> Result<(), String> {
+        if index >= self.column_count() {
+            return Err("No column selected".to_string());
+        }
+        if self.column_count() == 1 {
+            return Err("Can't delete the only column".to_string());
+        }
+        self.headers.remove(index);
+        self.alignments.remove(index);
+        for row in &mut self.rows {
+            row.remove(index);
+        }
+        Ok(())
+    }
+
+    /// Swap column `index` with its neighbour; returns the column's new index
+    pub fn move_column(&mut self, index: usize, right: bool) -> Result<usize, String> {
+        let target = neighbour(index, right, self.column_count())
+            .ok_or_else(|| "Column can't move further".to_string())?;
+        self.headers.swap(index, target);
+        self.alignments.swap(index, target);
+        for row in &mut self.rows {
+            row.swap(index, target);
+        }
+        Ok(target)
+    }
+
+    /// Cycle a column's alignment: left, center, right, none; returns the new one
+    pub fn cycle_alignment(&mut self, index: usize) -> Option<Alignment> {
+        let alignment = self.alignments.get_mut(index)?;
+        *alignment = match alignment {
+            Alignment::Left => Alignment::Center,
+            Alignment::Center => Alignment::Right,
+            Alignment::Right => Alignment::None,
+            Alignment::None => Alignment::Left,
+        };
+        Some(*alignment)
+    }
+
+    /// Emit the table as markdown with aligned pipes (no trailing newline)
+    pub fn to_markdown(&self) -> String {
+        let widths: Vec<usize> = (0..self.column_count())
+            .map(|col| {
+                self.rows
+                    .iter()
+                    .filter_map(|row| row.get(col))
+                    .chain(std::iter::once(&self.headers[col]))
+                    .map(|cell| cell.width())
+                    .max()
+                    .unwrap_or(0)
+                    .max(3) // Room for the delimiter dashes
+            })
+            .collect();
+
+        let delimiter: Vec<String> = widths
+            .iter()
+            .zip(&self.alignments)
+            .map(|(&width, alignment)| match alignment {
+                Alignment::Left => format!(":{}", "-".repeat(width - 1)),
+                Alignment::Center => format!(":{}:", "-".repeat(width - 2)),
+                Alignment::Right => format!("{}:", "-".repeat(width - 1)),
+                Alignment::None => "-".repeat(width),
+            })
+            .collect();
+
+        let mut lines = vec![self.format_row(&self.headers, &widths)];
+        lines.push(format!("{}| {} |", self.indent, delimiter.join(" | ")));
+        for row in &self.rows {
+            lines.push(self.format_row(row, &widths));
+        }
+        lines.join("\n")
+    }
+
+    fn format_row(&self, cells: &[String], widths: &[usize]) -> String {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .zip(&self.alignments)
+            .map(|((cell, &width), alignment)| {
+                let pad = width.saturating_sub(cell.width());
+                match alignment {
+                    Alignment::Right => format!("{}{}", " ".repeat(pad), cell),
+                    Alignment::Center => format!(
+                        "{}{}{}",
+                        " ".repeat(pad / 2),
+                        cell,
+                        " ".repeat(pad - pad / 2)
+                    ),
+                    Alignment::Left | Alignment::None => format!("{}{}", cell, " ".repeat(pad)),
+                }
+            })
+            .collect();
+        format!("{}| {} |", self.indent, padded.join(" | "))
+    }
+}
+
+/// A structural change applied at the selected cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableOp {
+    InsertRow { below: bool },
+    DeleteRow,
+    MoveRow { down: bool },
+    InsertColumn { right: bool },
+    DeleteColumn,
+    MoveColumn { right: bool },
+    CycleAlignment,
+}
+
+impl TableOp {
+    /// Apply the change at (`row`, `col`), where row 0 is the header.
+    ///
+    /// Returns the cell to select afterwards and a description of the change.
+    pub fn apply(
+        self,
+        table: &mut MarkdownTable,
+        row: usize,
+        col: usize,
+    ) -> Result<((usize, usize), String), String> {
+        let data_row = row.checked_sub(1);
+        let header_error = |what: &str| format!("Can't {} the header row", what);
+
+        match self {
+            TableOp::InsertRow { below } => {
+                let at = match (data_row, below) {
+                    (Some(r), true) => r + 1,
+                    (Some(r), false) => r,
+                    (None, true) => 0,
+                    (None, false) => return Err(header_error("add a row above")),
+                };
+                table.insert_row(at);
+                Ok(((at + 1, col), "Add row".to_string()))
+            }
+            TableOp::DeleteRow => {
+                let r = data_row.ok_or_else(|| header_error("delete"))?;
+                table.delete_row(r)?;
+                let next = if r < table.rows.len() { r + 1 } else { r };
+                Ok(((next, col), format!("Delete row {}", row)))
+            }
+            TableOp::MoveRow { down } => {
+                let r = data_row.ok_or_else(|| header_error("move"))?;
+                let target = table.move_row(r, down)?;
+                let direction = if down { "down" } else { "up" };
+                Ok(((target + 1, col), format!("Move row {}", direction)))
+            }
+            TableOp::InsertColumn { right } => {
+                let at = if right { col + 1 } else { col };
+                table.insert_column(at, &format!("Column {}", at + 1));
+                Ok(((row, at), "Add column".to_string()))
+            }
+            TableOp::DeleteColumn => {
+                let header = table.headers.get(col).cloned().unwrap_or_default();
+                table.delete_column(col)?;
+                let next = col.min(table.column_count() - 1);
+                Ok(((row, next), format!("Delete column '{}'", header)))
+            }
+            TableOp::MoveColumn { right } => {
+                let target = table.move_column(col, right)?;
+                let direction = if right { "right" } else { "left" };
+                Ok(((row, target), format!("Move column {}", direction)))
+            }
+            TableOp::CycleAlignment => {
+                let alignment = table
+                    .cycle_alignment(col)
+                    .ok_or_else(|| "No column selected".to_string())?;
+                let name = match alignment {
+                    Alignment::Left => "left",
+                    Alignment::Center => "center",
+                    Alignment::Right => "right",
+                    Alignment::None => "default",
+                };
+                Ok(((row, col), format!("Align column {}", name)))
+            }
+        }
+    }
+
+    /// Where `column` ends up after applying this change at column `col`
+    /// (`None` if it was deleted)
+    pub fn map_column(self, col: usize, column: usize) -> Option<usize> {
+        match self {
+            TableOp::InsertColumn { right } => {
+                let at = if right { col + 1 } else { col };
+                Some(if column >= at { column + 1 } else { column })
+            }
+            TableOp::DeleteColumn if column == col => None,
+            TableOp::DeleteColumn if column > col => Some(column - 1),
+            TableOp::MoveColumn { right } => {
+                let target = if right { col + 1 } else { col.wrapping_sub(1) };
+                Some(match column {
+                    c if c == col => target,
+                    c if c == target => col,
+                    c => c,
+                })
+            }
+            _ => Some(column),
+        }
+    }
+}
+
+/// Index of the neighbour of `index` in a list of `len` items, if any
+fn neighbour(index: usize, forward: bool, len: usize) -> Option<usize> {
+    if forward {
+        (index + 1 < len).then_some(index + 1)
+    } else {
+        index.checked_sub(1).filter(|_| index < len)
+    }
+}
+
+/// Split a table row into trimmed cells, honouring `\|` escapes
+fn split_row(line: &str) -> Vec<String> {
+    let trimmed = line.trim();
+    let trimmed = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let trimmed = match trimmed.strip_suffix('|') {
+        Some(rest) if !rest.ends_with('\\') => rest,
+        _ => trimmed,
+    };
+
+    let mut cells = Vec::new();
+    let mut current = String::new();
+    let mut escaped = false;
+    for c in trimmed.chars() {
+        if c == '|' && !escaped {
+            cells.push(current.trim().to_string());
+            current.clear();
+        } else {
+            current.push(c);
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    cells.push(current.trim().to_string());
+    cells
+}
+
+/// Parse a delimiter row such as `| :--- | :-: | --: |`
+fn parse_delimiter_row(line: &str) -> Option<Vec<Alignment>> {
+    if !line.contains('-') {
+        return None;
+    }
+    split_row(line)
+        .iter()
+        .map(|cell| {
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+                return None;
+            }
+            Some(match (cell.starts_with(':'), cell.ends_with(':')) {
+                (true, true) => Alignment::Center,
+                (true, false) => Alignment::Left,
+                (false, true) => Alignment::Right,
+                (false, false) => Alignment::None,
+            })
+        })
+        .collect()
+}
+
+/// Byte ranges of the tables in `content`, in document order.
+///
+/// Each range covers the table's lines without the final newline. Tables
+/// inside fenced code blocks are ignored.
+pub fn find_tables(content: &str) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let text = line.trim_end_matches(['\n', '\r']);
+        lines.push((offset, text));
+        offset += line.len();
+    }
+
+    let mut tables = Vec::new();
+    let mut fences = FenceTracker::default();
+    let mut i = 0;
+    while i < lines.len() {
+        let (start, text) = lines[i];
+        if fences.line(text).is_code() {
+            i += 1;
+            continue;
+        }
+
+        let is_table = text.contains('|')
+            && lines
+                .get(i + 1)
+                .is_some_and(|(_, next)| parse_delimiter_row(next).is_some());
+        if !is_table {
+            i += 1;
+            continue;
+        }
+
+        let mut end = i + 1;
+        while lines
+            .get(end + 1)
+            .is_some_and(|(_, next)| next.contains('|') && !next.trim().is_empty())
+        {
+            end += 1;
+        }
+        for (_, row) in &lines[i + 1..=end] {
+            fences.line(row);
+        }
+        let (last_start, last_text) = lines[end];
+        tables.push(start..last_start + last_text.len());
+        i = end + 1;
+    }
+    tables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: &str = "| Name | Status | Count |\n|:-----|:------:|------:|\n| api | ok | 12 |\n| db | down \\| degraded | 3 |";
+
+    #[test]
+    fn test_parse_and_emit_aligned() {
+        let table = MarkdownTable::parse(TABLE).unwrap();
+        assert_eq!(
+            table.alignments,
+            vec![Alignment::Left, Alignment::Center, Alignment::Right]
+        );
+        assert_eq!(table.rows[1][1], "down \\| degraded");
+
+        assert_eq!(
+            table.to_markdown(),
+            "| Name |      Status      | Count |\n\
+             | :--- | :--------------: | ----: |\n\
+             | api  |        ok        |    12 |\n\
+             | db   | down \\| degraded |     3 |"
+        );
+    }
+
+    #[test]
+    fn test_row_and_column_operations() {
+        let mut table = MarkdownTable::parse(TABLE).unwrap();
+
+        table.insert_row(1);
+        assert_eq!(table.rows[1], vec!["", "", ""]);
+        table.delete_row(1).unwrap();
+        assert_eq!(table.move_row(0, true), Ok(1));
+        assert_eq!(table.rows[0][0], "db");
+        assert!(table.move_row(1, true).is_err());
+
+        table.insert_column(1, "Owner");
+        assert_eq!(table.headers, vec!["Name", "Owner", "Status", "Count"]);
+        assert_eq!(table.rows[0].len(), 4);
+        assert_eq!(table.move_column(3, false), Ok(2));
+        assert_eq!(table.headers, vec!["Name", "Owner", "Count", "Status"]);
+        assert_eq!(table.alignments[2], Alignment::Right);
+        table.delete_column(1).unwrap();
+        assert_eq!(table.headers, vec!["Name", "Count", "Status"]);
+
+        assert_eq!(table.cycle_alignment(0), Some(Alignment::Center));
+        assert_eq!(table.cycle_alignment(1), Some(Alignment::None));
+    }
+
+    #[test]
+    fn test_ops_keep_selection_on_the_changed_cell() {
+        let mut table = MarkdownTable::parse(TABLE).unwrap();
+
+        // Row 0 is the header
+        assert!(TableOp::DeleteRow.apply(&mut table, 0, 0).is_err());
+        let (cell, _) = TableOp::InsertRow { below: true }
+            .apply(&mut table, 0, 1)
+            .unwrap();
+        assert_eq!(cell, (1, 1));
+        assert_eq!(table.rows[0], vec!["", "", ""]);
+
+        let (cell, label) = TableOp::MoveColumn { right: true }
+            .apply(&mut table, 2, 0)
+            .unwrap();
+        assert_eq!((cell, label.as_str()), ((2, 1), "Move column right"));
+        assert_eq!(
+            TableOp::MoveColumn { right: true }.map_column(0, 1),
+            Some(0)
+        );
+        assert_eq!(TableOp::DeleteColumn.map_column(1, 1), None);
+        assert_eq!(
+            TableOp::InsertColumn { right: false }.map_column(1, 2),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn test_from_records_pads_ragged_rows() {
+        let records = vec![
+            vec!["Name".to_string(), String::new()],
+            vec!["a".to_string(), "1".to_string(), "extra".to_string()],
+            vec!["b".to_string()],
+        ];
+        let existing = MarkdownTable::parse(TABLE).unwrap();
+        let table = MarkdownTable::from_records(records)
+            .unwrap()
+            .with_layout_of(&existing);
+
+        assert_eq!(table.headers, vec!["Name", "Column 2", "Column 3"]);
+        assert_eq!(table.rows[1], vec!["b", "", ""]);
+        assert_eq!(table.alignments, existing.alignments);
+        assert!(MarkdownTable::from_records(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_find_tables_skips_code_blocks() {
+        let content = format!(
+            "Intro\n\n{}\n\n```\n| a | b |\n|---|---|\n```\n\n````md\n```\n| a | b |\n|---|---|\n````\n\nx | y\n--|--\n1 | 2\n",
+            TABLE
+        );
+        let tables = find_tables(&content);
+
+        assert_eq!(tables.len(), 2);
+        assert_eq!(&content[tables[0].clone()], TABLE);
+        assert_eq!(&content[tables[1].clone()], "x | y\n--|--\n1 | 2");
+        let second = MarkdownTable::parse(&content[tables[1].clone()]).unwrap();
+        assert_eq!(second.rows, vec![vec!["1", "2"]]);
+    }
+}
@@ -0,0 +1,173 @@
+//! Tag browser: the tags of the document (or of every note in its vault)
+//! with counts, and the places each one is used.
+
+use crate::parser::parse_markdown;
+use crate::parser::tags::{Tag, extract_tags, group_tags};
+use crate::parser::utils::line_of;
+use std::path::{Path, PathBuf};
+
+/// Where a tag is used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagLocation {
+    pub path: PathBuf,
+    /// 1-indexed source line
+    pub line: usize,
+    /// Innermost heading above the tag
+    pub heading: Option<String>,
+    /// The source line, trimmed
+    pub text: String,
+}
+
+/// A distinct tag and where it is used
+#[derive(Debug, Clone)]
+pub struct TagGroup {
+    pub name: String,
+    pub locations: Vec<TagLocation>,
+}
+
+/// State of the tag browser popup
+#[derive(Debug, Clone)]
+pub struct TagBrowser {
+    /// Whether tags come from every note in the workspace rather than the document
+    pub workspace: bool,
+    pub tags: Vec<TagGroup>,
+    pub selected: usize,
+    /// Selected location, while a tag's locations are listed
+    pub location: Option<usize>,
+}
+
+impl TagBrowser {
+    /// Collect the tags of `notes` (path and content), most used first
+    pub fn new(workspace: bool, notes: &[(PathBuf, String)]) -> Self {
+        let occurrences = notes
+            .iter()
+            .flat_map(|(path, content)| locations(path, content));
+        let tags = group_tags(occurrences)
+            .into_iter()
+            .map(|(name, occurrences)| TagGroup {
+                name,
+                locations: occurrences
+                    .into_iter()
+                    .map(|(_, location)| location)
+                    .collect(),
+            })
+            .collect();
+        Self {
+            workspace,
+            tags,
+            selected: 0,
+            location: None,
+        }
+    }
+
+    pub fn selected_tag(&self) -> Option<&TagGroup> {
+        self.tags.get(self.selected)
+    }
+
+    pub fn selected_location(&self) -> Option<&TagLocation> {
+        self.selected_tag()?.locations.get(self.location?)
+    }
+
+    pub fn next(&mut self) {
+        match &mut self.location {
+            Some(location) => {
+                let count = self
+                    .tags
+                    .get(self.selected)
+                    .map_or(0, |t| t.locations.len());
+                *location = (*location + 1).min(count.saturating_sub(1));
+            }
+            None => self.selected = (self.selected + 1).min(self.tags.len().saturating_sub(1)),
+        }
+    }
+
+    pub fn previous(&mut self) {
+        match &mut self.location {
+            Some(location) => *location = location.saturating_sub(1),
+            None => self.selected = self.selected.saturating_sub(1),
+        }
+    }
+
+    /// List the selected tag's locations
+    pub fn open(&mut self) {
+        if self.selected_tag().is_some() {
+            self.location = Some(0);
+        }
+    }
+
+    /// Back from a tag's locations to the tag list. False if already there.
+    pub fn back(&mut self) -> bool {
+        self.location.take().is_some()
+    }
+}
+
+/// Tags in one note, with their locations
+fn locations(path: &Path, content: &str) -> Vec<(Tag, TagLocation)> {
+    let tags = extract_tags(content);
+    if tags.is_empty() {
+        return Vec::new();
+    }
+    let lines: Vec<&str> = content.lines().collect();
+    let headings: Vec<(usize, String)> = parse_markdown(content)
+        .headings
+        .into_iter()
+        .map(|h| (line_of(content, h.offset), h.text))
+        .collect();
+
+    tags.into_iter()
+        .map(|tag| {
+            let heading = headings
+                .iter()
+                .take_while(|(line, _)| *line <= tag.line)
+                .last()
+                .map(|(_, text)| text.clone());
+            let location = TagLocation {
+                path: path.to_path_buf(),
+                line: tag.line,
+                heading,
+                text: lines
+                    .get(tag.line - 1)
+                    .map_or(String::new(), |line| line.trim().to_string()),
+            };
+            (tag, location)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_and_navigate() {
+        let notes = vec![
+            (
+                PathBuf::from("a.md"),
+                "# A\n\n## Plan\n\nShip it #todo #Rust\n".to_string(),
+            ),
+            (
+                PathBuf::from("b.md"),
+                "---\ntags: [rust]\n---\nIntro #todo\n".to_string(),
+            ),
+        ];
+        let mut browser = TagBrowser::new(true, &notes);
+        let names: Vec<&str> = browser.tags.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["Rust", "todo"]);
+
+        let first = &browser.tags[0].locations[0];
+        assert_eq!(first.line, 5);
+        assert_eq!(first.heading.as_deref(), Some("Plan"));
+        assert_eq!(first.text, "Ship it #todo #Rust");
+        assert_eq!(browser.tags[0].locations[1].heading, None);
+
+        browser.next();
+        browser.next();
+        assert_eq!(browser.selected, 1);
+        browser.open();
+        browser.next();
+        let location = browser.selected_location().unwrap();
+        assert_eq!((location.path.to_str(), location.line), (Some("b.md"), 4));
+        assert!(browser.back());
+        assert!(!browser.back());
+    }
+}
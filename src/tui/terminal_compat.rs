@@ -1,3 +1,4 @@
+use super::images::ImageProtocol;
 use supports_color::{Stream, on};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -13,6 +14,7 @@ pub struct TerminalCapabilities {
     pub macos_version: Option<u32>,
     pub recommended_color_mode: ColorMode,
     pub should_warn: bool,
+    pub image_protocol: ImageProtocol,
 }
 
 impl TerminalCapabilities {
@@ -57,6 +59,40 @@ impl TerminalCapabilities {
             macos_version,
             recommended_color_mode,
             should_warn,
+            image_protocol: Self::detect_image_protocol(),
+        }
+    }
+
+    /// Detect the best graphics protocol for inline images.
+    ///
+    /// Terminals are recognized from the environment variables they set;
+    /// anything unknown (including terminal multiplexers, which don't pass
+    /// graphics through reliably) gets Unicode half blocks.
+    pub fn detect_image_protocol() -> ImageProtocol {
+        let var = |name: &str| std::env::var(name).unwrap_or_default().to_lowercase();
+        let (term, term_program) = (var("TERM"), var("TERM_PROGRAM"));
+
+        if std::env::var_os("TMUX").is_some() || term.starts_with("screen") {
+            ImageProtocol::HalfBlock
+        } else if std::env::var_os("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || term.contains("ghostty")
+            || term_program == "ghostty"
+        {
+            ImageProtocol::Kitty
+        } else if term_program == "iterm.app"
+            || term_program == "wezterm"
+            || std::env::var_os("ITERM_SESSION_ID").is_some()
+        {
+            ImageProtocol::Iterm2
+        } else if term.starts_with("foot")
+            || term.starts_with("mlterm")
+            || term.contains("sixel")
+            || term_program == "contour"
+        {
+            ImageProtocol::Sixel
+        } else {
+            ImageProtocol::HalfBlock
         }
     }
 
@@ -1,3 +1,4 @@
+use crate::parser::callouts::CalloutStyle;
 use crate::tui::terminal_compat::ColorMode;
 use ratatui::style::{Color, Modifier, Style};
 
@@ -13,6 +14,47 @@ pub enum ThemeName {
     CatppuccinMocha,
 }
 
+impl ThemeName {
+    /// All themes in picker order
+    pub const ALL: [ThemeName; 8] = [
+        ThemeName::OceanDark,
+        ThemeName::Nord,
+        ThemeName::Dracula,
+        ThemeName::Solarized,
+        ThemeName::Monokai,
+        ThemeName::Gruvbox,
+        ThemeName::TokyoNight,
+        ThemeName::CatppuccinMocha,
+    ];
+
+    /// Short kebab-case name used on the command line (e.g. `tokyo-night`)
+    pub fn slug(&self) -> &'static str {
+        match self {
+            ThemeName::OceanDark => "ocean-dark",
+            ThemeName::Nord => "nord",
+            ThemeName::Dracula => "dracula",
+            ThemeName::Solarized => "solarized",
+            ThemeName::Monokai => "monokai",
+            ThemeName::Gruvbox => "gruvbox",
+            ThemeName::TokyoNight => "tokyo-night",
+            ThemeName::CatppuccinMocha => "catppuccin-mocha",
+        }
+    }
+
+    /// Parse a theme name, ignoring case and `-`/`_`/space separators
+    pub fn parse(name: &str) -> Option<ThemeName> {
+        let normalized: String = name
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .collect::<String>()
+            .to_lowercase();
+
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.slug().replace('-', "") == normalized)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Theme {
     pub name: &'static str,
@@ -50,6 +92,12 @@ pub struct Theme {
     pub search_match_fg: Color,
     pub search_current_bg: Color,
     pub search_current_fg: Color,
+    // Callouts, by color group (see `parser::callouts::CalloutStyle`)
+    pub callout_note: Color,
+    pub callout_tip: Color,
+    pub callout_important: Color,
+    pub callout_warning: Color,
+    pub callout_caution: Color,
 }
 
 impl Theme {
@@ -116,6 +164,11 @@ impl Theme {
             search_match_fg: Color::White,
             search_current_bg: Color::Yellow,
             search_current_fg: Color::Black,
+            callout_note: Color::Rgb(102, 153, 204),
+            callout_tip: Color::Rgb(153, 199, 148),
+            callout_important: Color::Rgb(197, 148, 197),
+            callout_warning: Color::Rgb(250, 200, 99),
+            callout_caution: Color::Rgb(236, 95, 103),
         }
     }
 
@@ -156,6 +209,11 @@ impl Theme {
             search_match_fg: Color::Rgb(46, 52, 64),
             search_current_bg: Color::Rgb(235, 203, 139),
             search_current_fg: Color::Rgb(46, 52, 64),
+            callout_note: Color::Rgb(136, 192, 208),
+            callout_tip: Color::Rgb(163, 190, 140),
+            callout_important: Color::Rgb(180, 142, 173),
+            callout_warning: Color::Rgb(235, 203, 139),
+            callout_caution: Color::Rgb(191, 97, 106),
         }
     }
 
@@ -196,6 +254,11 @@ impl Theme {
             search_match_fg: Color::Rgb(40, 42, 54),
             search_current_bg: Color::Rgb(241, 250, 140),
             search_current_fg: Color::Rgb(40, 42, 54),
+            callout_note: Color::Rgb(139, 233, 253),
+            callout_tip: Color::Rgb(80, 250, 123),
+            callout_important: Color::Rgb(189, 147, 249),
+            callout_warning: Color::Rgb(241, 250, 140),
+            callout_caution: Color::Rgb(255, 85, 85),
         }
     }
 
@@ -236,6 +299,11 @@ impl Theme {
             search_match_fg: Color::Rgb(253, 246, 227),
             search_current_bg: Color::Rgb(181, 137, 0),
             search_current_fg: Color::Rgb(0, 43, 54),
+            callout_note: Color::Rgb(38, 139, 210),
+            callout_tip: Color::Rgb(133, 153, 0),
+            callout_important: Color::Rgb(108, 113, 196),
+            callout_warning: Color::Rgb(181, 137, 0),
+            callout_caution: Color::Rgb(220, 50, 47),
         }
     }
 
@@ -276,6 +344,11 @@ impl Theme {
             search_match_fg: Color::Rgb(39, 40, 34),
             search_current_bg: Color::Rgb(230, 219, 116),
             search_current_fg: Color::Rgb(39, 40, 34),
+            callout_note: Color::Rgb(102, 217, 239),
+            callout_tip: Color::Rgb(166, 226, 46),
+            callout_important: Color::Rgb(174, 129, 255),
+            callout_warning: Color::Rgb(230, 219, 116),
+            callout_caution: Color::Rgb(249, 38, 114),
         }
     }
 
@@ -316,6 +389,11 @@ impl Theme {
             search_match_fg: Color::Rgb(40, 40, 40),
             search_current_bg: Color::Rgb(250, 189, 47),
             search_current_fg: Color::Rgb(40, 40, 40),
+            callout_note: Color::Rgb(131, 165, 152),
+            callout_tip: Color::Rgb(184, 187, 38),
+            callout_important: Color::Rgb(211, 134, 155),
+            callout_warning: Color::Rgb(250, 189, 47),
+            callout_caution: Color::Rgb(251, 73, 52),
         }
     }
 
@@ -356,6 +434,11 @@ impl Theme {
             search_match_fg: Color::Rgb(26, 27, 38),
             search_current_bg: Color::Rgb(224, 175, 104),
             search_current_fg: Color::Rgb(26, 27, 38),
+            callout_note: Color::Rgb(122, 162, 247),
+            callout_tip: Color::Rgb(158, 206, 106),
+            callout_important: Color::Rgb(187, 154, 247),
+            callout_warning: Color::Rgb(224, 175, 104),
+            callout_caution: Color::Rgb(247, 118, 142),
         }
     }
 
@@ -396,6 +479,11 @@ impl Theme {
             search_match_fg: Color::Rgb(30, 30, 46),      // Base
             search_current_bg: Color::Rgb(249, 226, 175), // Yellow
             search_current_fg: Color::Rgb(30, 30, 46),    // Base
+            callout_note: Color::Rgb(137, 180, 250),      // Blue
+            callout_tip: Color::Rgb(166, 227, 161),       // Green
+            callout_important: Color::Rgb(203, 166, 247), // Mauve
+            callout_warning: Color::Rgb(249, 226, 175),   // Yellow
+            callout_caution: Color::Rgb(243, 139, 168),   // Red
         }
     }
 
@@ -438,6 +526,11 @@ impl Theme {
             search_match_fg: Color::White,
             search_current_bg: Color::Yellow,
             search_current_fg: Color::Black,
+            callout_note: Color::Indexed(68),
+            callout_tip: Color::Indexed(108),
+            callout_important: Color::Indexed(139),
+            callout_warning: Color::Indexed(221),
+            callout_caution: Color::Indexed(167),
         }
     }
 
@@ -478,6 +571,11 @@ impl Theme {
             search_match_fg: Color::Indexed(236),
             search_current_bg: Color::Indexed(222),
             search_current_fg: Color::Indexed(236),
+            callout_note: Color::Indexed(110),
+            callout_tip: Color::Indexed(108),
+            callout_important: Color::Indexed(139),
+            callout_warning: Color::Indexed(222),
+            callout_caution: Color::Indexed(131),
         }
     }
 
@@ -518,6 +616,11 @@ impl Theme {
             search_match_fg: Color::Indexed(236),
             search_current_bg: Color::Indexed(228),
             search_current_fg: Color::Indexed(236),
+            callout_note: Color::Indexed(117),
+            callout_tip: Color::Indexed(84),
+            callout_important: Color::Indexed(141),
+            callout_warning: Color::Indexed(228),
+            callout_caution: Color::Indexed(203),
         }
     }
 
@@ -558,6 +661,11 @@ impl Theme {
             search_match_fg: Color::Indexed(230),
             search_current_bg: Color::Indexed(136),
             search_current_fg: Color::Indexed(234),
+            callout_note: Color::Indexed(33),
+            callout_tip: Color::Indexed(100),
+            callout_important: Color::Indexed(61),
+            callout_warning: Color::Indexed(136),
+            callout_caution: Color::Indexed(160),
         }
     }
 
@@ -598,6 +706,11 @@ impl Theme {
             search_match_fg: Color::Indexed(235),
             search_current_bg: Color::Indexed(228),
             search_current_fg: Color::Indexed(235),
+            callout_note: Color::Indexed(81),
+            callout_tip: Color::Indexed(148),
+            callout_important: Color::Indexed(141),
+            callout_warning: Color::Indexed(186),
+            callout_caution: Color::Indexed(197),
         }
     }
 
@@ -639,6 +752,11 @@ impl Theme {
             search_match_fg: Color::Indexed(235),
             search_current_bg: Color::Indexed(214), // Bright orange
             search_current_fg: Color::Indexed(235),
+            callout_note: Color::Indexed(109),
+            callout_tip: Color::Indexed(142),
+            callout_important: Color::Indexed(175),
+            callout_warning: Color::Indexed(214),
+            callout_caution: Color::Indexed(203),
         }
     }
 
@@ -680,6 +798,11 @@ impl Theme {
             search_match_fg: Color::Indexed(234),
             search_current_bg: Color::Indexed(215), // Orange
             search_current_fg: Color::Indexed(234),
+            callout_note: Color::Indexed(111),
+            callout_tip: Color::Indexed(149),
+            callout_important: Color::Indexed(141),
+            callout_warning: Color::Indexed(179),
+            callout_caution: Color::Indexed(211),
         }
     }
 
@@ -721,6 +844,11 @@ impl Theme {
             search_match_fg: Color::Indexed(235),
             search_current_bg: Color::Indexed(223), // Peach/Yellow
             search_current_fg: Color::Indexed(235),
+            callout_note: Color::Indexed(111),
+            callout_tip: Color::Indexed(151),
+            callout_important: Color::Indexed(183),
+            callout_warning: Color::Indexed(223),
+            callout_caution: Color::Indexed(211),
         }
     }
 
@@ -833,6 +961,18 @@ impl Theme {
         self.blockquote_fg
     }
 
+    /// Accent color for a callout's border, icon and title
+    pub fn callout_color(&self, style: CalloutStyle) -> Color {
+        match style {
+            CalloutStyle::Note => self.callout_note,
+            CalloutStyle::Tip => self.callout_tip,
+            CalloutStyle::Important => self.callout_important,
+            CalloutStyle::Warning => self.callout_warning,
+            CalloutStyle::Caution => self.callout_caution,
+            CalloutStyle::Quote => self.blockquote_border,
+        }
+    }
+
     /// Apply custom color overrides from config
     pub fn with_custom_colors(
         mut self,
@@ -889,6 +1029,12 @@ impl Theme {
         apply_color!(search_match_fg);
         apply_color!(search_current_bg);
         apply_color!(search_current_fg);
+        // Callouts
+        apply_color!(callout_note);
+        apply_color!(callout_tip);
+        apply_color!(callout_important);
+        apply_color!(callout_warning);
+        apply_color!(callout_caution);
 
         self
     }
@@ -939,6 +1085,11 @@ impl Theme {
                 self.link_selected_bg = rgb_to_256(self.link_selected_bg);
                 self.link_selected_fg = rgb_to_256(self.link_selected_fg);
                 self.table_border = rgb_to_256(self.table_border);
+                self.callout_note = rgb_to_256(self.callout_note);
+                self.callout_tip = rgb_to_256(self.callout_tip);
+                self.callout_important = rgb_to_256(self.callout_important);
+                self.callout_warning = rgb_to_256(self.callout_warning);
+                self.callout_caution = rgb_to_256(self.callout_caution);
                 self
             }
         }
@@ -946,7 +1097,7 @@ impl Theme {
 }
 
 /// Convert RGB color to nearest 256-color palette entry
-fn rgb_to_256(color: Color) -> Color {
+pub(crate) fn rgb_to_256(color: Color) -> Color {
     match color {
         Color::Rgb(r, g, b) => {
             // Check if it's grayscale
@@ -1,15 +1,18 @@
 mod layout;
 mod popups;
-mod table;
+pub mod table;
 mod util;
 
 use layout::{DynamicLayout, Section};
 
-use crate::tui::app::{App, AppMode, Focus};
+use crate::tui::app::{App, AppMode, EmbeddedNotes, Focus, ensure_within};
 use crate::tui::theme::Theme;
 use popups::{
-    render_cell_edit_overlay, render_command_palette, render_file_create_confirm,
-    render_help_popup, render_link_picker, render_save_width_confirm, render_theme_picker,
+    render_cell_edit_overlay, render_code_run_confirm, render_command_palette,
+    render_conflict_confirm, render_file_create_confirm, render_footnote_popup, render_graph_view,
+    render_help_popup, render_link_picker, render_note_picker, render_rename_confirm,
+    render_save_width_confirm, render_section_delete_confirm, render_tag_browser, render_task_list,
+    render_theme_picker,
 };
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Layout, Rect};
@@ -18,7 +21,7 @@ use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{
     Block, Borders, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation, Wrap,
 };
-use table::render_table;
+use table::{TableRenderOptions, TableViewport, render_table, render_table_with};
 use util::detect_checkbox_in_text;
 
 pub fn render(frame: &mut Frame, app: &mut App) {
@@ -27,6 +30,7 @@ pub fn render(frame: &mut Frame, app: &mut App) {
 
     // Clear expired status messages (auto-dismiss after timeout)
     app.clear_expired_status_message();
+    app.images.begin_frame();
 
     let area = frame.area();
 
@@ -62,6 +66,13 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         Layout::horizontal([Constraint::Percentage(100)]).split(content_area)
     };
 
+    // Remember the content pane's inner size for navigation that depends on it (tables)
+    let content_pane = content_chunks[usize::from(app.show_outline)];
+    app.content_viewport = (
+        content_pane.width.saturating_sub(2),
+        content_pane.height.saturating_sub(2),
+    );
+
     // Render outline (left pane) only if visible
     if app.show_outline {
         render_outline(frame, app, content_chunks[0]);
@@ -72,6 +83,17 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         render_content(frame, app, content_chunks[0]);
     }
 
+    // Show the definition of a selected footnote reference
+    if app.mode == AppMode::Interactive
+        && let Some(crate::tui::interactive::ElementType::Link { link, .. }) = app
+            .interactive_state
+            .current_element()
+            .map(|elem| &elem.element_type)
+        && let crate::parser::LinkTarget::Footnote(label) = &link.target
+    {
+        render_footnote_popup(frame, app, label, content_pane);
+    }
+
     // Render status bar at bottom
     render_status_bar(frame, app, main_layout.require(Section::Status));
 
@@ -85,6 +107,26 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         render_theme_picker(frame, app, area);
     }
 
+    // Render task list popup
+    if matches!(app.mode, AppMode::TaskList) {
+        render_task_list(frame, app, area);
+    }
+
+    // Render tag browser popup
+    if matches!(app.mode, AppMode::TagBrowser) {
+        render_tag_browser(frame, app, area);
+    }
+
+    // Render link graph popup
+    if matches!(app.mode, AppMode::GraphView) {
+        render_graph_view(frame, app, area);
+    }
+
+    // Render note picker for ambiguous wikilinks
+    if matches!(app.mode, AppMode::NotePicker) {
+        render_note_picker(frame, app, area);
+    }
+
     // Render cell edit overlay if in cell edit mode
     if matches!(app.mode, crate::tui::app::AppMode::CellEdit) {
         render_cell_edit_overlay(frame, app, area);
@@ -107,6 +149,34 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         render_save_width_confirm(frame, app.outline_width, &app.theme);
     }
 
+    // Render external-modification conflict dialog
+    if matches!(app.mode, AppMode::ConfirmConflict)
+        && let Some(conflict) = &app.pending_conflict
+    {
+        render_conflict_confirm(frame, conflict, &app.filename, &app.theme);
+    }
+
+    // Render code block run confirmation dialog
+    if matches!(app.mode, AppMode::ConfirmCodeRun)
+        && let Some(request) = &app.pending_code_run
+    {
+        render_code_run_confirm(frame, request, &app.theme);
+    }
+
+    // Render rename preview
+    if matches!(app.mode, AppMode::ConfirmRename)
+        && let Some(plan) = &app.pending_rename
+    {
+        render_rename_confirm(frame, plan, &app.theme);
+    }
+
+    // Render section delete confirmation dialog
+    if matches!(app.mode, AppMode::ConfirmDeleteSection)
+        && let Some(heading) = &app.pending_section_delete
+    {
+        render_section_delete_confirm(frame, heading, &app.theme);
+    }
+
     // Render command palette
     if matches!(app.mode, AppMode::CommandPalette) {
         render_command_palette(frame, app, &app.theme);
@@ -216,6 +286,25 @@ fn render_outline(frame: &mut Frame, app: &mut App, area: Rect) {
         None
     };
 
+    // Open/total task counts per heading text (first heading with that text,
+    // as with section lookup), and for the whole document
+    let tasks = crate::parser::tasks::extract_tasks(&app.document);
+    let mut task_counts: std::collections::HashMap<&str, (usize, usize)> =
+        std::collections::HashMap::new();
+    for (heading, counts) in
+        app.document
+            .headings
+            .iter()
+            .zip(crate::parser::tasks::task_counts_by_heading(
+                &app.document,
+                &tasks,
+            ))
+    {
+        task_counts.entry(heading.text.as_str()).or_insert(counts);
+    }
+    let open_tasks = tasks.iter().filter(|t| !t.checked).count();
+    task_counts.insert(DOCUMENT_OVERVIEW, (open_tasks, tasks.len()));
+
     let items: Vec<ListItem> = app
         .outline_items
         .iter()
@@ -252,7 +341,7 @@ fn render_outline(frame: &mut Frame, app: &mut App, area: Rect) {
             };
 
             // Build line with search highlighting using shared utility
-            let line = build_highlighted_line(
+            let mut line = build_highlighted_line(
                 vec![Span::styled(prefix_text, base_style)],
                 &item.text,
                 search_query,
@@ -260,6 +349,21 @@ fn render_outline(frame: &mut Frame, app: &mut App, area: Rect) {
                 theme.search_match_style(),
             );
 
+            // Task badge: open/total, dimmed once everything is done
+            if let Some(&(open, total)) = task_counts.get(item.text.as_str())
+                && total > 0
+            {
+                let badge_style = if open > 0 {
+                    Style::default().fg(theme.list_bullet)
+                } else {
+                    Style::default()
+                        .fg(theme.border_unfocused)
+                        .add_modifier(Modifier::DIM)
+                };
+                line.spans
+                    .push(Span::styled(format!(" [{}/{}]", open, total), badge_style));
+            }
+
             ListItem::new(line)
         })
         .collect();
@@ -295,7 +399,7 @@ fn render_outline(frame: &mut Frame, app: &mut App, area: Rect) {
     );
 }
 
-fn render_content(frame: &mut Frame, app: &App, area: Rect) {
+fn render_content(frame: &mut Frame, app: &mut App, area: Rect) {
     use crate::tui::app::AppMode;
 
     let theme = &app.theme;
@@ -337,13 +441,30 @@ fn render_content(frame: &mut Frame, app: &App, area: Rect) {
     };
 
     // Check if we should render raw source or enhanced markdown
+    let section = app.selected_heading_text().unwrap_or_default().to_string();
+    let document = app
+        .current_file_path
+        .canonicalize()
+        .unwrap_or_else(|_| app.current_file_path.clone());
+    let mut files = FileContext {
+        previews: &mut app.images,
+        notes: &mut app.embedded_notes,
+        vault: app.vault.as_ref(),
+        safe_root: app.safe_root.as_deref(),
+        stack: vec![(document, section)],
+        spots: Vec::new(),
+        embed_rows: Vec::new(),
+    };
     let mut rendered_text = if app.show_raw_source {
         // Raw source view - show unprocessed markdown
         render_raw_markdown(&content_text, theme)
     } else {
         // Enhanced markdown rendering with syntax highlighting
         // Pass interactive state if in interactive mode
-        let (selected_element_id, interactive_state_ref) = if app.mode == AppMode::Interactive {
+        let (selected_element_id, interactive_state_ref) = if matches!(
+            app.mode,
+            AppMode::Interactive | AppMode::TableFilter | AppMode::ConfirmCodeRun
+        ) {
             (
                 app.interactive_state.current_element().map(|elem| elem.id),
                 Some(&app.interactive_state),
@@ -358,8 +479,23 @@ fn render_content(frame: &mut Frame, app: &App, area: Rect) {
             theme,
             selected_element_id,
             interactive_state_ref,
+            app.content_viewport,
+            &mut files,
         )
     };
+    let (preview_spots, embed_rows) = (files.spots, files.embed_rows);
+
+    // Footnotes referenced in this section, collected at its end
+    let mut footnote_lines = std::collections::HashMap::new();
+    if !app.show_raw_source {
+        let (lines, positions) = render_footnotes(&content_text, &app.document.content, theme);
+        let start = rendered_text.lines.len();
+        rendered_text.lines.extend(lines);
+        footnote_lines = positions
+            .into_iter()
+            .map(|(label, idx)| (label, (start + idx) as u16))
+            .collect();
+    }
 
     // Apply search highlighting only for document/content search mode
     // Outline search (s) only filters headings, it doesn't highlight content
@@ -373,6 +509,21 @@ fn render_content(frame: &mut Frame, app: &App, area: Rect) {
         );
     }
 
+    // Rows (before scrolling) where image previews start once lines wrap
+    let text_width = area.width.saturating_sub(2);
+    let mut spot_rows = Vec::with_capacity(preview_spots.len());
+    let mut row = 0;
+    let mut line_idx = 0;
+    for spot in &preview_spots {
+        for line in &rendered_text.lines[line_idx..spot.line] {
+            row += Paragraph::new(line.clone())
+                .wrap(Wrap { trim: false })
+                .line_count(text_width);
+        }
+        line_idx = spot.line;
+        spot_rows.push(row);
+    }
+
     let paragraph = Paragraph::new(rendered_text)
         .block(
             Block::default()
@@ -400,14 +551,51 @@ fn render_content(frame: &mut Frame, app: &App, area: Rect) {
         }),
         &mut app.content_scroll_state.clone(),
     );
+
+    app.footnote_lines = footnote_lines;
+
+    // Graphics go over previews that are fully in view, unless a popup may cover them
+    let scroll = app.content_scroll as usize;
+    let height = area.height.saturating_sub(2) as usize;
+    let unobstructed = matches!(app.mode, AppMode::Normal | AppMode::Interactive) && !app.show_help;
+    for (spot, &row) in preview_spots.iter().zip(&spot_rows) {
+        if unobstructed && row >= scroll && row + spot.rows <= scroll + height {
+            let y = area.y + 1 + (row - scroll) as u16;
+            app.images.place(spot.key.clone(), area.x + 3, y);
+        }
+    }
+
+    // Keep line counts in step with the previews and embeds just drawn
+    let preview_rows: std::collections::HashMap<String, usize> = preview_spots
+        .into_iter()
+        .map(|spot| (spot.src, spot.rows))
+        .chain(embed_rows)
+        .collect();
+    if preview_rows != app.interactive_state.preview_rows {
+        app.interactive_state.preview_rows = preview_rows;
+        app.reindex_interactive_elements();
+        app.update_content_metrics();
+    }
 }
 
 fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     use crate::tui::app::AppMode;
 
+    // Read-only / safe badge leads the status bar, even over status messages
+    let access_badge = app
+        .access_mode
+        .badge()
+        .map(|badge| format!(" {}", badge))
+        .unwrap_or_default();
+
     // If there's a status message, display it prominently
     if let Some(ref msg) = app.status_message {
-        let status = Paragraph::new(msg.clone()).style(
+        let text = if access_badge.is_empty() {
+            msg.clone()
+        } else {
+            format!("{} {}", access_badge.trim_start(), msg)
+        };
+        let status = Paragraph::new(text).style(
             Style::default()
                 .bg(Color::Rgb(0, 80, 120))
                 .fg(Color::White)
@@ -448,6 +636,7 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
                             url.clone()
                         }
                     }
+                    LinkTarget::Footnote(label) => app.footnote_preview(label, 40),
                 };
 
                 format!(
@@ -512,7 +701,10 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
 
     let theme_name = format!(" Theme:{} ", app.theme.name);
     let raw_indicator = if app.show_raw_source { " [RAW]" } else { "" };
-    let status_text = format!("{}{}{}", status_text, theme_name, raw_indicator);
+    let status_text = format!(
+        "{}{}{}{}",
+        access_badge, status_text, theme_name, raw_indicator
+    );
 
     let status_style = if app.mode == AppMode::LinkFollow {
         Style::default()
@@ -528,9 +720,18 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(status, area);
 }
 
+use crate::parser::callouts::{Callout, CalloutFold};
 use crate::parser::content::parse_content;
+use crate::parser::embeds::{Embed, EmbedPart, MAX_EMBED_DEPTH};
+use crate::parser::footnotes::{find_definitions, marker as footnote_marker, referenced_labels};
+use crate::parser::links::resolve_wikilink;
+use crate::parser::math::render_display;
+use crate::parser::mermaid::render_mermaid;
 use crate::parser::output::{Block as ContentBlock, InlineElement};
+use crate::tui::images::{ImagePreviews, PreviewSpot, standalone_image};
 use crate::tui::syntax::SyntaxHighlighter;
+use crate::vault::Vault;
+use std::path::{Path, PathBuf};
 
 /// Render raw markdown source with line numbers
 fn render_raw_markdown(content: &str, theme: &Theme) -> Text<'static> {
@@ -554,12 +755,234 @@ fn render_raw_markdown(content: &str, theme: &Theme) -> Text<'static> {
     Text::from(lines)
 }
 
+/// Footnotes section for the references in `content`, with definitions
+/// looked up in the whole document. Also returns the line of each footnote.
+fn render_footnotes(
+    content: &str,
+    document: &str,
+    theme: &Theme,
+) -> (Vec<Line<'static>>, Vec<(String, usize)>) {
+    let labels = referenced_labels(content);
+    if labels.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+
+    let definitions = find_definitions(document);
+    let rule_style = Style::default().fg(Color::Rgb(80, 80, 100));
+    let mut lines = vec![Line::from(vec![
+        Span::styled("── ", rule_style),
+        Span::styled("Footnotes ", theme.bold_style()),
+        Span::styled("─".repeat(20), rule_style),
+    ])];
+    let mut positions = Vec::new();
+
+    for label in labels {
+        positions.push((label.clone(), lines.len()));
+        let mut spans = vec![Span::styled(
+            format!("{} ", footnote_marker(&label)),
+            Style::default()
+                .fg(theme.link_fg)
+                .add_modifier(Modifier::BOLD),
+        )];
+        match definitions.iter().find(|def| def.label == label) {
+            Some(def) => {
+                let text = def.content.split_whitespace().collect::<Vec<_>>().join(" ");
+                spans.extend(format_inline_markdown(&text, theme));
+            }
+            None => spans.push(Span::styled("missing definition", theme.italic_style())),
+        }
+        lines.push(Line::from(spans));
+    }
+
+    (lines, positions)
+}
+
+/// Files the rendered content refers to: local images and embedded notes
+struct FileContext<'a> {
+    previews: &'a mut ImagePreviews,
+    notes: &'a mut EmbeddedNotes,
+    /// Vault embeds are looked up in, if the document is in one
+    vault: Option<&'a Vault>,
+    /// Directory embeds may not leave, in safe mode
+    safe_root: Option<&'a Path>,
+    /// What is being drawn as (file, heading or embed anchor): the document's
+    /// section, then the notes embedded into it. Relative paths resolve
+    /// against the last file's directory.
+    stack: Vec<(PathBuf, String)>,
+    /// Previews placed so far
+    spots: Vec<PreviewSpot>,
+    /// Lines drawn below each embed's label line, by image source
+    embed_rows: Vec<(String, usize)>,
+}
+
+/// Push an image's alt text line, followed by a preview of local images.
+/// Files that can't be read get the error appended to the alt text instead.
+fn push_image(
+    mut alt_line: Vec<Span<'static>>,
+    src: &str,
+    images: &mut FileContext,
+    viewport: (u16, u16),
+    theme: &Theme,
+    lines: &mut Vec<Line<'static>>,
+) {
+    // Indented by two columns, leaving one for the scrollbar
+    let max_cols = viewport.0.saturating_sub(3);
+    let max_rows = viewport.1.saturating_sub(2);
+    let path = Embed::from_src(src).map_or(src, |embed| embed.file);
+    let base_dir = images.stack.last().and_then(|(file, _)| file.parent());
+    let previews = &mut images.previews;
+    match previews.preview(path, base_dir, images.safe_root, max_cols, max_rows) {
+        Ok(Some((key, preview))) => {
+            lines.push(Line::from(alt_line));
+            images.spots.push(PreviewSpot {
+                line: lines.len(),
+                rows: preview.len(),
+                src: src.to_string(),
+                key,
+            });
+            for row in preview {
+                let mut spans = vec![Span::raw("  ")];
+                spans.extend(row.spans.iter().cloned());
+                lines.push(Line::from(spans));
+            }
+        }
+        Ok(None) => lines.push(Line::from(alt_line)),
+        Err(e) => {
+            alt_line.push(Span::styled(format!(" ({})", e), theme.italic_style()));
+            lines.push(Line::from(alt_line));
+        }
+    }
+}
+
+/// Push an embed's label line, followed by the embedded note, section or
+/// block inside a left border. Embeds inside embedded notes are followed up
+/// to [`MAX_EMBED_DEPTH`] deep, and one that would draw itself again shows
+/// an error instead.
+fn push_embed(
+    src: &str,
+    selected: bool,
+    files: &mut FileContext,
+    highlighter: &SyntaxHighlighter,
+    theme: &Theme,
+    viewport: (u16, u16),
+    lines: &mut Vec<Line<'static>>,
+) {
+    let target = src.strip_prefix("wikilink:").unwrap_or(src);
+    let embed = Embed::parse(target);
+    let border = Style::default().fg(theme.blockquote_border);
+
+    let mut label = vec![];
+    if selected {
+        label.push(Span::styled(
+            "→ ",
+            Style::default()
+                .fg(theme.selection_indicator_fg)
+                .bg(theme.selection_indicator_bg)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    label.push(Span::styled(
+        format!("↪ {}", target),
+        Style::default()
+            .fg(theme.link_fg)
+            .add_modifier(Modifier::ITALIC),
+    ));
+
+    let source = embedded_source(&embed, files);
+    let (path, markdown) = match source {
+        Ok(source) => source,
+        Err(e) => {
+            label.push(Span::styled(format!(" ({})", e), theme.italic_style()));
+            lines.push(Line::from(label));
+            return;
+        }
+    };
+    label.insert(label.len() - 1, Span::styled("┌─ ", border));
+    lines.push(Line::from(label));
+
+    // Nested previews stay half blocks: graphics are only placed for the document itself
+    let spots = std::mem::take(&mut files.spots);
+    let embed_rows = std::mem::take(&mut files.embed_rows);
+    let anchor = target.split_once('#').map_or("", |(_, anchor)| anchor);
+    files.stack.push((path, anchor.to_string()));
+    let nested_viewport = (viewport.0.saturating_sub(2), viewport.1);
+    let mut body = render_markdown_enhanced(
+        &markdown,
+        highlighter,
+        theme,
+        None,
+        None,
+        nested_viewport,
+        files,
+    )
+    .lines;
+    files.stack.pop();
+    files.spots = spots;
+    files.embed_rows = embed_rows;
+
+    while body.last().is_some_and(|line| line.width() == 0) {
+        body.pop();
+    }
+    let rows = body.len() + 1;
+    for line in body {
+        let mut spans = vec![Span::styled("│ ", border)];
+        spans.extend(line.spans);
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from(Span::styled("└─", border)));
+    files.embed_rows.push((src.to_string(), rows));
+}
+
+/// Path and markdown of the part of a note an embed shows
+fn embedded_source(embed: &Embed, files: &mut FileContext) -> Result<(PathBuf, String), String> {
+    let stack = &files.stack;
+    let Some((current, _)) = stack.last() else {
+        return Err("no document".to_string());
+    };
+    if stack.len() > MAX_EMBED_DEPTH {
+        return Err("embeds nested too deep".to_string());
+    }
+
+    let path = if embed.file.is_empty() {
+        current.clone()
+    } else if let Some(path) = files
+        .vault
+        .and_then(|vault| vault.resolve(embed.file, current).into_iter().next())
+    {
+        path
+    } else {
+        let dir = current.parent().unwrap_or(Path::new("."));
+        let candidate = resolve_wikilink(dir, embed.file)?
+            .ok_or_else(|| format!("'{}' not found", embed.file))?;
+        dir.join(candidate)
+    };
+    let anchor = match embed.part {
+        EmbedPart::Note => String::new(),
+        EmbedPart::Heading(heading) => heading.to_string(),
+        EmbedPart::Block(id) => format!("^{}", id),
+    };
+    if stack
+        .iter()
+        .any(|(file, part)| *file == path && part.eq_ignore_ascii_case(&anchor))
+    {
+        return Err("embeds itself".to_string());
+    }
+
+    if let Some(root) = files.safe_root {
+        ensure_within(root, &path)?;
+    }
+    let markdown = embed.extract(files.notes.read(&path)?)?;
+    Ok((path, markdown))
+}
+
 fn render_markdown_enhanced(
     content: &str,
     highlighter: &SyntaxHighlighter,
     theme: &Theme,
     selected_element_id: Option<crate::tui::interactive::ElementId>,
     interactive_state: Option<&crate::tui::interactive::InteractiveState>,
+    viewport: (u16, u16),
+    images: &mut FileContext,
 ) -> Text<'static> {
     let mut lines = Vec::new();
 
@@ -637,7 +1060,41 @@ fn render_markdown_enhanced(
                     );
                 }
 
-                lines.push(Line::from(formatted));
+                match standalone_image(inline) {
+                    Some(src) if Embed::from_src(src).is_some_and(|embed| !embed.is_image()) => {
+                        push_embed(
+                            src,
+                            is_block_selected,
+                            images,
+                            highlighter,
+                            theme,
+                            viewport,
+                            &mut lines,
+                        )
+                    }
+                    Some(src) => push_image(formatted, src, images, viewport, theme, &mut lines),
+                    None => lines.push(Line::from(formatted)),
+                }
+            }
+            ContentBlock::Code {
+                language: Some(language),
+                content,
+                ..
+            } if language == "math" => {
+                render_math_block(content, is_block_selected, theme, &mut lines);
+            }
+            ContentBlock::Code {
+                language: Some(language),
+                content,
+                ..
+            } if language == "mermaid"
+                && !interactive_state.is_some_and(|state| {
+                    state
+                        .diagram_sources
+                        .contains(&crate::tui::interactive::ElementId::new(block_idx, None))
+                }) =>
+            {
+                render_mermaid_block(content, is_block_selected, highlighter, theme, &mut lines);
             }
             ContentBlock::Code {
                 language, content, ..
@@ -671,6 +1128,15 @@ fn render_markdown_enhanced(
                     "```".to_string(),
                     theme.code_fence_style(),
                 )]));
+
+                // Output panel of a block that has been run
+                if let Some(output) = interactive_state.and_then(|state| {
+                    state
+                        .code_outputs
+                        .get(&crate::tui::interactive::ElementId::new(block_idx, None))
+                }) {
+                    render_code_output(output, theme, &mut lines);
+                }
             }
             ContentBlock::List { ordered, items } => {
                 for (idx, item) in items.iter().enumerate() {
@@ -925,19 +1391,55 @@ fn render_markdown_enhanced(
                     (false, None)
                 };
 
-                let table_lines = render_table(
-                    headers,
-                    alignments,
-                    rows,
-                    theme,
-                    is_block_selected,
-                    in_table_mode,
-                    selected_cell,
-                );
+                // Sort/filter/scroll view, fitted to the pane in table mode
+                let element_id = crate::tui::interactive::ElementId {
+                    block_idx,
+                    sub_idx: None,
+                };
+                let default_view = crate::tui::interactive::TableView::default();
+                let view = interactive_state.and_then(|state| state.table_view(element_id));
+                let width = (viewport.0 as usize).saturating_sub(table::TABLE_GUTTER_WIDTH);
+                let viewport = if in_table_mode {
+                    Some(TableViewport {
+                        view: view.unwrap_or(&default_view),
+                        width,
+                        max_lines: Some(table::table_rows_that_fit(viewport.1 as usize)),
+                    })
+                } else {
+                    view.map(|view| TableViewport {
+                        view,
+                        width,
+                        max_lines: None,
+                    })
+                };
+
+                let table_lines = match viewport {
+                    Some(viewport) => render_table_with(
+                        headers,
+                        alignments,
+                        rows,
+                        &TableRenderOptions {
+                            theme,
+                            is_selected: is_block_selected,
+                            in_table_mode,
+                            selected_cell,
+                            viewport: Some(viewport),
+                        },
+                    ),
+                    None => render_table(
+                        headers,
+                        alignments,
+                        rows,
+                        theme,
+                        is_block_selected,
+                        in_table_mode,
+                        selected_cell,
+                    ),
+                };
                 lines.extend(table_lines);
             }
-            ContentBlock::Image { alt, .. } => {
-                // Render image as placeholder with alt text only
+            ContentBlock::Image { alt, src, .. } => {
+                // Alt text, followed by a preview of local images
                 let mut image_spans = vec![];
                 if is_block_selected {
                     image_spans.push(Span::styled(
@@ -958,7 +1460,33 @@ fn render_markdown_enhanced(
                         .fg(Color::Rgb(100, 150, 200))
                         .add_modifier(Modifier::ITALIC),
                 ));
-                lines.push(Line::from(image_spans));
+                push_image(image_spans, src, images, viewport, theme, &mut lines);
+            }
+            ContentBlock::Details {
+                summary,
+                blocks: nested,
+                ..
+            } if Callout::from_summary(summary).is_some() => {
+                let callout = Callout::from_summary(summary).unwrap();
+                let element_id = crate::tui::interactive::ElementId {
+                    block_idx,
+                    sub_idx: None,
+                };
+                // Foldable callouts start as written (`+` open, `-` closed)
+                let is_expanded = !callout.is_foldable()
+                    || interactive_state
+                        .and_then(|state| state.details_state(element_id))
+                        .unwrap_or(callout.fold == CalloutFold::Open);
+
+                render_callout(
+                    &callout,
+                    nested,
+                    is_expanded,
+                    is_block_selected,
+                    highlighter,
+                    theme,
+                    &mut lines,
+                );
             }
             ContentBlock::Details {
                 summary,
@@ -1175,6 +1703,202 @@ fn safe_slice(s: &str, start: usize, end: usize) -> Option<&str> {
     Some(&s[start..end])
 }
 
+/// Output panel beneath a code block that has been run
+fn render_code_output(
+    output: &crate::tui::code_runner::CodeOutput,
+    theme: &Theme,
+    lines: &mut Vec<Line<'static>>,
+) {
+    use crate::tui::code_runner::RunStatus;
+
+    let header_color = match output.status {
+        RunStatus::Running => theme.modal_title(),
+        RunStatus::Exited(0) => Color::Green,
+        RunStatus::Exited(_) | RunStatus::Killed => Color::Red,
+    };
+    lines.push(Line::from(Span::styled(
+        output.header(),
+        Style::default()
+            .fg(header_color)
+            .add_modifier(Modifier::BOLD),
+    )));
+    if output.collapsed {
+        return;
+    }
+
+    let gutter = Span::styled("│ ", theme.code_fence_style());
+    if output.dropped > 0 {
+        lines.push(Line::from(vec![
+            gutter.clone(),
+            Span::styled(
+                format!("… {} earlier lines", output.dropped),
+                theme.code_fence_style().add_modifier(Modifier::ITALIC),
+            ),
+        ]));
+    }
+    for line in &output.lines {
+        let style = if line.stderr {
+            Style::default().fg(Color::Red)
+        } else {
+            theme.text_style()
+        };
+        lines.push(Line::from(vec![
+            gutter.clone(),
+            Span::styled(line.text.clone(), style),
+        ]));
+    }
+}
+
+/// Display math laid out in Unicode, or its TeX source between `$$` lines
+/// when it uses something that can't be converted
+fn render_math_block(tex: &str, is_selected: bool, theme: &Theme, lines: &mut Vec<Line<'static>>) {
+    let gutter = if is_selected {
+        Span::styled(
+            "→ ",
+            Style::default()
+                .fg(theme.selection_indicator_fg)
+                .bg(theme.selection_indicator_bg)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else {
+        Span::raw("  ")
+    };
+
+    match render_display(tex) {
+        Some(rendered) => {
+            for (idx, line) in rendered.into_iter().enumerate() {
+                let prefix = if idx == 0 {
+                    gutter.clone()
+                } else {
+                    Span::raw("  ")
+                };
+                lines.push(Line::from(vec![
+                    prefix,
+                    Span::styled(line, theme.text_style()),
+                ]));
+            }
+        }
+        None => {
+            lines.push(Line::from(vec![
+                gutter,
+                Span::styled("$$", theme.code_fence_style()),
+            ]));
+            for line in tex.lines() {
+                lines.push(Line::from(vec![
+                    Span::raw("  "),
+                    Span::styled(line.to_string(), theme.inline_code_style()),
+                ]));
+            }
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled("$$", theme.code_fence_style()),
+            ]));
+        }
+    }
+}
+
+/// Mermaid diagram drawn as text, or its highlighted source when the
+/// diagram type isn't supported
+fn render_mermaid_block(
+    source: &str,
+    is_selected: bool,
+    highlighter: &SyntaxHighlighter,
+    theme: &Theme,
+    lines: &mut Vec<Line<'static>>,
+) {
+    let gutter = if is_selected {
+        Span::styled(
+            "→ ",
+            Style::default()
+                .fg(theme.selection_indicator_fg)
+                .bg(theme.selection_indicator_bg)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else {
+        Span::raw("  ")
+    };
+
+    match render_mermaid(source) {
+        Some(diagram) => {
+            for (idx, line) in diagram.into_iter().enumerate() {
+                let prefix = if idx == 0 {
+                    gutter.clone()
+                } else {
+                    Span::raw("  ")
+                };
+                lines.push(Line::from(vec![
+                    prefix,
+                    Span::styled(line, theme.text_style()),
+                ]));
+            }
+        }
+        None => {
+            let mut fence = if is_selected {
+                vec![gutter]
+            } else {
+                Vec::new()
+            };
+            fence.push(Span::styled("```mermaid", theme.code_fence_style()));
+            lines.push(Line::from(fence));
+            lines.extend(highlighter.highlight_code(source, "mermaid"));
+            lines.push(Line::from(vec![Span::styled(
+                "```".to_string(),
+                theme.code_fence_style(),
+            )]));
+        }
+    }
+}
+
+/// Callout title line with its icon, then (when expanded) its contents
+/// behind a bar in the callout's color
+fn render_callout(
+    callout: &Callout,
+    nested: &[ContentBlock],
+    expanded: bool,
+    is_selected: bool,
+    highlighter: &SyntaxHighlighter,
+    theme: &Theme,
+    lines: &mut Vec<Line<'static>>,
+) {
+    let accent = theme.callout_color(callout.style());
+    let bar = Span::styled("┃ ", Style::default().fg(accent));
+
+    let mut title_spans = vec![];
+    if is_selected {
+        title_spans.push(Span::styled(
+            "→ ",
+            Style::default()
+                .fg(theme.selection_indicator_fg)
+                .bg(theme.selection_indicator_bg)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    title_spans.push(bar.clone());
+    title_spans.push(Span::styled(
+        format!("{} {}", callout.icon(), callout.title),
+        Style::default().fg(accent).add_modifier(Modifier::BOLD),
+    ));
+    if callout.is_foldable() {
+        let indicator = if expanded { " ▾" } else { " ▸" };
+        title_spans.push(Span::styled(
+            indicator,
+            Style::default().fg(theme.list_bullet),
+        ));
+    }
+    lines.push(Line::from(title_spans));
+
+    if !expanded {
+        return;
+    }
+    for nested_block in nested {
+        for nested_line in render_block_to_lines(nested_block, highlighter, theme) {
+            let mut spans = vec![bar.clone()];
+            spans.extend(nested_line.spans);
+            lines.push(Line::from(spans));
+        }
+    }
+}
+
 fn render_block_to_lines(
     block: &ContentBlock,
     highlighter: &SyntaxHighlighter,
@@ -1215,6 +1939,37 @@ fn render_block_to_lines(
             };
             lines.push(Line::from(formatted));
         }
+        ContentBlock::List { ordered, items } => {
+            // One line per item, like the interactive line count
+            for (idx, item) in items.iter().enumerate() {
+                let prefix = if let Some(checked) = item.checked {
+                    let checkbox = if checked { "☑" } else { "☐" };
+                    format!("  {} ", checkbox)
+                } else if *ordered {
+                    format!("  {}. ", idx + 1)
+                } else {
+                    "  • ".to_string()
+                };
+                let first_line = item.content.lines().next().unwrap_or("");
+                let mut spans = vec![Span::styled(prefix, Style::default().fg(theme.list_bullet))];
+                spans.extend(format_inline_markdown(first_line, theme));
+                lines.push(Line::from(spans));
+            }
+        }
+        ContentBlock::Code {
+            language: Some(language),
+            content,
+            ..
+        } if language == "math" => {
+            render_math_block(content, false, theme, &mut lines);
+        }
+        ContentBlock::Code {
+            language: Some(language),
+            content,
+            ..
+        } if language == "mermaid" => {
+            render_mermaid_block(content, false, highlighter, theme, &mut lines);
+        }
         ContentBlock::Code {
             language, content, ..
         } => {
@@ -1241,6 +1996,19 @@ fn render_block_to_lines(
             blocks: nested,
             ..
         } => {
+            if let Some(callout) = Callout::from_summary(summary) {
+                render_callout(
+                    &callout,
+                    nested,
+                    true,
+                    false,
+                    highlighter,
+                    theme,
+                    &mut lines,
+                );
+                return lines;
+            }
+
             // Render details with collapsed indicator
             let summary_spans = vec![
                 Span::styled("▶ ", Style::default().fg(theme.list_bullet)),
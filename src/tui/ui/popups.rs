@@ -1,7 +1,7 @@
 //! Popup and overlay rendering for the TUI
 //!
 //! Handles modal dialogs including help, link picker, search, theme selector,
-//! and cell edit overlays.
+//! task list, tag browser, link graph, and cell edit overlays.
 
 use crate::tui::app::App;
 use crate::tui::help_text;
@@ -13,6 +13,7 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{
     Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap,
 };
+use std::path::Path;
 
 use super::util::centered_area;
 
@@ -151,6 +152,7 @@ pub fn render_link_picker(frame: &mut Frame, app: &App, area: Rect) {
                     url.clone()
                 }
             }
+            LinkTarget::Footnote(label) => app.footnote_preview(label, 50),
         };
 
         // Different styles for selected vs unselected
@@ -391,6 +393,467 @@ pub fn render_theme_picker(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(paragraph, popup_area);
 }
 
+/// Render the task list popup, grouping tasks under their headings
+pub fn render_task_list(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let tasks = app.visible_tasks();
+    let selected = app.task_list_selection(tasks.len());
+
+    let popup_area = centered_area(area, 70, 70);
+    frame.render_widget(Clear, popup_area);
+
+    let header_text = if app.task_list_show_done {
+        format!("All tasks ({})", tasks.len())
+    } else {
+        format!("Open tasks ({})", tasks.len())
+    };
+    let mut lines = vec![
+        Line::from(vec![Span::styled(
+            header_text,
+            Style::default()
+                .fg(theme.modal_title())
+                .add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(""),
+    ];
+
+    let mut selected_line = 0;
+    let mut current_path: Option<&[String]> = None;
+
+    for (idx, task) in tasks.iter().enumerate() {
+        // Heading line whenever the section changes
+        if current_path != Some(task.heading_path.as_slice()) {
+            if current_path.is_some() {
+                lines.push(Line::from(""));
+            }
+            let level = task.heading_path.len().max(1);
+            let heading = if task.heading_path.is_empty() {
+                "(no heading)".to_string()
+            } else {
+                task.heading_path.join(" › ")
+            };
+            lines.push(Line::from(vec![Span::styled(
+                heading,
+                Style::default()
+                    .fg(theme.heading_color(level))
+                    .add_modifier(Modifier::BOLD),
+            )]));
+            current_path = Some(task.heading_path.as_slice());
+        }
+
+        let is_selected = idx == selected;
+        if is_selected {
+            selected_line = lines.len();
+        }
+
+        let marker = if is_selected { "▶ " } else { "  " };
+        let checkbox = if task.checked { "[x] " } else { "[ ] " };
+        let indent = "  ".repeat(task.depth);
+        let text_style = if is_selected {
+            Style::default()
+                .fg(theme.modal_selected_fg())
+                .add_modifier(Modifier::BOLD)
+        } else if task.checked {
+            Style::default()
+                .fg(theme.modal_description())
+                .add_modifier(Modifier::CROSSED_OUT)
+        } else {
+            Style::default().fg(theme.modal_text())
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(
+                marker,
+                Style::default()
+                    .fg(theme.modal_selected_marker())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("{}{}", indent, checkbox),
+                Style::default().fg(theme.modal_key_fg()),
+            ),
+            Span::styled(task.text.clone(), text_style),
+            Span::styled(
+                format!("  :{}", task.line),
+                Style::default().fg(theme.modal_description()),
+            ),
+        ]));
+    }
+
+    if tasks.is_empty() {
+        lines.push(Line::from(vec![Span::styled(
+            "No open tasks (a: show completed)",
+            Style::default()
+                .fg(theme.modal_description())
+                .add_modifier(Modifier::ITALIC),
+        )]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![Span::styled(
+        "j/k: Navigate • Space: Check • Enter: Go to • a: Show done • u: Undo • Esc: Close",
+        Style::default()
+            .fg(theme.modal_description())
+            .add_modifier(Modifier::ITALIC),
+    )]));
+
+    // Keep the selected task visible, roughly centered
+    let total_lines = lines.len();
+    let inner_height = popup_area.height.saturating_sub(2) as usize;
+    let max_scroll = total_lines.saturating_sub(inner_height);
+    let scroll_offset = selected_line
+        .saturating_sub(inner_height / 2)
+        .min(max_scroll) as u16;
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.modal_border()))
+                .title(" Tasks ")
+                .style(Style::default().bg(theme.modal_bg())),
+        )
+        .scroll((scroll_offset, 0));
+
+    frame.render_widget(paragraph, popup_area);
+
+    if total_lines > inner_height {
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("↑"))
+            .end_symbol(Some("↓"))
+            .style(Style::default().fg(theme.modal_border()));
+
+        let mut scrollbar_state = ScrollbarState::new(max_scroll).position(scroll_offset as usize);
+
+        frame.render_stateful_widget(
+            scrollbar,
+            popup_area.inner(ratatui::layout::Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut scrollbar_state,
+        );
+    }
+}
+
+/// Render the picker for a wikilink that matches several vault notes
+pub fn render_note_picker(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(picker) = &app.note_picker else {
+        return;
+    };
+    let theme = &app.theme;
+
+    let popup_area = centered_area(area, 60, 50);
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines = vec![
+        Line::from(vec![Span::styled(
+            format!("[[{}]] matches {} notes", picker.target, picker.notes.len()),
+            Style::default()
+                .fg(theme.modal_title())
+                .add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(""),
+    ];
+
+    let root = app.vault.as_ref().map(|vault| vault.root());
+    for (idx, path) in picker.notes.iter().enumerate() {
+        let is_selected = idx == picker.selected;
+        let shown = root
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        let marker = if is_selected { "▶ " } else { "  " };
+        let text_style = if is_selected {
+            Style::default()
+                .fg(theme.modal_selected_fg())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.modal_text())
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                marker,
+                Style::default()
+                    .fg(theme.modal_selected_marker())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(shown.display().to_string(), text_style),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![Span::styled(
+        "j/k: Navigate • Enter: Open • Esc: Cancel",
+        Style::default()
+            .fg(theme.modal_description())
+            .add_modifier(Modifier::ITALIC),
+    )]));
+
+    // Keep the selected note visible
+    let inner_height = popup_area.height.saturating_sub(2) as usize;
+    let scroll_offset = (picker.selected + 2 + 1).saturating_sub(inner_height) as u16;
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.modal_border()))
+                .title(" Open Note ")
+                .style(Style::default().bg(theme.modal_bg())),
+        )
+        .scroll((scroll_offset, 0));
+
+    frame.render_widget(paragraph, popup_area);
+}
+
+/// Render the tag browser: tags with counts, or the selected tag's locations
+pub fn render_tag_browser(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(browser) = &app.tag_browser else {
+        return;
+    };
+    let theme = &app.theme;
+
+    let popup_area = centered_area(area, 70, 70);
+    frame.render_widget(Clear, popup_area);
+
+    let scope = match (browser.workspace, app.vault.is_some()) {
+        (false, _) => "this document",
+        (true, true) => "the vault",
+        (true, false) => "this folder",
+    };
+    let header_text = match browser.selected_tag() {
+        Some(tag) if browser.location.is_some() => {
+            format!("#{} in {} ({})", tag.name, scope, tag.locations.len())
+        }
+        _ => format!("Tags in {} ({})", scope, browser.tags.len()),
+    };
+    let mut lines = vec![
+        Line::from(vec![Span::styled(
+            header_text,
+            Style::default()
+                .fg(theme.modal_title())
+                .add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(""),
+    ];
+
+    let item_style = |is_selected: bool| {
+        if is_selected {
+            Style::default()
+                .fg(theme.modal_selected_fg())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.modal_text())
+        }
+    };
+    let marker = |is_selected: bool| {
+        Span::styled(
+            if is_selected { "▶ " } else { "  " },
+            Style::default()
+                .fg(theme.modal_selected_marker())
+                .add_modifier(Modifier::BOLD),
+        )
+    };
+
+    let root = app.vault.as_ref().map(|vault| vault.root());
+    let selected = match (browser.selected_tag(), browser.location) {
+        (Some(tag), Some(location)) => {
+            for (idx, place) in tag.locations.iter().enumerate() {
+                let is_selected = idx == location;
+                let mut spans = vec![marker(is_selected)];
+                if browser.workspace {
+                    let shown = root
+                        .and_then(|root| place.path.strip_prefix(root).ok())
+                        .or_else(|| place.path.file_name().map(Path::new))
+                        .unwrap_or(&place.path);
+                    spans.push(Span::styled(
+                        format!("{}:", shown.display()),
+                        Style::default().fg(theme.modal_key_fg()),
+                    ));
+                }
+                spans.push(Span::styled(
+                    format!("{}  ", place.line),
+                    Style::default().fg(theme.modal_key_fg()),
+                ));
+                if let Some(heading) = &place.heading {
+                    spans.push(Span::styled(
+                        format!("{} › ", heading),
+                        Style::default().fg(theme.modal_description()),
+                    ));
+                }
+                spans.push(Span::styled(place.text.clone(), item_style(is_selected)));
+                lines.push(Line::from(spans));
+            }
+            location
+        }
+        _ => {
+            for (idx, tag) in browser.tags.iter().enumerate() {
+                let is_selected = idx == browser.selected;
+                lines.push(Line::from(vec![
+                    marker(is_selected),
+                    Span::styled(format!("#{}", tag.name), item_style(is_selected)),
+                    Span::styled(
+                        format!("  {}", tag.locations.len()),
+                        Style::default().fg(theme.modal_description()),
+                    ),
+                ]));
+            }
+            browser.selected
+        }
+    };
+
+    if browser.tags.is_empty() {
+        lines.push(Line::from(vec![Span::styled(
+            "No tags (Tab: search the workspace)",
+            Style::default()
+                .fg(theme.modal_description())
+                .add_modifier(Modifier::ITALIC),
+        )]));
+    }
+
+    lines.push(Line::from(""));
+    let hint = if browser.location.is_some() {
+        "j/k: Navigate • Enter: Go to • Bksp: Tags • Tab: Scope • Esc: Close"
+    } else {
+        "j/k: Navigate • Enter: Locations • Tab: Scope • Esc: Close"
+    };
+    lines.push(Line::from(vec![Span::styled(
+        hint,
+        Style::default()
+            .fg(theme.modal_description())
+            .add_modifier(Modifier::ITALIC),
+    )]));
+
+    // Keep the selected entry visible, roughly centered
+    let selected_line = selected + 2;
+    let inner_height = popup_area.height.saturating_sub(2) as usize;
+    let max_scroll = lines.len().saturating_sub(inner_height);
+    let scroll_offset = selected_line
+        .saturating_sub(inner_height / 2)
+        .min(max_scroll) as u16;
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.modal_border()))
+                .title(" Tags ")
+                .style(Style::default().bg(theme.modal_bg())),
+        )
+        .scroll((scroll_offset, 0));
+
+    frame.render_widget(paragraph, popup_area);
+}
+
+/// Render the link graph: the centre note with its links drawn as a tree
+pub fn render_graph_view(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(view) = &app.graph_view else {
+        return;
+    };
+    let theme = &app.theme;
+    let graph = &view.graph;
+
+    let popup_area = centered_area(area, 70, 70);
+    frame.render_widget(Clear, popup_area);
+
+    let center = view.center;
+    let mut loose_ends = Vec::new();
+    if graph.orphans().contains(&center) {
+        loose_ends.push("orphan");
+    } else if graph.dead_ends().contains(&center) {
+        loose_ends.push("dead end");
+    }
+    let mut summary = format!(
+        "→ {} out • ← {} in",
+        graph.outgoing(center).len(),
+        graph.incoming(center).len()
+    );
+    let missing = graph.unresolved_from(center).len();
+    if missing > 0 {
+        summary.push_str(&format!(" • {} missing", missing));
+    }
+    for loose_end in loose_ends {
+        summary.push_str(&format!(" • {}", loose_end));
+    }
+
+    let mut lines = vec![
+        Line::from(vec![Span::styled(
+            format!("{} (depth {})", graph.notes()[center].display(), view.depth),
+            Style::default()
+                .fg(theme.modal_title())
+                .add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(vec![Span::styled(
+            summary,
+            Style::default().fg(theme.modal_description()),
+        )]),
+        Line::from(""),
+    ];
+
+    for (idx, row) in view.rows.iter().enumerate() {
+        let is_selected = idx == view.selected;
+        let label_style = if is_selected {
+            Style::default()
+                .fg(theme.modal_selected_fg())
+                .add_modifier(Modifier::BOLD)
+        } else if row.note.is_none() {
+            Style::default()
+                .fg(theme.modal_description())
+                .add_modifier(Modifier::ITALIC)
+        } else {
+            Style::default().fg(theme.modal_text())
+        };
+        let mut spans = vec![
+            Span::styled(
+                if is_selected { "▶ " } else { "  " },
+                Style::default()
+                    .fg(theme.modal_selected_marker())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                row.prefix.clone(),
+                Style::default().fg(theme.modal_border()),
+            ),
+            Span::styled(row.label.clone(), label_style),
+        ];
+        if row.repeated {
+            spans.push(Span::styled(
+                " ↺",
+                Style::default().fg(theme.modal_description()),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![Span::styled(
+        "j/k: Navigate • Enter: Open • l: Center • +/-: Depth • Esc: Close",
+        Style::default()
+            .fg(theme.modal_description())
+            .add_modifier(Modifier::ITALIC),
+    )]));
+
+    // Keep the selected row visible, roughly centered
+    let selected_line = view.selected + 3;
+    let inner_height = popup_area.height.saturating_sub(2) as usize;
+    let max_scroll = lines.len().saturating_sub(inner_height);
+    let scroll_offset = selected_line
+        .saturating_sub(inner_height / 2)
+        .min(max_scroll) as u16;
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.modal_border()))
+                .title(" Link Graph ")
+                .style(Style::default().bg(theme.modal_bg())),
+        )
+        .scroll((scroll_offset, 0));
+
+    frame.render_widget(paragraph, popup_area);
+}
+
 /// Render the cell edit overlay for table editing
 pub fn render_cell_edit_overlay(frame: &mut Frame, app: &App, area: Rect) {
     let theme = &app.theme;
@@ -442,6 +905,44 @@ pub fn render_cell_edit_overlay(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(paragraph, edit_area);
 }
 
+/// Render the definition of the selected footnote reference at the bottom
+/// of the content pane (interactive mode)
+pub fn render_footnote_popup(frame: &mut Frame, app: &App, label: &str, content_area: Rect) {
+    let theme = &app.theme;
+
+    let text = match app.footnote_definition(label) {
+        Some(def) => def.content,
+        None => "(no definition)".to_string(),
+    };
+    let lines: Vec<Line> = text
+        .lines()
+        .map(|line| Line::from(super::format_inline_markdown(line, theme)))
+        .collect();
+
+    // Sit just above the bottom border, at most half the pane tall
+    let width = content_area.width.saturating_sub(4);
+    let height = (lines.len() as u16 + 2).min(content_area.height / 2).max(3);
+    let popup_area = Rect {
+        x: content_area.x + 2,
+        y: (content_area.y + content_area.height).saturating_sub(height + 1),
+        width,
+        height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" Footnote [^{}] ", label))
+            .title_style(Style::default().fg(theme.modal_title()))
+            .border_style(Style::default().fg(theme.modal_border()))
+            .style(Style::default().bg(theme.modal_bg())),
+    );
+
+    frame.render_widget(paragraph, popup_area);
+}
+
 /// Render file creation confirmation dialog
 pub fn render_file_create_confirm(frame: &mut Frame, message: &str, theme: &Theme) {
     use crate::tui::ui::util::centered_area;
@@ -491,6 +992,209 @@ pub fn render_file_create_confirm(frame: &mut Frame, message: &str, theme: &Them
     frame.render_widget(paragraph, area);
 }
 
+/// Render the confirmation modal for deleting a section
+pub fn render_section_delete_confirm(frame: &mut Frame, heading: &str, theme: &Theme) {
+    let area = centered_area(frame.area(), 50, 30);
+    frame.render_widget(Clear, area);
+
+    let label = Style::default().fg(theme.modal_description());
+    let text = vec![
+        Line::from(vec![Span::styled(
+            "Delete Section?",
+            Style::default()
+                .fg(theme.modal_title())
+                .add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            format!("'{}' and its subsections will be removed.", heading),
+            Style::default().fg(theme.modal_text()),
+        )]),
+        Line::from(vec![Span::styled("Undo with U.", label)]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("[y]", Style::default().fg(theme.modal_key_fg())),
+            Span::styled(" Delete  ", label),
+            Span::styled("[n/Esc]", Style::default().fg(theme.modal_key_fg())),
+            Span::styled(" Cancel", label),
+        ]),
+    ];
+
+    let paragraph = Paragraph::new(text)
+        .alignment(ratatui::layout::Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Confirm ")
+                .title_style(Style::default().fg(theme.modal_title()))
+                .border_style(Style::default().fg(theme.modal_border()))
+                .style(Style::default().bg(theme.modal_bg())),
+        );
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Render the confirmation modal for running a code block
+pub fn render_code_run_confirm(
+    frame: &mut Frame,
+    request: &crate::tui::code_runner::CodeRunRequest,
+    theme: &Theme,
+) {
+    use crate::tui::ui::util::centered_area;
+
+    // Code lines shown before the rest is summarized
+    const PREVIEW_LINES: usize = 12;
+
+    let area = centered_area(frame.area(), 70, 60);
+    frame.render_widget(Clear, area);
+
+    let label = Style::default().fg(theme.modal_description());
+    let mut text = vec![
+        Line::from(vec![Span::styled(
+            format!("Run this {} block?", request.language),
+            Style::default()
+                .fg(theme.modal_title())
+                .add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Command: ", label),
+            Span::styled(
+                format!("{} <script>", request.command.join(" ")),
+                Style::default().fg(theme.modal_text()),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("In:      ", label),
+            Span::styled(
+                request.dir.display().to_string(),
+                Style::default().fg(theme.modal_text()),
+            ),
+        ]),
+        Line::from(""),
+    ];
+
+    let code_lines: Vec<&str> = request.code.lines().collect();
+    for line in code_lines.iter().take(PREVIEW_LINES) {
+        text.push(Line::from(Span::styled(
+            format!("  {}", line),
+            theme.inline_code_style(),
+        )));
+    }
+    if code_lines.len() > PREVIEW_LINES {
+        text.push(Line::from(Span::styled(
+            format!("  … {} more lines", code_lines.len() - PREVIEW_LINES),
+            label.add_modifier(Modifier::ITALIC),
+        )));
+    }
+
+    text.push(Line::from(""));
+    text.push(Line::from(vec![
+        Span::styled("[y]", Style::default().fg(theme.modal_key_fg())),
+        Span::styled(" Run  ", label),
+        Span::styled("[n/Esc]", Style::default().fg(theme.modal_key_fg())),
+        Span::styled(" Cancel", label),
+    ]));
+
+    let paragraph = Paragraph::new(text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Confirm ")
+            .title_style(Style::default().fg(theme.modal_title()))
+            .border_style(Style::default().fg(theme.modal_border()))
+            .style(Style::default().bg(theme.modal_bg())),
+    );
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Render the preview of the links a rename rewrites
+pub fn render_rename_confirm(frame: &mut Frame, plan: &crate::rename::RenamePlan, theme: &Theme) {
+    use crate::tui::ui::util::centered_area;
+
+    // Changed lines shown before the rest is summarized
+    const PREVIEW_CHANGES: usize = 8;
+
+    let area = centered_area(frame.area(), 75, 70);
+    frame.render_widget(Clear, area);
+
+    let label = Style::default().fg(theme.modal_description());
+    let title = match (&plan.heading, &plan.move_file) {
+        (_, Some((from, to))) => format!(
+            "Move {} → {}?",
+            plan.display_path(from),
+            plan.display_path(to)
+        ),
+        (Some((old, new)), None) => format!("Rename heading '{}' → '{}'?", old, new),
+        (None, None) => "Rename?".to_string(),
+    };
+    let count = plan.change_count();
+    let mut text = vec![
+        Line::from(vec![Span::styled(
+            title,
+            Style::default()
+                .fg(theme.modal_title())
+                .add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(Span::styled(
+            format!(
+                "{} line{} in {} note{} will change:",
+                count,
+                if count == 1 { "" } else { "s" },
+                plan.rewrites.len(),
+                if plan.rewrites.len() == 1 { "" } else { "s" }
+            ),
+            label,
+        )),
+        Line::from(""),
+    ];
+
+    let changes = plan
+        .rewrites
+        .iter()
+        .flat_map(|rewrite| rewrite.changes.iter().map(move |c| (&rewrite.path, c)));
+    for (path, change) in changes.take(PREVIEW_CHANGES) {
+        text.push(Line::from(Span::styled(
+            format!("{}:{}", plan.display_path(path), change.line),
+            Style::default().fg(theme.modal_text()),
+        )));
+        text.push(Line::from(Span::styled(
+            format!("  - {}", change.before),
+            Style::default().fg(Color::Red),
+        )));
+        text.push(Line::from(Span::styled(
+            format!("  + {}", change.after),
+            Style::default().fg(Color::Green),
+        )));
+    }
+    if count > PREVIEW_CHANGES {
+        text.push(Line::from(Span::styled(
+            format!("  … {} more", count - PREVIEW_CHANGES),
+            label.add_modifier(Modifier::ITALIC),
+        )));
+    }
+
+    text.push(Line::from(""));
+    text.push(Line::from(vec![
+        Span::styled("[y]", Style::default().fg(theme.modal_key_fg())),
+        Span::styled(" Apply  ", label),
+        Span::styled("[n/Esc]", Style::default().fg(theme.modal_key_fg())),
+        Span::styled(" Cancel", label),
+    ]));
+
+    let paragraph = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Rename ")
+            .title_style(Style::default().fg(theme.modal_title()))
+            .border_style(Style::default().fg(theme.modal_border()))
+            .style(Style::default().bg(theme.modal_bg())),
+    );
+
+    frame.render_widget(paragraph, area);
+}
+
 /// Render the save width confirmation modal
 pub fn render_save_width_confirm(frame: &mut Frame, width: u16, theme: &Theme) {
     use crate::tui::ui::util::centered_area;
@@ -537,9 +1241,86 @@ pub fn render_save_width_confirm(frame: &mut Frame, width: u16, theme: &Theme) {
     frame.render_widget(paragraph, area);
 }
 
-/// Render the command palette with fuzzy search
+/// Render the external-modification conflict modal
+pub fn render_conflict_confirm(
+    frame: &mut Frame,
+    conflict: &crate::tui::conflict::WriteConflict,
+    filename: &str,
+    theme: &Theme,
+) {
+    // Create a centered dialog area
+    let area = centered_area(frame.area(), 60, 40);
+
+    // Clear the area
+    frame.render_widget(Clear, area);
+
+    let key_style = Style::default().fg(theme.modal_key_fg());
+    let desc_style = Style::default().fg(theme.modal_description());
+
+    let mut text = vec![
+        Line::from(vec![Span::styled(
+            "File Changed on Disk",
+            Style::default()
+                .fg(theme.modal_title())
+                .add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            format!(
+                "{} was modified outside treemd since it was loaded.",
+                filename
+            ),
+            Style::default().fg(theme.modal_text()),
+        )]),
+        Line::from(""),
+    ];
+
+    // Show the line our edit changes so the user knows what's at stake
+    if let Some((line, old, new)) = conflict.summary() {
+        text.push(Line::from(vec![Span::styled(
+            format!("{} (line {}):", conflict.label, line),
+            desc_style,
+        )]));
+        text.push(Line::from(vec![Span::styled(
+            format!("- {}", old),
+            Style::default().fg(Color::Red),
+        )]));
+        text.push(Line::from(vec![Span::styled(
+            format!("+ {}", new),
+            Style::default().fg(Color::Green),
+        )]));
+        text.push(Line::from(""));
+    }
+
+    text.push(Line::from(vec![
+        Span::styled("[r]", key_style),
+        Span::styled(" Reload  ", desc_style),
+        Span::styled("[o]", key_style),
+        Span::styled(" Overwrite  ", desc_style),
+        Span::styled("[m]", key_style),
+        Span::styled(" Merge line  ", desc_style),
+        Span::styled("[Esc]", key_style),
+        Span::styled(" Cancel", desc_style),
+    ]));
+
+    let paragraph = Paragraph::new(text)
+        .alignment(ratatui::layout::Alignment::Center)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Conflict ")
+                .title_style(Style::default().fg(theme.modal_title()))
+                .border_style(Style::default().fg(theme.modal_border()))
+                .style(Style::default().bg(theme.modal_bg())),
+        );
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Render the command palette with fuzzy search and ex-style command line
 pub fn render_command_palette(frame: &mut Frame, app: &App, theme: &Theme) {
-    use crate::tui::app::PALETTE_COMMANDS;
+    use crate::tui::app::{PALETTE_COMMANDS, PaletteEntry};
     use crate::tui::ui::util::centered_area;
 
     // Create a centered popup
@@ -548,7 +1329,7 @@ pub fn render_command_palette(frame: &mut Frame, app: &App, theme: &Theme) {
     // Clear the area
     frame.render_widget(Clear, area);
 
-    // Build the content
+    // Build the header
     let mut lines = vec![
         // Title
         Line::from(vec![Span::styled(
@@ -558,26 +1339,51 @@ pub fn render_command_palette(frame: &mut Frame, app: &App, theme: &Theme) {
                 .add_modifier(Modifier::BOLD),
         )]),
         Line::from(""),
-        // Search input
+        // Command line input
         Line::from(vec![
             Span::styled(": ", Style::default().fg(theme.modal_key_fg())),
             Span::styled(&app.command_query, Style::default().fg(theme.modal_text())),
             Span::styled("█", Style::default().fg(Color::White)), // Cursor
         ]),
-        Line::from(""),
     ];
 
+    // Completion candidates (wildmenu style) when Tab found several
+    if app.command_completions.len() > 1 {
+        let mut spans = vec![Span::raw("  ")];
+        for (idx, candidate) in app.command_completions.iter().enumerate() {
+            // Show only the last word, the rest is already on the command line
+            let label = candidate
+                .rsplit([' ', '=', '/'])
+                .find(|part| !part.is_empty())
+                .unwrap_or(candidate);
+            let style = if app.command_completion_idx == Some(idx) {
+                Style::default()
+                    .fg(theme.modal_selected_marker())
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+            } else {
+                Style::default().fg(theme.modal_description())
+            };
+            spans.push(Span::styled(label.to_string(), style));
+            spans.push(Span::raw("  "));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from(""));
+
+    let header_len = lines.len();
+    let mut entry_lines = Vec::new();
+    let mut selected_line = 0;
+
     // Show filtered commands
     if app.command_filtered.is_empty() {
-        lines.push(Line::from(vec![Span::styled(
+        entry_lines.push(Line::from(vec![Span::styled(
             "  No matching commands",
             Style::default()
                 .fg(theme.modal_description())
                 .add_modifier(Modifier::ITALIC),
         )]));
     } else {
-        for (display_idx, &cmd_idx) in app.command_filtered.iter().enumerate() {
-            let cmd = &PALETTE_COMMANDS[cmd_idx];
+        for (display_idx, entry) in app.command_filtered.iter().enumerate() {
             let is_selected = display_idx == app.command_selected;
 
             let prefix = if is_selected { "▸ " } else { "  " };
@@ -589,24 +1395,37 @@ pub fn render_command_palette(frame: &mut Frame, app: &App, theme: &Theme) {
                 Style::default().fg(theme.modal_text())
             };
 
-            // Command name
-            let mut spans = vec![Span::styled(prefix, style), Span::styled(cmd.name, style)];
-
-            // Show aliases in dimmer text
-            if !cmd.aliases.is_empty() {
-                let aliases_str = format!(" ({})", cmd.aliases.join(", "));
-                spans.push(Span::styled(
-                    aliases_str,
-                    Style::default().fg(theme.modal_description()),
-                ));
+            if is_selected {
+                selected_line = entry_lines.len();
             }
 
-            lines.push(Line::from(spans));
+            let (name, detail, description) = match entry {
+                PaletteEntry::Command(idx) => {
+                    let cmd = &PALETTE_COMMANDS[*idx];
+                    // Show usage for argument commands, aliases otherwise
+                    let detail = match cmd.action.usage() {
+                        Some(usage) => format!(" :{}", usage),
+                        None => format!(" ({})", cmd.aliases.join(", ")),
+                    };
+                    (cmd.name.to_string(), detail, cmd.description)
+                }
+                PaletteEntry::Action(action) => (
+                    action.to_string(),
+                    format!(" [{}]", action.category()),
+                    action.description(),
+                ),
+            };
+
+            entry_lines.push(Line::from(vec![
+                Span::styled(prefix, style),
+                Span::styled(name, style),
+                Span::styled(detail, Style::default().fg(theme.modal_description())),
+            ]));
 
             // Show description for selected item
             if is_selected {
-                lines.push(Line::from(vec![Span::styled(
-                    format!("    {}", cmd.description),
+                entry_lines.push(Line::from(vec![Span::styled(
+                    format!("    {}", description),
                     Style::default()
                         .fg(theme.modal_description())
                         .add_modifier(Modifier::ITALIC),
@@ -615,14 +1434,28 @@ pub fn render_command_palette(frame: &mut Frame, app: &App, theme: &Theme) {
         }
     }
 
+    // Keep the selected entry visible (border + header + footer take the rest)
+    let visible = (area.height as usize)
+        .saturating_sub(2 + header_len + 2)
+        .max(1);
+    let offset = (selected_line + 2).saturating_sub(visible);
+    lines.extend(entry_lines.into_iter().skip(offset).take(visible));
+
     // Footer with hints
     lines.push(Line::from(""));
     lines.push(Line::from(vec![
-        Span::styled("↑↓/Tab", Style::default().fg(theme.modal_key_fg())),
+        Span::styled("↑↓", Style::default().fg(theme.modal_key_fg())),
         Span::styled(
             " navigate  ",
             Style::default().fg(theme.modal_description()),
         ),
+        Span::styled("Tab", Style::default().fg(theme.modal_key_fg())),
+        Span::styled(
+            " complete  ",
+            Style::default().fg(theme.modal_description()),
+        ),
+        Span::styled("^P/^N", Style::default().fg(theme.modal_key_fg())),
+        Span::styled(" history  ", Style::default().fg(theme.modal_description())),
         Span::styled("Enter", Style::default().fg(theme.modal_key_fg())),
         Span::styled(" execute  ", Style::default().fg(theme.modal_description())),
         Span::styled("Esc", Style::default().fg(theme.modal_key_fg())),
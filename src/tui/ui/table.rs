@@ -1,13 +1,16 @@
 //! Table rendering for the TUI
 //!
 //! Handles rendering of markdown tables with proper alignment,
-//! borders, selection highlighting, and cell navigation.
+//! borders, selection highlighting, and cell navigation. In table mode the
+//! view can sort, filter and scroll the table without touching the file.
 
 use crate::parser::output::Alignment;
+use crate::tui::interactive::{TableSort, TableView};
 use crate::tui::theme::Theme;
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use unicode_width::UnicodeWidthStr;
+use std::ops::Range;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use super::util::align_text;
 
@@ -21,6 +24,37 @@ pub struct TableRenderContext<'a> {
     pub selected_cell: Option<(usize, usize)>,
 }
 
+/// Widest a column may get when the table doesn't fit the pane
+const MAX_COLUMN_WIDTH: usize = 32;
+
+/// Width of the selection arrow column to the left of a table
+pub const TABLE_GUTTER_WIDTH: usize = 2;
+
+/// Number of data rows that fit in a pane `height` lines tall, leaving room
+/// for the borders, the frozen header and a line of context
+pub fn table_rows_that_fit(height: usize) -> usize {
+    height.saturating_sub(5).max(1)
+}
+
+/// Sort/filter/scroll view applied when rendering a table in table mode
+pub struct TableViewport<'a> {
+    pub view: &'a TableView,
+    /// Width available to the table itself (excluding the selection gutter)
+    pub width: usize,
+    /// Maximum number of lines of data rows to show; the header always stays visible
+    pub max_lines: Option<usize>,
+}
+
+/// Selection state and optional view for [`render_table_with`]
+pub struct TableRenderOptions<'a> {
+    pub theme: &'a Theme,
+    pub is_selected: bool,
+    pub in_table_mode: bool,
+    /// Selected (display row, column); row 0 is the header
+    pub selected_cell: Option<(usize, usize)>,
+    pub viewport: Option<TableViewport<'a>>,
+}
+
 /// Render a complete table with headers, alignments, and rows
 ///
 /// # Arguments
@@ -40,13 +74,43 @@ pub fn render_table(
     in_table_mode: bool,
     selected_cell: Option<(usize, usize)>,
 ) -> Vec<Line<'static>> {
-    let mut lines = Vec::new();
+    render_table_with(
+        headers,
+        alignments,
+        rows,
+        &TableRenderOptions {
+            theme,
+            is_selected,
+            in_table_mode,
+            selected_cell,
+            viewport: None,
+        },
+    )
+}
 
-    if headers.is_empty() {
-        return lines;
-    }
+/// Header labels, with a sort indicator on the sorted column
+pub fn table_header_labels(headers: &[String], sort: Option<TableSort>) -> Vec<String> {
+    headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| match sort {
+            Some(sort) if sort.column == i => {
+                format!("{} {}", header, if sort.ascending { "▲" } else { "▼" })
+            }
+            _ => header.clone(),
+        })
+        .collect()
+}
 
-    // Calculate column widths using Unicode display width
+/// Column widths including one space of padding on each side.
+///
+/// When the table is wider than `available`, columns are capped so that
+/// more of them fit side by side.
+pub fn table_column_widths(
+    headers: &[String],
+    rows: &[Vec<String>],
+    available: Option<usize>,
+) -> Vec<usize> {
     let col_count = headers.len();
     let mut col_widths: Vec<usize> = headers.iter().map(|h| h.width()).collect();
 
@@ -61,6 +125,172 @@ pub fn render_table(
         *width += 2; // 1 space on each side
     }
 
+    if let Some(available) = available {
+        let total: usize = col_widths.iter().map(|w| w + 1).sum::<usize>() + 1;
+        if total > available {
+            let cap = MAX_COLUMN_WIDTH.min(available.saturating_sub(2)).max(3);
+            for width in &mut col_widths {
+                *width = (*width).min(cap);
+            }
+        }
+    }
+
+    col_widths
+}
+
+/// Range of columns that fit within `available` when starting at `start`.
+///
+/// At least one column is always included.
+pub fn visible_table_columns(col_widths: &[usize], start: usize, available: usize) -> Range<usize> {
+    if col_widths.is_empty() {
+        return 0..0;
+    }
+    let start = start.min(col_widths.len() - 1);
+    let mut used = 1; // Left border
+    let mut end = start;
+    while end < col_widths.len() && used + col_widths[end] < available {
+        used += col_widths[end] + 1;
+        end += 1;
+    }
+    start..end.max(start + 1)
+}
+
+/// Range of data rows to show within `max_lines`, starting at `offset`.
+///
+/// `heights` are the line counts of each row (more than one when wrapped).
+/// The start moves forward if needed so the selected row stays visible.
+pub fn visible_table_rows(
+    heights: &[usize],
+    offset: usize,
+    max_lines: usize,
+    selected: Option<usize>,
+) -> Range<usize> {
+    if heights.is_empty() {
+        return 0..0;
+    }
+    let fits = |start: usize| {
+        let mut used = 0;
+        let mut end = start;
+        while end < heights.len() && used + heights[end] <= max_lines {
+            used += heights[end];
+            end += 1;
+        }
+        start..end.max(start + 1)
+    };
+
+    let mut window = fits(offset.min(heights.len() - 1));
+    if let Some(selected) = selected.filter(|&row| row < heights.len()) {
+        if selected < window.start {
+            window = fits(selected);
+        }
+        while !window.contains(&selected) {
+            window = fits(window.start + 1);
+        }
+    }
+    // Don't leave empty space at the bottom when scrolled past the end
+    while window.start > 0 && window.end == heights.len() {
+        let earlier = fits(window.start - 1);
+        if earlier.end < heights.len() {
+            break;
+        }
+        window = earlier;
+    }
+    window
+}
+
+/// Render a table, applying the sort/filter/scroll view if one is given
+pub fn render_table_with(
+    headers: &[String],
+    alignments: &[Alignment],
+    rows: &[Vec<String>],
+    opts: &TableRenderOptions,
+) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+
+    if headers.is_empty() {
+        return lines;
+    }
+
+    let theme = opts.theme;
+    let is_selected = opts.is_selected;
+    let in_table_mode = opts.in_table_mode;
+    let view = opts.viewport.as_ref().map(|viewport| viewport.view);
+
+    // Display order of data rows (filtered and sorted by the view)
+    let order: Vec<usize> = match view {
+        Some(view) => view.visible_rows(rows),
+        None => (0..rows.len()).collect(),
+    };
+    let labels = table_header_labels(headers, view.and_then(|view| view.sort));
+
+    // Calculate column widths using Unicode display width
+    let available = opts.viewport.as_ref().map(|viewport| viewport.width);
+    let all_widths = table_column_widths(&labels, rows, available);
+    let columns = match available {
+        Some(available) => visible_table_columns(
+            &all_widths,
+            view.map_or(0, |view| view.col_offset),
+            available,
+        ),
+        None => 0..all_widths.len(),
+    };
+    let col_widths = &all_widths[columns.clone()];
+    let alignments = alignments.get(columns.start..).unwrap_or(&[]);
+    let hidden_left = columns.start > 0;
+    let hidden_right = columns.end < all_widths.len();
+    let wrap = view.is_some_and(|view| view.wrap);
+
+    // Selection relative to the visible columns
+    let selected_cell = opts.selected_cell.map(|(row, col)| {
+        if columns.contains(&col) {
+            (row, col - columns.start)
+        } else {
+            (row, usize::MAX)
+        }
+    });
+
+    // Cells for the visible columns, truncated to fit unless wrapping
+    let fit_cells = |cells: &[String]| -> Vec<Vec<String>> {
+        columns
+            .clone()
+            .zip(col_widths)
+            .map(|(i, &width)| {
+                let cell = cells.get(i).map(String::as_str).unwrap_or("");
+                let inner = width.saturating_sub(2);
+                if available.is_none() {
+                    vec![cell.to_string()]
+                } else if wrap {
+                    wrap_to_width(cell, inner)
+                } else {
+                    vec![truncate_to_width(cell, inner)]
+                }
+            })
+            .collect()
+    };
+    let body: Vec<Vec<Vec<String>>> = order.iter().map(|&i| fit_cells(&rows[i])).collect();
+
+    // Data rows shown (display index into `order`), limited to the pane
+    let row_window = match opts
+        .viewport
+        .as_ref()
+        .and_then(|viewport| viewport.max_lines)
+    {
+        Some(max_lines) => {
+            let heights: Vec<usize> = body
+                .iter()
+                .map(|cells| cells.iter().map(Vec::len).max().unwrap_or(1).max(1))
+                .collect();
+            let selected_row = opts.selected_cell.and_then(|(row, _)| row.checked_sub(1));
+            visible_table_rows(
+                &heights,
+                view.map_or(0, |view| view.row_offset),
+                max_lines,
+                selected_row,
+            )
+        }
+        None => 0..order.len(),
+    };
+
     // Top border (add selection indicator or spacing)
     let mut top_border_spans = vec![];
 
@@ -78,72 +308,61 @@ pub fn render_table(
         ));
     }
 
-    let mut top_border = String::from("┌");
-    for (i, &width) in col_widths.iter().enumerate() {
-        top_border.push_str(&"─".repeat(width));
-        if i < col_widths.len() - 1 {
-            top_border.push('┬');
-        }
-    }
-    top_border.push('┐');
+    // Scroll indicators replace the corners when columns are hidden
+    let (left, right) = (
+        if hidden_left { '‹' } else { '┌' },
+        if hidden_right { '›' } else { '┐' },
+    );
     top_border_spans.push(Span::styled(
-        top_border,
+        border_line(col_widths, left, '┬', right),
         Style::default().fg(theme.table_border),
     ));
     lines.push(Line::from(top_border_spans));
 
     // Header row (row 0)
-    let header_line = render_table_row(
-        headers,
-        &col_widths,
+    let header_ctx = TableRenderContext {
+        theme,
+        row_num: 0,
+        is_header: true,
+        in_table_mode,
+        is_table_selected: is_selected,
+        selected_cell,
+    };
+    push_row_lines(
+        &mut lines,
+        &fit_cells(&labels),
+        col_widths,
         alignments,
-        &TableRenderContext {
-            theme,
-            row_num: 0,
-            is_header: true,
-            in_table_mode,
-            is_table_selected: is_selected,
-            selected_cell,
-        },
+        &header_ctx,
     );
-    lines.push(header_line);
 
     // Header separator
     let mut separator_spans = vec![];
     if in_table_mode || is_selected {
         separator_spans.push(Span::raw("  "));
     }
-    let mut separator = String::from("├");
-    for (i, &width) in col_widths.iter().enumerate() {
-        separator.push_str(&"─".repeat(width));
-        if i < col_widths.len() - 1 {
-            separator.push('┼');
-        }
-    }
-    separator.push('┤');
     separator_spans.push(Span::styled(
-        separator,
+        border_line(col_widths, '├', '┼', '┤'),
         Style::default().fg(theme.table_border),
     ));
     lines.push(Line::from(separator_spans));
 
     // Data rows
-    for (row_idx, row) in rows.iter().enumerate() {
-        let data_row = row_idx + 1; // +1 because row 0 is header
-        let row_line = render_table_row(
-            row,
-            &col_widths,
-            alignments,
-            &TableRenderContext {
-                theme,
-                row_num: data_row,
-                is_header: false,
-                in_table_mode,
-                is_table_selected: is_selected,
-                selected_cell,
-            },
-        );
-        lines.push(row_line);
+    for display_idx in row_window {
+        let row = &rows[order[display_idx]];
+        let ctx = TableRenderContext {
+            theme,
+            row_num: display_idx + 1, // +1 because row 0 is header
+            is_header: false,
+            in_table_mode,
+            is_table_selected: is_selected,
+            selected_cell,
+        };
+        if available.is_some() {
+            push_row_lines(&mut lines, &body[display_idx], col_widths, alignments, &ctx);
+        } else {
+            lines.push(render_table_row(row, col_widths, alignments, &ctx));
+        }
     }
 
     // Bottom border
@@ -151,16 +370,12 @@ pub fn render_table(
     if in_table_mode || is_selected {
         bottom_border_spans.push(Span::raw("  "));
     }
-    let mut bottom_border = String::from("└");
-    for (i, &width) in col_widths.iter().enumerate() {
-        bottom_border.push_str(&"─".repeat(width));
-        if i < col_widths.len() - 1 {
-            bottom_border.push('┴');
-        }
-    }
-    bottom_border.push('┘');
+    let (left, right) = (
+        if hidden_left { '‹' } else { '└' },
+        if hidden_right { '›' } else { '┘' },
+    );
     bottom_border_spans.push(Span::styled(
-        bottom_border,
+        border_line(col_widths, left, '┴', right),
         Style::default().fg(theme.table_border),
     ));
     lines.push(Line::from(bottom_border_spans));
@@ -168,6 +383,92 @@ pub fn render_table(
     lines
 }
 
+/// Horizontal border such as `┌───┬───┐`
+fn border_line(col_widths: &[usize], left: char, middle: char, right: char) -> String {
+    let mut border = String::from(left);
+    for (i, &width) in col_widths.iter().enumerate() {
+        border.push_str(&"─".repeat(width));
+        if i < col_widths.len() - 1 {
+            border.push(middle);
+        }
+    }
+    border.push(right);
+    border
+}
+
+/// Render a row whose cells may span several lines (when wrapped)
+fn push_row_lines(
+    lines: &mut Vec<Line<'static>>,
+    cells: &[Vec<String>],
+    col_widths: &[usize],
+    alignments: &[Alignment],
+    ctx: &TableRenderContext,
+) {
+    let height = cells.iter().map(Vec::len).max().unwrap_or(1).max(1);
+    for line_idx in 0..height {
+        let line_cells: Vec<String> = cells
+            .iter()
+            .map(|cell| cell.get(line_idx).cloned().unwrap_or_default())
+            .collect();
+        let mut line = render_table_row(&line_cells, col_widths, alignments, ctx);
+        // Only the first line of a wrapped row carries the row arrow
+        if line_idx > 0 && ctx.in_table_mode {
+            line.spans[0] = Span::raw("  ");
+        }
+        lines.push(line);
+    }
+}
+
+/// Cut `text` to at most `width` display columns, ending in `…` if shortened
+fn truncate_to_width(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    let mut result = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > width {
+            break;
+        }
+        result.push(c);
+        used += w;
+    }
+    result.push('…');
+    result
+}
+
+/// Split `text` into lines of at most `width` display columns, at spaces
+/// where possible
+fn wrap_to_width(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split(' ') {
+        let sep = usize::from(!current.is_empty());
+        if current.width() + sep + word.width() <= width {
+            if sep == 1 {
+                current.push(' ');
+            }
+            current.push_str(word);
+            continue;
+        }
+        if !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+        }
+        // Break words that are wider than the column
+        for c in word.chars() {
+            if current.width() + c.width().unwrap_or(0) > width {
+                lines.push(std::mem::take(&mut current));
+            }
+            current.push(c);
+        }
+    }
+    lines.push(current);
+    lines
+}
+
 /// Render a single table row with proper alignment and styling
 ///
 /// # Arguments
@@ -469,4 +770,120 @@ mod tests {
             assert_eq!(line.spans[0].content, "  ");
         }
     }
+
+    mod render_table_view_tests {
+        use super::*;
+
+        fn line_text(line: &Line) -> String {
+            line.spans.iter().map(|s| s.content.as_ref()).collect()
+        }
+
+        #[test]
+        fn test_visible_columns_fit_width() {
+            let widths = vec![10, 10, 10, 10];
+            // Left border + (width + right border) per column
+            assert_eq!(visible_table_columns(&widths, 0, 34), 0..3);
+            assert_eq!(visible_table_columns(&widths, 2, 34), 2..4);
+            // A column wider than the pane is still shown on its own
+            assert_eq!(visible_table_columns(&widths, 1, 5), 1..2);
+        }
+
+        #[test]
+        fn test_visible_rows_follow_selection() {
+            let heights = vec![1; 10];
+            assert_eq!(visible_table_rows(&heights, 0, 4, Some(2)), 0..4);
+            assert_eq!(visible_table_rows(&heights, 0, 4, Some(6)), 3..7);
+            assert_eq!(visible_table_rows(&heights, 5, 4, Some(1)), 1..5);
+            // Wrapped rows take several lines
+            assert_eq!(visible_table_rows(&[3, 3, 3], 0, 4, Some(1)), 1..2);
+        }
+
+        #[test]
+        fn test_wide_table_scrolls_with_frozen_header() {
+            let theme = test_theme();
+            let headers: Vec<String> = (1..=6).map(|i| format!("Column {}", i)).collect();
+            let rows: Vec<Vec<String>> = (0..20)
+                .map(|r| (1..=6).map(|c| format!("r{}c{}", r, c)).collect())
+                .collect();
+            let view = TableView {
+                col_offset: 2,
+                row_offset: 10,
+                ..TableView::default()
+            };
+
+            let lines = render_table_with(
+                &headers,
+                &[],
+                &rows,
+                &TableRenderOptions {
+                    theme: &theme,
+                    is_selected: true,
+                    in_table_mode: true,
+                    selected_cell: Some((12, 3)),
+                    viewport: Some(TableViewport {
+                        view: &view,
+                        width: 40,
+                        max_lines: Some(5),
+                    }),
+                },
+            );
+
+            // Borders + header + separator + 5 rows
+            assert_eq!(lines.len(), 9);
+            let top = line_text(&lines[0]);
+            assert!(top.contains('‹') && top.contains('›'));
+            let header = line_text(&lines[1]);
+            assert!(header.contains("Column 3") && !header.contains("Column 1"));
+            assert!(line_text(&lines[3]).contains("r10c3"));
+            assert!(lines.iter().all(|line| line_text(line).width() <= 42));
+        }
+
+        #[test]
+        fn test_long_cells_truncate_or_wrap() {
+            let theme = test_theme();
+            let headers = vec!["Id".to_string(), "Notes".to_string()];
+            let rows = vec![vec!["1".to_string(), "word ".repeat(20)]];
+            let render = |view: &TableView| {
+                render_table_with(
+                    &headers,
+                    &[],
+                    &rows,
+                    &TableRenderOptions {
+                        theme: &theme,
+                        is_selected: true,
+                        in_table_mode: true,
+                        selected_cell: Some((1, 1)),
+                        viewport: Some(TableViewport {
+                            view,
+                            width: 40,
+                            max_lines: None,
+                        }),
+                    },
+                )
+            };
+
+            let truncated = render(&TableView::default());
+            assert_eq!(truncated.len(), 5);
+            assert!(line_text(&truncated[3]).contains('…'));
+
+            let wrapped = render(&TableView {
+                wrap: true,
+                ..TableView::default()
+            });
+            assert!(wrapped.len() > 5);
+            assert!(wrapped.iter().all(|line| line_text(line).width() <= 42));
+        }
+
+        #[test]
+        fn test_sorted_column_has_indicator() {
+            let labels = table_header_labels(
+                &["Name".to_string(), "Size".to_string()],
+                Some(TableSort {
+                    column: 1,
+                    ascending: false,
+                }),
+            );
+            assert_eq!(labels, vec!["Name".to_string(), "Size ▼".to_string()]);
+        }
+    }
 }
@@ -0,0 +1,310 @@
+//! Vault-wide wikilink resolution.
+//!
+//! A vault is a folder of notes: the nearest directory above a document that
+//! holds `.obsidian/`, or the `[vault] root` set in the config. Wikilinks in
+//! a vault resolve the way Obsidian resolves them, so `[[Design Doc]]` finds
+//! `projects/Design Doc.md` from anywhere:
+//!
+//! - the target is matched against note paths without their extension,
+//!   case-insensitively, as a whole path or a trailing part of one
+//!   (`[[projects/design doc]]`, `[[Design Doc]]`)
+//! - otherwise it is matched against the `aliases:` in notes' front matter
+//! - of several matches, a note next to the linking document wins; the rest
+//!   are offered in order of path length
+
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
+use turbovault_parser::{ParseOptions, ParsedContent};
+
+/// File extensions of notes
+pub const NOTE_EXTENSIONS: &[&str] = &["md", "markdown", "mdown"];
+
+/// A note in a vault
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Note {
+    /// Path relative to the vault root
+    pub path: PathBuf,
+    /// `aliases:` from the note's front matter
+    pub aliases: Vec<String>,
+}
+
+/// The notes under a vault root
+#[derive(Debug, Clone)]
+pub struct Vault {
+    root: PathBuf,
+    notes: Vec<Note>,
+}
+
+impl Vault {
+    /// Vault root for a document: the configured root if the document is
+    /// inside it, else the nearest ancestor holding `.obsidian/`
+    pub fn detect(document: &Path, configured: Option<&str>) -> Option<PathBuf> {
+        let document = document.canonicalize().ok()?;
+        if let Some(root) = configured.map(expand_home)
+            && let Ok(root) = root.canonicalize()
+            && document.starts_with(&root)
+        {
+            return Some(root);
+        }
+        document
+            .ancestors()
+            .skip(1)
+            .find(|dir| dir.join(".obsidian").is_dir())
+            .map(Path::to_path_buf)
+    }
+
+    /// The vault holding a document, or the notes in its folder outside a
+    /// vault. `None` if the document doesn't exist.
+    pub fn workspace(document: &Path, configured: Option<&str>) -> Option<Self> {
+        match Self::detect(document, configured) {
+            Some(root) => Some(Self::open(root)),
+            None => {
+                let dir = document.canonicalize().ok()?.parent()?.to_path_buf();
+                Some(Self::open_folder(dir))
+            }
+        }
+    }
+
+    /// Scan the notes under `root`. Hidden files and folders and symlinks are
+    /// skipped.
+    pub fn open(root: PathBuf) -> Self {
+        let mut vault = Self {
+            root,
+            notes: Vec::new(),
+        };
+        vault.rescan();
+        vault
+    }
+
+    /// The notes directly in `dir`, for documents outside any vault
+    pub fn open_folder(dir: PathBuf) -> Self {
+        let mut notes = Vec::new();
+        scan(&dir, &dir, false, &mut notes);
+        notes.sort_by(|a, b| a.path.cmp(&b.path));
+        Self { root: dir, notes }
+    }
+
+    /// Scan the notes again, picking up notes created or renamed since
+    pub fn rescan(&mut self) {
+        let mut notes = Vec::new();
+        scan(&self.root, &self.root, true, &mut notes);
+        notes.sort_by(|a, b| a.path.cmp(&b.path));
+        self.notes = notes;
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn notes(&self) -> &[Note] {
+        &self.notes
+    }
+
+    /// Absolute paths of the notes a wikilink's file part refers to, best
+    /// match first. `from` is the linking document.
+    pub fn resolve(&self, target: &str, from: &Path) -> Vec<PathBuf> {
+        let target = link_key(target);
+        if target.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<&Note> = self
+            .notes
+            .iter()
+            .filter(|note| {
+                let stem = link_key(&note.path.to_string_lossy());
+                stem == target || stem.ends_with(&format!("/{}", target))
+            })
+            .collect();
+        if matches.is_empty() && !target.contains('/') {
+            matches = self
+                .notes
+                .iter()
+                .filter(|note| note.aliases.iter().any(|a| a.to_lowercase() == target))
+                .collect();
+        }
+
+        let mut paths: Vec<PathBuf> = matches
+            .into_iter()
+            .map(|note| self.root.join(&note.path))
+            .collect();
+        let from_dir = from.parent().and_then(|dir| dir.canonicalize().ok());
+        if let Some(beside) = paths
+            .iter()
+            .position(|path| from_dir.is_some() && path.parent() == from_dir.as_deref())
+        {
+            return vec![paths.swap_remove(beside)];
+        }
+        paths.sort_by_key(|path| path.as_os_str().len());
+        paths
+    }
+}
+
+/// Whether `ext` is a note's file extension (case-insensitive)
+pub fn is_note_extension(ext: &OsStr) -> bool {
+    NOTE_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str())
+}
+
+/// Resolve `.` and `..` without touching the filesystem; `None` if the path
+/// climbs above its start. Relative links between notes are resolved with
+/// this, so they agree with wikilinks on which note is meant.
+pub fn normalize_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+    Some(normalized)
+}
+
+/// Lowercased path with `/` separators and no note extension or leading `./`
+fn link_key(target: &str) -> String {
+    let target = target.trim().replace('\\', "/").to_lowercase();
+    let target = target.trim_start_matches("./").trim_start_matches('/');
+    match target.rsplit_once('.') {
+        Some((stem, ext)) if NOTE_EXTENSIONS.contains(&ext) => stem.to_string(),
+        _ => target.to_string(),
+    }
+}
+
+/// Expand a leading `~/` to the home directory
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn scan(root: &Path, dir: &Path, recursive: bool, notes: &mut Vec<Note>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if hidden || file_type.is_symlink() {
+            continue;
+        }
+        if file_type.is_dir() {
+            if recursive {
+                scan(root, &path, recursive, notes);
+            }
+        } else if path.extension().is_some_and(is_note_extension) {
+            let aliases = std::fs::read_to_string(&path)
+                .map(|content| aliases(&content))
+                .unwrap_or_default();
+            if let Ok(relative) = path.strip_prefix(root) {
+                notes.push(Note {
+                    path: relative.to_path_buf(),
+                    aliases,
+                });
+            }
+        }
+    }
+}
+
+/// `aliases:` (a string or a list) from a note's front matter
+fn aliases(content: &str) -> Vec<String> {
+    if !content.starts_with("---") {
+        return Vec::new();
+    }
+    ParsedContent::parse_with_options(content, ParseOptions::none().with_frontmatter())
+        .frontmatter
+        .map(|frontmatter| frontmatter.aliases())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn vault() -> (tempfile::TempDir, Vault) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join(".obsidian")).unwrap();
+        fs::create_dir_all(root.join("projects/old")).unwrap();
+        fs::create_dir_all(root.join("daily")).unwrap();
+        fs::write(root.join("index.md"), "# Index").unwrap();
+        fs::write(
+            root.join("projects/Design Doc.md"),
+            "---\naliases: [DD, Spec]\n---\n# Design",
+        )
+        .unwrap();
+        fs::write(root.join("projects/old/Design Doc.md"), "# Old").unwrap();
+        fs::write(root.join("daily/notes.markdown"), "# Notes").unwrap();
+        fs::write(root.join(".obsidian/hidden.md"), "").unwrap();
+        (dir, Vault::open(root))
+    }
+
+    #[test]
+    fn test_detect_and_scan() {
+        let (_dir, vault) = vault();
+        let daily = vault.root().join("daily/notes.markdown");
+        assert_eq!(Vault::detect(&daily, None).as_deref(), Some(vault.root()));
+        assert_eq!(vault.notes().len(), 4);
+        assert_eq!(
+            vault.notes()[2].aliases,
+            vec!["DD".to_string(), "Spec".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        let (_dir, vault) = vault();
+        let root = vault.root().to_path_buf();
+        let from = root.join("daily/notes.markdown");
+
+        // Shortest path first when the note isn't beside the linking document
+        assert_eq!(
+            vault.resolve("design doc", &from),
+            vec![
+                root.join("projects/Design Doc.md"),
+                root.join("projects/old/Design Doc.md")
+            ]
+        );
+        assert_eq!(
+            vault.resolve("old/Design Doc.md", &from),
+            vec![root.join("projects/old/Design Doc.md")]
+        );
+        // A match beside the linking document wins outright
+        let beside = root.join("projects/old/Other.md");
+        assert_eq!(
+            vault.resolve("Design Doc", &beside),
+            vec![root.join("projects/old/Design Doc.md")]
+        );
+        assert_eq!(
+            vault.resolve("spec", &from),
+            vec![root.join("projects/Design Doc.md")]
+        );
+        assert_eq!(vault.resolve("Notes", &root.join("index.md")).len(), 1);
+        assert!(vault.resolve("hidden", &from).is_empty());
+        assert!(vault.resolve("esign Doc", &from).is_empty());
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        let root = Path::new("/vault");
+        assert_eq!(
+            normalize_path(&root.join("daily/../projects/./plan.md")),
+            Some(root.join("projects/plan.md"))
+        );
+        assert_eq!(
+            normalize_path(Path::new("./a/b/../c.md")),
+            Some(PathBuf::from("a/c.md"))
+        );
+        assert_eq!(normalize_path(Path::new("a/../../b.md")), None);
+        assert!(is_note_extension(OsStr::new("Markdown")));
+        assert!(!is_note_extension(OsStr::new("txt")));
+    }
+}